@@ -0,0 +1,61 @@
+use serde_json::{json, Value};
+
+/// A tool call picked without the model, for when Gemini can't be reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallbackCall {
+    pub tool_name: &'static str,
+    pub args: Value,
+}
+
+const LIST_PHRASES: [&str; 5] = [
+    "list goals",
+    "list my goals",
+    "show goals",
+    "show my goals",
+    "what are my goals",
+];
+const CREATE_PREFIXES: [&str; 6] = [
+    "create goal ",
+    "create a goal ",
+    "add goal ",
+    "add a goal ",
+    "create task ",
+    "add task ",
+];
+
+/// Match the handful of commands that don't need the model: listing goals and creating a
+/// goal by name (created as a task, which needs no dates). Anything else returns None.
+pub fn match_intent(query: &str) -> Option<FallbackCall> {
+    let trimmed = query.trim().trim_end_matches(['.', '!', '?']);
+    let lower = trimmed.to_lowercase();
+
+    if LIST_PHRASES.contains(&lower.as_str()) {
+        return Some(FallbackCall {
+            tool_name: "get_list_data",
+            args: json!({}),
+        });
+    }
+
+    for prefix in CREATE_PREFIXES {
+        let Some(head) = trimmed.get(..prefix.len()) else {
+            continue;
+        };
+        if !head.eq_ignore_ascii_case(prefix) {
+            continue;
+        }
+        // Taken from the original so the name keeps its casing
+        let name = trimmed[prefix.len()..]
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .trim();
+        if name.is_empty() {
+            return None;
+        }
+        return Some(FallbackCall {
+            tool_name: "create_goal",
+            args: json!({ "goal": { "name": name, "goal_type": "task" } }),
+        });
+    }
+
+    None
+}
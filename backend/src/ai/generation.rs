@@ -0,0 +1,94 @@
+use tracing::warn;
+
+/// Sampling settings shared by every LLM call site (Gemini chat and OpenRouter prompts).
+///
+/// Each value can be overridden with an env var:
+/// - `GOALS_AI_TEMPERATURE` (0.0..=2.0)
+/// - `GOALS_AI_TOP_P` (0.0..=1.0)
+/// - `GOALS_AI_TOP_K` (>= 1)
+/// - `GOALS_AI_MAX_OUTPUT_TOKENS` (>= 1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GenerationSettings {
+    pub temperature: f32,
+    pub top_p: f32,
+    pub top_k: i32,
+    pub max_output_tokens: i32,
+}
+
+impl Default for GenerationSettings {
+    fn default() -> Self {
+        Self {
+            temperature: 0.7,
+            top_p: 0.95,
+            top_k: 40,
+            max_output_tokens: 1024,
+        }
+    }
+}
+
+impl GenerationSettings {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Build settings from an arbitrary key lookup; out-of-range or unparsable values
+    /// fall back to the default for that field.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        let overrides = GenerationOverrides::from_lookup(lookup);
+        Self {
+            temperature: overrides.temperature.unwrap_or(defaults.temperature),
+            top_p: overrides.top_p.unwrap_or(defaults.top_p),
+            top_k: overrides.top_k.unwrap_or(defaults.top_k),
+            max_output_tokens: overrides
+                .max_output_tokens
+                .unwrap_or(defaults.max_output_tokens),
+        }
+    }
+}
+
+/// Only the sampling settings that were explicitly configured, for providers that should
+/// keep their own defaults for everything else (OpenRouter routes to many models).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GenerationOverrides {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub top_k: Option<i32>,
+    pub max_output_tokens: Option<i32>,
+}
+
+impl GenerationOverrides {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read the same env vars as `GenerationSettings`; unset, out-of-range or unparsable
+    /// values are left as `None`.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            temperature: parse_in_range(&lookup, "GOALS_AI_TEMPERATURE", |v: f32| {
+                (0.0..=2.0).contains(&v)
+            }),
+            top_p: parse_in_range(&lookup, "GOALS_AI_TOP_P", |v: f32| (0.0..=1.0).contains(&v)),
+            top_k: parse_in_range(&lookup, "GOALS_AI_TOP_K", |v: i32| v >= 1),
+            max_output_tokens: parse_in_range(&lookup, "GOALS_AI_MAX_OUTPUT_TOKENS", |v: i32| {
+                v >= 1
+            }),
+        }
+    }
+}
+
+fn parse_in_range<T: std::str::FromStr + Copy>(
+    lookup: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    valid: impl Fn(T) -> bool,
+) -> Option<T> {
+    let raw = lookup(name)?;
+    match raw.trim().parse::<T>() {
+        Ok(value) if valid(value) => Some(value),
+        _ => {
+            warn!(name, value = %raw, "Ignoring invalid AI generation setting");
+            None
+        }
+    }
+}
@@ -0,0 +1,144 @@
+/*
+small catalog of user-facing assistant messages, keyed by locale
+only the strings the assistant sends directly to the user live here; logs stay in English
+*/
+use axum::http::{header, HeaderMap};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    En,
+    Es,
+    Fr,
+    De,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageKey {
+    ProcessingFailed,
+    EmptyResponse,
+    UnexpectedMessageType,
+    ParseFailed,
+    QueryTooLong,
+    HistoryTooLong,
+    Busy,
+    OfflineFallback,
+}
+
+impl Locale {
+    /// Pick the first supported language from an `Accept-Language` header value,
+    /// e.g. "fr-CA,fr;q=0.9,en;q=0.8" -> Fr. Quality weights are assumed to be in
+    /// descending order, which is what browsers send.
+    pub fn from_accept_language(value: &str) -> Locale {
+        value
+            .split(',')
+            .filter_map(|part| {
+                let tag = part.split(';').next()?.trim();
+                let primary = tag.split(['-', '_']).next()?.to_ascii_lowercase();
+                Locale::from_code(&primary)
+            })
+            .next()
+            .unwrap_or(Locale::En)
+    }
+
+    pub fn from_headers(headers: &HeaderMap) -> Locale {
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .map(Locale::from_accept_language)
+            .unwrap_or(Locale::En)
+    }
+
+    fn from_code(code: &str) -> Option<Locale> {
+        match code {
+            "en" => Some(Locale::En),
+            "es" => Some(Locale::Es),
+            "fr" => Some(Locale::Fr),
+            "de" => Some(Locale::De),
+            _ => None,
+        }
+    }
+}
+
+pub fn message(locale: Locale, key: MessageKey) -> &'static str {
+    match (locale, key) {
+        (Locale::En, MessageKey::ProcessingFailed) => {
+            "I'm sorry, I couldn't process your request. Please try again."
+        }
+        (Locale::En, MessageKey::EmptyResponse) => {
+            "I'm sorry, I don't have a response for that. Could you rephrase?"
+        }
+        (Locale::En, MessageKey::UnexpectedMessageType) => "Unexpected message type",
+        (Locale::En, MessageKey::ParseFailed) => "Failed to parse message",
+        (Locale::En, MessageKey::QueryTooLong) => "Your message is too long. Please shorten it.",
+        (Locale::En, MessageKey::HistoryTooLong) => {
+            "This conversation is too long. Please start a new one."
+        }
+        (Locale::En, MessageKey::Busy) => {
+            "The assistant is busy right now. Please try again in a few seconds."
+        }
+        (Locale::En, MessageKey::OfflineFallback) => {
+            "The assistant is offline right now, so I ran that as a basic command."
+        }
+
+        (Locale::Es, MessageKey::ProcessingFailed) => {
+            "Lo siento, no pude procesar tu solicitud. Inténtalo de nuevo."
+        }
+        (Locale::Es, MessageKey::EmptyResponse) => {
+            "Lo siento, no tengo una respuesta para eso. ¿Podrías reformularlo?"
+        }
+        (Locale::Es, MessageKey::UnexpectedMessageType) => "Tipo de mensaje inesperado",
+        (Locale::Es, MessageKey::ParseFailed) => "No se pudo interpretar el mensaje",
+        (Locale::Es, MessageKey::QueryTooLong) => "Tu mensaje es demasiado largo. Acórtalo.",
+        (Locale::Es, MessageKey::HistoryTooLong) => {
+            "Esta conversación es demasiado larga. Empieza una nueva."
+        }
+        (Locale::Es, MessageKey::Busy) => {
+            "El asistente está ocupado. Inténtalo de nuevo en unos segundos."
+        }
+        (Locale::Es, MessageKey::OfflineFallback) => {
+            "El asistente no está disponible, así que lo ejecuté como un comando básico."
+        }
+
+        (Locale::Fr, MessageKey::ProcessingFailed) => {
+            "Désolé, je n'ai pas pu traiter votre demande. Veuillez réessayer."
+        }
+        (Locale::Fr, MessageKey::EmptyResponse) => {
+            "Désolé, je n'ai pas de réponse à cela. Pourriez-vous reformuler ?"
+        }
+        (Locale::Fr, MessageKey::UnexpectedMessageType) => "Type de message inattendu",
+        (Locale::Fr, MessageKey::ParseFailed) => "Impossible d'analyser le message",
+        (Locale::Fr, MessageKey::QueryTooLong) => {
+            "Votre message est trop long. Veuillez le raccourcir."
+        }
+        (Locale::Fr, MessageKey::HistoryTooLong) => {
+            "Cette conversation est trop longue. Veuillez en commencer une nouvelle."
+        }
+        (Locale::Fr, MessageKey::Busy) => {
+            "L'assistant est occupé. Veuillez réessayer dans quelques secondes."
+        }
+        (Locale::Fr, MessageKey::OfflineFallback) => {
+            "L'assistant est hors ligne, j'ai donc exécuté cela comme une commande simple."
+        }
+
+        (Locale::De, MessageKey::ProcessingFailed) => {
+            "Entschuldigung, ich konnte deine Anfrage nicht verarbeiten. Bitte versuche es erneut."
+        }
+        (Locale::De, MessageKey::EmptyResponse) => {
+            "Entschuldigung, darauf habe ich keine Antwort. Kannst du es anders formulieren?"
+        }
+        (Locale::De, MessageKey::UnexpectedMessageType) => "Unerwarteter Nachrichtentyp",
+        (Locale::De, MessageKey::ParseFailed) => "Nachricht konnte nicht gelesen werden",
+        (Locale::De, MessageKey::QueryTooLong) => {
+            "Deine Nachricht ist zu lang. Bitte kürze sie."
+        }
+        (Locale::De, MessageKey::HistoryTooLong) => {
+            "Diese Unterhaltung ist zu lang. Bitte beginne eine neue."
+        }
+        (Locale::De, MessageKey::Busy) => {
+            "Der Assistent ist gerade ausgelastet. Bitte versuche es in ein paar Sekunden erneut."
+        }
+        (Locale::De, MessageKey::OfflineFallback) => {
+            "Der Assistent ist gerade offline, daher habe ich das als einfachen Befehl ausgeführt."
+        }
+    }
+}
@@ -2,15 +2,44 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
 use std::error::Error;
+use std::sync::LazyLock;
+use std::time::Duration;
 use tracing::{error, info};
 
+use crate::ai::generation::GenerationOverrides;
+use crate::server::metrics;
+
 // Embed the prompts.json file at compile time
 const PROMPTS_JSON: &str = include_str!("prompts.json");
 
+const DEFAULT_OPENROUTER_TIMEOUT_SECS: u64 = 20;
+
+// One pooled client for every OpenRouter call. GOALS_OPENROUTER_TIMEOUT_SECS bounds the
+// whole request so a hung connection can't hang the caller.
+static OPENROUTER_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    let timeout = env::var("GOALS_OPENROUTER_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_OPENROUTER_TIMEOUT_SECS);
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+});
+
 #[derive(Serialize)]
 struct OpenRouterRequest {
     model: String,
     messages: Vec<Message>,
+    // Unset values are omitted so OpenRouter applies the routed model's own defaults
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<i32>,
 }
 
 #[derive(Serialize)]
@@ -49,6 +78,12 @@ fn load_prompts() -> Result<HashMap<String, String>, Box<dyn Error>> {
 /// # Returns
 /// The text response from the model.
 pub async fn call_openrouter(prompt_key: &str, input: Option<&str>) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let result = request_openrouter(prompt_key, input).await;
+    metrics::record_ai_call("openrouter", result.is_ok());
+    result
+}
+
+async fn request_openrouter(prompt_key: &str, input: Option<&str>) -> Result<String, Box<dyn Error + Send + Sync>> {
     let api_key = env::var("OPENROUTER_API_KEY").map_err(|_| "OPENROUTER_API_KEY not set")?;
     let model = env::var("OPENROUTER_MODEL").unwrap_or_else(|_| "gpt-4o-mini".to_string());
     
@@ -71,16 +106,20 @@ pub async fn call_openrouter(prompt_key: &str, input: Option<&str>) -> Result<St
         "Calling OpenRouter API"
     );
 
-    let client = reqwest::Client::new();
+    let settings = GenerationOverrides::from_env();
     let request_body = OpenRouterRequest {
         model: model.clone(),
         messages: vec![Message {
             role: "user".to_string(),
             content: prompt_content,
         }],
+        temperature: settings.temperature,
+        top_p: settings.top_p,
+        top_k: settings.top_k,
+        max_tokens: settings.max_output_tokens,
     };
 
-    let resp = client
+    let resp = OPENROUTER_CLIENT
         .post("https://openrouter.ai/api/v1/chat/completions")
         .header("Authorization", format!("Bearer {}", api_key))
         // OpenRouter optional headers for ranking
@@ -3,17 +3,23 @@ use axum::{
         ws::{Message as WsMessage, WebSocket},
         Extension, WebSocketUpgrade,
     },
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
 
+use crate::ai::fallback;
+use crate::ai::generation::GenerationSettings;
+use crate::ai::messages::{self, Locale, MessageKey};
 use crate::ai::tool_registry;
+use crate::server::metrics;
 
 // Alias for UserLocks, matching the one in tool_registry
 type UserLocks = Arc<Mutex<HashMap<i64, Arc<Mutex<()>>>>>;
@@ -26,12 +32,33 @@ You can make use of the included tools to help the user. If you need
 to use a tool, call it using the function call syntax. If you don't
 need to use a tool, just respond with the natural language response."#;
 
+// Bounds on what a client can push into a Gemini request. Frames above the byte cap are
+// rejected by the websocket layer before they are even parsed.
+pub const MAX_WS_MESSAGE_BYTES: usize = 64 * 1024;
+pub const MAX_QUERY_CHARS: usize = 4_000;
+pub const MAX_HISTORY_MESSAGES: usize = 100;
+
+/// Reject a query that is too long on its own, or that would push the conversation
+/// (including tool calls and results already in it) past the history cap.
+pub fn validate_user_query(content: &str, history_len: usize) -> Result<(), MessageKey> {
+    if content.chars().count() > MAX_QUERY_CHARS {
+        return Err(MessageKey::QueryTooLong);
+    }
+    if history_len >= MAX_HISTORY_MESSAGES {
+        return Err(MessageKey::HistoryTooLong);
+    }
+    Ok(())
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum WsQueryMessage {
     UserQuery {
         content: String,
         conversation_id: Option<String>,
+        // Opt-in: also stream DebugTrace messages describing each tool step
+        #[serde(default)]
+        debug: bool,
     },
     AssistantText {
         content: String,
@@ -48,6 +75,43 @@ pub enum WsQueryMessage {
     Error {
         message: String,
     },
+    // Only sent for debug queries: what the model chose, what the tool returned, and
+    // whether a fallback was substituted (non-string tool data or an empty final answer)
+    DebugTrace {
+        tool_name: Option<String>,
+        args: Option<serde_json::Value>,
+        tool_result: Option<serde_json::Value>,
+        fallback_used: bool,
+    },
+}
+
+impl WsQueryMessage {
+    /// Debug trace for one tool step. `fallback_used` marks a result whose data was not
+    /// a string, so a placeholder went into the conversation history instead.
+    pub fn tool_trace(
+        tool_name: &str,
+        args: &serde_json::Value,
+        tool_result: serde_json::Value,
+        fallback_used: bool,
+    ) -> Self {
+        WsQueryMessage::DebugTrace {
+            tool_name: Some(tool_name.to_string()),
+            args: Some(args.clone()),
+            tool_result: Some(tool_result),
+            fallback_used,
+        }
+    }
+
+    /// Debug trace for a final answer that came back empty and was replaced by the
+    /// localized fallback text.
+    pub fn empty_response_trace() -> Self {
+        WsQueryMessage::DebugTrace {
+            tool_name: None,
+            args: None,
+            tool_result: None,
+            fallback_used: true,
+        }
+    }
 }
 
 // Internal representation of a conversation message
@@ -106,6 +170,18 @@ struct GenerationConfig {
     max_output_tokens: Option<i32>,
 }
 
+impl From<GenerationSettings> for GenerationConfig {
+    fn from(settings: GenerationSettings) -> Self {
+        Self {
+            temperature: Some(settings.temperature),
+            top_p: Some(settings.top_p),
+            top_k: Some(settings.top_k),
+            candidate_count: Some(1),
+            max_output_tokens: Some(settings.max_output_tokens),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct GeminiApiResponse {
     candidates: Vec<Candidate>,
@@ -141,9 +217,19 @@ pub async fn handle_query_ws(
     Extension(pool): Extension<neo4rs::Graph>,
     Extension(user_id): Extension<i64>,
     Extension(user_locks): Extension<UserLocks>,
-) -> impl IntoResponse {
+    headers: HeaderMap,
+) -> Response {
     info!(user_id = user_id, "WebSocket upgrade request received for user");
-    ws.on_upgrade(move |socket| handle_websocket_connection(socket, pool, user_id, user_locks))
+    let locale = Locale::from_headers(&headers);
+    if GEMINI_PERMITS.available_permits() == 0 {
+        return busy_response(locale);
+    }
+    ws.max_message_size(MAX_WS_MESSAGE_BYTES)
+        .max_frame_size(MAX_WS_MESSAGE_BYTES)
+        .on_upgrade(move |socket| {
+            handle_websocket_connection(socket, pool, user_id, user_locks, locale)
+        })
+        .into_response()
 }
 
 async fn handle_websocket_connection(
@@ -151,6 +237,7 @@ async fn handle_websocket_connection(
     pool: neo4rs::Graph,
     user_id: i64,
     user_locks: UserLocks,
+    locale: Locale,
 ) {
     info!(user_id = user_id, "WebSocket connection established");
     let (mut sender, mut receiver) = socket.split();
@@ -169,7 +256,14 @@ async fn handle_websocket_connection(
                     Ok(WsQueryMessage::UserQuery {
                         content,
                         conversation_id,
+                        debug,
                     }) => {
+                        if let Err(key) = validate_user_query(&content, conversation_history.len())
+                        {
+                            let _ = send_error(&mut sender, messages::message(locale, key)).await;
+                            continue;
+                        }
+
                         // Append user's message to conversation
                         conversation_history.push(Message {
                             role: "user".to_string(),
@@ -184,25 +278,38 @@ async fn handle_websocket_connection(
                             &user_locks, // Pass as reference
                             conversation_id,
                             user_id,
+                            locale,
+                            debug,
                         )
                         .await;
 
                         if let Err(e) = query_result {
-                            let error_string = format!("{}", e);
                             error!(
                                 user_id = user_id,
-                                "Error in handle_user_query_loop: {}", error_string
+                                "Error in handle_user_query_loop: {}", e
                             );
-                            let _ = send_error(&mut sender, &error_string).await;
+                            let _ = send_error(
+                                &mut sender,
+                                messages::message(locale, MessageKey::ProcessingFailed),
+                            )
+                            .await;
                         }
                     }
                     // We only handle UserQuery in this example; everything else -> error
                     Ok(_) => {
-                        let _ = send_error(&mut sender, "Unexpected message type").await;
+                        let _ = send_error(
+                            &mut sender,
+                            messages::message(locale, MessageKey::UnexpectedMessageType),
+                        )
+                        .await;
                     }
                     Err(e) => {
                         error!(user_id = user_id, "Failed to parse message: {}", e);
-                        let _ = send_error(&mut sender, "Failed to parse message").await;
+                        let _ = send_error(
+                            &mut sender,
+                            messages::message(locale, MessageKey::ParseFailed),
+                        )
+                        .await;
                     }
                 }
             }
@@ -223,6 +330,7 @@ async fn handle_websocket_connection(
 // Main Loop for a Single User Query
 // ==================================================================
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_user_query_loop(
     sender: &mut futures_util::stream::SplitSink<WebSocket, WsMessage>,
     conversation_history: &mut Vec<Message>,
@@ -230,6 +338,8 @@ async fn handle_user_query_loop(
     user_locks: &UserLocks,
     conversation_id: Option<String>,
     user_id: i64,
+    locale: Locale,
+    debug: bool,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let conversation_uuid = conversation_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
@@ -239,10 +349,41 @@ async fn handle_user_query_loop(
         "Starting user query loop"
     );
 
+    // Held for every Gemini round trip of this query. Fail fast instead of queueing when the
+    // instance is at its concurrency cap, dropping the unanswered message from the history.
+    let Ok(_permit) = GEMINI_PERMITS.try_acquire() else {
+        info!(conversation_id = %conversation_uuid, "Gemini concurrency limit reached");
+        conversation_history.pop();
+        send_error(sender, messages::message(locale, MessageKey::Busy)).await?;
+        return Ok(());
+    };
+
+    let mut tools_ran = false;
     loop {
         // 1. Call Gemini with the current conversation
         info!(conversation_id = %conversation_uuid, "Calling Gemini API");
-        let chunks = call_gemini(conversation_history).await?;
+        let gemini_result = call_gemini(conversation_history).await;
+        metrics::record_ai_call("gemini", gemini_result.is_ok());
+        let chunks = match gemini_result {
+            Ok(chunks) => chunks,
+            // Only before any tool has run, so a command is never executed twice
+            Err(e) if !tools_ran && is_gemini_unreachable(e.as_ref()) => {
+                if run_offline_fallback(
+                    sender,
+                    conversation_history,
+                    pool,
+                    user_locks,
+                    user_id,
+                    locale,
+                )
+                .await?
+                {
+                    return Ok(());
+                }
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
         // 2. Parse chunks (text or function call)
         let mut found_function_call = false;
         let mut collected_text = String::new();
@@ -286,6 +427,7 @@ async fn handle_user_query_loop(
                     }
 
                     found_function_call = true;
+                    tools_ran = true;
                     let tool_call_name = function_call.name.clone();
                     let args = function_call.args.clone();
 
@@ -339,12 +481,24 @@ async fn handle_user_query_loop(
 
                             // Extract the actual data part (which is now the Debug string)
                             // for the conversation history. The LLM needs the function's output.
-                            let data_for_history_str = tool_result
+                            let data_str = tool_result
                                 .get("data") // Get the value of the "data" key
-                                .and_then(|v| v.as_str()) // Attempt to get it as a string
+                                .and_then(|v| v.as_str()); // Attempt to get it as a string
+                            let data_for_history_str = data_str
                                 .unwrap_or("Tool returned non-string data") // Fallback if data wasn't a string
                                 .to_string();
 
+                            if debug {
+                                send_debug_trace(
+                                    sender,
+                                    &tool_call_name,
+                                    &args,
+                                    tool_result.clone(),
+                                    data_str.is_none(),
+                                )
+                                .await?;
+                            }
+
                             // Add the extracted string data to history
                             conversation_history.push(Message {
                                 role: "model".to_string(), // Gemini expects "function" role for tool results
@@ -372,6 +526,17 @@ async fn handle_user_query_loop(
                             };
                             send_ws_message(sender, &tool_result_msg).await?;
 
+                            if debug {
+                                send_debug_trace(
+                                    sender,
+                                    &tool_call_name,
+                                    &args,
+                                    error_val.clone(),
+                                    false,
+                                )
+                                .await?;
+                            }
+
                             // Insert the error as a "function" role message
                             conversation_history.push(Message {
                                 role: "model".to_string(),
@@ -423,6 +588,14 @@ async fn handle_user_query_loop(
                 conversation_id = %conversation_uuid,
                 "No text content in final response"
             );
+            let fallback_msg = WsQueryMessage::AssistantText {
+                content: messages::message(locale, MessageKey::EmptyResponse).to_string(),
+            };
+            send_ws_message(sender, &fallback_msg).await?;
+
+            if debug {
+                send_ws_message(sender, &WsQueryMessage::empty_response_trace()).await?;
+            }
         }
 
         // We've got our final text output for this user query
@@ -440,10 +613,212 @@ async fn handle_user_query_loop(
     Ok(())
 }
 
+/// Whether a Gemini failure means the provider couldn't be reached at all (after retries),
+/// as opposed to it rejecting or mangling the request.
+fn is_gemini_unreachable(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    err.downcast_ref::<reqwest::Error>()
+        .is_some_and(is_retryable_error)
+}
+
+/// The deterministic command for the latest user message in the conversation, if any.
+pub fn offline_fallback_call(conversation_history: &[Message]) -> Option<fallback::FallbackCall> {
+    conversation_history
+        .iter()
+        .rev()
+        .find(|m| m.role == "user")
+        .and_then(|m| fallback::match_intent(&m.content))
+}
+
+/// When Gemini is unreachable, try the latest user message against the deterministic
+/// commands in `fallback` (GOALS_AI_OFFLINE_FALLBACK=false turns this off). Returns true
+/// if a command was run and answered.
+async fn run_offline_fallback(
+    sender: &mut futures_util::stream::SplitSink<WebSocket, WsMessage>,
+    conversation_history: &mut Vec<Message>,
+    pool: &neo4rs::Graph,
+    user_locks: &UserLocks,
+    user_id: i64,
+    locale: Locale,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    if !env_or("GOALS_AI_OFFLINE_FALLBACK", true) {
+        return Ok(false);
+    }
+    let Some(call) = offline_fallback_call(conversation_history) else {
+        return Ok(false);
+    };
+
+    info!(
+        user_id = user_id,
+        tool_name = call.tool_name,
+        "Gemini unreachable, using offline fallback"
+    );
+    metrics::record_ai_call("offline_fallback", true);
+    send_ws_message(
+        sender,
+        &WsQueryMessage::ToolCall {
+            name: call.tool_name.to_string(),
+            args: call.args.clone(),
+        },
+    )
+    .await?;
+
+    let (success, content) =
+        match tool_registry::dispatch_tool(call.tool_name, &call.args, pool, user_locks, user_id)
+            .await
+        {
+            Ok(result) => (true, result),
+            Err(e) => (
+                false,
+                serde_json::json!({ "error": e, "tool_name": call.tool_name }),
+            ),
+        };
+    send_ws_message(
+        sender,
+        &WsQueryMessage::ToolResult {
+            success,
+            name: call.tool_name.to_string(),
+            content,
+        },
+    )
+    .await?;
+
+    let notice = messages::message(locale, MessageKey::OfflineFallback);
+    send_ws_message(
+        sender,
+        &WsQueryMessage::AssistantText {
+            content: notice.to_string(),
+        },
+    )
+    .await?;
+    conversation_history.push(Message {
+        role: "model".to_string(),
+        content: notice.to_string(),
+    });
+    Ok(true)
+}
+
 // ==================================================================
 // Gemini (LLM) Call
 // ==================================================================
 
+const GEMINI_URL: &str =
+    "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:generateContent";
+const DEFAULT_GEMINI_TIMEOUT_SECS: u64 = 20;
+const DEFAULT_GEMINI_MAX_ATTEMPTS: u32 = 3;
+const GEMINI_RETRY_BASE_DELAY_MS: u64 = 500;
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+// One pooled client for every Gemini call. GOALS_GEMINI_TIMEOUT_SECS bounds the whole
+// request so a hung connection can't hang the user's websocket.
+static GEMINI_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    let timeout = env_or("GOALS_GEMINI_TIMEOUT_SECS", DEFAULT_GEMINI_TIMEOUT_SECS);
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+});
+
+pub const DEFAULT_GEMINI_MAX_CONCURRENT: usize = 16;
+pub const GEMINI_BUSY_RETRY_AFTER_SECS: u64 = 5;
+
+// Cap on in-flight Gemini calls across all users on this instance (GOALS_GEMINI_MAX_CONCURRENT),
+// so a burst can't exhaust GEMINI_CLIENT's connection pool or our quota. Complements the
+// per-user lock, which only serializes a single user's requests.
+static GEMINI_PERMITS: LazyLock<Semaphore> =
+    LazyLock::new(|| Semaphore::new(gemini_max_concurrent(|name| std::env::var(name).ok())));
+
+/// Size of the Gemini permit pool from GOALS_GEMINI_MAX_CONCURRENT. Unset or unparsable
+/// values use the default; zero is raised to one so queries can still run.
+pub fn gemini_max_concurrent(lookup: impl Fn(&str) -> Option<String>) -> usize {
+    lookup("GOALS_GEMINI_MAX_CONCURRENT")
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_GEMINI_MAX_CONCURRENT)
+        .max(1)
+}
+
+/// 503 with Retry-After, for clients connecting while every Gemini permit is taken.
+pub fn busy_response(locale: Locale) -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(
+            header::RETRY_AFTER,
+            GEMINI_BUSY_RETRY_AFTER_SECS.to_string(),
+        )],
+        messages::message(locale, MessageKey::Busy),
+    )
+        .into_response()
+}
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn is_retryable_error(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect()
+}
+
+/// POST to Gemini, retrying 429/5xx responses and timeouts/connection failures with
+/// exponential backoff (up to GOALS_GEMINI_MAX_ATTEMPTS tries). Other 4xx errors are
+/// returned immediately since retrying won't change the answer.
+async fn send_gemini_request(
+    api_key: &str,
+    request_body: &GeminiApiRequest,
+) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+    let max_attempts = env_or("GOALS_GEMINI_MAX_ATTEMPTS", DEFAULT_GEMINI_MAX_ATTEMPTS).max(1);
+    let mut attempt = 1;
+    loop {
+        let result = GEMINI_CLIENT
+            .post(GEMINI_URL)
+            .query(&[("key", api_key)])
+            .json(request_body)
+            .send()
+            .await;
+
+        let retry_reason = match result {
+            Ok(resp) if resp.status().is_success() => return Ok(resp),
+            Ok(resp) if is_retryable_status(resp.status()) && attempt < max_attempts => {
+                format!("status {}", resp.status())
+            }
+            Ok(resp) => {
+                let status = resp.status();
+                let err_text = resp
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                error!(
+                    status = %status,
+                    error = %err_text,
+                    attempt,
+                    "Gemini API request failed"
+                );
+                return Err(format!("Gemini API error (status {}): {}", status, err_text).into());
+            }
+            Err(e) if is_retryable_error(&e) && attempt < max_attempts => e.to_string(),
+            Err(e) => {
+                error!(error = %e, attempt, "Gemini API request failed");
+                return Err(e.into());
+            }
+        };
+
+        let delay = Duration::from_millis(GEMINI_RETRY_BASE_DELAY_MS * 2u64.pow(attempt - 1));
+        info!(
+            attempt,
+            max_attempts,
+            reason = %retry_reason,
+            delay_ms = delay.as_millis() as u64,
+            "Retrying Gemini API request"
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
 async fn call_gemini(
     conversation_history: &[Message],
 ) -> Result<Vec<LlmChunk>, Box<dyn std::error::Error + Send + Sync>> {
@@ -478,39 +853,12 @@ async fn call_gemini(
 
     let request_body = GeminiApiRequest {
         contents,
-        generation_config: Some(GenerationConfig {
-            temperature: Some(0.7),
-            top_p: Some(0.95),
-            top_k: Some(40),
-            candidate_count: Some(1),
-            max_output_tokens: Some(1024),
-        }),
+        generation_config: Some(GenerationConfig::from(GenerationSettings::from_env())),
         tools: Some(tools),
     };
 
     info!("Sending request to Gemini API");
-    let client = reqwest::Client::new();
-    let resp = client
-        .post("https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:generateContent")
-        .query(&[("key", api_key)])
-        .json(&request_body)
-        .send()
-        .await?;
-
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let err_text = resp
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        let msg = format!("Gemini API error (status {}): {}", status, err_text);
-        error!(
-            status = %status,
-            error = %err_text,
-            "Gemini API request failed"
-        );
-        return Err(msg.into());
-    }
+    let resp = send_gemini_request(&api_key, &request_body).await?;
 
     info!("Gemini API request successful, parsing response");
     let response_text = resp.text().await?;
@@ -568,6 +916,17 @@ async fn send_error(
     send_ws_message(sender, &error_message).await
 }
 
+async fn send_debug_trace(
+    sender: &mut futures_util::stream::SplitSink<WebSocket, WsMessage>,
+    tool_name: &str,
+    args: &serde_json::Value,
+    tool_result: serde_json::Value,
+    fallback_used: bool,
+) -> Result<(), axum::Error> {
+    let trace = WsQueryMessage::tool_trace(tool_name, args, tool_result, fallback_used);
+    send_ws_message(sender, &trace).await
+}
+
 async fn send_ws_message(
     sender: &mut futures_util::stream::SplitSink<WebSocket, WsMessage>,
     msg: &WsQueryMessage,
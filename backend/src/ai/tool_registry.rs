@@ -1,6 +1,6 @@
 // Removed the unused Axum imports, as this file no longer
 // needs to implement a web-handler function:
-use axum::http::StatusCode; // Import StatusCode
+use axum::{http::StatusCode, Json}; // Import StatusCode
 use neo4rs::Graph;
 use serde::Serialize; // Removed unused Deserialize
 use std::collections::HashMap;
@@ -8,6 +8,7 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 // Import the relevant base functions and types
+use crate::ai::tool_result::format_tool_result;
 use crate::tools::calendar::get_calendar_data;
 use crate::tools::day::{get_day_tasks, toggle_complete_task};
 use crate::tools::goal::{
@@ -387,14 +388,14 @@ pub async fn dispatch_tool(
             let goal_val = must_get_value(args, "goal")?;
             let goal_obj: Goal = serde_json::from_value(goal_val)
                 .map_err(|e| format!("Invalid 'goal' object: {e}"))?;
-            let result = update_goal_handler(graph.clone(), id, goal_obj).await;
+            let result = update_goal_handler(graph.clone(), user_id, id, goal_obj).await;
             wrap_result(result)
         }
 
         // 3) delete_goal
         "delete_goal" => {
             let id = must_get_i64(args, "id")?;
-            let result = delete_goal_handler(graph.clone(), id).await;
+            let result = delete_goal_handler(graph.clone(), user_id, id).await;
             wrap_result(result)
         }
 
@@ -403,7 +404,7 @@ pub async fn dispatch_tool(
             let rel_val = must_get_value(args, "relationship")?;
             let rel_obj: Relationship = serde_json::from_value(rel_val)
                 .map_err(|e| format!("Invalid 'relationship': {e}"))?;
-            let result = create_relationship_handler(graph.clone(), rel_obj).await;
+            let result = create_relationship_handler(graph.clone(), user_id, rel_obj).await;
             wrap_result(result)
         }
 
@@ -411,7 +412,7 @@ pub async fn dispatch_tool(
         "delete_relationship" => {
             let from_id = must_get_i64(args, "from_id")?;
             let to_id = must_get_i64(args, "to_id")?;
-            let result = delete_relationship_handler(graph.clone(), from_id, to_id).await;
+            let result = delete_relationship_handler(graph.clone(), user_id, from_id, to_id).await;
             wrap_result(result)
         }
 
@@ -419,7 +420,7 @@ pub async fn dispatch_tool(
         "get_network_data" => {
             //let user_id = must_get_i64(args, "user_id")?;
             let result = get_network_data(graph.clone(), user_id).await;
-            wrap_result(result)
+            wrap_json_result("get_network_data", result)
         }
 
         // 7) update_node_position
@@ -427,15 +428,15 @@ pub async fn dispatch_tool(
             let id = must_get_i64(args, "id")?;
             let x = must_get_f64(args, "x")?;
             let y = must_get_f64(args, "y")?;
-            let result = update_node_position(graph.clone(), id, x, y).await;
+            let result = update_node_position(graph.clone(), user_id, id, x, y).await;
             wrap_result(result)
         }
 
         // 8) query_hierarchy
         "query_hierarchy" => {
             let goal_id = must_get_i64(args, "goal_id")?;
-            let result = query_hierarchy_handler(graph.clone(), goal_id).await;
-            wrap_result(result)
+            let result = query_hierarchy_handler(graph.clone(), user_id, goal_id).await;
+            wrap_json_result("query_hierarchy", result)
         }
 
         // 9) get_calendar_data
@@ -445,14 +446,14 @@ pub async fn dispatch_tool(
             let end_timestamp = args.get("end_timestamp").and_then(|v| v.as_i64());
             let result =
                 get_calendar_data(graph.clone(), user_id, start_timestamp, end_timestamp).await;
-            wrap_result(result)
+            wrap_json_result("get_calendar_data", result)
         }
 
         // 10) get_list_data
         "get_list_data" => {
             //let user_id = must_get_i64(args, "user_id")?;
             let result = get_list_data(graph.clone(), user_id).await;
-            wrap_result(result)
+            wrap_json_result("get_list_data", result)
         }
 
         // 11) get_day_tasks
@@ -462,13 +463,13 @@ pub async fn dispatch_tool(
             let end_timestamp = args.get("end_timestamp").and_then(|v| v.as_i64());
             let result =
                 get_day_tasks(graph.clone(), user_id, start_timestamp, end_timestamp).await;
-            wrap_result(result)
+            wrap_json_result("get_day_tasks", result)
         }
 
         // 13) toggle_complete_task
         "toggle_complete_task" => {
             let id = must_get_i64(args, "id")?;
-            let result = toggle_complete_task(graph.clone(), id).await;
+            let result = toggle_complete_task(graph.clone(), user_id, id).await;
             wrap_result(result)
         }
 
@@ -504,6 +505,21 @@ fn wrap_result<T: std::fmt::Debug>(
     }
 }
 
+/// Like `wrap_result`, but for read tools that return JSON: the payload is serialized with
+/// its real field names and rendered by `format_tool_result` instead of Debug-formatted.
+fn wrap_json_result<T: Serialize>(
+    tool_name: &str,
+    base_result: Result<Json<T>, (StatusCode, String)>,
+) -> Result<serde_json::Value, String> {
+    let Json(payload) = base_result.map_err(|(_status_code, err_message)| err_message)?;
+    let data = serde_json::to_value(payload)
+        .map_err(|e| format!("Failed to serialize {} result: {}", tool_name, e))?;
+    Ok(serde_json::json!({
+        "result": "success",
+        "data": format_tool_result(tool_name, &data),
+    }))
+}
+
 // -----------------------------------------------------------
 // Helper: Extract an i64 from `args[key]` or return an error.
 // -----------------------------------------------------------
@@ -0,0 +1,47 @@
+use serde_json::Value;
+
+/// Text handed back to the model for a tool's JSON output. Day events are listed one per
+/// line, read with the keys `get_day_tasks` actually returns (`name`, `resolution_status`,
+/// ...); anything else is passed through as compact JSON so the real field names reach
+/// the model instead of a Rust debug dump.
+pub fn format_tool_result(tool_name: &str, data: &Value) -> String {
+    match (tool_name, data) {
+        ("get_day_tasks", Value::Array(events)) if events.is_empty() => {
+            "No events scheduled.".to_string()
+        }
+        ("get_day_tasks", Value::Array(events)) => events
+            .iter()
+            .map(format_day_event)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => data.to_string(),
+    }
+}
+
+fn format_day_event(event: &Value) -> String {
+    let name = event
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or("Untitled");
+    let time = event
+        .get("scheduled_timestamp")
+        .and_then(Value::as_i64)
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|dt| dt.format("%H:%M UTC").to_string())
+        .unwrap_or_else(|| "unscheduled".to_string());
+    let status = event
+        .get("resolution_status")
+        .and_then(Value::as_str)
+        .unwrap_or("pending");
+
+    let mut details = Vec::new();
+    if let Some(id) = event.get("id").and_then(Value::as_i64) {
+        details.push(format!("id {}", id));
+    }
+    if let Some(duration) = event.get("duration").and_then(Value::as_i64) {
+        details.push(format!("{} min", duration));
+    }
+    details.push(status.to_string());
+
+    format!("- {} {} ({})", time, name, details.join(", "))
+}
@@ -0,0 +1,66 @@
+use chrono::Utc;
+use neo4rs::{query, Graph};
+use tracing::{error, info};
+
+use crate::tools::achievements::reset_period_start;
+use crate::tools::goal::RESET_PERIODS;
+
+/// Close out recurring achievements whose reset period has rolled over: the final
+/// current_value is kept as an AchievementSnapshot, then the value goes back to 0 and a
+/// completed achievement is reopened. Returns the number of achievements reset.
+pub async fn reset_recurring_achievements(graph: &Graph) -> Result<i64, String> {
+    let now = Utc::now();
+    let mut total = 0;
+
+    for period in RESET_PERIODS {
+        let Some(period_start) = reset_period_start(period, now) else {
+            continue;
+        };
+
+        let reset_query = query(
+            "MATCH (g:Goal)
+             WHERE g.goal_type = 'achievement'
+             AND g.reset_period = $period
+             AND coalesce(g.is_deleted, false) <> true
+             AND coalesce(g.last_reset_at, 0) < $period_start
+             CREATE (g)-[:HAS_SNAPSHOT]->(:AchievementSnapshot {
+                 goal_id: id(g),
+                 user_id: g.user_id,
+                 period_start: coalesce(g.last_reset_at, g.start_timestamp, $period_start),
+                 period_end: $period_start,
+                 final_value: coalesce(g.current_value, 0.0),
+                 target_value: g.target_value,
+                 resolution_status: coalesce(g.resolution_status, 'pending'),
+                 created_at: timestamp()
+             })
+             SET g.current_value = 0.0,
+                 g.last_reset_at = $period_start,
+                 g.resolution_status = 'pending',
+                 g.resolved_at = null,
+                 g.updated_at = timestamp(),
+                 g.last_modified = timestamp()
+             RETURN count(g) as reset_count",
+        )
+        .param("period", period)
+        .param("period_start", period_start);
+
+        let mut result = graph
+            .execute(reset_query)
+            .await
+            .map_err(|e| format!("Failed to reset {} achievements: {}", period, e))?;
+
+        if let Some(row) = result.next().await.map_err(|e| e.to_string())? {
+            total += row.get::<i64>("reset_count").unwrap_or(0);
+        }
+    }
+
+    Ok(total)
+}
+
+pub async fn run_achievement_resetter(graph: Graph) {
+    match reset_recurring_achievements(&graph).await {
+        Ok(count) if count > 0 => info!(count, "Reset recurring achievements"),
+        Ok(_) => {}
+        Err(e) => error!(error = %e, "Recurring achievement reset failed"),
+    }
+}
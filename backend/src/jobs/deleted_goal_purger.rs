@@ -0,0 +1,48 @@
+use chrono::{Duration, Utc};
+use neo4rs::{query, Graph};
+use tracing::{error, info};
+
+/// How long a soft-deleted goal stays restorable before it is purged.
+pub const DELETED_GOAL_RETENTION_DAYS: i64 = 30;
+
+/// Permanently remove goals that were soft-deleted more than
+/// `DELETED_GOAL_RETENTION_DAYS` ago, along with their events, routine states and
+/// routine exceptions.
+pub async fn purge_deleted_goals(graph: &Graph) -> Result<i64, String> {
+    let cutoff = (Utc::now() - Duration::days(DELETED_GOAL_RETENTION_DAYS)).timestamp_millis();
+
+    let purge_query = "
+        MATCH (g:Goal)
+        WHERE g.goal_type <> 'event'
+        AND g.is_deleted = true
+        AND g.deleted_at IS NOT NULL
+        AND g.deleted_at < $cutoff
+        OPTIONAL MATCH (g)-[:HAS_EVENT]->(e:Goal)
+        OPTIONAL MATCH (g)-[:HAS_STATE]->(s:Goal)
+        OPTIONAL MATCH (g)-[:HAS_EXCEPTION]->(x:RoutineException)
+        WITH g, collect(DISTINCT e) + collect(DISTINCT s) + collect(DISTINCT x) as dependents
+        FOREACH (d IN dependents | DETACH DELETE d)
+        DETACH DELETE g
+        RETURN count(g) as purged_count
+    ";
+
+    let mut result = graph
+        .execute(query(purge_query).param("cutoff", cutoff))
+        .await
+        .map_err(|e| format!("Failed to purge deleted goals: {}", e))?;
+
+    let purged = match result.next().await.map_err(|e| e.to_string())? {
+        Some(row) => row.get::<i64>("purged_count").unwrap_or(0),
+        None => 0,
+    };
+
+    Ok(purged)
+}
+
+pub async fn run_deleted_goal_purge(graph: Graph) {
+    match purge_deleted_goals(&graph).await {
+        Ok(count) if count > 0 => info!(count, "Permanently removed deleted goals"),
+        Ok(_) => {}
+        Err(e) => error!(error = %e, "Deleted goal purge failed"),
+    }
+}
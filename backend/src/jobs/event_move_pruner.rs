@@ -0,0 +1,68 @@
+use chrono::{Duration, Utc};
+use neo4rs::{query, Graph};
+use tracing::{error, info};
+
+/// How long EventMove records are kept when EVENT_MOVE_RETENTION_DAYS is unset.
+pub const DEFAULT_EVENT_MOVE_RETENTION_DAYS: i64 = 365;
+const MAX_EVENT_MOVE_RETENTION_DAYS: i64 = 3650;
+// Deleted a chunk at a time so a large backlog doesn't build one huge transaction
+const PRUNE_BATCH_SIZE: i64 = 5000;
+
+/// Parse an EVENT_MOVE_RETENTION_DAYS value; unset or blank means the default.
+pub fn parse_event_move_retention_days(raw: Option<&str>) -> Result<i64, String> {
+    match raw.map(str::trim).filter(|s| !s.is_empty()) {
+        None => Ok(DEFAULT_EVENT_MOVE_RETENTION_DAYS),
+        Some(value) => value
+            .parse::<i64>()
+            .ok()
+            .filter(|days| (1..=MAX_EVENT_MOVE_RETENTION_DAYS).contains(days))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid EVENT_MOVE_RETENTION_DAYS '{}'. Expected days from 1 to {}.",
+                    value, MAX_EVENT_MOVE_RETENTION_DAYS
+                )
+            }),
+    }
+}
+
+/// Delete EventMove records older than `retention_days`. Returns the number removed.
+pub async fn prune_event_moves(graph: &Graph, retention_days: i64) -> Result<i64, String> {
+    let cutoff = (Utc::now() - Duration::days(retention_days)).timestamp_millis();
+
+    let prune_query = "
+        MATCH (em:EventMove)
+        WHERE em.move_timestamp < $cutoff
+        WITH em LIMIT $batch_size
+        DETACH DELETE em
+        RETURN count(em) as pruned_count
+    ";
+
+    let mut total = 0;
+    loop {
+        let mut result = graph
+            .execute(
+                query(prune_query)
+                    .param("cutoff", cutoff)
+                    .param("batch_size", PRUNE_BATCH_SIZE),
+            )
+            .await
+            .map_err(|e| format!("Failed to prune event moves: {}", e))?;
+
+        let pruned = match result.next().await.map_err(|e| e.to_string())? {
+            Some(row) => row.get::<i64>("pruned_count").unwrap_or(0),
+            None => 0,
+        };
+        total += pruned;
+        if pruned < PRUNE_BATCH_SIZE {
+            return Ok(total);
+        }
+    }
+}
+
+pub async fn run_event_move_pruner(graph: Graph, retention_days: i64) {
+    match prune_event_moves(&graph, retention_days).await {
+        Ok(count) if count > 0 => info!(count, retention_days, "Pruned old event moves"),
+        Ok(_) => {}
+        Err(e) => error!(error = %e, "Event move pruning failed"),
+    }
+}
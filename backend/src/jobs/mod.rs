@@ -1,3 +1,8 @@
+pub mod achievement_resetter;
+pub mod deleted_goal_purger;
+pub mod event_move_pruner;
 pub mod gcal_sync_scheduler;
 pub mod notification_scheduler;
-pub mod routine_generator;
\ No newline at end of file
+pub mod overdue_event_flagger;
+pub mod routine_generator;
+pub mod task_recurrence;
@@ -0,0 +1,51 @@
+use chrono::Utc;
+use neo4rs::{query, Graph};
+use tracing::{error, info};
+
+use crate::tools::event_settings::DEFAULT_EVENT_DURATION_MINUTES;
+
+/// Mark deadline events whose end has passed while still pending as "overdue", for users
+/// who opted in via `flag_overdue_deadlines`. Returns the number of events flagged.
+pub async fn flag_overdue_deadline_events(graph: &Graph) -> Result<i64, String> {
+    let flag_query = "
+        MATCH (u:User)
+        WHERE u.flag_overdue_deadlines = true
+        MATCH (e:Goal)
+        WHERE e.user_id = id(u)
+        AND e.goal_type = 'event'
+        AND e.is_deadline = true
+        AND coalesce(e.is_deleted, false) <> true
+        AND coalesce(e.resolution_status, 'pending') = 'pending'
+        AND e.scheduled_timestamp IS NOT NULL
+        AND e.scheduled_timestamp
+            + coalesce(e.duration, u.default_event_duration, $default_duration) * 60 * 1000 < $now
+        SET e.resolution_status = 'overdue',
+            e.version = coalesce(e.version, 0) + 1,
+            e.last_modified = timestamp()
+        RETURN count(e) as flagged_count
+    ";
+
+    let mut result = graph
+        .execute(
+            query(flag_query)
+                .param("now", Utc::now().timestamp_millis())
+                .param("default_duration", DEFAULT_EVENT_DURATION_MINUTES as i64),
+        )
+        .await
+        .map_err(|e| format!("Failed to flag overdue events: {}", e))?;
+
+    let flagged = match result.next().await.map_err(|e| e.to_string())? {
+        Some(row) => row.get::<i64>("flagged_count").unwrap_or(0),
+        None => 0,
+    };
+
+    Ok(flagged)
+}
+
+pub async fn run_overdue_event_flagger(graph: Graph) {
+    match flag_overdue_deadline_events(&graph).await {
+        Ok(count) if count > 0 => info!(count, "Flagged elapsed deadline events as overdue"),
+        Ok(_) => {}
+        Err(e) => error!(error = %e, "Overdue event flagging failed"),
+    }
+}
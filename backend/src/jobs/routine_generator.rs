@@ -1,8 +1,46 @@
+use crate::server::metrics;
+use crate::tools::event_settings::{self, DEFAULT_EVENT_DURATION_MINUTES};
 use crate::tools::goal::Goal;
 use crate::tools::routine_exceptions;
-use chrono::{Datelike, Duration, TimeZone, Utc};
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Utc};
+use futures_util::{stream, StreamExt};
 use neo4rs::{query, Graph};
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::time::Instant;
+use tracing::{error, info};
+
+// Previews are computed live while editing, so keep the window bounded
+const MAX_PREVIEW_DAYS: i64 = 366;
+
+// How many users' routines are generated at once; keeps Neo4j load bounded as users grow
+const CONCURRENCY_ENV: &str = "ROUTINE_GENERATOR_CONCURRENCY";
+const DEFAULT_GENERATOR_CONCURRENCY: usize = 4;
+
+// The generator keeps ~180 days of events ahead; a routine whose future events run out
+// sooner than this has most likely stopped generating
+pub const ROUTINE_GAP_THRESHOLD_DAYS: i64 = 7;
+
+// (routine, routine id, timestamp of its last live event)
+type PendingRoutine = (Goal, i64, Option<i64>);
+
+/// Holidays to skip for this routine: the owner's holiday list if the routine opted in.
+async fn routine_holidays(graph: &Graph, routine: &Goal) -> HashSet<NaiveDate> {
+    match (routine.skip_holidays, routine.user_id) {
+        (Some(true), Some(user_id)) => event_settings::holiday_dates(graph, user_id).await,
+        _ => HashSet::new(),
+    }
+}
+
+// Routine timestamps are on a UTC basis (see set_time_of_day), so holidays are matched on the UTC date
+fn falls_on_holiday(timestamp: i64, holidays: &HashSet<NaiveDate>) -> bool {
+    !holidays.is_empty()
+        && Utc
+            .timestamp_millis_opt(timestamp)
+            .single()
+            .is_some_and(|dt| holidays.contains(&dt.date_naive()))
+}
 
 pub async fn generate_future_routine_events(graph: &Graph) -> Result<(), String> {
     let now = Utc::now().timestamp_millis();
@@ -15,8 +53,10 @@ pub async fn generate_future_routine_events(graph: &Graph) -> Result<(), String>
         WHERE r.goal_type = 'routine'
         AND r.end_timestamp < $now
         AND (r.resolution_status IS NULL OR r.resolution_status = 'pending')
+        AND coalesce(r.is_deleted, false) <> true
         SET r.resolution_status = 'completed',
-            r.resolved_at = r.end_timestamp
+            r.resolved_at = r.end_timestamp,
+            r.last_modified = timestamp()
         RETURN count(r) as completed_count
     ";
 
@@ -37,20 +77,25 @@ pub async fn generate_future_routine_events(graph: &Graph) -> Result<(), String>
         MATCH (r:Goal)
         WHERE r.goal_type = 'routine'
         AND (r.end_timestamp IS NULL OR r.end_timestamp > $now)
+        AND coalesce(r.is_deleted, false) <> true
         WITH r
         OPTIONAL MATCH (r)-[:HAS_EVENT]->(e:Goal)
         WHERE (e.is_deleted IS NULL OR e.is_deleted = false)
+        AND e.routine_instance_id IS NOT NULL // one-off copies aren't part of the series
         WITH r, max(e.scheduled_timestamp) as last_event_time
         WHERE last_event_time < $horizon OR last_event_time IS NULL
         RETURN r, id(r) as routine_id, last_event_time
     ";
 
-    let mut result = graph
-        .execute(query(query_str).param("now", now).param("horizon", horizon))
-        .await
-        .map_err(|e| format!("Failed to query routines: {}", e))?;
+    let mut result = metrics::time_routine_generator_query(
+        "find_routines",
+        graph.execute(query(query_str).param("now", now).param("horizon", horizon)),
+    )
+    .await
+    .map_err(|e| format!("Failed to query routines: {}", e))?;
 
-    let mut routine_count = 0;
+    // Group by owner so each user's routines are generated together
+    let mut by_user: HashMap<Option<i64>, Vec<PendingRoutine>> = HashMap::new();
     while let Some(row) = result
         .next()
         .await
@@ -63,75 +108,170 @@ pub async fn generate_future_routine_events(graph: &Graph) -> Result<(), String>
             .get("routine_id")
             .map_err(|e| format!("Failed to get routine_id: {}", e))?;
         let last_event_time: Option<i64> = row.get("last_event_time").ok();
+        by_user
+            .entry(routine.user_id)
+            .or_default()
+            .push((routine, routine_id, last_event_time));
+    }
 
-        // Determine the correct starting point for generation:
-        // - If we have a last event, start from the NEXT occurrence (not +1 day)
-        // - Otherwise, advance from the routine start to the first occurrence >= now
-        let start_from = if let Some(last) = last_event_time {
-            match calculate_next_occurrence(last, routine.frequency.as_ref().ok_or("Routine missing frequency")?) {
-                Ok(next) => next,
-                Err(e) => {
-                    eprintln!("[routine_generator] Failed to calculate next occurrence from last event: {}. Falling back to +1 day.", e);
-                    last + 86_400_000
-                }
-            }
-        } else {
-            // IMPORTANT: `start_timestamp` is stored as a UTC day boundary (midnight UTC) for many routines,
-            // while `routine_time` encodes the time-of-day (also on a UTC basis via modulo arithmetic).
-            //
-            // If we only compare `t < now` (where `t` is midnight), we can incorrectly skip "today"
-            // even when the routine occurrence later in the day is still in the future.
-            //
-            // We instead compare the *scheduled* occurrence (t + routine_time offset) against `now`.
-            let mut t = routine.start_timestamp.unwrap_or(now);
-            if let Some(freq) = &routine.frequency {
-                let guard_limit = 10_000; // safety guard
-                let mut guard = 0;
-                loop {
-                    if guard >= guard_limit {
-                        break;
-                    }
-
-                    // Apply routine_time for the purpose of deciding whether this occurrence is already in the past.
-                    let scheduled_at_t = if let Some(routine_time) = routine.routine_time {
-                        set_time_of_day(t, routine_time)
-                    } else {
-                        t
-                    };
+    let started = Instant::now();
+    let user_count = by_user.len();
+    let concurrency = generator_concurrency(env::var(CONCURRENCY_ENV).ok().as_deref());
+
+    let outcomes = stream::iter(by_user)
+        .map(|(user_id, routines)| {
+            generate_for_user_routines(graph, user_id, routines, now, horizon)
+        })
+        .buffer_unordered(concurrency)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut totals = UserGenerationOutcome::default();
+    for outcome in outcomes {
+        totals.routines += outcome.routines;
+        totals.events += outcome.events;
+        totals.failed_routines += outcome.failed_routines;
+    }
 
-                    if scheduled_at_t >= now {
-                        break;
-                    }
+    info!(
+        events = totals.events,
+        routines = totals.routines,
+        failed_routines = totals.failed_routines,
+        users = user_count,
+        concurrency,
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Generated future routine events"
+    );
+    Ok(())
+}
 
-                    t = match calculate_next_occurrence(t, freq) {
-                        Ok(v) => v,
-                        Err(e) => {
-                            eprintln!(
-                                "[routine_generator] Failed to advance to now for routine id {:?}: {}",
-                                routine.id, e
-                            );
-                            break;
-                        }
-                    };
-                    guard += 1;
-                }
+/// Parse the generator's per-user concurrency limit, defaulting when unset or invalid.
+pub fn generator_concurrency(raw: Option<&str>) -> usize {
+    match raw.map(str::trim).filter(|v| !v.is_empty()) {
+        None => DEFAULT_GENERATOR_CONCURRENCY,
+        Some(value) => match value.parse::<usize>() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                eprintln!(
+                    "[routine_generator] Ignoring invalid {}='{}'; using {}",
+                    CONCURRENCY_ENV, value, DEFAULT_GENERATOR_CONCURRENCY
+                );
+                DEFAULT_GENERATOR_CONCURRENCY
             }
-            t
-        };
+        },
+    }
+}
 
+#[derive(Debug, Default)]
+struct UserGenerationOutcome {
+    routines: usize,
+    events: usize,
+    failed_routines: usize,
+}
+
+/// Generate events for one user's routines in sequence. A routine that fails is logged and
+/// skipped, so one bad routine doesn't hold back the user's others.
+async fn generate_for_user_routines(
+    graph: &Graph,
+    user_id: Option<i64>,
+    routines: Vec<PendingRoutine>,
+    now: i64,
+    horizon: i64,
+) -> UserGenerationOutcome {
+    let mut outcome = UserGenerationOutcome::default();
+
+    for (routine, routine_id, last_event_time) in routines {
         // Respect the routine's explicit end date if it exists and is sooner than the 180-day horizon
         let effective_until = match routine.end_timestamp {
             Some(end_ts) if end_ts < horizon => end_ts,
             _ => horizon,
         };
 
-        generate_events_for_routine(graph, &routine, routine_id, start_from, effective_until)
-            .await?;
-        routine_count += 1;
+        let generated = async {
+            let start_from = generation_start(&routine, last_event_time, now)?;
+            generate_events_for_routine(graph, &routine, routine_id, start_from, effective_until)
+                .await
+        };
+        match generated.await {
+            Ok(events) => {
+                outcome.routines += 1;
+                outcome.events += events;
+            }
+            Err(e) => {
+                outcome.failed_routines += 1;
+                error!(
+                    user_id = ?user_id,
+                    routine_id,
+                    error = %e,
+                    "Failed to generate routine events"
+                );
+            }
+        }
     }
 
-    println!("Generated future events for {} routines", routine_count);
-    Ok(())
+    outcome
+}
+
+// Determine the correct starting point for generation:
+// - If we have a last event, start from the NEXT occurrence (not +1 day)
+// - Otherwise, advance from the routine start to the first occurrence >= now
+fn generation_start(routine: &Goal, last_event_time: Option<i64>, now: i64) -> Result<i64, String> {
+    if let Some(last) = last_event_time {
+        let frequency = routine
+            .frequency
+            .as_ref()
+            .ok_or("Routine missing frequency")?;
+        return match calculate_next_occurrence(last, frequency) {
+            Ok(next) => Ok(next),
+            Err(e) => {
+                eprintln!("[routine_generator] Failed to calculate next occurrence from last event: {}. Falling back to +1 day.", e);
+                Ok(last + 86_400_000)
+            }
+        };
+    }
+
+    // IMPORTANT: `start_timestamp` is stored as a UTC day boundary (midnight UTC) for many routines,
+    // while `routine_time` encodes the time-of-day (also on a UTC basis via modulo arithmetic).
+    //
+    // If we only compare `t < now` (where `t` is midnight), we can incorrectly skip "today"
+    // even when the routine occurrence later in the day is still in the future.
+    //
+    // We instead compare the *scheduled* occurrence (t + routine_time offset) against `now`.
+    let mut t = routine.start_timestamp.unwrap_or(now);
+    if let Some(freq) = &routine.frequency {
+        let guard_limit = 10_000; // safety guard
+        let mut guard = 0;
+        loop {
+            if guard >= guard_limit {
+                break;
+            }
+
+            // Apply routine_time for the purpose of deciding whether this occurrence is already in the past.
+            // With several times a day, the day counts as long as its last time is still ahead.
+            let scheduled_at_t = times_of_day(routine)
+                .into_iter()
+                .map(|time| time.map_or(t, |time| set_time_of_day(t, time)))
+                .max()
+                .unwrap_or(t);
+
+            if scheduled_at_t >= now {
+                break;
+            }
+
+            t = match calculate_next_occurrence(t, freq) {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!(
+                        "[routine_generator] Failed to advance to now for routine id {:?}: {}",
+                        routine.id, e
+                    );
+                    break;
+                }
+            };
+            guard += 1;
+        }
+    }
+    Ok(t)
 }
 
 // Helper function to validate if a given timestamp matches the routine's frequency pattern
@@ -184,13 +324,333 @@ fn is_valid_day_for_routine(timestamp: i64, frequency: &str) -> Result<bool, Str
     }
 }
 
+/// A routine occurrence to materialize, with the routine (or HAS_STATE override) whose
+/// properties apply at that time.
+pub struct PlannedOccurrence<'a> {
+    pub timestamp: i64,
+    pub routine: &'a Goal,
+}
+
+/// The occurrence algorithm shared by generation, recompute and previews. Walks the
+/// frequency from `start_from` to `until`, honouring state overrides and the routine's own
+/// end_timestamp, without touching the database. Skip exceptions and holidays are left
+/// to the caller.
+pub fn plan_routine_occurrences<'a>(
+    routine: &'a Goal,
+    states: &'a [(i64, Goal)],
+    start_from: i64,
+    until: i64,
+) -> Result<Vec<PlannedOccurrence<'a>>, String> {
+    let mut plan = Vec::new();
+    let mut current_time = start_from;
+
+    while current_time <= until {
+        // Determine effective routine state for current_time
+        // Filter for states covering this time, then pick the one with highest ID (latest created)
+        let effective_routine = states
+            .iter()
+            .filter(|(_, s)| {
+                let start = s.start_timestamp.unwrap_or(0);
+                let end = s.end_timestamp.unwrap_or(i64::MAX);
+                current_time >= start && current_time <= end
+            })
+            .max_by_key(|(id, _)| id)
+            .map(|(_, s)| s)
+            .unwrap_or(routine);
+
+        let frequency = effective_routine
+            .frequency
+            .as_ref()
+            .ok_or_else(|| format!("Routine missing frequency (ID: {:?})", effective_routine.id))?;
+
+        // Check if this day is valid for the routine's frequency pattern
+        if !is_valid_day_for_routine(current_time, frequency)? {
+            current_time = calculate_next_occurrence(current_time, frequency)?;
+            continue;
+        }
+
+        // Apply each time of day to the current timestamp
+        for time in times_of_day(effective_routine) {
+            let scheduled_timestamp = match time {
+                Some(routine_time) => set_time_of_day(current_time, routine_time),
+                None => current_time,
+            };
+
+            // If the calculated timestamp would exceed the GLOBAL routine's end date, stop generation.
+            // We always respect the parent routine's end date as the master stop signal.
+            if let Some(end_ts) = routine.end_timestamp {
+                if scheduled_timestamp > end_ts {
+                    return Ok(plan);
+                }
+            }
+
+            plan.push(PlannedOccurrence {
+                timestamp: scheduled_timestamp,
+                routine: effective_routine,
+            });
+        }
+
+        // Calculate next occurrence based on frequency
+        let calculated_next = calculate_next_occurrence(current_time, frequency)?;
+
+        // Peek ahead: if a state override starts BEFORE calculated_next, we must land on it
+        // to evaluate it properly.
+        let next_state_start = states
+            .iter()
+            .map(|(_, s)| s.start_timestamp.unwrap_or(0))
+            .filter(|&start| start > current_time && start < calculated_next)
+            .min();
+
+        current_time = next_state_start.unwrap_or(calculated_next);
+    }
+
+    Ok(plan)
+}
+
+/// The times of day a routine fires on each qualifying day, earliest first: `routine_times`
+/// when set, otherwise the single `routine_time` (None = keep the walk's own time).
+fn times_of_day(routine: &Goal) -> Vec<Option<i64>> {
+    match routine.routine_times.as_deref() {
+        Some(times) if !times.is_empty() => {
+            let mut times = times.to_vec();
+            times.sort_by_key(|&time| set_time_of_day(0, time));
+            times.dedup_by_key(|time| set_time_of_day(0, *time));
+            times.into_iter().map(Some).collect()
+        }
+        _ => vec![routine.routine_time],
+    }
+}
+
+/// Occurrences of a not-yet-generated routine that fall on the UTC dates of `exception_dates`
+/// (any timestamp within the day works), so they can be seeded as skip exceptions before the
+/// first generation pass. Dates outside the routine's active window are ignored.
+pub fn creation_exception_occurrences(
+    routine: &Goal,
+    exception_dates: &[i64],
+) -> Result<Vec<i64>, String> {
+    let Some(start) = routine.start_timestamp else {
+        return Ok(Vec::new());
+    };
+    let end = routine.end_timestamp.unwrap_or(i64::MAX);
+    let utc_date = |ts: i64| {
+        Utc.timestamp_millis_opt(ts)
+            .single()
+            .map(|dt| dt.date_naive())
+    };
+    let Some(start_date) = utc_date(start) else {
+        return Ok(Vec::new());
+    };
+
+    let dates: HashSet<NaiveDate> = exception_dates
+        .iter()
+        .filter(|ts| **ts <= end)
+        .filter_map(|ts| utc_date(*ts))
+        .filter(|date| *date >= start_date)
+        .collect();
+    let Some(last_date) = dates.iter().max() else {
+        return Ok(Vec::new());
+    };
+
+    // Plan from the routine start so the series lines up with what generation will produce
+    let until = (*last_date + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+        .timestamp_millis()
+        - 1;
+    let plan = plan_routine_occurrences(routine, &[], start, until.min(end))?;
+    Ok(plan
+        .into_iter()
+        .map(|occurrence| occurrence.timestamp)
+        .filter(|ts| utc_date(*ts).is_some_and(|date| dates.contains(&date)))
+        .collect())
+}
+
+/// Record skip exceptions for a freshly created routine's `exception_dates`, returning how
+/// many occurrences will be skipped.
+pub async fn seed_creation_exceptions(
+    graph: &Graph,
+    user_id: i64,
+    routine: &Goal,
+    exception_dates: &[i64],
+) -> Result<usize, String> {
+    let routine_id = routine.id.ok_or("Routine has no id")?;
+    let occurrences = creation_exception_occurrences(routine, exception_dates)?;
+    for timestamp in &occurrences {
+        routine_exceptions::create_skip_exception(graph, user_id, routine_id, *timestamp)
+            .await
+            .map_err(|e| format!("Failed to create routine exception: {}", e))?;
+    }
+    Ok(occurrences.len())
+}
+
+/// Timestamps a routine with this frequency and time of day would get between `start` and
+/// `end` (inclusive), ignoring skip exceptions and holidays.
+pub fn routine_occurrences(
+    frequency: &str,
+    start: i64,
+    end: i64,
+    routine_time: Option<i64>,
+) -> Result<Vec<i64>, String> {
+    let routine = Goal {
+        frequency: Some(frequency.to_string()),
+        routine_time,
+        ..Default::default()
+    };
+    occurrences_for_routine(&routine, start, end)
+}
+
+/// Like `routine_occurrences`, for a routine as stored (so `routine_times` applies too).
+pub fn occurrences_for_routine(routine: &Goal, start: i64, end: i64) -> Result<Vec<i64>, String> {
+    let routine = Goal {
+        end_timestamp: Some(
+            routine
+                .end_timestamp
+                .map_or(end, |own_end| own_end.min(end)),
+        ),
+        ..routine.clone()
+    };
+    Ok(plan_routine_occurrences(&routine, &[], start, end)?
+        .into_iter()
+        .map(|occurrence| occurrence.timestamp)
+        .collect())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoutinePreviewRequest {
+    pub frequency: String,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub routine_time: Option<i64>,
+    #[serde(default)]
+    pub routine_times: Option<Vec<i64>>, // several times a day; see Goal::routine_times
+    pub skip_holidays: Option<bool>,
+}
+
+/// Candidate event timestamps for an unsaved routine, using the same occurrence planning as
+/// the generator. Nothing is written; the graph is only read for the user's holidays.
+pub async fn preview_routine_events(
+    graph: &Graph,
+    user_id: i64,
+    request: &RoutinePreviewRequest,
+) -> Result<Vec<i64>, String> {
+    if request.end_timestamp < request.start_timestamp {
+        return Err("end_timestamp must not be before start_timestamp".to_string());
+    }
+    if request.end_timestamp - request.start_timestamp
+        > Duration::days(MAX_PREVIEW_DAYS).num_milliseconds()
+    {
+        return Err(format!(
+            "Preview window cannot exceed {} days",
+            MAX_PREVIEW_DAYS
+        ));
+    }
+
+    let routine = Goal {
+        frequency: Some(request.frequency.clone()),
+        routine_time: request.routine_time,
+        routine_times: request.routine_times.clone(),
+        ..Default::default()
+    };
+    let mut occurrences =
+        occurrences_for_routine(&routine, request.start_timestamp, request.end_timestamp)?;
+
+    if request.skip_holidays == Some(true) {
+        let holidays = event_settings::holiday_dates(graph, user_id).await;
+        occurrences.retain(|ts| !falls_on_holiday(*ts, &holidays));
+    }
+
+    Ok(occurrences)
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoutineGap {
+    pub routine_id: i64,
+    pub name: String,
+    pub last_event_timestamp: Option<i64>, // None = no future events at all
+    pub last_event_date: Option<String>,   // "YYYY-MM-DD" (UTC)
+}
+
+/// Whether an active routine's future events end suspiciously early. Routines that haven't
+/// started yet or end within the threshold are expected to have few or no future events.
+pub fn is_generation_stalled(
+    routine_start: Option<i64>,
+    routine_end: Option<i64>,
+    last_future_event: Option<i64>,
+    now: i64,
+) -> bool {
+    let threshold = now + Duration::days(ROUTINE_GAP_THRESHOLD_DAYS).num_milliseconds();
+    if routine_start.is_some_and(|start| start > threshold)
+        || routine_end.is_some_and(|end| end <= threshold)
+    {
+        return false;
+    }
+    last_future_event.is_none_or(|last| last < threshold)
+}
+
+/// Active routines of the user whose upcoming events stop within
+/// `ROUTINE_GAP_THRESHOLD_DAYS`, which usually means generation stalled for them.
+pub async fn detect_routine_gaps(graph: &Graph, user_id: i64) -> Result<Vec<RoutineGap>, String> {
+    let now = Utc::now().timestamp_millis();
+    let query_str = "
+        MATCH (r:Goal)
+        WHERE r.goal_type = 'routine'
+        AND r.user_id = $user_id
+        AND (r.end_timestamp IS NULL OR r.end_timestamp > $now)
+        AND coalesce(r.is_deleted, false) <> true
+        AND COALESCE(r.resolution_status, 'pending') = 'pending'
+        OPTIONAL MATCH (r)-[:HAS_EVENT]->(e:Goal)
+        WHERE e.scheduled_timestamp >= $now
+        AND (e.is_deleted IS NULL OR e.is_deleted = false)
+        WITH r, max(e.scheduled_timestamp) as last_event_time
+        RETURN id(r) as routine_id, r.name as name,
+               r.start_timestamp as start_timestamp, r.end_timestamp as end_timestamp,
+               last_event_time
+        ORDER BY last_event_time
+    ";
+
+    let mut result = graph
+        .execute(query(query_str).param("user_id", user_id).param("now", now))
+        .await
+        .map_err(|e| format!("Failed to query routines: {}", e))?;
+
+    let mut gaps = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| format!("Error fetching row: {}", e))?
+    {
+        let last_event_time: Option<i64> = row.get("last_event_time").ok();
+        if !is_generation_stalled(
+            row.get("start_timestamp").ok(),
+            row.get("end_timestamp").ok(),
+            last_event_time,
+            now,
+        ) {
+            continue;
+        }
+        gaps.push(RoutineGap {
+            routine_id: row
+                .get("routine_id")
+                .map_err(|e| format!("Failed to get routine_id: {}", e))?,
+            name: row.get("name").unwrap_or_default(),
+            last_event_timestamp: last_event_time,
+            last_event_date: last_event_time
+                .and_then(|ts| Utc.timestamp_millis_opt(ts).single())
+                .map(|dt| dt.format("%Y-%m-%d").to_string()),
+        });
+    }
+
+    Ok(gaps)
+}
+
 async fn generate_events_for_routine(
     graph: &Graph,
     routine: &Goal,
     routine_id: i64,
     start_from: i64,
     until: i64,
-) -> Result<(), String> {
+) -> Result<usize, String> {
     let instance_id = format!("{}-{}", routine_id, Utc::now().timestamp_millis());
 
     // Load skip exceptions for this routine in the generation window (inclusive)
@@ -203,6 +663,12 @@ async fn generate_events_for_routine(
     .await
     .map_err(|e| format!("Failed to fetch routine exceptions: {}", e))?;
     let skip_set: HashSet<i64> = skip_ts.into_iter().collect();
+    let holidays = routine_holidays(graph, routine).await;
+
+    let default_duration = match routine.user_id {
+        Some(user_id) => event_settings::default_event_duration(graph, user_id).await,
+        None => DEFAULT_EVENT_DURATION_MINUTES,
+    };
 
     // Fetch routine states (overrides)
     // We fetch ID as well to implement "latest edit wins" policy for overlaps
@@ -225,55 +691,19 @@ async fn generate_events_for_routine(
         }
     }
 
-    // Calculate event timestamps
-    let mut current_time = start_from;
+    let plan = plan_routine_occurrences(routine, &states, start_from, until)?;
     let mut event_count = 0;
 
-    while current_time <= until {
-        // Determine effective routine state for current_time
-        // Filter for states covering this time, then pick the one with highest ID (latest created)
-        let effective_routine = states.iter()
-            .filter(|(_, s)| {
-                let start = s.start_timestamp.unwrap_or(0);
-                let end = s.end_timestamp.unwrap_or(i64::MAX);
-                current_time >= start && current_time <= end
-            })
-            .max_by_key(|(id, _)| id)
-            .map(|(_, s)| s)
-            .unwrap_or(routine);
-
-        let frequency = effective_routine
-            .frequency
-            .as_ref()
-            .ok_or_else(|| format!("Routine missing frequency (ID: {:?})", effective_routine.id))?;
-
-        // Check if this day is valid for the routine's frequency pattern
-        if !is_valid_day_for_routine(current_time, frequency)? {
-            current_time = calculate_next_occurrence(current_time, frequency)?;
-            continue;
-        }
-
-        // Apply routine_time to the current timestamp
-        let scheduled_timestamp = if let Some(routine_time) = effective_routine.routine_time {
-            set_time_of_day(current_time, routine_time)
-        } else {
-            current_time
-        };
-
+    for PlannedOccurrence {
+        timestamp: scheduled_timestamp,
+        routine: effective_routine,
+    } in plan
+    {
         // If there is a skip exception at this exact timestamp, do not generate.
-        if skip_set.contains(&scheduled_timestamp) {
-            current_time = calculate_next_occurrence(current_time, frequency)?;
+        if skip_set.contains(&scheduled_timestamp) || falls_on_holiday(scheduled_timestamp, &holidays) {
             continue;
         }
 
-        // If the calculated timestamp would exceed the GLOBAL routine's end date, stop generation.
-        // We always respect the parent routine's end date as the master stop signal.
-        if let Some(end_ts) = routine.end_timestamp {
-            if scheduled_timestamp > end_ts {
-                break;
-            }
-        }
-
         // Check/Create/Revive event
         let mut existing_result = graph
             .execute(
@@ -307,11 +737,12 @@ async fn generate_events_for_routine(
                              e.name = $name,
                              e.duration = $duration,
                              e.priority = $priority,
-                             e.description = $desc"
+                             e.description = $desc,
+                             e.last_modified = timestamp()"
                     )
                     .param("event_id", event_id)
                     .param("name", effective_routine.name.clone())
-                    .param("duration", effective_routine.duration.unwrap_or(60))
+                    .param("duration", effective_routine.duration.unwrap_or(default_duration))
                     .param("priority", effective_routine.priority.clone().unwrap_or_default())
                     .param("desc", effective_routine.description.clone().unwrap_or_default())
                 )
@@ -336,7 +767,9 @@ async fn generate_events_for_routine(
                      description: $desc,
                      resolution_status: 'pending',
                      resolved_at: null,
-                     is_deleted: false
+                     is_deleted: false,
+                     version: 0,
+                     last_modified: timestamp()
                  })
                  CREATE (r)-[:HAS_EVENT]->(e)",
             )
@@ -344,42 +777,26 @@ async fn generate_events_for_routine(
             .param("timestamp", scheduled_timestamp)
             .param("instance_id", instance_id.clone())
             .param("name", effective_routine.name.clone())
-            .param("duration", effective_routine.duration.unwrap_or(60))
+            .param("duration", effective_routine.duration.unwrap_or(default_duration))
             .param("priority", effective_routine.priority.clone().unwrap_or_default())
             .param("desc", effective_routine.description.clone().unwrap_or_default());
 
-            graph
-                .run(create_query)
+            metrics::time_routine_generator_query("create_event", graph.run(create_query))
                 .await
                 .map_err(|e| format!("Failed to create routine event: {}", e))?;
 
             event_count += 1;
         }
-
-        // Calculate next occurrence based on frequency
-        let calculated_next = calculate_next_occurrence(current_time, frequency)?;
-        
-        // Peek ahead: if a state override starts BEFORE calculated_next, we must land on it
-        // to evaluate it properly.
-        let next_state_start = states.iter()
-            .map(|(_, s)| s.start_timestamp.unwrap_or(0))
-            .filter(|&start| start > current_time && start < calculated_next)
-            .min();
-            
-        if let Some(start) = next_state_start {
-            current_time = start;
-        } else {
-            current_time = calculated_next;
-        }
     }
 
     if event_count > 0 {
+        metrics::add_routine_events_generated(event_count as u64);
         println!(
             "Created {} new events for routine '{}'",
             event_count, routine.name
         );
     }
-    Ok(())
+    Ok(event_count)
 }
 
 fn set_time_of_day(base_timestamp: i64, time_of_day: i64) -> i64 {
@@ -546,7 +963,8 @@ pub async fn recompute_future_for_routine(
         .await
         .map_err(|e| format!("Failed to clear routine exceptions: {}", e))?;
 
-    // 3) Soft-delete all events for this routine at-or-after cutoff (including completed)
+    // 3) Soft-delete all series events for this routine at-or-after cutoff (including completed);
+    //    one-off copies without a routine_instance_id are left alone
     let mut del_result = graph
         .execute(
             query(
@@ -557,8 +975,10 @@ pub async fn recompute_future_for_routine(
                  OPTIONAL MATCH (r)-[:HAS_EVENT]->(e:Goal)
                  WHERE e.goal_type = 'event'
                    AND e.scheduled_timestamp >= $cutoff
+                   AND e.routine_instance_id IS NOT NULL
                  WITH e
-                 SET e.is_deleted = true
+                 SET e.is_deleted = true,
+                     e.last_modified = timestamp()
                  RETURN count(e) as deleted_count",
             )
             .param("rid", routine_id)
@@ -585,33 +1005,23 @@ pub async fn recompute_future_for_routine(
     };
 
     // 5) Regenerate from cutoff, counting ensured occurrences.
-    let frequency = routine
-        .frequency
-        .as_ref()
-        .ok_or("Routine missing frequency")?;
+    if routine.frequency.is_none() {
+        return Err("Routine missing frequency".to_string());
+    }
 
     let instance_id = format!("{}-{}", routine_id, Utc::now().timestamp_millis());
-    let mut current_time = cutoff;
+    let holidays = routine_holidays(graph, &routine).await;
     let mut created_count: i64 = 0;
 
-    while current_time <= effective_until {
-        if !is_valid_day_for_routine(current_time, frequency)? {
-            current_time = calculate_next_occurrence(current_time, frequency)?;
+    for PlannedOccurrence {
+        timestamp: scheduled_timestamp,
+        ..
+    } in plan_routine_occurrences(&routine, &[], cutoff, effective_until)?
+    {
+        if falls_on_holiday(scheduled_timestamp, &holidays) {
             continue;
         }
 
-        let scheduled_timestamp = if let Some(routine_time) = routine.routine_time {
-            set_time_of_day(current_time, routine_time)
-        } else {
-            current_time
-        };
-
-        if let Some(end_ts) = routine.end_timestamp {
-            if scheduled_timestamp > end_ts {
-                break;
-            }
-        }
-
         // If an event exists at this timestamp (deleted or not), revive it and reset it to routine defaults.
         //
         // Note: recompute intentionally resets `resolution_status` to 'pending' and clears `resolved_at`.
@@ -651,7 +1061,8 @@ pub async fn recompute_future_for_routine(
                              e.priority = r.priority,
                              e.description = r.description,
                              e.resolution_status = 'pending',
-                             e.resolved_at = null",
+                             e.resolved_at = null,
+                             e.last_modified = timestamp()",
                     )
                     .param("routine_id", routine_id)
                     .param("user_id", user_id)
@@ -680,7 +1091,9 @@ pub async fn recompute_future_for_routine(
                              description: r.description,
                              resolution_status: 'pending',
                              resolved_at: null,
-                             is_deleted: false
+                             is_deleted: false,
+                             version: 0,
+                             last_modified: timestamp()
                          })
                          CREATE (r)-[:HAS_EVENT]->(e)",
                     )
@@ -693,8 +1106,6 @@ pub async fn recompute_future_for_routine(
                 .map_err(|e| format!("Failed to create routine event during recompute: {}", e))?;
             created_count += 1;
         }
-
-        current_time = calculate_next_occurrence(current_time, frequency)?;
     }
 
     if created_count > 0 {
@@ -711,3 +1122,116 @@ pub async fn recompute_future_for_routine(
 
     Ok((deleted_count, created_count))
 }
+
+// How many upcoming occurrences to try when they keep landing on skip exceptions or holidays
+const MAX_ADVANCE_ATTEMPTS: usize = 14;
+
+/// Where to resume generating a routine that should have an event after `now`. A last
+/// event that's already in the past (the generator stalled) is ignored, so we don't
+/// backfill missed occurrences on the way to the next one.
+pub fn advance_generation_start(
+    routine: &Goal,
+    last_event_time: Option<i64>,
+    now: i64,
+) -> Result<i64, String> {
+    generation_start(routine, last_event_time.filter(|&last| last >= now), now)
+}
+
+/// The earliest live series event of `routine_id` scheduled after `after`.
+async fn next_series_event(
+    graph: &Graph,
+    routine_id: i64,
+    after: i64,
+) -> Result<Option<Goal>, String> {
+    let mut result = graph
+        .execute(
+            query(
+                "MATCH (r:Goal)-[:HAS_EVENT]->(e:Goal)
+                 WHERE id(r) = $routine_id
+                   AND e.goal_type = 'event'
+                   AND e.scheduled_timestamp > $after
+                   AND (e.is_deleted IS NULL OR e.is_deleted = false)
+                   AND e.routine_instance_id IS NOT NULL
+                 RETURN e
+                 ORDER BY e.scheduled_timestamp ASC
+                 LIMIT 1",
+            )
+            .param("routine_id", routine_id)
+            .param("after", after),
+        )
+        .await
+        .map_err(|e| format!("Failed to fetch next routine event: {}", e))?;
+
+    match result.next().await.map_err(|e| e.to_string())? {
+        Some(row) => row
+            .get("e")
+            .map(Some)
+            .map_err(|e| format!("Failed to get next routine event: {}", e)),
+        None => Ok(None),
+    }
+}
+
+/// Make sure the routine has a live event after `after`, generating its next occurrence
+/// on the spot if the hourly job hasn't got there yet. Returns that event, or None when
+/// the routine has nothing left to schedule (ended, or every nearby slot is skipped).
+pub async fn ensure_next_routine_event(
+    graph: &Graph,
+    user_id: i64,
+    routine_id: i64,
+    after: i64,
+) -> Result<Option<Goal>, String> {
+    if let Some(next) = next_series_event(graph, routine_id, after).await? {
+        return Ok(Some(next));
+    }
+
+    let mut fetch_result = graph
+        .execute(
+            query(
+                "MATCH (r:Goal)
+                 WHERE id(r) = $routine_id
+                   AND r.goal_type = 'routine'
+                   AND r.user_id = $user_id
+                   AND coalesce(r.is_deleted, false) <> true
+                 OPTIONAL MATCH (r)-[:HAS_EVENT]->(e:Goal)
+                 WHERE (e.is_deleted IS NULL OR e.is_deleted = false)
+                   AND e.routine_instance_id IS NOT NULL
+                 RETURN r, max(e.scheduled_timestamp) as last_event_time",
+            )
+            .param("routine_id", routine_id)
+            .param("user_id", user_id),
+        )
+        .await
+        .map_err(|e| format!("Failed to fetch routine: {}", e))?;
+
+    let Some(row) = fetch_result.next().await.map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let routine: Goal = row
+        .get("r")
+        .map_err(|e| format!("Failed to get routine: {}", e))?;
+    let last_event_time: Option<i64> = row.get("last_event_time").ok();
+
+    let now = Utc::now().timestamp_millis();
+    let horizon = now + Duration::days(180).num_milliseconds();
+    let until = match routine.end_timestamp {
+        Some(end_ts) if end_ts < horizon => end_ts,
+        _ => horizon,
+    };
+    let start_from = advance_generation_start(&routine, last_event_time, now.max(after))?;
+
+    // Widen the window one occurrence at a time so only what's needed gets created
+    let candidates: Vec<i64> = plan_routine_occurrences(&routine, &[], start_from, until)?
+        .into_iter()
+        .map(|occurrence| occurrence.timestamp)
+        .filter(|&ts| ts > after)
+        .take(MAX_ADVANCE_ATTEMPTS)
+        .collect();
+    for candidate in candidates {
+        generate_events_for_routine(graph, &routine, routine_id, start_from, candidate).await?;
+        if let Some(next) = next_series_event(graph, routine_id, after).await? {
+            return Ok(Some(next));
+        }
+    }
+
+    Ok(None)
+}
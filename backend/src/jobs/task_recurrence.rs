@@ -0,0 +1,161 @@
+use crate::jobs::routine_generator;
+use chrono::{Duration, Utc};
+use neo4rs::{query, Graph};
+use tracing::{error, info, warn};
+
+// Same look-ahead as the routine generator keeps for routine events
+const RECURRENCE_HORIZON_DAYS: i64 = 180;
+
+/// Occurrences of a recurring task event after `after`, up to `until` (inclusive). The
+/// pattern is walked from the source event's own time with the routine occurrence algorithm,
+/// so every occurrence keeps the source's time of day.
+pub fn next_task_occurrences(
+    recurrence: &str,
+    source_timestamp: i64,
+    after: i64,
+    until: i64,
+) -> Result<Vec<i64>, String> {
+    if until <= after {
+        return Ok(Vec::new());
+    }
+    Ok(
+        routine_generator::routine_occurrences(recurrence, source_timestamp, until, None)?
+            .into_iter()
+            .filter(|&ts| ts > after)
+            .collect(),
+    )
+}
+
+/// Add the missing future occurrences of one recurring task event under the same task, up
+/// to the horizon and never past the task's end date. Copies carry `recurrence_source_id`;
+/// deleted copies still count as generated, so removing one occurrence doesn't bring it back.
+/// Returns how many events were created.
+pub async fn materialize_task_recurrence(
+    graph: &Graph,
+    source_event_id: i64,
+) -> Result<usize, String> {
+    let now = Utc::now().timestamp_millis();
+    let horizon = now + Duration::days(RECURRENCE_HORIZON_DAYS).num_milliseconds();
+
+    let mut result = graph
+        .execute(
+            query(
+                "MATCH (t:Goal)-[:HAS_EVENT]->(src:Goal)
+                 WHERE id(src) = $source_id
+                 AND src.goal_type = 'event'
+                 AND src.recurrence IS NOT NULL
+                 AND coalesce(src.is_deleted, false) <> true
+                 AND t.goal_type = 'task'
+                 AND coalesce(t.is_deleted, false) <> true
+                 AND coalesce(t.resolution_status, 'pending') <> 'completed'
+                 OPTIONAL MATCH (t)-[:HAS_EVENT]->(copy:Goal)
+                 WHERE copy.recurrence_source_id = id(src)
+                 RETURN src.recurrence as recurrence,
+                        src.scheduled_timestamp as source_timestamp,
+                        t.end_timestamp as task_end,
+                        max(copy.scheduled_timestamp) as last_copy",
+            )
+            .param("source_id", source_event_id),
+        )
+        .await
+        .map_err(|e| format!("Failed to load recurring event: {}", e))?;
+
+    let Some(row) = result.next().await.map_err(|e| e.to_string())? else {
+        return Ok(0);
+    };
+    let recurrence: String = row
+        .get("recurrence")
+        .map_err(|e| format!("Failed to get recurrence: {}", e))?;
+    let source_timestamp: i64 = row
+        .get("source_timestamp")
+        .map_err(|e| format!("Failed to get scheduled_timestamp: {}", e))?;
+    let until = row
+        .get::<i64>("task_end")
+        .map_or(horizon, |end| end.min(horizon));
+    let after = row
+        .get::<i64>("last_copy")
+        .map_or(source_timestamp, |last| last.max(source_timestamp))
+        .max(now);
+
+    let timestamps = next_task_occurrences(&recurrence, source_timestamp, after, until)?;
+    if timestamps.is_empty() {
+        return Ok(0);
+    }
+
+    graph
+        .run(
+            query(
+                "MATCH (t:Goal)-[:HAS_EVENT]->(src:Goal)
+                 WHERE id(src) = $source_id
+                 UNWIND $timestamps AS ts
+                 CREATE (e:Goal {
+                     name: src.name,
+                     goal_type: 'event',
+                     scheduled_timestamp: ts,
+                     duration: src.duration,
+                     duration_seconds: src.duration_seconds,
+                     parent_id: id(t),
+                     parent_type: 'task',
+                     recurrence_source_id: id(src),
+                     user_id: src.user_id,
+                     priority: src.priority,
+                     description: src.description,
+                     color: src.color,
+                     resolution_status: 'pending',
+                     is_deleted: false,
+                     version: 0,
+                     last_modified: timestamp()
+                 })
+                 CREATE (t)-[:HAS_EVENT]->(e)",
+            )
+            .param("source_id", source_event_id)
+            .param("timestamps", timestamps.clone()),
+        )
+        .await
+        .map_err(|e| format!("Failed to create recurring task events: {}", e))?;
+
+    Ok(timestamps.len())
+}
+
+/// Top up every live recurring task event. A failing series is logged and skipped so it
+/// doesn't hold up the others.
+pub async fn generate_task_recurrences(graph: &Graph) -> Result<usize, String> {
+    let mut result = graph
+        .execute(query(
+            "MATCH (t:Goal)-[:HAS_EVENT]->(src:Goal)
+             WHERE src.goal_type = 'event'
+             AND src.recurrence IS NOT NULL
+             AND coalesce(src.is_deleted, false) <> true
+             AND t.goal_type = 'task'
+             AND coalesce(t.is_deleted, false) <> true
+             AND coalesce(t.resolution_status, 'pending') <> 'completed'
+             AND (t.end_timestamp IS NULL OR t.end_timestamp > timestamp())
+             RETURN id(src) as source_id",
+        ))
+        .await
+        .map_err(|e| format!("Failed to query recurring task events: {}", e))?;
+
+    let mut source_ids = Vec::new();
+    while let Some(row) = result.next().await.map_err(|e| e.to_string())? {
+        if let Ok(id) = row.get::<i64>("source_id") {
+            source_ids.push(id);
+        }
+    }
+
+    let mut created = 0;
+    for source_id in source_ids {
+        match materialize_task_recurrence(graph, source_id).await {
+            Ok(count) => created += count,
+            Err(e) => warn!(source_id, error = %e, "Failed to extend recurring task event"),
+        }
+    }
+    Ok(created)
+}
+
+pub async fn run_task_recurrence_generator(graph: Graph) {
+    match generate_task_recurrences(&graph).await {
+        Ok(count) if count > 0 => info!(count, "Generated recurring task events"),
+        Ok(_) => {}
+        Err(e) => error!(error = %e, "Recurring task event generation failed"),
+    }
+}
@@ -39,14 +39,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 return Ok(());
             }
             "migrate-resolution-status" => {
-                run_resolution_status_migration().await?;
+                let force = args[2..].iter().any(|a| a == "--force");
+                let batch_size = match batch_size_arg(&args[2..]) {
+                    Ok(size) => size,
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+                run_resolution_status_migration(force, batch_size).await?;
+                return Ok(());
+            }
+            "migration-status" => {
+                print_migration_status().await?;
                 return Ok(());
             }
             _ => {
                 eprintln!("Unknown command: {}", args[1]);
                 eprintln!("Available commands:");
                 eprintln!("  migrate [--force]            - Run the event migration");
-                eprintln!("  migrate-resolution-status    - Migrate from completed to resolution_status");
+                eprintln!("  migrate-resolution-status [--force] [--batch-size N] - Migrate from completed to resolution_status");
+                eprintln!("  migration-status             - List applied and pending migrations");
                 eprintln!("  verify-migration             - Verify migration integrity");
                 eprintln!("  reset-migration              - Reset migration status (for development)");
                 eprintln!("  rollback-migration <backup>  - Rollback migration from backup");
@@ -166,12 +179,33 @@ async fn reset_migration() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-async fn run_resolution_status_migration() -> Result<(), Box<dyn std::error::Error>> {
+// Parse an optional `--batch-size N` flag
+fn batch_size_arg(args: &[String]) -> Result<i64, String> {
+    match args.iter().position(|a| a == "--batch-size") {
+        None => Ok(tools::migration::DEFAULT_MIGRATION_BATCH_SIZE),
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|n| *n > 0)
+            .ok_or_else(|| "--batch-size expects a positive number".to_string()),
+    }
+}
+
+async fn run_resolution_status_migration(
+    force: bool,
+    batch_size: i64,
+) -> Result<(), Box<dyn std::error::Error>> {
     println!("🚀 Running resolution_status migration...");
 
+    if force {
+        println!("⚠️ Force flag detected - re-running even if already applied");
+    }
+
     let graph = create_graph_connection().await?;
 
-    match tools::migration::migrate_to_resolution_status(&graph).await {
+    println!("   Batch size: {}", batch_size);
+
+    match tools::migration::migrate_to_resolution_status(&graph, force, batch_size).await {
         Ok(result) => {
             println!("✅ Migration completed successfully!");
             println!("📊 Migration results:");
@@ -186,6 +220,31 @@ async fn run_resolution_status_migration() -> Result<(), Box<dyn std::error::Err
     Ok(())
 }
 
+async fn print_migration_status() -> Result<(), Box<dyn std::error::Error>> {
+    let graph = create_graph_connection().await?;
+
+    match tools::migration::migration_status(&graph).await {
+        Ok(records) => {
+            println!("📋 Migration ledger:");
+            for record in records {
+                let applied = match record.applied_at {
+                    Some(ts) if record.applied => chrono::DateTime::from_timestamp_millis(ts)
+                        .map(|dt| format!("applied {}", dt.format("%Y-%m-%d %H:%M:%S UTC")))
+                        .unwrap_or_else(|| "applied".to_string()),
+                    _ => "pending".to_string(),
+                };
+                println!("  v{:<3} {:<32} {}", record.version, record.name, applied);
+            }
+        }
+        Err(e) => {
+            eprintln!("❌ Failed to read migration status: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
 async fn create_graph_connection() -> Result<Graph, Box<dyn std::error::Error>> {
     let neo4j_uri = env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".to_string());
     let neo4j_user = env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".to_string());
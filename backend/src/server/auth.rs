@@ -8,6 +8,8 @@ use oauth2::{
     ClientSecret, CsrfToken, RedirectUrl, Scope, TokenResponse, TokenUrl,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::env;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -16,6 +18,11 @@ pub struct AuthPayload {
     pub password: String,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct RefreshTokenPayload {
+    pub refresh_token: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[allow(dead_code)]
 pub struct GoogleAuthPayload {
@@ -28,6 +35,8 @@ pub struct AuthResponse {
     pub message: String,
     pub token: String,
     pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -41,6 +50,8 @@ pub struct Claims {
     pub user_id: i64,
     pub username: String,
     pub exp: usize,
+    #[serde(default)]
+    pub is_admin: bool, // tokens issued before the claim existed decode as non-admin
 }
 
 #[derive(Debug, Deserialize)]
@@ -112,6 +123,7 @@ pub async fn generate_google_auth_url(
                 message: "OAuth configuration error".to_string(),
                 token: "".to_string(),
                 username: None,
+                refresh_token: None,
             }),
         )
     })?;
@@ -159,6 +171,7 @@ pub async fn handle_google_callback(
                 message: "OAuth configuration error".to_string(),
                 token: "".to_string(),
                 username: None,
+                refresh_token: None,
             }),
         )
     })?;
@@ -179,6 +192,7 @@ pub async fn handle_google_callback(
                     message: "Failed to exchange authorization code".to_string(),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             )
         })?;
@@ -201,6 +215,7 @@ pub async fn handle_google_callback(
                 message: "Failed to get user information".to_string(),
                 token: "".to_string(),
                 username: None,
+                refresh_token: None,
             }),
         )
     })?;
@@ -232,6 +247,7 @@ pub async fn handle_google_callback(
                     message: format!("Database error during account link check: {}", e),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             )
         })?;
@@ -243,6 +259,7 @@ pub async fn handle_google_callback(
                     message: "This Google account is already linked to another user".to_string(),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             ));
         }
@@ -267,6 +284,7 @@ pub async fn handle_google_callback(
                     message: format!("Failed to link Google account: {}", e),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             )
         })?;
@@ -291,6 +309,7 @@ pub async fn handle_google_callback(
                     message: format!("Account linking error: {}", e),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             )
         })?
@@ -306,7 +325,8 @@ pub async fn handle_google_callback(
     let claims = Claims {
         user_id,
         username: user_info.email.clone(),
-        exp: (Utc::now() + Duration::hours(24)).timestamp() as usize,
+        exp: access_token_exp(),
+        is_admin: is_admin_user(user_id),
     };
 
     let jwt_secret = env::var("JWT_SECRET").unwrap_or_else(|_| "default_secret".to_string());
@@ -323,6 +343,7 @@ pub async fn handle_google_callback(
                 message: "Failed to create token".to_string(),
                 token: "".to_string(),
                 username: None,
+                refresh_token: None,
             }),
         )
     })?;
@@ -333,10 +354,24 @@ pub async fn handle_google_callback(
         user_info.email
     );
 
+    let refresh_token = issue_refresh_token(&graph, user_id).await.map_err(|e| {
+        eprintln!("❌ Failed to issue refresh token: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(AuthResponse {
+                message: "Failed to create token".to_string(),
+                token: "".to_string(),
+                username: None,
+                refresh_token: None,
+            }),
+        )
+    })?;
+
     Ok(Json(AuthResponse {
         message: "Google sign-in successful".to_string(),
         token,
         username: Some(user_info.name.clone()),
+        refresh_token: Some(refresh_token),
     }))
 }
 
@@ -572,6 +607,7 @@ pub async fn sign_up(
                     message: "Database error".to_string(),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             ));
         }
@@ -585,6 +621,7 @@ pub async fn sign_up(
                 message: "Username already exists".to_string(),
                 token: "".to_string(),
                 username: None,
+                refresh_token: None,
             }),
         ));
     }
@@ -607,6 +644,7 @@ pub async fn sign_up(
                     message: "Error processing password".to_string(),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             ));
         }
@@ -630,6 +668,7 @@ pub async fn sign_up(
                     message: "User created successfully".to_string(),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             ))
         }
@@ -642,6 +681,7 @@ pub async fn sign_up(
                     message: "Error creating user".to_string(),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             ))
         }
@@ -683,6 +723,7 @@ pub async fn enhanced_sign_in(
                     message: "Database error".to_string(),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             ));
         }
@@ -711,6 +752,7 @@ pub async fn enhanced_sign_in(
                             message: "Authentication error".to_string(),
                             token: "".to_string(),
                             username: None,
+                            refresh_token: None,
                         }),
                     ));
                 }
@@ -721,7 +763,8 @@ pub async fn enhanced_sign_in(
                 let claims = Claims {
                     user_id,
                     username: username.clone(),
-                    exp: (Utc::now() + Duration::hours(24)).timestamp() as usize,
+                    exp: access_token_exp(),
+                    is_admin: is_admin_user(user_id),
                 };
 
                 let jwt_secret =
@@ -740,6 +783,20 @@ pub async fn enhanced_sign_in(
                             message: "Token creation failed".to_string(),
                             token: "".to_string(),
                             username: None,
+                            refresh_token: None,
+                        }),
+                    )
+                })?;
+
+                let refresh_token = issue_refresh_token(&graph, user_id).await.map_err(|e| {
+                    println!("❌ Refresh token creation error: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(AuthResponse {
+                            message: "Token creation failed".to_string(),
+                            token: "".to_string(),
+                            username: None,
+                            refresh_token: None,
                         }),
                     )
                 })?;
@@ -749,6 +806,7 @@ pub async fn enhanced_sign_in(
                     message: "Sign-in successful".to_string(),
                     token,
                     username: display_name.or(Some(username)),
+                    refresh_token: Some(refresh_token),
                 }))
             } else {
                 println!("❌ Password verification failed for user: {}", username);
@@ -758,6 +816,7 @@ pub async fn enhanced_sign_in(
                         message: "Invalid username or password".to_string(),
                         token: "".to_string(),
                         username: None,
+                        refresh_token: None,
                     }),
                 ))
             }
@@ -770,6 +829,7 @@ pub async fn enhanced_sign_in(
                     message: "This account uses Google sign-in. Please sign in with Google or set a password.".to_string(),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             ))
         } else {
@@ -781,6 +841,7 @@ pub async fn enhanced_sign_in(
                     message: "Account configuration error. Please contact support.".to_string(),
                     token: "".to_string(),
                     username: None,
+                    refresh_token: None,
                 }),
             ))
         }
@@ -792,11 +853,187 @@ pub async fn enhanced_sign_in(
                 message: "Invalid username or password".to_string(),
                 token: "".to_string(),
                 username: None,
+                refresh_token: None,
             }),
         ))
     }
 }
 
+// Access token lifetime, configurable via JWT_EXPIRY_SECONDS (defaults to 24 hours)
+pub fn jwt_expiry_seconds() -> i64 {
+    env::var("JWT_EXPIRY_SECONDS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(60 * 60 * 24)
+}
+
+// Refresh token lifetime, configurable via REFRESH_TOKEN_EXPIRY_SECONDS (defaults to 30 days)
+pub fn refresh_token_expiry_seconds() -> i64 {
+    env::var("REFRESH_TOKEN_EXPIRY_SECONDS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(60 * 60 * 24 * 30)
+}
+
+/// Parse a comma-separated list of user ids, ignoring blanks and anything non-numeric.
+pub fn parse_admin_user_ids(raw: &str) -> HashSet<i64> {
+    raw.split(',')
+        .filter_map(|id| id.trim().parse::<i64>().ok())
+        .collect()
+}
+
+// Admins are configured via ADMIN_USER_IDS and carried in the JWT as the is_admin claim
+fn is_admin_user(user_id: i64) -> bool {
+    env::var("ADMIN_USER_IDS")
+        .map(|raw| parse_admin_user_ids(&raw).contains(&user_id))
+        .unwrap_or(false)
+}
+
+fn access_token_exp() -> usize {
+    (Utc::now() + Duration::seconds(jwt_expiry_seconds())).timestamp() as usize
+}
+
+fn create_access_token(user_id: i64, username: &str) -> Result<String, String> {
+    let claims = Claims {
+        user_id,
+        username: username.to_string(),
+        exp: access_token_exp(),
+        is_admin: is_admin_user(user_id),
+    };
+    let jwt_secret = env::var("JWT_SECRET").unwrap_or_else(|_| "default_secret".to_string());
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret.as_bytes()),
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn hash_refresh_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Refresh tokens are "<user_id>.<random>" so the owning user can be found without a scan.
+// Only "<sha256>:<expires_at_ms>" entries are stored, in u.refresh_tokens.
+pub async fn issue_refresh_token(graph: &Graph, user_id: i64) -> Result<String, String> {
+    let token = format!(
+        "{}.{}{}",
+        user_id,
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let expires_at = Utc::now().timestamp_millis() + refresh_token_expiry_seconds() * 1000;
+    let entry = format!("{}:{}", hash_refresh_token(&token), expires_at);
+
+    graph
+        .run(
+            Query::new(
+                "MATCH (u:User) WHERE id(u) = $user_id
+                 SET u.refresh_tokens = [t IN coalesce(u.refresh_tokens, [])
+                                         WHERE toInteger(split(t, ':')[1]) > $now] + $entry"
+                    .to_string(),
+            )
+            .param("user_id", user_id)
+            .param("now", Utc::now().timestamp_millis())
+            .param("entry", entry),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(token)
+}
+
+fn refresh_token_user_id(token: &str) -> Option<i64> {
+    token.split_once('.')?.0.parse().ok()
+}
+
+/// Removes a refresh token from its user, e.g. on logout. Unknown tokens are ignored.
+pub async fn revoke_refresh_token(graph: &Graph, token: &str) -> Result<(), String> {
+    let Some(user_id) = refresh_token_user_id(token) else {
+        return Ok(());
+    };
+    let prefix = format!("{}:", hash_refresh_token(token));
+
+    graph
+        .run(
+            Query::new(
+                "MATCH (u:User) WHERE id(u) = $user_id
+                 SET u.refresh_tokens = [t IN coalesce(u.refresh_tokens, [])
+                                         WHERE NOT t STARTS WITH $prefix]"
+                    .to_string(),
+            )
+            .param("user_id", user_id)
+            .param("prefix", prefix),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Exchanges a valid refresh token for a new access token. The presented refresh token
+/// is consumed and a new one is returned in its place.
+pub async fn refresh_token_handler(
+    graph: Graph,
+    refresh_token: String,
+) -> Result<Json<AuthResponse>, (StatusCode, String)> {
+    let unauthorized = || {
+        (
+            StatusCode::UNAUTHORIZED,
+            "Invalid or expired refresh token".to_string(),
+        )
+    };
+    let user_id = refresh_token_user_id(&refresh_token).ok_or_else(unauthorized)?;
+    let prefix = format!("{}:", hash_refresh_token(&refresh_token));
+    let now = Utc::now().timestamp_millis();
+
+    // Consume the token atomically so a replayed token cannot be exchanged twice
+    let mut result = graph
+        .execute(
+            Query::new(
+                "MATCH (u:User) WHERE id(u) = $user_id
+                 WITH u, [t IN coalesce(u.refresh_tokens, [])
+                          WHERE t STARTS WITH $prefix
+                            AND toInteger(split(t, ':')[1]) > $now] AS matched
+                 WHERE size(matched) > 0
+                 SET u.refresh_tokens = [t IN coalesce(u.refresh_tokens, [])
+                                         WHERE NOT t STARTS WITH $prefix]
+                 RETURN coalesce(u.username, u.google_email, '') AS username,
+                        u.display_name AS display_name"
+                    .to_string(),
+            )
+            .param("user_id", user_id)
+            .param("prefix", prefix)
+            .param("now", now),
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let row = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(unauthorized)?;
+    let username: String = row.get("username").unwrap_or_default();
+    let display_name: Option<String> = row.get("display_name").ok();
+
+    let token = create_access_token(user_id, &username)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let new_refresh_token = issue_refresh_token(&graph, user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    Ok(Json(AuthResponse {
+        message: "Token refreshed".to_string(),
+        token,
+        username: display_name.or(Some(username)),
+        refresh_token: Some(new_refresh_token),
+    }))
+}
+
 // Token validation function
 pub async fn validate_token(token: &str) -> Result<StatusCode, StatusCode> {
     let jwt_secret = env::var("JWT_SECRET").unwrap_or_else(|_| "default_secret".to_string());
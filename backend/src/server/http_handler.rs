@@ -14,25 +14,24 @@ use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-// use crate::ai::query as ai_query;
-use crate::jobs::routine_generator;
+use crate::ai::query as ai_query;
+use crate::jobs::{routine_generator, task_recurrence};
 use crate::server::auth::{self};
-use crate::server::middleware;
+use crate::server::metrics;
+use crate::server::middleware::{self, AdminUser, AuthUser};
 use crate::tools::{
-    achievements, autofill, calendar, day, event, gcal_client,
+    achievements, admin, autofill, calendar, day, event, event_settings, gcal_client, goal_import,
     goal::{self, DuplicateOptions, ExpandTaskDateRangeRequest, Goal, ResolveGoalRequest, Relationship},
-    list, migration, network, notification_settings, relations, stats, telegram, theme_settings, traversal,
+    list, migration, network, notification_settings, quick_add, relations, stats, telegram, theme_settings, traversal,
 };
 
 // Type alias for user locks that's used in routine processing
 type UserLocks = Arc<Mutex<HashMap<i64, Arc<Mutex<()>>>>>;
 
 fn validated_tz(params: &HashMap<String, String>) -> Result<String, (StatusCode, String)> {
-    let tz_raw = params
-        .get("tz")
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .unwrap_or("UTC");
+    let Some(tz_raw) = params.get("tz").map(|s| s.trim()).filter(|s| !s.is_empty()) else {
+        return Ok(event_settings::default_timezone().to_string());
+    };
 
     let tz_raw = if tz_raw.eq_ignore_ascii_case("utc") {
         "UTC"
@@ -60,6 +59,7 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
         .route("/google", get(handle_google_auth))
         .route("/callback", get(handle_google_callback))
         .route("/validate", get(handle_validate_token))
+        .route("/refresh", post(handle_refresh_token))
         .route("/logout", get(handle_logout));
 
     // Protected auth routes (require authentication)
@@ -69,13 +69,24 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
 
     let goal_routes = Router::new()
         .route("/create", post(handle_create_goal))
+        .route("/deleted", get(handle_list_deleted_goals))
+        .route("/since", get(handle_get_goals_since))
+        .route("/unscheduled", get(handle_get_unscheduled_goals))
+        .route("/metadata", get(handle_get_goal_metadata))
+        .route("/import-csv", post(handle_import_goals_csv))
+        .route("/task-with-events", post(handle_create_task_with_events))
         .route("/:id", get(handle_get_goal))
         .route("/:id", put(handle_update_goal))
         .route("/:id", delete(handle_delete_goal))
         .route("/relationship", post(handle_create_relationship))
         .route("/relationship", delete(handle_delete_relationship))
+        .route("/:id/restore", post(handle_restore_goal))
         .route("/:id/resolve", put(handle_resolve_goal))
         .route("/:id/duplicate", post(handle_duplicate_goal))
+        .route("/:id/duplicate-routine", post(handle_duplicate_routine))
+        .route("/:id/sync-events", post(handle_sync_events_to_parent))
+        .route("/:id/merge-into/:target_id", post(handle_merge_goals))
+        .route("/:id/change-type", put(handle_change_goal_type))
         .route("/:id/relations", get(handle_get_goal_relations))
         .route("/:id/subgraph", get(handle_get_goal_subgraph))
         .route("/expand-date-range", post(handle_expand_task_date_range));
@@ -83,8 +94,18 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
     let event_routes = Router::new()
         .route("/", post(handle_create_event))
         .route("/:id/complete", put(handle_complete_event))
+        .route("/:id/complete-and-advance", put(handle_complete_and_advance))
+        .route("/:id/cancel", put(handle_cancel_event))
+        .route("/:id/snooze", put(handle_snooze_event))
+        .route("/:id/duplicate-to", post(handle_duplicate_event_to))
+        .route("/bulk-complete", put(handle_bulk_complete_events))
+        .route("/bulk-delete", post(handle_bulk_delete_events))
+        .route("/quick-add", post(handle_quick_add))
         .route("/:id/delete", delete(handle_delete_event))
         .route("/task/:id", get(handle_get_task_events))
+        .route("/instance/:instance_id", get(handle_get_events_by_instance))
+        .route("/:id/context", get(handle_get_event_with_context))
+        .route("/:id/moves", get(handle_get_event_move_history))
         .route("/:id/update", put(handle_update_event))
         .route("/:id/routine-update", put(handle_update_routine_event))
         .route(
@@ -95,11 +116,21 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
             "/:id/reschedule-options",
             get(handle_get_reschedule_options),
         )
-        .route("/smart-schedule", post(handle_get_smart_schedule_options));
+        .route("/:id/auto-reschedule", put(handle_auto_reschedule))
+        .route("/resolve-conflicts", post(handle_resolve_conflicts))
+        .route("/smart-schedule", post(handle_get_smart_schedule_options))
+        .route("/free-slots", get(handle_get_free_slots))
+        .route("/overlaps", get(handle_get_overlapping_events))
+        .route("/tight-schedules", get(handle_find_tight_schedules))
+        .route("/tight-schedules/space", post(handle_space_tight_schedules))
+        .route("/settings", get(handle_get_event_settings))
+        .route("/settings", put(handle_update_event_settings));
 
     let task_routes = Router::new()
+        .route("/bulk-completion", put(handle_bulk_set_completion))
         .route("/:id/complete", put(handle_complete_task))
         .route("/:id/uncomplete", put(handle_uncomplete_task))
+        .route("/:id/schedule-pattern", post(handle_apply_schedule_pattern))
         .route(
             "/:id/completion-status",
             get(handle_check_task_completion_status),
@@ -107,21 +138,32 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
 
     let network_routes = Router::new()
         .route("/", get(handle_get_network_data))
-        .route("/:id/position", put(handle_update_node_position));
+        .route("/:id/position", put(handle_update_node_position))
+        .route("/positions", put(handle_update_goal_positions));
 
-    let traversal_routes = Router::new().route("/:goal_id", get(handle_query_hierarchy));
+    let traversal_routes = Router::new()
+        .route("/:goal_id", get(handle_query_hierarchy))
+        .route("/:goal_id/tree", get(handle_get_goal_tree));
 
-    let calendar_routes = Router::new().route("/", get(handle_get_calendar_data));
+    let calendar_routes = Router::new()
+        .route("/", get(handle_get_calendar_data))
+        .route("/events", get(handle_get_events_in_range));
 
     let list_routes = Router::new().route("/", get(handle_get_list_data));
 
     let day_routes = Router::new()
         .route("/", get(handle_get_day_tasks))
+        .route("/week", get(handle_get_week_plan))
+        .route("/upcoming-incomplete", get(handle_get_upcoming_incomplete))
         .route("/complete/:id", put(handle_toggle_complete_task));
 
-    // let query_routes = Router::new().route("/ws", get(ai_query::handle_query_ws));
+    let query_routes = Router::new().route("/ws", get(ai_query::handle_query_ws));
 
-    let achievements_routes = Router::new().route("/", get(handle_get_achievements_data));
+    let achievements_routes = Router::new()
+        .route("/", get(handle_get_achievements_data))
+        .route("/:id/progress", post(handle_increment_achievement_progress))
+        .route("/:id/history", get(handle_get_achievement_history))
+        .route("/:id/forecast", get(handle_get_goal_forecast));
 
     let _misc_routes: Router = Router::new()
         .route("/health", get(handle_health_check))
@@ -136,15 +178,26 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
         .route("/event/:goal_id", delete(handle_delete_gcal_event))
         .route("/resolve-conflict", post(handle_resolve_conflict))
         .route("/reset-sync/:calendar_id", post(handle_reset_sync_state))
+        .route("/sync-status", get(handle_get_gcal_sync_status))
         .route("/settings", get(handle_get_gcal_settings))
         .route("/settings", put(handle_update_gcal_settings));
 
     let stats_routes = Router::new()
         .route("/", get(handle_get_stats_data))
+        .route("/recompute", post(handle_recompute_year_stats))
         .route("/extended", get(handle_get_extended_stats))
+        .route("/range", get(handle_get_range_stats))
+        .route("/today", get(handle_get_today_score))
+        .route("/week-review", get(handle_get_week_review))
         .route("/analytics", get(handle_get_event_analytics))
         .route("/effort", get(handle_get_effort_stats))
+        .route("/latency", get(handle_get_completion_latency_stats))
         .route("/effort/:id/children", get(handle_get_goal_children_effort))
+        .route("/progress/:id", get(handle_get_goal_progress))
+        .route("/trend/:id", get(handle_get_goal_trend))
+        .route("/focus-time", get(handle_get_focus_time_stats))
+        .route("/breakdown", get(handle_get_stats_breakdown))
+        .route("/priority-distribution", get(handle_get_priority_distribution))
         .route("/routines/search", get(handle_search_routines))
         .route("/routines/stats", post(handle_get_routine_stats))
         .route("/rescheduling", get(handle_get_rescheduling_stats))
@@ -159,6 +212,8 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
 
     // New route group for on-demand routine event generation
     let routine_generation_routes = Router::new()
+        .route("/preview", post(handle_preview_routine_events))
+        .route("/gaps", get(handle_detect_routine_gaps))
         .route("/:end_timestamp", post(handle_generate_routine_events))
         .route("/:id/recompute-future", post(handle_recompute_routine_future));
 
@@ -181,6 +236,8 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
         .route("/set-password", post(handle_set_password))
         .route("/unlink-google", post(handle_unlink_google_account));
 
+    let admin_routes = Router::new().route("/users", get(handle_list_users));
+
     // Protected routes with auth middleware
     let protected_routes = Router::new()
         .nest("/goals", goal_routes)
@@ -191,7 +248,7 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
         .nest("/calendar", calendar_routes)
         .nest("/list", list_routes)
         .nest("/day", day_routes)
-        // .nest("/query", query_routes)
+        .nest("/query", query_routes)
         .nest("/achievements", achievements_routes)
         .nest("/gcal", gcal_routes)
         .nest("/stats", stats_routes)
@@ -201,6 +258,7 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
         .nest("/notifications", notification_settings_routes)
         .nest("/theme", theme_settings_routes)
         .nest("/account", account_routes)
+        .nest("/admin", admin_routes)
         .nest("/auth", auth_protected_routes)
         .route("/autofill", post(handle_autofill_suggestions))
         .layer(from_fn(middleware::auth_middleware));
@@ -208,6 +266,7 @@ pub fn create_routes(pool: Graph, user_locks: UserLocks) -> Router {
     Router::new()
         .nest("/auth", auth_routes)
         .merge(protected_routes)
+        .route_layer(from_fn(metrics::track_metrics))
         .layer(Extension(pool))
         .layer(Extension(user_locks))
 }
@@ -228,13 +287,7 @@ async fn handle_signin(
     match auth::enhanced_sign_in(graph, payload.username.clone(), payload.password).await {
         Ok(Json(resp)) => {
             // Build Set-Cookie header for HttpOnly session cookie
-            let cookie_value = build_auth_cookie(&resp.token);
-            let mut headers = HeaderMap::new();
-            headers.insert(
-                header::SET_COOKIE,
-                HeaderValue::from_str(&cookie_value)
-                    .unwrap_or_else(|_| HeaderValue::from_static("")),
-            );
+            let headers = build_session_cookies(&resp);
             Ok((StatusCode::OK, headers, Json(resp)))
         }
         Err((status, _json)) => Err(status),
@@ -268,6 +321,25 @@ async fn handle_validate_token(
     auth::validate_token(&token).await
 }
 
+async fn handle_refresh_token(
+    Extension(graph): Extension<Graph>,
+    headers: HeaderMap,
+    payload: Option<Json<auth::RefreshTokenPayload>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    // Browsers send the HttpOnly cookie; other clients may post the token in the body
+    let refresh_token = payload
+        .and_then(|Json(p)| p.refresh_token)
+        .or_else(|| cookie_value(&headers, "refresh_token"))
+        .ok_or((
+            StatusCode::UNAUTHORIZED,
+            "Missing refresh token".to_string(),
+        ))?;
+
+    let Json(resp) = auth::refresh_token_handler(graph, refresh_token).await?;
+    let headers = build_session_cookies(&resp);
+    Ok((StatusCode::OK, headers, Json(resp)))
+}
+
 // Google OAuth handlers
 async fn handle_google_auth() -> Result<impl IntoResponse, impl IntoResponse> {
     auth::generate_google_auth_url().await
@@ -289,6 +361,7 @@ async fn handle_google_callback(
                 message: "Missing authorization code".to_string(),
                 token: "".to_string(),
                 username: None,
+                refresh_token: None,
             }),
         )
     })?;
@@ -301,6 +374,7 @@ async fn handle_google_callback(
                 message: "Missing state parameter".to_string(),
                 token: "".to_string(),
                 username: None,
+                refresh_token: None,
             }),
         )
     })?;
@@ -354,13 +428,7 @@ async fn handle_google_callback(
     match result {
         Ok(Json(resp)) => {
             eprintln!("✅ [ROUTE] Google OAuth callback completed successfully");
-            let cookie_value = build_auth_cookie(&resp.token);
-            let mut headers = HeaderMap::new();
-            headers.insert(
-                header::SET_COOKIE,
-                HeaderValue::from_str(&cookie_value)
-                    .unwrap_or_else(|_| HeaderValue::from_static("")),
-            );
+            let headers = build_session_cookies(&resp);
             Ok((StatusCode::OK, headers, Json(resp)))
         }
         Err((status, response)) => {
@@ -377,51 +445,225 @@ async fn handle_google_callback(
 // Goal handlers
 async fn handle_get_goal(
     Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    crate::tools::goal::get_goal_handler(graph, id).await
+    crate::tools::goal::get_goal_handler(graph, user_id, id).await
+}
+
+async fn handle_get_goal_metadata() -> impl IntoResponse {
+    goal::get_goal_metadata_handler()
 }
 
 async fn handle_create_goal(
     Extension(graph): Extension<Graph>,
     Extension(user_id): Extension<i64>,
-    Json(goal): Json<Goal>,
+    Json(request): Json<goal::CreateGoalRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
+    if !request.exceptions.is_empty() && request.goal.goal_type != goal::GoalType::Routine {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "exceptions are only supported when creating a routine".to_string(),
+        ));
+    }
     let goal_with_user_id = Goal {
         user_id: Some(user_id),
-        ..goal
+        ..request.goal
     };
-    crate::tools::goal::create_goal_handler(graph, user_id, goal_with_user_id).await
+    let (status, Json(created)) =
+        crate::tools::goal::create_goal_handler(graph.clone(), user_id, goal_with_user_id).await?;
+
+    // The routine already exists at this point, so a failure here is logged rather than
+    // surfaced; the skipped dates can still be removed by hand
+    if !request.exceptions.is_empty() {
+        if let Err(e) = routine_generator::seed_creation_exceptions(
+            &graph,
+            user_id,
+            &created,
+            &request.exceptions,
+        )
+        .await
+        {
+            eprintln!("Warning: Failed to seed routine exceptions: {}", e);
+        }
+    }
+
+    Ok((status, Json(created)))
 }
 
 async fn handle_update_goal(
     Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
     Path(id): Path<i64>,
+    Query(params): Query<HashMap<String, String>>,
     Json(goal): Json<Goal>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    crate::tools::goal::update_goal_handler(graph, id, goal).await
+    let cascade_events = params
+        .get("cascade_events")
+        .map(|v| v == "true")
+        .unwrap_or(false);
+
+    let updated = crate::tools::goal::update_goal_handler(graph.clone(), user_id, id, goal).await?;
+    if cascade_events {
+        let Json(synced) = goal::sync_events_to_parent_handler(graph, user_id, id).await?;
+        println!("Cascaded goal {} update to {} events", id, synced.updated);
+    }
+    Ok(updated)
+}
+
+async fn handle_sync_events_to_parent(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    goal::sync_events_to_parent_handler(graph, user_id, id).await
 }
 
 async fn handle_delete_goal(
     Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    crate::tools::goal::delete_goal_handler(graph, id).await
+    crate::tools::goal::delete_goal_handler(graph, user_id, id).await
+}
+
+async fn handle_restore_goal(
+    Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    goal::restore_goal_handler(graph, user_id, id).await
+}
+
+async fn handle_merge_goals(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path((id, target_id)): Path<(i64, i64)>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    goal::merge_goals_handler(graph, user_id, id, target_id).await
+}
+
+async fn handle_change_goal_type(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+    Json(request): Json<goal::ChangeGoalTypeRequest>,
+) -> Result<Json<goal::ChangeGoalTypeResponse>, (StatusCode, String)> {
+    let generate_events = request.generate_events.unwrap_or(false);
+    let response = goal::change_goal_type_handler(graph.clone(), user_id, id, request).await?;
+
+    // The conversion is already committed, so a generation failure is logged; the hourly
+    // routine job fills the events in later anyway
+    if generate_events && response.goal.goal_type == goal::GoalType::Routine {
+        if let Err(e) =
+            routine_generator::recompute_future_for_routine(&graph, user_id, id, None).await
+        {
+            eprintln!(
+                "Warning: Failed to generate events for converted routine {}: {}",
+                id, e
+            );
+        }
+    }
+
+    Ok(response)
+}
+
+async fn handle_duplicate_routine(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+    Json(request): Json<goal::DuplicateRoutineRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let (status, Json(created)) = goal::duplicate_routine_handler(
+        graph.clone(),
+        user_id,
+        id,
+        request.time_offset_ms.unwrap_or(0),
+        request.name_suffix.as_deref().unwrap_or_default(),
+    )
+    .await?;
+
+    // The copy is already saved, so a generation failure is logged; the hourly routine
+    // job fills the events in later anyway
+    if request.generate_events.unwrap_or(false) {
+        if let Some(new_id) = created.id {
+            if let Err(e) =
+                routine_generator::recompute_future_for_routine(&graph, user_id, new_id, None).await
+            {
+                eprintln!(
+                    "Warning: Failed to generate events for duplicated routine {}: {}",
+                    new_id, e
+                );
+            }
+        }
+    }
+
+    Ok((status, Json(created)))
+}
+
+async fn handle_list_deleted_goals(
+    Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    goal::list_deleted_goals(graph, user_id).await
+}
+
+async fn handle_get_goals_since(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let since = params
+        .get("since")
+        .and_then(|s| s.parse::<i64>().ok())
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            "since must be a millisecond timestamp".to_string(),
+        ))?;
+    goal::get_goals_since_handler(graph, user_id, since).await
+}
+
+async fn handle_create_task_with_events(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Json(request): Json<event::CreateTaskWithEventsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    event::create_task_with_events_handler(graph, user_id, request).await
+}
+
+async fn handle_import_goals_csv(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let tz = Tz::from_str(&validated_tz(&params)?).unwrap_or(Tz::UTC);
+    goal_import::import_goals_csv_handler(graph, user_id, body, tz).await
+}
+
+async fn handle_get_unscheduled_goals(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    goal::get_unscheduled_goals_handler(graph, user_id).await
 }
 
 async fn handle_create_relationship(
     Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
     Json(relationship): Json<Relationship>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    crate::tools::goal::create_relationship_handler(graph, relationship).await
+    crate::tools::goal::create_relationship_handler(graph, user_id, relationship).await
 }
 
 async fn handle_delete_relationship(
     Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
     Json(relationship): Json<Relationship>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     crate::tools::goal::delete_relationship_handler(
         graph,
+        user_id,
         relationship.from_id,
         relationship.to_id,
     )
@@ -455,24 +697,38 @@ async fn handle_get_goal_subgraph(
 // Event handlers
 async fn handle_create_event(
     Extension(graph): Extension<Graph>,
-    Extension(user_id): Extension<i64>,
+    AuthUser(user_id): AuthUser,
     Json(request): Json<event::CreateEventRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    event::create_event_handler(graph, user_id, request).await
+    let has_recurrence = request.recurrence.is_some();
+    let (status, Json(created)) =
+        event::create_event_handler(graph.clone(), user_id, request).await?;
+
+    // Fill in the upcoming occurrences now rather than waiting for the hourly job; the
+    // event itself is already saved, so a failure is only logged
+    if has_recurrence {
+        if let Some(event_id) = created.event.id {
+            if let Err(e) = task_recurrence::materialize_task_recurrence(&graph, event_id).await {
+                eprintln!("Warning: Failed to generate recurring task events: {}", e);
+            }
+        }
+    }
+
+    Ok((status, Json(created)))
 }
 
 async fn handle_update_event(
     Extension(graph): Extension<Graph>,
-    Extension(user_id): Extension<i64>,
+    AuthUser(user_id): AuthUser,
     Path(id): Path<i64>,
     Json(request): Json<event::UpdateEventRequest>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
+) -> Result<impl IntoResponse, event::UpdateEventError> {
     event::update_event_handler(graph, user_id, id, request).await
 }
 
 async fn handle_update_routine_event(
     Extension(graph): Extension<Graph>,
-    Extension(user_id): Extension<i64>,
+    AuthUser(user_id): AuthUser,
     Path(id): Path<i64>,
     Json(request): Json<event::UpdateRoutineEventRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
@@ -496,7 +752,7 @@ async fn handle_update_routine_event(
 
 async fn handle_update_routine_event_properties(
     Extension(graph): Extension<Graph>,
-    Extension(user_id): Extension<i64>,
+    AuthUser(user_id): AuthUser,
     Path(id): Path<i64>,
     Json(request): Json<event::UpdateRoutineEventPropertiesRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
@@ -519,15 +775,107 @@ async fn handle_update_routine_event_properties(
 
 async fn handle_complete_event(
     Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    event::complete_event_handler(graph, id).await
+    event::complete_event_handler(graph, user_id, id).await
+}
+
+async fn handle_complete_and_advance(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    event::complete_and_advance_handler(graph, user_id, id).await
+}
+
+async fn handle_duplicate_event_to(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+    Json(request): Json<event::DuplicateEventToRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    event::duplicate_event_to_handler(graph, user_id, id, request.scheduled_timestamp).await
+}
+
+async fn handle_snooze_event(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+    Json(request): Json<event::SnoozeEventRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let mut params = HashMap::new();
+    if let Some(tz) = request.tz {
+        params.insert("tz".to_string(), tz);
+    }
+    let tz = Tz::from_str(&validated_tz(&params)?).unwrap_or(Tz::UTC);
+    event::snooze_event_handler(graph, user_id, id, &request.preset, tz).await
+}
+
+async fn handle_cancel_event(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+    request: Option<Json<event::CancelEventRequest>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let Json(request) = request.unwrap_or_default();
+    event::cancel_event_handler(graph, user_id, id, request.reason).await
+}
+
+async fn handle_bulk_complete_events(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Json(request): Json<event::BulkCompleteEventsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    event::bulk_complete_events_handler(
+        graph,
+        user_id,
+        request.start_timestamp,
+        request.end_timestamp,
+        request.parent_id,
+    )
+    .await
+}
+
+async fn handle_bulk_delete_events(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Json(request): Json<event::BulkDeleteEventsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    event::bulk_delete_events_handler(graph, user_id, request.event_ids, request.hard).await
+}
+
+async fn handle_bulk_set_completion(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Json(updates): Json<Vec<event::TaskCompletionUpdate>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    event::bulk_set_completion_handler(graph, user_id, updates).await
+}
+
+async fn handle_quick_add(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+    Json(request): Json<quick_add::QuickAddRequest>,
+) -> Result<impl IntoResponse, quick_add::QuickAddError> {
+    let tz = validated_tz(&params)?;
+    quick_add::quick_add_handler(graph, user_id, request.text, tz).await
+}
+
+async fn handle_apply_schedule_pattern(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+    Json(request): Json<event::SchedulePatternRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    event::apply_schedule_pattern_handler(graph, user_id, id, request).await
 }
 
 // New task completion handlers
 async fn handle_complete_task(
     Extension(graph): Extension<Graph>,
-    Extension(user_id): Extension<i64>,
+    AuthUser(user_id): AuthUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     event::complete_task_handler(graph, id, user_id).await
@@ -535,7 +883,7 @@ async fn handle_complete_task(
 
 async fn handle_uncomplete_task(
     Extension(graph): Extension<Graph>,
-    Extension(user_id): Extension<i64>,
+    AuthUser(user_id): AuthUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     event::uncomplete_task_handler(graph, user_id, id).await
@@ -550,7 +898,7 @@ async fn handle_check_task_completion_status(
 
 async fn handle_delete_event(
     Extension(graph): Extension<Graph>,
-    Extension(user_id): Extension<i64>,
+    AuthUser(user_id): AuthUser,
     Path(id): Path<i64>,
     Query(params): Query<HashMap<String, String>>,
 ) -> Result<impl IntoResponse, impl IntoResponse> {
@@ -566,24 +914,166 @@ async fn handle_delete_event(
 
 async fn handle_get_task_events(
     Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
     Path(task_id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    event::get_task_events_handler(graph, task_id).await
+    event::get_task_events_handler(graph, user_id, task_id).await
+}
+
+async fn handle_get_events_by_instance(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(instance_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    event::get_events_by_instance(graph, user_id, instance_id).await
+}
+
+async fn handle_get_event_with_context(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(event_id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    event::get_event_with_context_handler(graph, user_id, event_id).await
+}
+
+async fn handle_get_event_move_history(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(event_id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    stats::get_event_move_history_handler(graph, user_id, event_id).await
 }
 
 async fn handle_get_reschedule_options(
     Extension(graph): Extension<Graph>,
-    Extension(user_id): Extension<i64>,
+    AuthUser(user_id): AuthUser,
     Path(event_id): Path<i64>,
     Query(params): Query<HashMap<String, i32>>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     let look_ahead_days = params.get("look_ahead_days").copied().unwrap_or(7);
-    event::get_reschedule_options_handler(graph, user_id, event_id, look_ahead_days).await
+    let lookback_days = params
+        .get("lookback_days")
+        .copied()
+        .unwrap_or(event::DEFAULT_SCHEDULING_LOOKBACK_DAYS);
+    event::get_reschedule_options_handler(graph, user_id, event_id, look_ahead_days, lookback_days)
+        .await
+}
+
+async fn handle_auto_reschedule(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(event_id): Path<i64>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let look_ahead_days = params.get("look_ahead_days").copied().unwrap_or(7);
+    event::auto_reschedule_handler(graph, user_id, event_id, look_ahead_days).await
+}
+
+async fn handle_resolve_conflicts(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Json(request): Json<event::ResolveConflictsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let look_ahead_days = request.look_ahead_days.unwrap_or(7);
+    event::resolve_conflicts_handler(graph, user_id, request.event_ids, look_ahead_days).await
+}
+
+async fn handle_get_free_slots(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let date = match params.get("date") {
+        Some(raw) => chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d").map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Invalid date '{}'; expected YYYY-MM-DD", raw),
+            )
+        })?,
+        None => chrono::Utc::now().date_naive(),
+    };
+    let number = |key: &str, default: i64| -> Result<i64, (StatusCode, String)> {
+        match params.get(key) {
+            Some(raw) => raw
+                .parse()
+                .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid {}", key))),
+            None => Ok(default),
+        }
+    };
+    let min_slot_minutes = number("min_slot_minutes", 30)?;
+    let work_start_hour = number("work_start_hour", 8)?;
+    let work_end_hour = number("work_end_hour", 18)?;
+    if !(0..=24).contains(&work_start_hour) || !(0..=24).contains(&work_end_hour) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Working hours must be within 0-24".to_string(),
+        ));
+    }
+
+    event::get_free_slots_handler(
+        graph,
+        user_id,
+        date,
+        min_slot_minutes,
+        work_start_hour as u32,
+        work_end_hour as u32,
+    )
+    .await
+}
+
+async fn handle_get_overlapping_events(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let timestamp_param = |name: &str| {
+        params.get(name).and_then(|s| s.parse::<i64>().ok()).ok_or((
+            StatusCode::BAD_REQUEST,
+            format!("{} is required (epoch milliseconds)", name),
+        ))
+    };
+    let start = timestamp_param("start")?;
+    let end = timestamp_param("end")?;
+    event::get_overlapping_events_handler(graph, user_id, start, end).await
+}
+
+async fn handle_find_tight_schedules(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let (min_gap_minutes, start, end) = tight_schedule_params(&params)?;
+    event::find_tight_schedules_handler(graph, user_id, min_gap_minutes, start, end, false).await
+}
+
+async fn handle_space_tight_schedules(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let (min_gap_minutes, start, end) = tight_schedule_params(&params)?;
+    event::find_tight_schedules_handler(graph, user_id, min_gap_minutes, start, end, true).await
+}
+
+fn tight_schedule_params(
+    params: &HashMap<String, String>,
+) -> Result<(i64, i64, i64), (StatusCode, String)> {
+    let number_param = |name: &str| {
+        params
+            .get(name)
+            .and_then(|s| s.parse::<i64>().ok())
+            .ok_or((StatusCode::BAD_REQUEST, format!("{} is required", name)))
+    };
+    Ok((
+        number_param("min_gap_minutes")?,
+        number_param("start")?,
+        number_param("end")?,
+    ))
 }
 
 async fn handle_get_smart_schedule_options(
     Extension(graph): Extension<Graph>,
-    Extension(user_id): Extension<i64>,
+    AuthUser(user_id): AuthUser,
     Json(request): Json<event::SmartScheduleRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     let start = std::time::Instant::now();
@@ -619,18 +1109,38 @@ async fn handle_get_network_data(
 
 async fn handle_update_node_position(
     Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
     Path(id): Path<i64>,
     Json(position): Json<network::PositionUpdate>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    network::update_node_position(graph, id, position.x, position.y).await
+    network::update_node_position(graph, user_id, id, position.x, position.y).await
+}
+
+async fn handle_update_goal_positions(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Json(positions): Json<Vec<network::GoalPosition>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    network::update_goal_positions_handler(graph, user_id, positions).await
 }
 
 // Traversal handlers
 async fn handle_query_hierarchy(
     Path(goal_id): Path<i64>,
     Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
 ) -> Result<impl IntoResponse, impl IntoResponse> {
-    traversal::query_hierarchy_handler(graph, goal_id).await
+    traversal::query_hierarchy_handler(graph, user_id, goal_id).await
+}
+
+async fn handle_get_goal_tree(
+    Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
+    Path(goal_id): Path<i64>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let max_depth = params.get("max_depth").copied();
+    traversal::get_goal_tree(graph, user_id, goal_id, max_depth).await
 }
 
 // Calendar handlers
@@ -645,6 +1155,26 @@ async fn handle_get_calendar_data(
     calendar::get_calendar_data(graph, user_id, start_timestamp, end_timestamp).await
 }
 
+async fn handle_get_events_in_range(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let timestamp_param = |name: &str| {
+        params.get(name).and_then(|s| s.parse::<i64>().ok()).ok_or((
+            StatusCode::BAD_REQUEST,
+            format!("{} is required (epoch milliseconds)", name),
+        ))
+    };
+    let start = timestamp_param("start")?;
+    let end = timestamp_param("end")?;
+    let include_completed = params
+        .get("include_completed")
+        .map(|v| v == "true")
+        .unwrap_or(false);
+    calendar::get_events_in_range_handler(graph, user_id, start, end, include_completed).await
+}
+
 // List handlers
 async fn handle_get_list_data(
     Extension(graph): Extension<Graph>,
@@ -665,11 +1195,39 @@ async fn handle_get_day_tasks(
     day::get_day_tasks(graph, user_id, start_timestamp, end_timestamp).await
 }
 
+async fn handle_get_week_plan(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, i64>>,
+) -> Result<Json<day::WeekPlan>, (StatusCode, String)> {
+    day::get_week_plan_handler(graph, user_id, params.get("start").copied()).await
+}
+
+async fn handle_get_upcoming_incomplete(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<day::IncompleteDay>>, (StatusCode, String)> {
+    let timestamp = |key: &str| -> Result<i64, (StatusCode, String)> {
+        params
+            .get(key)
+            .ok_or((StatusCode::BAD_REQUEST, format!("Missing {}", key)))?
+            .parse::<i64>()
+            .map_err(|_| (StatusCode::BAD_REQUEST, format!("Invalid {}", key)))
+    };
+    let start = timestamp("start")?;
+    let end = timestamp("end")?;
+    let tz = Tz::from_str(&validated_tz(&params)?).unwrap_or(Tz::UTC);
+
+    day::get_upcoming_incomplete_handler(graph, user_id, start, end, tz).await
+}
+
 async fn handle_toggle_complete_task(
     Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    day::toggle_complete_task(graph, id).await
+    day::toggle_complete_task(graph, user_id, id).await
 }
 
 // Achievements handlers
@@ -680,6 +1238,32 @@ async fn handle_get_achievements_data(
     achievements::get_achievements_data(graph, user_id).await
 }
 
+async fn handle_increment_achievement_progress(
+    Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
+    Path(id): Path<i64>,
+    Json(request): Json<achievements::IncrementAchievementProgressRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let amount = request.amount.unwrap_or(1.0);
+    achievements::increment_achievement_progress_handler(graph, user_id, id, amount).await
+}
+
+async fn handle_get_achievement_history(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    achievements::get_achievement_history(graph, user_id, id).await
+}
+
+async fn handle_get_goal_forecast(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    achievements::get_goal_forecast(graph, user_id, id).await
+}
+
 // Stats handlers
 async fn handle_get_stats_data(
     Extension(graph): Extension<Graph>,
@@ -687,8 +1271,57 @@ async fn handle_get_stats_data(
     Query(params): Query<HashMap<String, String>>,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     let year = params.get("year").and_then(|s| s.parse::<i32>().ok());
+    let force = params.get("force").is_some_and(|v| v == "true");
     let tz = validated_tz(&params)?;
-    stats::get_year_stats(graph, user_id, year, tz).await
+    let grace_minutes = grace_minutes_param(&params)?;
+    // Routine events are counted unless explicitly turned off
+    let include_routines = params
+        .get("include_routines")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+    stats::get_year_stats(
+        graph,
+        user_id,
+        year,
+        tz,
+        force,
+        grace_minutes,
+        include_routines,
+    )
+    .await
+}
+
+/// Optional `grace_minutes` override for the stats grace period.
+fn grace_minutes_param(params: &HashMap<String, String>) -> Result<i64, (StatusCode, String)> {
+    stats::parse_grace_minutes(
+        params.get("grace_minutes").map(String::as_str),
+        stats::default_grace_minutes(),
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+async fn handle_recompute_year_stats(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let year = params.get("year").and_then(|s| s.parse::<i32>().ok());
+    let tz = validated_tz(&params)?;
+    let grace_minutes = grace_minutes_param(&params)?;
+    let include_routines = params
+        .get("include_routines")
+        .map(|v| v != "false")
+        .unwrap_or(true);
+    stats::get_year_stats(
+        graph,
+        user_id,
+        year,
+        tz,
+        true,
+        grace_minutes,
+        include_routines,
+    )
+    .await
 }
 
 async fn handle_get_extended_stats(
@@ -698,7 +1331,72 @@ async fn handle_get_extended_stats(
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     let year = params.get("year").and_then(|s| s.parse::<i32>().ok());
     let tz = validated_tz(&params)?;
-    stats::get_extended_stats(graph, user_id, year, tz).await
+    let monday_start = week_start_param(&params)?;
+    stats::get_extended_stats(graph, user_id, year, tz, monday_start).await
+}
+
+async fn handle_get_range_stats(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let timestamp_param = |name: &str| {
+        params.get(name).and_then(|s| s.parse::<i64>().ok()).ok_or((
+            StatusCode::BAD_REQUEST,
+            format!("{} is required (epoch milliseconds)", name),
+        ))
+    };
+    let start = timestamp_param("start")?;
+    let end = timestamp_param("end")?;
+    let tz = validated_tz(&params)?;
+    let monday_start = week_start_param(&params)?;
+    stats::get_range_stats(graph, user_id, start, end, tz, monday_start).await
+}
+
+/// Optional `week_start` ("monday" or "sunday") for weekly stats buckets.
+fn week_start_param(params: &HashMap<String, String>) -> Result<bool, (StatusCode, String)> {
+    stats::parse_week_start(params.get("week_start").map(String::as_str))
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+async fn handle_get_week_review(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let week_start = params
+        .get("week_start")
+        .map(|raw| {
+            chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d").map_err(|_| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid week_start '{}'; expected YYYY-MM-DD", raw),
+                )
+            })
+        })
+        .transpose()?;
+    let tz = validated_tz(&params)?;
+    stats::get_week_review(graph, user_id, week_start, tz).await
+}
+
+async fn handle_get_today_score(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<stats::DailyStats>, (StatusCode, String)> {
+    let date = params
+        .get("date")
+        .map(|raw| {
+            chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d").map_err(|_| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid date '{}'; expected YYYY-MM-DD", raw),
+                )
+            })
+        })
+        .transpose()?;
+    let tz = validated_tz(&params)?;
+    stats::get_today_score_handler(graph, user_id, date, tz).await
 }
 
 async fn handle_get_event_analytics(
@@ -721,6 +1419,77 @@ async fn handle_get_effort_stats(
     stats::get_effort_stats(graph, user_id, range, tz).await
 }
 
+async fn handle_get_completion_latency_stats(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let range = params.get("range").cloned();
+    let tz = validated_tz(&params)?;
+    stats::get_completion_latency_stats(graph, user_id, range, tz).await
+}
+
+async fn handle_get_goal_progress(
+    Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
+    Path(id): Path<i64>,
+    Query(params): Query<HashMap<String, i64>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let start = params.get("start").copied();
+    let end = params.get("end").copied();
+    stats::get_goal_progress(graph, user_id, id, start, end).await
+}
+
+async fn handle_get_goal_trend(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Path(id): Path<i64>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let bucket = params
+        .get("bucket")
+        .cloned()
+        .unwrap_or_else(|| "week".to_string());
+    let range = params.get("range").cloned();
+    let tz = validated_tz(&params)?;
+    stats::get_goal_trend(graph, user_id, id, bucket, range, tz).await
+}
+
+async fn handle_get_focus_time_stats(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let bucket = params
+        .get("bucket")
+        .cloned()
+        .unwrap_or_else(|| "week".to_string());
+    let range = params.get("range").cloned();
+    let tz = validated_tz(&params)?;
+    stats::get_focus_time_stats(graph, user_id, range, bucket, tz).await
+}
+
+async fn handle_get_stats_breakdown(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let group_by = params
+        .get("group_by")
+        .cloned()
+        .unwrap_or_else(|| "goal_type".to_string());
+    let range = params.get("range").cloned();
+    let tz = validated_tz(&params)?;
+    stats::get_stats_breakdown(graph, user_id, range, group_by, tz).await
+}
+
+async fn handle_get_priority_distribution(
+    Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    stats::get_priority_distribution(graph, user_id).await
+}
+
 async fn handle_get_goal_children_effort(
     Extension(graph): Extension<Graph>,
     Extension(user_id): Extension<i64>,
@@ -749,13 +1518,14 @@ async fn handle_get_routine_stats(
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
     let year = params.get("year").and_then(|s| s.parse::<i32>().ok());
     let tz = validated_tz(&params)?;
+    let grace_minutes = grace_minutes_param(&params)?;
     let routine_ids: Vec<i64> = payload
         .get("routine_ids")
         .and_then(|v| v.as_array())
         .map(|arr| arr.iter().filter_map(|v| v.as_i64()).collect())
         .unwrap_or_default();
 
-    stats::get_routine_stats(graph, user_id, routine_ids, year, tz).await
+    stats::get_routine_stats(graph, user_id, routine_ids, year, tz, grace_minutes).await
 }
 
 async fn handle_get_rescheduling_stats(
@@ -859,6 +1629,28 @@ async fn handle_generate_routine_events(
     Ok(StatusCode::OK)
 }
 
+// Preview handler – candidate timestamps for a routine that hasn't been saved yet
+async fn handle_preview_routine_events(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Json(request): Json<routine_generator::RoutinePreviewRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    routine_generator::preview_routine_events(&graph, user_id, &request)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+async fn handle_detect_routine_gaps(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    routine_generator::detect_routine_gaps(&graph, user_id)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
 #[derive(serde::Serialize)]
 struct RecomputeResult {
     deleted: i64,
@@ -902,6 +1694,48 @@ fn build_auth_cookie(token: &str) -> String {
     )
 }
 
+// Helper to build HttpOnly refresh token cookie string
+fn build_refresh_cookie(token: &str) -> String {
+    let host_url = std::env::var("HOST_URL").unwrap_or_else(|_| "localhost".to_string());
+    let is_development = host_url == "localhost" || host_url.starts_with("127.0.0.1");
+    let secure_attr = if is_development { "" } else { "; Secure" };
+
+    format!(
+        "refresh_token={}; Max-Age={}; Path=/; HttpOnly; SameSite=None{}",
+        token,
+        auth::refresh_token_expiry_seconds(),
+        secure_attr
+    )
+}
+
+// Set-Cookie headers for a freshly issued access token and (if any) refresh token
+fn build_session_cookies(resp: &auth::AuthResponse) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_str(&build_auth_cookie(&resp.token))
+            .unwrap_or_else(|_| HeaderValue::from_static("")),
+    );
+    if let Some(refresh_token) = &resp.refresh_token {
+        headers.append(
+            header::SET_COOKIE,
+            HeaderValue::from_str(&build_refresh_cookie(refresh_token))
+                .unwrap_or_else(|_| HeaderValue::from_static("")),
+        );
+    }
+    headers
+}
+
+fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    let cookie_header = headers.get(header::COOKIE)?.to_str().ok()?;
+    cookie_header.split(';').find_map(|part| {
+        part.trim()
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+            .map(|value| value.to_string())
+    })
+}
+
 // Google account status handler
 async fn handle_google_status(
     Extension(graph): Extension<Graph>,
@@ -918,13 +1752,28 @@ async fn handle_google_unlink(
     auth::unlink_google_account(&graph, user_id).await
 }
 
-// Logout handler clears the auth cookie
-async fn handle_logout() -> impl IntoResponse {
+// Logout handler revokes the refresh token and clears both session cookies
+async fn handle_logout(
+    Extension(graph): Extension<Graph>,
+    request_headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Some(refresh_token) = cookie_value(&request_headers, "refresh_token") {
+        if let Err(e) = auth::revoke_refresh_token(&graph, &refresh_token).await {
+            eprintln!("⚠️ Failed to revoke refresh token on logout: {}", e);
+        }
+    }
+
     let mut headers = HeaderMap::new();
-    headers.insert(
+    headers.append(
         header::SET_COOKIE,
         HeaderValue::from_static("auth_token=deleted; Max-Age=0; Path=/; HttpOnly; SameSite=None"),
     );
+    headers.append(
+        header::SET_COOKIE,
+        HeaderValue::from_static(
+            "refresh_token=deleted; Max-Age=0; Path=/; HttpOnly; SameSite=None",
+        ),
+    );
     (
         StatusCode::OK,
         headers,
@@ -940,6 +1789,13 @@ async fn handle_list_calendars(
     gcal_client::list_calendars(&graph, user_id).await
 }
 
+async fn handle_get_gcal_sync_status(
+    Extension(graph): Extension<Graph>,
+    Extension(user_id): Extension<i64>,
+) -> Result<Json<gcal_client::GCalSyncStatus>, (StatusCode, String)> {
+    gcal_client::get_gcal_sync_status_handler(graph, user_id).await
+}
+
 async fn handle_sync_from_gcal(
     Extension(graph): Extension<Graph>,
     Extension(user_id): Extension<i64>,
@@ -1118,6 +1974,13 @@ async fn handle_update_notification_settings(
 }
 
 // Theme settings handlers
+async fn handle_list_users(
+    Extension(graph): Extension<Graph>,
+    AdminUser(_): AdminUser,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    admin::list_users_handler(graph).await
+}
+
 async fn handle_get_theme_settings(
     Extension(graph): Extension<Graph>,
     Extension(user_id): Extension<i64>,
@@ -1139,6 +2002,44 @@ async fn handle_update_theme_settings(
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
 }
 
+// Event settings handlers
+async fn handle_get_event_settings(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+) -> Result<Json<event_settings::EventSettings>, (StatusCode, String)> {
+    event_settings::get_event_settings(&graph, user_id)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+async fn handle_update_event_settings(
+    Extension(graph): Extension<Graph>,
+    AuthUser(user_id): AuthUser,
+    Json(settings): Json<event_settings::EventSettings>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    if settings.default_event_duration <= 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "default_event_duration must be a positive number of minutes".to_string(),
+        ));
+    }
+    if let Some(windows) = &settings.energy_windows {
+        event_settings::validate_energy_windows(windows)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+    if let Some(holidays) = &settings.holidays {
+        event_settings::validate_holidays(holidays).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+    if let Some(day) = &settings.week_start_day {
+        event_settings::validate_week_start_day(day).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    }
+    event_settings::update_event_settings(&graph, user_id, settings)
+        .await
+        .map(|_| StatusCode::OK)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
 // Account handlers
 async fn handle_get_account(
     Extension(graph): Extension<Graph>,
@@ -6,109 +6,138 @@ use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tower_http::cors::CorsLayer;
-use tracing::Level;
+use tracing::{debug, error, info, warn};
+use tracing_subscriber::EnvFilter;
 
-use crate::jobs::{gcal_sync_scheduler, notification_scheduler, routine_generator};
+use crate::jobs::{
+    achievement_resetter, deleted_goal_purger, event_move_pruner, gcal_sync_scheduler, notification_scheduler, overdue_event_flagger,
+    routine_generator, task_recurrence,
+};
 use crate::server::db;
+use crate::server::metrics;
 use crate::server::http_handler;
-use crate::tools::migration;
+use crate::tools::{event_settings, migration, stats};
 
 type UserLocks = Arc<Mutex<HashMap<i64, Arc<Mutex<()>>>>>;
 
-pub async fn start_server() -> Result<(), Box<dyn std::error::Error>> {
-    println!("🚀 Starting Goals Backend Server...");
+/// Whether LOG_FORMAT asks for JSON log lines (anything else means human-readable text).
+pub fn log_format_is_json(raw: Option<&str>) -> bool {
+    raw.is_some_and(|v| v.trim().eq_ignore_ascii_case("json"))
+}
 
-    tracing_subscriber::fmt().with_max_level(Level::INFO).init();
-    println!("✅ Tracing subscriber initialized");
+/// Install the global subscriber. Filtering follows RUST_LOG (default "info"), and
+/// LOG_FORMAT=json emits one JSON object per line for log aggregation.
+fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    if log_format_is_json(env::var("LOG_FORMAT").ok().as_deref()) {
+        builder.json().init();
+    } else {
+        builder.init();
+    }
+}
 
-    println!("🔧 Loading environment variables...");
+pub async fn start_server() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
+    init_tracing();
+    info!("Starting Goals backend server");
 
-    // Log environment variables (without sensitive data)
-    println!("🌍 Environment Configuration:");
-    println!(
-        "   NEO4J_URI: {}",
-        env::var("NEO4J_URI").unwrap_or_else(|_| "[NOT SET]".to_string())
-    );
-    println!(
-        "   NEO4J_USERNAME: {}",
-        env::var("NEO4J_USERNAME").unwrap_or_else(|_| "[NOT SET]".to_string())
-    );
-    println!(
-        "   NEO4J_PASSWORD: [{}]",
-        if env::var("NEO4J_PASSWORD").is_ok() {
-            "SET"
-        } else {
-            "NOT SET"
-        }
-    );
-    println!(
-        "   JWT_SECRET: [{}]",
-        if env::var("JWT_SECRET").is_ok() {
+    // Fail fast on a bad DEFAULT_TIMEZONE rather than silently bucketing dates in UTC
+    let default_tz =
+        event_settings::parse_default_timezone(env::var("DEFAULT_TIMEZONE").ok().as_deref())
+            .inspect_err(|e| error!("{}", e))?;
+    info!(default_timezone = %default_tz, "Default timezone configured");
+
+    let stats_max_depth =
+        stats::parse_max_traversal_depth(env::var("STATS_MAX_DEPTH").ok().as_deref())
+            .inspect_err(|e| error!("{}", e))?;
+    info!(stats_max_depth, "Stats traversal depth configured");
+    let stats_grace_minutes = stats::parse_grace_minutes(
+        env::var("STATS_GRACE_MINUTES").ok().as_deref(),
+        stats::DEFAULT_GRACE_PERIOD_MINUTES,
+    )
+    .map_err(|e| format!("STATS_GRACE_MINUTES: {}", e))
+    .inspect_err(|e| error!("{}", e))?;
+    info!(stats_grace_minutes, "Stats grace period configured");
+    let event_move_retention_days = event_move_pruner::parse_event_move_retention_days(
+        env::var("EVENT_MOVE_RETENTION_DAYS").ok().as_deref(),
+    )
+    .inspect_err(|e| error!("{}", e))?;
+    info!(event_move_retention_days, "Event move retention configured");
+
+    // Environment configuration, without sensitive values
+    let set_or_not = |name: &str| {
+        if env::var(name).is_ok() {
             "SET"
         } else {
             "NOT SET"
         }
+    };
+    debug!(
+        neo4j_uri = %env::var("NEO4J_URI").unwrap_or_else(|_| "[NOT SET]".to_string()),
+        neo4j_username = %env::var("NEO4J_USERNAME").unwrap_or_else(|_| "[NOT SET]".to_string()),
+        neo4j_password = set_or_not("NEO4J_PASSWORD"),
+        jwt_secret = set_or_not("JWT_SECRET"),
+        host_url = %env::var("HOST_URL")
+            .unwrap_or_else(|_| "[NOT SET - will use localhost]".to_string()),
+        "Environment configuration"
     );
-    println!(
-        "   HOST_URL: {}",
-        env::var("HOST_URL").unwrap_or_else(|_| "[NOT SET - will use localhost]".to_string())
-    );
-
-    println!("🔄 Creating database connection pool...");
-    println!("   This is the critical step that's been failing...");
 
+    debug!("Creating database connection pool");
     let pool = match db::create_pool().await {
-        Ok(pool) => {
-            println!("✅ Database connection pool created successfully!");
-            pool
-        }
+        Ok(pool) => pool,
         Err(e) => {
-            println!("❌ CRITICAL ERROR: Failed to create database pool!");
-            println!("   Error details: {:?}", e);
-            println!("   Error type: {}", std::any::type_name_of_val(&e));
-
-            // More detailed error analysis
+            // Point operators at the likely cause
             let error_string = format!("{:?}", e);
-            if error_string.contains("Connection refused") {
-                println!("🚨 DIAGNOSIS: Connection refused error detected!");
-                println!("   This typically means:");
-                println!("   - Neo4j database is not running");
-                println!("   - Wrong host/port in NEO4J_URI");
-                println!("   - Docker networking issues");
-                println!("   - Firewall blocking connections");
+            let diagnosis = if error_string.contains("Connection refused") {
+                "connection refused: Neo4j not running, wrong host/port in NEO4J_URI, Docker networking, or a firewall"
             } else if error_string.contains("Network is unreachable") {
-                println!("🚨 DIAGNOSIS: Network unreachable error!");
-                println!("   Check Docker network configuration");
+                "network unreachable: check Docker network configuration"
             } else if error_string.contains("Invalid") {
-                println!("🚨 DIAGNOSIS: Configuration error!");
-                println!("   Check NEO4J_URI format and credentials");
-            }
-
-            eprintln!("Error creating database pool: {}", e);
+                "configuration error: check NEO4J_URI format and credentials"
+            } else {
+                "unknown"
+            };
+            error!(error = ?e, diagnosis, "Failed to create database pool");
             return Err(e.into());
         }
     };
-
-    println!("✅ Database connection pool created successfully");
+    info!("Database connection pool created");
 
     // Run automatic migrations if needed
-    println!("🔄 Checking for pending migrations...");
+    debug!("Checking for pending migrations");
     match migration::run_resolution_status_migration_if_needed(&pool).await {
         Ok(was_run) => {
             if was_run {
-                println!("✅ Migrations completed successfully");
+                info!("Migrations completed successfully");
             }
         }
         Err(e) => {
             // Log the error but don't fail startup - the server can still run
             // with old data, and the user can run migration manually
-            eprintln!("⚠️ Warning: Migration check/run failed: {}", e);
-            eprintln!("   The server will continue to start, but you may need to run migrations manually.");
+            warn!(
+                error = %e,
+                "Migration check/run failed; the server will start, but migrations may need to be run manually"
+            );
         }
     }
+    match migration::backfill_event_versions(&pool).await {
+        Ok(0) => {}
+        Ok(count) => info!(count, "Backfilled version on events"),
+        Err(e) => warn!(error = %e, "Event version backfill failed"),
+    }
+    match migration::backfill_last_modified(&pool).await {
+        Ok(0) => {}
+        Ok(count) => info!(count, "Backfilled last_modified on goals"),
+        Err(e) => warn!(error = %e, "last_modified backfill failed"),
+    }
+    match migration::normalize_priorities(&pool).await {
+        Ok(0) => {}
+        Ok(count) => info!(count, "Normalized goal priorities"),
+        Err(e) => warn!(error = %e, "Priority normalization failed"),
+    }
 
-    println!("🔧 Setting up background job scheduler...");
+    debug!("Setting up background job scheduler");
     // Set up the scheduler for background jobs
     let scheduler = JobScheduler::new().await?;
 
@@ -116,13 +145,18 @@ pub async fn start_server() -> Result<(), Box<dyn std::error::Error>> {
     let scheduler_pool = pool.clone();
     let notification_pool = pool.clone();
     let gcal_sync_pool = pool.clone();
+    let purge_pool = pool.clone();
+    let overdue_pool = pool.clone();
+    let achievement_pool = pool.clone();
+    let prune_pool = pool.clone();
 
     // Schedule routine event generation to run every hour
     let routine_job = Job::new_async("0 0 * * * *", move |_uuid, _l| {
         let pool = scheduler_pool.clone();
         Box::pin(async move {
-            println!("Running scheduled routine event generation...");
-            routine_generator::run_routine_generator(pool).await;
+            debug!("Running scheduled routine event generation");
+            routine_generator::run_routine_generator(pool.clone()).await;
+            task_recurrence::run_task_recurrence_generator(pool).await;
         })
     })?;
 
@@ -144,15 +178,50 @@ pub async fn start_server() -> Result<(), Box<dyn std::error::Error>> {
         })
     })?;
 
+    // Purge soft-deleted goals past their retention window once a day
+    let purge_job = Job::new_async("0 30 3 * * *", move |_uuid, _l| {
+        let pool = purge_pool.clone();
+        Box::pin(async move {
+            deleted_goal_purger::run_deleted_goal_purge(pool).await;
+        })
+    })?;
+
+    // Flag elapsed deadline events as overdue every hour, offset from routine generation
+    let overdue_job = Job::new_async("0 5 * * * *", move |_uuid, _l| {
+        let pool = overdue_pool.clone();
+        Box::pin(async move {
+            overdue_event_flagger::run_overdue_event_flagger(pool).await;
+        })
+    })?;
+
+    // Roll recurring achievements over shortly after each UTC day starts
+    let achievement_job = Job::new_async("0 10 0 * * *", move |_uuid, _l| {
+        let pool = achievement_pool.clone();
+        Box::pin(async move {
+            achievement_resetter::run_achievement_resetter(pool).await;
+        })
+    })?;
+
+    // Drop old move history once a day so the rescheduling stats stay fast
+    let prune_job = Job::new_async("0 45 3 * * *", move |_uuid, _l| {
+        let pool = prune_pool.clone();
+        Box::pin(async move {
+            event_move_pruner::run_event_move_pruner(pool, event_move_retention_days).await;
+        })
+    })?;
+
     scheduler.add(routine_job).await?;
     scheduler.add(notification_job).await?;
     scheduler.add(gcal_sync_job).await?;
+    scheduler.add(purge_job).await?;
+    scheduler.add(overdue_job).await?;
+    scheduler.add(achievement_job).await?;
+    scheduler.add(prune_job).await?;
 
     // Start the scheduler
     scheduler.start().await?;
-    println!("✅ Scheduler started - routines hourly, notifications every minute, GCal sync every 15 minutes");
+    info!("Scheduler started - routines hourly, notifications every minute, GCal sync every 15 minutes, deleted goal purge daily, overdue deadlines hourly, achievement resets daily, event move pruning daily");
 
-    println!("🌐 Configuring CORS and server settings...");
     let host_url = std::env::var("HOST_URL").unwrap_or_else(|_| "localhost".to_string());
 
     // Determine if we're in development or production based on HOST_URL
@@ -169,15 +238,15 @@ pub async fn start_server() -> Result<(), Box<dyn std::error::Error>> {
         vec![format!("https://{}", host_url)]
     };
 
-    println!(
-        "   Environment: {}",
-        if is_development {
-            "Development"
+    info!(
+        environment = if is_development {
+            "development"
         } else {
-            "Production"
-        }
+            "production"
+        },
+        frontend_origins = ?frontend_origins,
+        "Configured CORS"
     );
-    println!("   Frontend origins: {:?}", frontend_origins);
 
     let cors = CorsLayer::new()
         .allow_origin(
@@ -200,17 +269,35 @@ pub async fn start_server() -> Result<(), Box<dyn std::error::Error>> {
         ])
         .allow_credentials(true);
 
-    println!("🔐 Initializing user locks for routine processing...");
     let user_locks: UserLocks = Arc::new(Mutex::new(HashMap::new()));
 
-    println!("🛣️ Setting up HTTP routes...");
-    let app = http_handler::create_routes(pool.clone(), user_locks.clone()).layer(cors);
+    let mut app = http_handler::create_routes(pool.clone(), user_locks.clone()).layer(cors);
+
+    // Expose /metrics unauthenticated. If METRICS_ADDR is set it gets its own listener
+    // (e.g. an internal-only interface) instead of sharing the public port.
+    match env::var("METRICS_ADDR") {
+        Ok(metrics_addr) if !metrics_addr.trim().is_empty() => {
+            let metrics_listener = TcpListener::bind(metrics_addr.trim()).await?;
+            info!(
+                "Metrics available at http://{}/metrics",
+                metrics_addr.trim()
+            );
+            tokio::spawn(async move {
+                if let Err(e) =
+                    axum::serve(metrics_listener, metrics::metrics_routes().into_make_service())
+                        .await
+                {
+                    error!(error = %e, "Metrics server error");
+                }
+            });
+        }
+        _ => {
+            app = app.merge(metrics::metrics_routes());
+        }
+    }
 
-    println!("🔌 Binding to server address...");
     let listener = TcpListener::bind("0.0.0.0:5059").await.unwrap();
-    println!("✅ Server successfully bound to 0.0.0.0:5059");
-    println!("🚀 Server is now listening and ready to accept connections!");
-    println!("   Access the API at: http://localhost:5059");
+    info!("Server listening on 0.0.0.0:5059");
 
     axum::serve(listener, app.into_make_service())
         .await
@@ -0,0 +1,229 @@
+/*
+in-process metrics registry exposed in the Prometheus text format at /metrics
+*/
+use axum::{
+    extract::{MatchedPath, Request},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+// Upper bounds (seconds) shared by every latency histogram
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Default, Clone)]
+struct Histogram {
+    bucket_counts: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (i, upper) in LATENCY_BUCKETS.iter().enumerate() {
+            if seconds <= *upper {
+                self.bucket_counts[i] += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    // (method, route, status) -> count
+    http_requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    // (method, route) -> latency
+    http_latency: Mutex<BTreeMap<(String, String), Histogram>>,
+    // (source, outcome) -> count
+    ai_calls: Mutex<BTreeMap<(String, String), u64>>,
+    // routine generator operation -> Neo4j latency
+    routine_generator_db_latency: Mutex<BTreeMap<String, Histogram>>,
+    routine_events_generated: AtomicU64,
+}
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::default);
+
+pub fn record_http_request(method: &str, route: &str, status: u16, elapsed: Duration) {
+    if let Ok(mut requests) = REGISTRY.http_requests.lock() {
+        *requests
+            .entry((method.to_string(), route.to_string(), status))
+            .or_insert(0) += 1;
+    }
+    if let Ok(mut latency) = REGISTRY.http_latency.lock() {
+        latency
+            .entry((method.to_string(), route.to_string()))
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+}
+
+pub fn record_ai_call(source: &str, success: bool) {
+    let outcome = if success { "success" } else { "error" };
+    if let Ok(mut calls) = REGISTRY.ai_calls.lock() {
+        *calls
+            .entry((source.to_string(), outcome.to_string()))
+            .or_insert(0) += 1;
+    }
+}
+
+pub fn add_routine_events_generated(count: u64) {
+    REGISTRY
+        .routine_events_generated
+        .fetch_add(count, Ordering::Relaxed);
+}
+
+pub fn observe_routine_generator_query(operation: &str, elapsed: Duration) {
+    if let Ok(mut latency) = REGISTRY.routine_generator_db_latency.lock() {
+        latency
+            .entry(operation.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+}
+
+/// Await one of the routine generator's Neo4j calls and record how long it took under
+/// `operation`. Request-path queries aren't timed here; their cost shows up in the
+/// per-route HTTP latency instead.
+pub async fn time_routine_generator_query<F: Future>(operation: &str, fut: F) -> F::Output {
+    let start = Instant::now();
+    let output = fut.await;
+    observe_routine_generator_query(operation, start.elapsed());
+    output
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn write_histogram(out: &mut String, name: &str, labels: &str, histogram: &Histogram) {
+    let separator = if labels.is_empty() { "" } else { "," };
+    for (i, upper) in LATENCY_BUCKETS.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}{}le=\"{}\"}} {}",
+            name, labels, separator, upper, histogram.bucket_counts[i]
+        );
+    }
+    let _ = writeln!(
+        out,
+        "{}_bucket{{{}{}le=\"+Inf\"}} {}",
+        name, labels, separator, histogram.count
+    );
+    let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, histogram.sum);
+    let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, histogram.count);
+}
+
+/// Render every metric in the Prometheus text exposition format.
+pub fn render() -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP http_requests_total HTTP requests by method, route and status.\n");
+    out.push_str("# TYPE http_requests_total counter\n");
+    if let Ok(requests) = REGISTRY.http_requests.lock() {
+        for ((method, route, status), count) in requests.iter() {
+            let _ = writeln!(
+                out,
+                "http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                escape_label(method),
+                escape_label(route),
+                status,
+                count
+            );
+        }
+    }
+
+    out.push_str("# HELP http_request_duration_seconds HTTP request latency.\n");
+    out.push_str("# TYPE http_request_duration_seconds histogram\n");
+    if let Ok(latency) = REGISTRY.http_latency.lock() {
+        for ((method, route), histogram) in latency.iter() {
+            let labels = format!(
+                "method=\"{}\",route=\"{}\"",
+                escape_label(method),
+                escape_label(route)
+            );
+            write_histogram(&mut out, "http_request_duration_seconds", &labels, histogram);
+        }
+    }
+
+    out.push_str("# HELP ai_calls_total Calls to external AI providers by source and outcome.\n");
+    out.push_str("# TYPE ai_calls_total counter\n");
+    if let Ok(calls) = REGISTRY.ai_calls.lock() {
+        for ((source, outcome), count) in calls.iter() {
+            let _ = writeln!(
+                out,
+                "ai_calls_total{{source=\"{}\",outcome=\"{}\"}} {}",
+                escape_label(source),
+                escape_label(outcome),
+                count
+            );
+        }
+    }
+
+    out.push_str("# HELP routine_events_generated_total Events created by the routine generator.\n");
+    out.push_str("# TYPE routine_events_generated_total counter\n");
+    let _ = writeln!(
+        out,
+        "routine_events_generated_total {}",
+        REGISTRY.routine_events_generated.load(Ordering::Relaxed)
+    );
+
+    out.push_str(
+        "# HELP routine_generator_query_duration_seconds Neo4j latency of the routine generator's queries by operation.\n",
+    );
+    out.push_str("# TYPE routine_generator_query_duration_seconds histogram\n");
+    if let Ok(latency) = REGISTRY.routine_generator_db_latency.lock() {
+        for (operation, histogram) in latency.iter() {
+            let labels = format!("operation=\"{}\"", escape_label(operation));
+            write_histogram(
+                &mut out,
+                "routine_generator_query_duration_seconds",
+                &labels,
+                histogram,
+            );
+        }
+    }
+
+    out
+}
+
+/// Middleware recording request counts and latency. Routes are labelled by their
+/// matched path template (e.g. `/events/:id/complete`) to keep cardinality bounded.
+pub async fn track_metrics(request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(request).await;
+    record_http_request(&method, &route, response.status().as_u16(), start.elapsed());
+    response
+}
+
+async fn handle_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render(),
+    )
+}
+
+pub fn metrics_routes() -> Router {
+    Router::new().route("/metrics", get(handle_metrics))
+}
@@ -1,6 +1,7 @@
 use axum::{
-    extract::Request, // Removed unused WebSocketUpgrade
-    http::header,
+    async_trait,
+    extract::{FromRequestParts, Request}, // Removed unused WebSocketUpgrade
+    http::{header, request::Parts, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -11,6 +12,45 @@ use urlencoding;
 
 use crate::server::auth::Claims;
 
+/// The authenticated user's id, resolved once by `auth_middleware`.
+///
+/// Handlers take this instead of `Extension<i64>` so the id is always at hand when
+/// building queries. On a route mounted without the middleware the extractor rejects
+/// with 401 rather than letting the handler run unscoped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthUser(pub i64);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AuthUser {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<AuthUser>()
+            .copied()
+            .ok_or((StatusCode::UNAUTHORIZED, "Unauthorized"))
+    }
+}
+
+/// The authenticated user's id, present only when the token carries the admin claim.
+/// Admin-only handlers take this so non-admins get 403 before the handler runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdminUser(pub i64);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AdminUser {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<AdminUser>()
+            .copied()
+            .ok_or((StatusCode::FORBIDDEN, "Admin access required"))
+    }
+}
+
 pub async fn auth_middleware(mut request: Request, next: Next) -> Result<Response, Response> {
     // Get the token either from Authorization header or query parameter for WebSocket
     let token = match get_token_from_request(&request) {
@@ -37,8 +77,12 @@ pub async fn auth_middleware(mut request: Request, next: Next) -> Result<Respons
     // Extract the user ID from the validated token
     let user_id = token_data.claims.user_id;
 
-    // Add the user ID to the request extensions
+    // Add the user ID to the request extensions, both raw and as the typed extractor
     request.extensions_mut().insert(user_id);
+    request.extensions_mut().insert(AuthUser(user_id));
+    if token_data.claims.is_admin {
+        request.extensions_mut().insert(AdminUser(user_id));
+    }
 
     // Log the authenticated request
     info!("Authenticated request for user ID: {}", user_id);
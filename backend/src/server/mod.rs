@@ -2,5 +2,6 @@ pub mod auth;
 pub mod db;
 pub mod http_handler;
 pub mod main;
+pub mod metrics;
 pub mod middleware;
 pub mod token_manager;
@@ -1,8 +1,130 @@
 use axum::{http::StatusCode, Json};
+use chrono::{DateTime, Datelike, TimeZone, Utc};
 use neo4rs::{query, Graph};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::tools::goal::GOAL_RETURN_QUERY;
+use crate::tools::goal::{self, Goal, GoalType, GOAL_RETURN_QUERY};
+
+#[derive(Debug, Deserialize)]
+pub struct IncrementAchievementProgressRequest {
+    pub amount: Option<f64>, // defaults to 1
+}
+
+#[derive(Debug, Serialize)]
+pub struct AchievementProgressResponse {
+    pub goal: Goal,
+    pub progress: Option<f64>, // current / target, capped at 1.0; None without a target
+    pub completed: bool,       // true if this increment reached the target
+}
+
+#[derive(Debug, Serialize)]
+pub struct AchievementSnapshot {
+    pub period_start: i64,
+    pub period_end: i64,
+    pub final_value: f64,
+    pub target_value: Option<f64>,
+    pub resolution_status: String, // status the achievement had when the period closed
+}
+
+/// Start (UTC, ms) of the reset period containing `now`, or None for an unknown period.
+pub fn reset_period_start(period: &str, now: DateTime<Utc>) -> Option<i64> {
+    let month = match period {
+        "yearly" => 1,
+        "monthly" => now.month(),
+        _ => return None,
+    };
+    Utc.with_ymd_and_hms(now.year(), month, 1, 0, 0, 0)
+        .single()
+        .map(|start| start.timestamp_millis())
+}
+
+/// Fraction of the target reached, capped at 1.0. None when there's no usable target.
+pub fn achievement_progress(current_value: Option<f64>, target_value: Option<f64>) -> Option<f64> {
+    let target = target_value.filter(|t| *t > 0.0)?;
+    Some((current_value.unwrap_or(0.0) / target).clamp(0.0, 1.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastStatus {
+    OnTrack,
+    AtRisk,
+    Behind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgressForecast {
+    pub status: ForecastStatus,
+    pub required_pace_per_day: f64, // remaining amount / days left; 0 once the target is met
+    pub observed_pace_per_day: f64, // progress so far / days elapsed in the window
+    pub projected_completion: Option<i64>, // None when no progress has been made yet
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalForecast {
+    pub goal_id: i64,
+    pub target_value: f64,
+    pub current_value: f64,
+    pub deadline: i64,
+    #[serde(flatten)]
+    pub forecast: ProgressForecast,
+}
+
+const DAY_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+// Keeping up at least this share of the required pace counts as at risk rather than behind
+const AT_RISK_PACE_RATIO: f64 = 0.8;
+
+/// Project when `target` will be reached if progress keeps the pace it has had since
+/// `window_start`, and compare that pace with what's needed to make `deadline`.
+pub fn forecast_progress(
+    current: f64,
+    target: f64,
+    window_start: i64,
+    deadline: i64,
+    now: i64,
+) -> ProgressForecast {
+    let remaining = (target - current).max(0.0);
+    // At least a day, so a window that just opened doesn't project a wild pace
+    let elapsed_days = ((now - window_start) as f64 / DAY_MS).max(1.0);
+    let observed = current.max(0.0) / elapsed_days;
+
+    if remaining == 0.0 {
+        return ProgressForecast {
+            status: ForecastStatus::OnTrack,
+            required_pace_per_day: 0.0,
+            observed_pace_per_day: observed,
+            projected_completion: Some(now),
+        };
+    }
+
+    let projected_completion =
+        (observed > 0.0).then(|| now + (remaining / observed * DAY_MS) as i64);
+    if now >= deadline {
+        return ProgressForecast {
+            status: ForecastStatus::Behind,
+            required_pace_per_day: remaining,
+            observed_pace_per_day: observed,
+            projected_completion,
+        };
+    }
+
+    let required = remaining / ((deadline - now) as f64 / DAY_MS).max(1.0);
+    let status = if observed >= required {
+        ForecastStatus::OnTrack
+    } else if observed >= required * AT_RISK_PACE_RATIO {
+        ForecastStatus::AtRisk
+    } else {
+        ForecastStatus::Behind
+    };
+
+    ProgressForecast {
+        status,
+        required_pace_per_day: required,
+        observed_pace_per_day: observed,
+        projected_completion,
+    }
+}
 
 pub async fn get_achievements_data(
     graph: Graph,
@@ -13,6 +135,7 @@ pub async fn get_achievements_data(
     let achievements_query = query(&format!(
         "MATCH (g:Goal)
          WHERE g.user_id = $user_id AND g.goal_type = 'achievement'
+         AND coalesce(g.is_deleted, false) <> true
          ORDER BY
            CASE
              WHEN g.resolution_status IS NULL OR g.resolution_status = 'pending' THEN 0
@@ -40,10 +163,187 @@ pub async fn get_achievements_data(
             format!("Error processing row: {}", e),
         )
     })? {
-        if let Ok(achievement) = row.get::<Value>("g") {
+        if let Ok(mut achievement) = row.get::<Value>("g") {
+            let progress = achievement_progress(
+                achievement.get("current_value").and_then(Value::as_f64),
+                achievement.get("target_value").and_then(Value::as_f64),
+            );
+            if let Some(obj) = achievement.as_object_mut() {
+                obj.insert("progress".to_string(), serde_json::json!(progress));
+            }
             achievements.push(achievement);
         }
     }
 
     Ok((StatusCode::OK, Json(serde_json::json!(achievements))))
-} 
\ No newline at end of file
+}
+
+/// Add `amount` to an achievement's current_value. Reaching target_value marks a pending
+/// achievement completed; progress past the target is still recorded.
+pub async fn increment_achievement_progress_handler(
+    graph: Graph,
+    user_id: i64,
+    goal_id: i64,
+    amount: f64,
+) -> Result<Json<AchievementProgressResponse>, (StatusCode, String)> {
+    if !amount.is_finite() {
+        return Err((StatusCode::BAD_REQUEST, "amount must be a number".to_string()));
+    }
+
+    let increment_query = query(
+        "MATCH (g:Goal)
+         WHERE id(g) = $goal_id
+         AND g.user_id = $user_id
+         AND g.goal_type = 'achievement'
+         AND coalesce(g.is_deleted, false) <> true
+         WITH g, coalesce(g.resolution_status, 'pending') = 'pending' as was_pending
+         SET g.current_value = coalesce(g.current_value, 0.0) + $amount,
+             g.updated_at = timestamp(),
+             g.last_modified = timestamp()
+         WITH g, was_pending,
+              was_pending AND g.target_value IS NOT NULL
+                AND g.current_value >= g.target_value as reached
+         SET g.resolution_status = CASE WHEN reached THEN 'completed' ELSE g.resolution_status END,
+             g.resolved_at = CASE WHEN reached THEN timestamp() ELSE g.resolved_at END
+         RETURN reached",
+    )
+    .param("goal_id", goal_id)
+    .param("user_id", user_id)
+    .param("amount", amount);
+
+    let mut result = graph.execute(increment_query).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+
+    let completed = match result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        Some(row) => row.get::<bool>("reached").unwrap_or(false),
+        None => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                format!("Achievement with id {} not found", goal_id),
+            ))
+        }
+    };
+
+    let (_, Json(goal)) = goal::get_goal_handler(graph, user_id, goal_id).await?;
+    let progress = achievement_progress(goal.current_value, goal.target_value);
+
+    Ok(Json(AchievementProgressResponse {
+        goal,
+        progress,
+        completed,
+    }))
+}
+
+/// Values recorded at each past reset of a recurring achievement, most recent first.
+pub async fn get_achievement_history(
+    graph: Graph,
+    user_id: i64,
+    goal_id: i64,
+) -> Result<Json<Vec<AchievementSnapshot>>, (StatusCode, String)> {
+    let history_query = query(
+        "MATCH (g:Goal)
+         WHERE id(g) = $goal_id
+         AND g.user_id = $user_id
+         AND g.goal_type = 'achievement'
+         OPTIONAL MATCH (g)-[:HAS_SNAPSHOT]->(s:AchievementSnapshot)
+         RETURN s.period_start as period_start, s.period_end as period_end,
+                s.final_value as final_value, s.target_value as target_value,
+                s.resolution_status as resolution_status
+         ORDER BY s.period_start DESC",
+    )
+    .param("goal_id", goal_id)
+    .param("user_id", user_id);
+
+    let mut result = graph.execute(history_query).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {}", e),
+        )
+    })?;
+
+    let mut found = false;
+    let mut history = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        found = true;
+        // The OPTIONAL MATCH yields a single all-null row when there is no history yet
+        if let (Ok(period_start), Ok(period_end)) =
+            (row.get::<i64>("period_start"), row.get::<i64>("period_end"))
+        {
+            history.push(AchievementSnapshot {
+                period_start,
+                period_end,
+                final_value: row.get("final_value").unwrap_or(0.0),
+                target_value: row.get("target_value").ok(),
+                resolution_status: row
+                    .get("resolution_status")
+                    .unwrap_or_else(|_| "pending".to_string()),
+            });
+        }
+    }
+
+    if !found {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Achievement with id {} not found", goal_id),
+        ));
+    }
+
+    Ok(Json(history))
+}
+
+/// Whether an achievement will reach its target by its end date at the pace of progress so
+/// far. Recurring achievements measure that pace from the start of the current period.
+pub async fn get_goal_forecast(
+    graph: Graph,
+    user_id: i64,
+    goal_id: i64,
+) -> Result<Json<GoalForecast>, (StatusCode, String)> {
+    let (_, Json(goal)) = goal::get_goal_handler(graph, user_id, goal_id).await?;
+    if goal.goal_type != GoalType::Achievement {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Forecasts are only available for achievements".to_string(),
+        ));
+    }
+    let (Some(target), Some(deadline)) =
+        (goal.target_value.filter(|t| *t > 0.0), goal.end_timestamp)
+    else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Achievement needs a target_value and an end date to forecast".to_string(),
+        ));
+    };
+    let window_start = goal
+        .last_reset_at
+        .into_iter()
+        .chain(goal.start_timestamp)
+        .max()
+        .unwrap_or(deadline);
+    let current = goal.current_value.unwrap_or(0.0);
+
+    Ok(Json(GoalForecast {
+        goal_id,
+        target_value: target,
+        current_value: current,
+        deadline,
+        forecast: forecast_progress(
+            current,
+            target,
+            window_start,
+            deadline,
+            Utc::now().timestamp_millis(),
+        ),
+    }))
+}
@@ -0,0 +1,63 @@
+use axum::{http::StatusCode, Json};
+use neo4rs::{query, Graph};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct UserUsage {
+    pub user_id: i64,
+    pub username: Option<String>,
+    pub goal_count: i64,
+    pub event_count: i64,
+    pub last_activity: Option<i64>, // latest of past event times and resolution times
+    pub gcal_linked: bool,
+}
+
+/// Per-user usage for operating the service. Callers must already be gated as admin.
+pub async fn list_users_handler(
+    graph: Graph,
+) -> Result<Json<Vec<UserUsage>>, (StatusCode, String)> {
+    let query_str = "
+        MATCH (u:User)
+        OPTIONAL MATCH (g:Goal)
+        WHERE g.user_id = id(u) AND coalesce(g.is_deleted, false) <> true
+        WITH u,
+             count(CASE WHEN g.goal_type <> 'event' THEN 1 END) as goal_count,
+             count(CASE WHEN g.goal_type = 'event' THEN 1 END) as event_count,
+             max(CASE WHEN g.goal_type = 'event' AND g.scheduled_timestamp <= timestamp()
+                      THEN g.scheduled_timestamp END) as last_event,
+             max(g.resolved_at) as last_resolved
+        RETURN id(u) as user_id,
+               u.username as username,
+               goal_count,
+               event_count,
+               CASE WHEN last_resolved IS NULL OR last_event > last_resolved
+                    THEN last_event ELSE last_resolved END as last_activity,
+               u.google_refresh_token IS NOT NULL as gcal_linked
+        ORDER BY user_id
+    ";
+
+    let mut result = graph
+        .execute(query(query_str))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut users = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        users.push(UserUsage {
+            user_id: row
+                .get("user_id")
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+            username: row.get("username").ok(),
+            goal_count: row.get("goal_count").unwrap_or(0),
+            event_count: row.get("event_count").unwrap_or(0),
+            last_activity: row.get("last_activity").ok(),
+            gcal_linked: row.get("gcal_linked").unwrap_or(false),
+        });
+    }
+
+    Ok(Json(users))
+}
@@ -4,7 +4,7 @@ use chrono::{Duration, Utc};
 use neo4rs::{query, Graph};
 use serde::Serialize;
 
-use crate::tools::goal::{Goal, GOAL_RETURN_QUERY};
+use crate::tools::goal::{goal_from_row_lenient, Goal, GOAL_RETURN_QUERY};
 
 #[derive(Debug, Serialize)]
 pub struct CalendarData {
@@ -13,6 +13,7 @@ pub struct CalendarData {
     routines: Vec<Goal>,     // Keep for reference if needed
     achievements: Vec<Goal>, // Keep for reference if needed
     parents: Vec<Goal>,      // Parent tasks/routines for events
+    skipped_goals: usize,    // Malformed nodes left out of the lists above
 }
 
 #[allow(dead_code)]
@@ -105,6 +106,7 @@ pub async fn get_calendar_data(
 
     let mut events = Vec::new();
     let mut parent_ids = Vec::new();
+    let mut skipped_goals = 0;
 
     while let Some(row) = events_result.next().await.map_err(|e| {
         (
@@ -112,12 +114,10 @@ pub async fn get_calendar_data(
             format!("Error fetching event row: {}", e),
         )
     })? {
-        let event: Goal = row.get("g").map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Error deserializing event: {}", e),
-            )
-        })?;
+        let Some(event) = goal_from_row_lenient(&row, "g") else {
+            skipped_goals += 1;
+            continue;
+        };
 
         if let Some(parent_id) = event.parent_id {
             parent_ids.push(parent_id);
@@ -150,12 +150,10 @@ pub async fn get_calendar_data(
                 format!("Error fetching parent row: {}", e),
             )
         })? {
-            let parent: Goal = row.get("g").map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Error deserializing parent: {}", e),
-                )
-            })?;
+            let Some(parent) = goal_from_row_lenient(&row, "g") else {
+                skipped_goals += 1;
+                continue;
+            };
             parents.push(parent);
         }
         parents
@@ -191,12 +189,10 @@ pub async fn get_calendar_data(
             format!("Error fetching unscheduled task row: {}", e),
         )
     })? {
-        let task: Goal = row.get("g").map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Error deserializing task: {}", e),
-            )
-        })?;
+        let Some(task) = goal_from_row_lenient(&row, "g") else {
+            skipped_goals += 1;
+            continue;
+        };
         unscheduled_tasks.push(task);
     }
 
@@ -206,6 +202,7 @@ pub async fn get_calendar_data(
         WHERE g.user_id = $user_id
         AND g.goal_type = 'routine'
         AND (g.end_timestamp IS NULL OR g.end_timestamp >= $now)
+        AND coalesce(g.is_deleted, false) <> true
         {}
         ORDER BY g.name ASC",
         GOAL_RETURN_QUERY
@@ -229,12 +226,10 @@ pub async fn get_calendar_data(
             format!("Error fetching routine row: {}", e),
         )
     })? {
-        let routine: Goal = row.get("g").map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Error deserializing routine: {}", e),
-            )
-        })?;
+        let Some(routine) = goal_from_row_lenient(&row, "g") else {
+            skipped_goals += 1;
+            continue;
+        };
         routines.push(routine);
     }
 
@@ -244,5 +239,75 @@ pub async fn get_calendar_data(
         routines,
         achievements: vec![], // Keep empty for now, can populate if needed
         parents,
+        skipped_goals,
     }))
 }
+
+// Keep a single fetch to about a year of events
+pub const MAX_EVENT_RANGE_DAYS: i64 = 366;
+
+/// Every live event of the user's scheduled in `start..=end`, whatever its parent, oldest
+/// first. Completed events are left out unless `include_completed` is set.
+pub async fn get_events_in_range_handler(
+    graph: Graph,
+    user_id: i64,
+    start: i64,
+    end: i64,
+    include_completed: bool,
+) -> Result<Json<Vec<Goal>>, (StatusCode, String)> {
+    if start >= end {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "start must be before end".to_string(),
+        ));
+    }
+    if end - start > MAX_EVENT_RANGE_DAYS * 24 * 60 * 60 * 1000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Event ranges are limited to {} days", MAX_EVENT_RANGE_DAYS),
+        ));
+    }
+
+    let query_str = format!(
+        "MATCH (g:Goal)
+        WHERE g.user_id = $user_id
+        AND g.goal_type = 'event'
+        AND coalesce(g.is_deleted, false) <> true
+        AND g.scheduled_timestamp >= $start
+        AND g.scheduled_timestamp <= $end
+        AND ($include_completed OR coalesce(g.resolution_status, 'pending') <> 'completed')
+        {}
+        ORDER BY g.scheduled_timestamp ASC",
+        GOAL_RETURN_QUERY
+    );
+
+    let mut result = graph
+        .execute(
+            query(&query_str)
+                .param("user_id", user_id)
+                .param("start", start)
+                .param("end", end)
+                .param("include_completed", include_completed),
+        )
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch events: {}", e),
+            )
+        })?;
+
+    let mut events = Vec::new();
+    while let Some(row) = result.next().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Error fetching event row: {}", e),
+        )
+    })? {
+        if let Some(event) = goal_from_row_lenient(&row, "g") {
+            events.push(event);
+        }
+    }
+
+    Ok(Json(events))
+}
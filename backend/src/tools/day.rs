@@ -1,8 +1,147 @@
 use axum::{extract::Json, http::StatusCode};
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use chrono_tz::Tz;
 use neo4rs::{query, Graph};
+use serde::Serialize;
 use serde_json::Value;
 
+use crate::tools::{event_settings, stats};
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+// Longest window the upcoming-incomplete listing will scan in one request
+pub const MAX_UPCOMING_DAYS: i64 = 31;
+
+#[derive(Debug, Serialize)]
+pub struct WeekPlanDay {
+    pub date: String, // "YYYY-MM-DD" (UTC)
+    pub day_start: i64,
+    pub events: Vec<Value>,
+    pub total_events: usize,
+    pub completed_events: usize,
+    pub scheduled_minutes: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeekPlan {
+    pub week_start: i64,
+    pub week_start_day: String, // "sunday" or "monday"
+    pub days: Vec<WeekPlanDay>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IncompleteEvent {
+    pub id: i64,
+    pub name: String,
+    pub scheduled_timestamp: i64,
+    pub duration: Option<i64>,
+    pub priority: String,
+    pub resolution_status: String, // "pending" or "overdue"
+    pub parent_id: i64,
+    pub parent_name: String,
+    pub parent_type: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IncompleteDay {
+    pub date: String, // "YYYY-MM-DD" in the requested timezone
+    pub events: Vec<IncompleteEvent>,
+}
+
+/// Start (UTC midnight, in ms) of the week containing `timestamp`.
+pub fn align_week_start(timestamp: i64, monday_start: bool) -> i64 {
+    let date = Utc
+        .timestamp_millis_opt(timestamp)
+        .single()
+        .unwrap_or_else(Utc::now)
+        .date_naive();
+    let offset = if monday_start {
+        date.weekday().num_days_from_monday()
+    } else {
+        date.weekday().num_days_from_sunday()
+    };
+    (date - Duration::days(offset as i64))
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+        .timestamp_millis()
+}
+
+/// Group events into seven day buckets starting at `week_start`, with per-day totals.
+/// Events outside the week are dropped.
+pub fn bucket_week_events(week_start: i64, events: Vec<Value>) -> Vec<WeekPlanDay> {
+    let mut days: Vec<WeekPlanDay> = (0..7)
+        .map(|i| {
+            let day_start = week_start + i * DAY_MS;
+            WeekPlanDay {
+                date: Utc
+                    .timestamp_millis_opt(day_start)
+                    .single()
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                day_start,
+                events: Vec::new(),
+                total_events: 0,
+                completed_events: 0,
+                scheduled_minutes: 0,
+            }
+        })
+        .collect();
+
+    for event in events {
+        let Some(ts) = event.get("scheduled_timestamp").and_then(|v| v.as_i64()) else {
+            continue;
+        };
+        if ts < week_start {
+            continue;
+        }
+        let index = ((ts - week_start) / DAY_MS) as usize;
+        let Some(day) = days.get_mut(index) else {
+            continue;
+        };
+        day.total_events += 1;
+        if event.get("resolution_status").and_then(|v| v.as_str()) == Some("completed") {
+            day.completed_events += 1;
+        }
+        day.scheduled_minutes += event.get("duration").and_then(|v| v.as_i64()).unwrap_or(0);
+        day.events.push(event);
+    }
+
+    days
+}
+
+/// First and last millisecond of the local calendar day containing `now` in `tz`.
+pub fn local_day_bounds(tz: Tz, now: DateTime<Utc>) -> (i64, i64) {
+    let date = now.with_timezone(&tz).date_naive();
+    let midnight = |d: chrono::NaiveDate| {
+        tz.from_local_datetime(&d.and_hms_opt(0, 0, 0).unwrap())
+            .earliest()
+            .map(|dt| dt.timestamp_millis())
+            .unwrap_or_else(|| d.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis())
+    };
+    (midnight(date), midnight(date + Duration::days(1)) - 1)
+}
+
+/// Group time-ordered events by their local calendar date in `tz`. Only days that have
+/// events are returned, in date order.
+pub fn group_incomplete_by_day(events: Vec<IncompleteEvent>, tz: Tz) -> Vec<IncompleteDay> {
+    let mut days: Vec<IncompleteDay> = Vec::new();
+    for event in events {
+        let Some(local) = tz.timestamp_millis_opt(event.scheduled_timestamp).single() else {
+            continue;
+        };
+        let date = local.format("%Y-%m-%d").to_string();
+        match days.last_mut() {
+            Some(day) if day.date == date => day.events.push(event),
+            _ => days.push(IncompleteDay {
+                date,
+                events: vec![event],
+            }),
+        }
+    }
+    days
+}
+
 // Business logic functions with regular parameters
 pub async fn get_day_tasks(
     graph: Graph,
@@ -10,26 +149,13 @@ pub async fn get_day_tasks(
     start_timestamp: Option<i64>,
     end_timestamp: Option<i64>,
 ) -> Result<Json<Vec<Value>>, (StatusCode, String)> {
+    // Without explicit bounds, show "today" in the deployment's default timezone
+    let (today_start, today_end) = local_day_bounds(event_settings::default_timezone(), Utc::now());
     let start_timestamp = start_timestamp.unwrap_or_else(|| {
         println!("No start timestamp provided");
-        // Default to start of current UTC day if not provided
-        Utc::now()
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_utc()
-            .timestamp_millis()
-    });
-
-    let end_timestamp = end_timestamp.unwrap_or_else(|| {
-        // Default to end of current UTC day if not provided
-        Utc::now()
-            .date_naive()
-            .and_hms_opt(23, 59, 59)
-            .unwrap()
-            .and_utc()
-            .timestamp_millis()
+        today_start
     });
+    let end_timestamp = end_timestamp.unwrap_or(today_end);
 
     println!("Query Parameters:");
     println!("  user_id: {}", user_id);
@@ -57,6 +183,9 @@ pub async fn get_day_tasks(
             id: id(e),
             name: e.name,
             description: e.description,
+            notes: e.notes,
+            links: e.links,
+            allow_overlap: e.allow_overlap,
             goal_type: 'event',
             priority: COALESCE(e.priority, g.priority, 'medium'),
             color: COALESCE(e.color, g.color),
@@ -96,8 +225,118 @@ pub async fn get_day_tasks(
     }
 }
 
+/// Seven-day plan for the week containing `week_start_timestamp` (defaults to now),
+/// aligned to the user's week-start-day setting.
+pub async fn get_week_plan_handler(
+    graph: Graph,
+    user_id: i64,
+    week_start_timestamp: Option<i64>,
+) -> Result<Json<WeekPlan>, (StatusCode, String)> {
+    let monday_start = event_settings::week_starts_on_monday(&graph, user_id).await;
+    let week_start = align_week_start(
+        week_start_timestamp.unwrap_or_else(|| Utc::now().timestamp_millis()),
+        monday_start,
+    );
+    let week_end = week_start + 7 * DAY_MS - 1;
+
+    let Json(events) = get_day_tasks(graph, user_id, Some(week_start), Some(week_end)).await?;
+
+    Ok(Json(WeekPlan {
+        week_start,
+        week_start_day: if monday_start { "monday" } else { "sunday" }.to_string(),
+        days: bucket_week_events(week_start, events),
+    }))
+}
+
+/// Events in `[start, end]` that still need doing (pending or overdue, not deleted), with
+/// their parent's name, grouped by local day. Unlike the day plan this spans several days and
+/// leaves resolved events out; it feeds the upcoming-work digest.
+pub async fn get_upcoming_incomplete_handler(
+    graph: Graph,
+    user_id: i64,
+    start: i64,
+    end: i64,
+    tz: Tz,
+) -> Result<Json<Vec<IncompleteDay>>, (StatusCode, String)> {
+    if end < start {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "end must not be before start".to_string(),
+        ));
+    }
+    if end - start > MAX_UPCOMING_DAYS * DAY_MS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Ranges are limited to {} days", MAX_UPCOMING_DAYS),
+        ));
+    }
+
+    // One range-scoped query for the whole window; grouping happens in memory
+    let query = query(
+        "MATCH (g:Goal)-[:HAS_EVENT]->(e:Goal)
+         WHERE e.goal_type = 'event'
+         AND g.user_id = $user_id
+         AND e.scheduled_timestamp >= $start
+         AND e.scheduled_timestamp <= $end
+         AND coalesce(e.is_deleted, false) <> true
+         AND coalesce(e.resolution_status, 'pending') IN ['pending', 'overdue']
+         RETURN id(e) as id,
+                e.name as name,
+                e.scheduled_timestamp as scheduled_timestamp,
+                e.duration as duration,
+                coalesce(e.priority, g.priority, 'medium') as priority,
+                coalesce(e.resolution_status, 'pending') as resolution_status,
+                id(g) as parent_id,
+                g.name as parent_name,
+                g.goal_type as parent_type
+         ORDER BY e.scheduled_timestamp, id(e)",
+    )
+    .param("user_id", user_id)
+    .param("start", start)
+    .param("end", end);
+
+    let mut result = graph.execute(query).await.map_err(|e| {
+        eprintln!("Error fetching upcoming incomplete events: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch events: {}", e),
+        )
+    })?;
+
+    let mut events = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        let (Ok(id), Ok(scheduled_timestamp), Ok(parent_id)) = (
+            row.get::<i64>("id"),
+            row.get::<i64>("scheduled_timestamp"),
+            row.get::<i64>("parent_id"),
+        ) else {
+            continue;
+        };
+        events.push(IncompleteEvent {
+            id,
+            name: row.get("name").unwrap_or_default(),
+            scheduled_timestamp,
+            duration: row.get("duration").ok(),
+            priority: row.get("priority").unwrap_or_else(|_| "medium".to_string()),
+            resolution_status: row
+                .get("resolution_status")
+                .unwrap_or_else(|_| "pending".to_string()),
+            parent_id,
+            parent_name: row.get("parent_name").unwrap_or_default(),
+            parent_type: row.get("parent_type").unwrap_or_default(),
+        });
+    }
+
+    Ok(Json(group_incomplete_by_day(events, tz)))
+}
+
 pub async fn toggle_complete_task(
     graph: Graph,
+    user_id: i64,
     id: i64,
 ) -> Result<StatusCode, (StatusCode, String)> {
     let now = Utc::now().timestamp_millis();
@@ -107,6 +346,7 @@ pub async fn toggle_complete_task(
     let query = query(
         "MATCH (e:Goal) 
          WHERE id(e) = $id 
+         AND e.user_id = $user_id
          AND e.goal_type = 'event'
          SET e.resolution_status = CASE 
              WHEN e.resolution_status = 'completed' THEN 'pending' 
@@ -115,14 +355,28 @@ pub async fn toggle_complete_task(
          e.resolved_at = CASE 
              WHEN e.resolution_status = 'completed' THEN null 
              ELSE $resolved_at 
-         END
-         RETURN e",
+         END,
+         e.last_modified = timestamp()
+         RETURN e.scheduled_timestamp as scheduled_timestamp",
     )
     .param("id", id)
+    .param("user_id", user_id)
     .param("resolved_at", now);
 
-    match graph.run(query).await {
-        Ok(_) => Ok(StatusCode::OK),
+    match graph.execute(query).await {
+        Ok(mut result) => match result.next().await {
+            Ok(Some(row)) => {
+                if let Ok(timestamp) = row.get::<i64>("scheduled_timestamp") {
+                    stats::invalidate_year_stats_cache(&graph, user_id, timestamp).await;
+                }
+                Ok(StatusCode::OK)
+            }
+            Ok(None) => Err((StatusCode::NOT_FOUND, "Event not found".to_string())),
+            Err(e) => Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to toggle event resolution: {}", e),
+            )),
+        },
         Err(e) => {
             eprintln!("Error toggling event resolution: {}", e);
             Err((
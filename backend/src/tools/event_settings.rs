@@ -0,0 +1,346 @@
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use neo4rs::{query, Graph};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::env;
+
+/// Fallback duration (in minutes) for events that don't specify one.
+pub const DEFAULT_EVENT_DURATION_MINUTES: i32 = 60;
+
+/// Accepted values for `week_start_day`; weeks start on Sunday unless the user picks Monday.
+pub const WEEK_START_DAYS: [&str; 2] = ["sunday", "monday"];
+pub const DEFAULT_WEEK_START_DAY: &str = "sunday";
+
+/// Accepted values for `gcal_conflict_policy`, i.e. which side wins when a synced event was
+/// edited both here and in Google Calendar since the last sync.
+pub const GCAL_CONFLICT_POLICIES: [&str; 3] = ["goals_wins", "google_wins", "newest_wins"];
+pub const DEFAULT_GCAL_CONFLICT_POLICY: &str = "newest_wins";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EnergyWindow {
+    pub level: String,   // "high" or "low"
+    pub start_hour: u32, // inclusive, 0-23
+    pub end_hour: u32,   // exclusive, 1-24; a window with end <= start wraps past midnight
+}
+
+impl EnergyWindow {
+    pub fn contains_hour(&self, hour: u32) -> bool {
+        if self.start_hour < self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventSettings {
+    pub default_event_duration: i32, // minutes
+    // Hour ranges the user declared as high/low energy; omitted on update = leave unchanged
+    #[serde(default)]
+    pub energy_windows: Option<Vec<EnergyWindow>>,
+    // Mark elapsed deadline events as "overdue"; omitted on update = leave unchanged
+    #[serde(default)]
+    pub flag_overdue_deadlines: Option<bool>,
+    // "YYYY-MM-DD" dates skipped by routines with skip_holidays; omitted on update = leave unchanged
+    #[serde(default)]
+    pub holidays: Option<Vec<String>>,
+    // "sunday" or "monday"; omitted on update = leave unchanged
+    #[serde(default)]
+    pub week_start_day: Option<String>,
+    // Whether suggestions may land on Sat/Sun; unset (null) = infer from history
+    #[serde(default)]
+    pub schedule_on_weekends: Option<bool>,
+    // "goals_wins", "google_wins" or "newest_wins"; omitted on update = leave unchanged
+    #[serde(default)]
+    pub gcal_conflict_policy: Option<String>,
+}
+
+impl Default for EventSettings {
+    fn default() -> Self {
+        Self {
+            default_event_duration: DEFAULT_EVENT_DURATION_MINUTES,
+            energy_windows: None,
+            flag_overdue_deadlines: None,
+            holidays: None,
+            week_start_day: None,
+            schedule_on_weekends: None,
+            gcal_conflict_policy: None,
+        }
+    }
+}
+
+/// Energy level ("high", "low", or "medium" when no window covers it) for an hour of the day.
+pub fn energy_level_at(windows: &[EnergyWindow], hour: u32) -> &str {
+    windows
+        .iter()
+        .find(|w| w.contains_hour(hour))
+        .map(|w| w.level.as_str())
+        .unwrap_or("medium")
+}
+
+pub fn validate_energy_windows(windows: &[EnergyWindow]) -> Result<(), String> {
+    for w in windows {
+        if w.level != "high" && w.level != "low" {
+            return Err(format!(
+                "Invalid energy level '{}'; expected 'high' or 'low'",
+                w.level
+            ));
+        }
+        if w.start_hour > 23 || w.end_hour > 24 || w.start_hour == w.end_hour {
+            return Err(format!(
+                "Invalid energy window {}-{}; hours must be 0-24 and non-empty",
+                w.start_hour, w.end_hour
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_holidays(holidays: &[String]) -> Result<(), String> {
+    for day in holidays {
+        if NaiveDate::parse_from_str(day, "%Y-%m-%d").is_err() {
+            return Err(format!("Invalid holiday '{}'; expected YYYY-MM-DD", day));
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_week_start_day(day: &str) -> Result<(), String> {
+    if WEEK_START_DAYS.contains(&day) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid week_start_day '{}'; expected 'sunday' or 'monday'",
+            day
+        ))
+    }
+}
+
+pub fn validate_gcal_conflict_policy(policy: &str) -> Result<(), String> {
+    if GCAL_CONFLICT_POLICIES.contains(&policy) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid gcal_conflict_policy '{}'; expected one of: {}",
+            policy,
+            GCAL_CONFLICT_POLICIES.join(", ")
+        ))
+    }
+}
+
+pub async fn get_event_settings(graph: &Graph, user_id: i64) -> Result<EventSettings, String> {
+    let query_str = "
+        MATCH (u:User)
+        WHERE id(u) = $user_id
+        RETURN COALESCE(u.default_event_duration, $default_duration) as default_event_duration,
+               u.energy_windows as energy_windows,
+               COALESCE(u.flag_overdue_deadlines, false) as flag_overdue_deadlines,
+               COALESCE(u.holidays, []) as holidays,
+               COALESCE(u.week_start_day, $default_week_start) as week_start_day,
+               u.schedule_on_weekends as schedule_on_weekends,
+               COALESCE(u.gcal_conflict_policy, $default_conflict_policy) as gcal_conflict_policy
+    ";
+
+    let mut result = graph
+        .execute(
+            query(query_str)
+                .param("user_id", user_id)
+                .param("default_duration", DEFAULT_EVENT_DURATION_MINUTES as i64)
+                .param("default_week_start", DEFAULT_WEEK_START_DAY)
+                .param("default_conflict_policy", DEFAULT_GCAL_CONFLICT_POLICY),
+        )
+        .await
+        .map_err(|e| format!("Failed to get event settings: {}", e))?;
+
+    if let Some(row) = result.next().await.map_err(|e| e.to_string())? {
+        Ok(EventSettings {
+            default_event_duration: row
+                .get::<i64>("default_event_duration")
+                .map(|d| d as i32)
+                .unwrap_or(DEFAULT_EVENT_DURATION_MINUTES),
+            // Stored as a JSON string since Neo4j properties can't hold lists of maps
+            energy_windows: Some(
+                row.get::<String>("energy_windows")
+                    .ok()
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+            ),
+            flag_overdue_deadlines: Some(row.get::<bool>("flag_overdue_deadlines").unwrap_or(false)),
+            holidays: Some(row.get::<Vec<String>>("holidays").unwrap_or_default()),
+            week_start_day: Some(
+                row.get::<String>("week_start_day")
+                    .unwrap_or_else(|_| DEFAULT_WEEK_START_DAY.to_string()),
+            ),
+            // Left as None when unset so callers can fall back to inferring it
+            schedule_on_weekends: row.get::<bool>("schedule_on_weekends").ok(),
+            gcal_conflict_policy: Some(
+                row.get::<String>("gcal_conflict_policy")
+                    .unwrap_or_else(|_| DEFAULT_GCAL_CONFLICT_POLICY.to_string()),
+            ),
+        })
+    } else {
+        Err("User not found".to_string())
+    }
+}
+
+pub async fn update_event_settings(
+    graph: &Graph,
+    user_id: i64,
+    settings: EventSettings,
+) -> Result<(), String> {
+    let mut set_clauses = vec!["u.default_event_duration = $default_event_duration"];
+    let energy_json = match &settings.energy_windows {
+        Some(windows) => {
+            validate_energy_windows(windows)?;
+            set_clauses.push("u.energy_windows = $energy_windows");
+            Some(serde_json::to_string(windows).map_err(|e| e.to_string())?)
+        }
+        None => None,
+    };
+    if settings.flag_overdue_deadlines.is_some() {
+        set_clauses.push("u.flag_overdue_deadlines = $flag_overdue_deadlines");
+    }
+    if let Some(holidays) = &settings.holidays {
+        validate_holidays(holidays)?;
+        set_clauses.push("u.holidays = $holidays");
+    }
+    if let Some(day) = &settings.week_start_day {
+        validate_week_start_day(day)?;
+        set_clauses.push("u.week_start_day = $week_start_day");
+    }
+    if settings.schedule_on_weekends.is_some() {
+        set_clauses.push("u.schedule_on_weekends = $schedule_on_weekends");
+    }
+    if let Some(policy) = &settings.gcal_conflict_policy {
+        validate_gcal_conflict_policy(policy)?;
+        set_clauses.push("u.gcal_conflict_policy = $gcal_conflict_policy");
+    }
+
+    let query_str = format!(
+        "MATCH (u:User)
+         WHERE id(u) = $user_id
+         SET {}
+         RETURN u",
+        set_clauses.join(", ")
+    );
+
+    let mut update = query(&query_str)
+        .param("user_id", user_id)
+        .param("default_event_duration", settings.default_event_duration as i64);
+    if let Some(json) = energy_json {
+        update = update.param("energy_windows", json);
+    }
+    if let Some(flag) = settings.flag_overdue_deadlines {
+        update = update.param("flag_overdue_deadlines", flag);
+    }
+    if let Some(holidays) = settings.holidays {
+        update = update.param("holidays", holidays);
+    }
+    if let Some(day) = settings.week_start_day {
+        update = update.param("week_start_day", day);
+    }
+    if let Some(weekends) = settings.schedule_on_weekends {
+        update = update.param("schedule_on_weekends", weekends);
+    }
+    if let Some(policy) = settings.gcal_conflict_policy {
+        update = update.param("gcal_conflict_policy", policy);
+    }
+
+    graph
+        .run(update)
+        .await
+        .map_err(|e| format!("Failed to update event settings: {}", e))?;
+
+    Ok(())
+}
+
+/// Parse a DEFAULT_TIMEZONE value; unset or blank means UTC.
+pub fn parse_default_timezone(raw: Option<&str>) -> Result<Tz, String> {
+    match raw.map(str::trim).filter(|s| !s.is_empty()) {
+        None => Ok(Tz::UTC),
+        Some(name) if name.eq_ignore_ascii_case("utc") => Ok(Tz::UTC),
+        Some(name) => name.parse::<Tz>().map_err(|_| {
+            format!(
+                "Invalid DEFAULT_TIMEZONE '{}'. Expected an IANA timezone like 'America/New_York'.",
+                name
+            )
+        }),
+    }
+}
+
+/// Deployment-wide timezone for requests that don't send one. Startup rejects invalid
+/// values, so the UTC fallback here only covers tests and tools that skip that check.
+pub fn default_timezone() -> Tz {
+    parse_default_timezone(env::var("DEFAULT_TIMEZONE").ok().as_deref()).unwrap_or(Tz::UTC)
+}
+
+/// Resolve the user's default event duration, falling back to the global default
+/// if the user can't be loaded.
+pub async fn default_event_duration(graph: &Graph, user_id: i64) -> i32 {
+    get_event_settings(graph, user_id)
+        .await
+        .map(|s| s.default_event_duration)
+        .unwrap_or(DEFAULT_EVENT_DURATION_MINUTES)
+}
+
+/// The user's declared energy windows; empty if none are configured or the user can't be loaded.
+pub async fn energy_windows(graph: &Graph, user_id: i64) -> Vec<EnergyWindow> {
+    get_event_settings(graph, user_id)
+        .await
+        .ok()
+        .and_then(|s| s.energy_windows)
+        .unwrap_or_default()
+}
+
+/// The user's holiday dates; empty if none are set or the user can't be loaded.
+pub async fn holiday_dates(graph: &Graph, user_id: i64) -> HashSet<NaiveDate> {
+    get_event_settings(graph, user_id)
+        .await
+        .ok()
+        .and_then(|s| s.holidays)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .collect()
+}
+
+/// Whether the user's weeks start on Monday; defaults to Sunday if unset or the user can't be loaded.
+pub async fn week_starts_on_monday(graph: &Graph, user_id: i64) -> bool {
+    get_event_settings(graph, user_id)
+        .await
+        .ok()
+        .and_then(|s| s.week_start_day)
+        .is_some_and(|d| d == "monday")
+}
+
+/// The user's explicit weekend scheduling preference; None if unset or the user can't be loaded.
+pub async fn schedule_on_weekends(graph: &Graph, user_id: i64) -> Option<bool> {
+    get_event_settings(graph, user_id)
+        .await
+        .ok()
+        .and_then(|s| s.schedule_on_weekends)
+}
+
+/// The user's gcal conflict policy; the default if unset or the user can't be loaded.
+pub async fn gcal_conflict_policy(graph: &Graph, user_id: i64) -> String {
+    get_event_settings(graph, user_id)
+        .await
+        .ok()
+        .and_then(|s| s.gcal_conflict_policy)
+        .unwrap_or_else(|| DEFAULT_GCAL_CONFLICT_POLICY.to_string())
+}
+
+/// Whether schedule suggestions should skip a weekend day. An explicit preference always
+/// wins; otherwise weekends are skipped when under 10% of past events fell on one.
+pub fn skip_weekend_day(
+    schedule_on_weekends: Option<bool>,
+    weekend_events: usize,
+    total_events: usize,
+) -> bool {
+    match schedule_on_weekends {
+        Some(allowed) => !allowed,
+        None => weekend_events < total_events / 10,
+    }
+}
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::server::token_manager;
+use crate::tools::event_settings::{self, DEFAULT_EVENT_DURATION_MINUTES};
 use crate::tools::goal::{Goal, GoalType, GOAL_RETURN_QUERY};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -42,6 +43,37 @@ pub struct SyncConflict {
     pub gcal_updated: String,
     pub local_summary: String,
     pub gcal_summary: String,
+    pub winner: ConflictWinner, // Side kept under the user's gcal_conflict_policy
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictWinner {
+    Goals,
+    Google,
+}
+
+/// Pick the side to keep when an event was edited both here and in Google since the last
+/// sync. `newest_wins` compares Google's `updated` time with our `updated_at` and keeps the
+/// local edit if Google's time is missing or unparseable; unknown policies act as the default.
+pub fn resolve_sync_conflict(
+    policy: &str,
+    local_updated_at: i64,
+    gcal_updated: Option<&str>,
+) -> ConflictWinner {
+    match policy {
+        "goals_wins" => ConflictWinner::Goals,
+        "google_wins" => ConflictWinner::Google,
+        _ => {
+            let gcal_updated_at = gcal_updated
+                .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+                .map(|dt| dt.timestamp_millis());
+            match gcal_updated_at {
+                Some(google) if google > local_updated_at => ConflictWinner::Google,
+                _ => ConflictWinner::Goals,
+            }
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -253,17 +285,47 @@ async fn fetch_events_incremental(
     Ok((all_events, final_sync_token))
 }
 
+// Google Calendar's fixed event palette, keyed by colorId
+const GCAL_EVENT_COLORS: [(&str, (u8, u8, u8)); 11] = [
+    ("1", (0x79, 0x86, 0xcb)),  // Lavender
+    ("2", (0x33, 0xb6, 0x79)),  // Sage
+    ("3", (0x8e, 0x24, 0xaa)),  // Grape
+    ("4", (0xe6, 0x7c, 0x73)),  // Flamingo
+    ("5", (0xf6, 0xbf, 0x26)),  // Banana
+    ("6", (0xf4, 0x51, 0x1e)),  // Tangerine
+    ("7", (0x03, 0x9b, 0xe5)),  // Peacock
+    ("8", (0x61, 0x61, 0x61)),  // Graphite
+    ("9", (0x3f, 0x51, 0xb5)),  // Blueberry
+    ("10", (0x0b, 0x80, 0x43)), // Basil
+    ("11", (0xd5, 0x00, 0x00)), // Tomato
+];
+
+/// The Google Calendar colorId closest (by RGB distance) to a "#RRGGBB" color.
+pub fn nearest_gcal_color_id(hex: &str) -> Option<&'static str> {
+    let hex = hex.strip_prefix('#').filter(|h| h.len() == 6)?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok().map(i32::from);
+    let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+
+    GCAL_EVENT_COLORS
+        .iter()
+        .min_by_key(|(_, (pr, pg, pb))| {
+            let (dr, dg, db) = (r - *pr as i32, g - *pg as i32, b - *pb as i32);
+            dr * dr + dg * dg + db * db
+        })
+        .map(|(id, _)| *id)
+}
+
 /// Create an event in Google Calendar
 async fn create_event(token: &str, calendar_id: &str, goal: &Goal) -> Result<String, String> {
     let start_time = goal
         .scheduled_timestamp
         .ok_or("Goal must have a scheduled timestamp")?;
-    let duration_minutes = goal.duration.unwrap_or(60);
+    let duration_minutes = goal.duration.unwrap_or(DEFAULT_EVENT_DURATION_MINUTES);
     let start_dt = DateTime::from_timestamp_millis(start_time).unwrap();
     let end_time = start_time + (duration_minutes as i64 * 60 * 1000);
     let end_dt = DateTime::from_timestamp_millis(end_time).unwrap();
 
-    let event = if goal.duration == Some(1440) {
+    let mut event = if goal.duration == Some(1440) {
         // All-day event
         json!({
             "summary": goal.name,
@@ -289,6 +351,9 @@ async fn create_event(token: &str, calendar_id: &str, goal: &Goal) -> Result<Str
             }
         })
     };
+    if let Some(color_id) = goal.color.as_deref().and_then(nearest_gcal_color_id) {
+        event["colorId"] = json!(color_id);
+    }
 
     let client = Client::new();
     let response = client
@@ -325,12 +390,12 @@ async fn update_event(
     let start_time = goal
         .scheduled_timestamp
         .ok_or("Goal must have a scheduled timestamp")?;
-    let duration_minutes = goal.duration.unwrap_or(60);
+    let duration_minutes = goal.duration.unwrap_or(DEFAULT_EVENT_DURATION_MINUTES);
     let start_dt = DateTime::from_timestamp_millis(start_time).unwrap();
     let end_time = start_time + (duration_minutes as i64 * 60 * 1000);
     let end_dt = DateTime::from_timestamp_millis(end_time).unwrap();
 
-    let event = if goal.duration == Some(1440) {
+    let mut event = if goal.duration == Some(1440) {
         json!({
             "summary": goal.name,
             "description": goal.description,
@@ -355,6 +420,9 @@ async fn update_event(
             }
         })
     };
+    if let Some(color_id) = goal.color.as_deref().and_then(nearest_gcal_color_id) {
+        event["colorId"] = json!(color_id);
+    }
 
     let client = Client::new();
     let response = client
@@ -508,6 +576,7 @@ pub async fn sync_from_gcal(
     let mut updated_events = 0;
     let mut errors = Vec::new();
     let mut conflicts = Vec::new();
+    let conflict_policy = event_settings::gcal_conflict_policy(&graph, user_id).await;
 
     for gcal_event in gcal_events {
         eprintln!(
@@ -605,8 +674,15 @@ pub async fn sync_from_gcal(
                 _ => false,
             };
 
-            if has_conflict {
-                // Record conflict instead of overwriting
+            let winner = has_conflict.then(|| {
+                resolve_sync_conflict(
+                    &conflict_policy,
+                    updated_at.unwrap_or(0),
+                    gcal_event.updated.as_deref(),
+                )
+            });
+
+            if let Some(winner) = winner {
                 conflicts.push(SyncConflict {
                     goal_id,
                     goal_name: local_name.clone(),
@@ -615,13 +691,17 @@ pub async fn sync_from_gcal(
                     gcal_updated: gcal_event.updated.clone().unwrap_or_default(),
                     local_summary: local_name,
                     gcal_summary: gcal_event.summary.clone(),
+                    winner,
                 });
                 eprintln!(
-                    "⚠️  [GCAL←] Conflict detected for event id='{}' - local modified after last sync",
-                    gcal_event.id
+                    "⚠️  [GCAL←] Conflict for event id='{}' - edited on both sides since last sync; policy '{}' keeps {:?}",
+                    gcal_event.id, conflict_policy, winner
                 );
-            } else {
-                // No conflict - safe to update
+            }
+
+            // A local win keeps the local edit; the push to Google carries it over
+            if winner != Some(ConflictWinner::Goals) {
+                // No conflict, or Google won it - take Google's version
                 let update_query = query(
                     "MATCH (g:Goal) 
                      WHERE g.user_id = $user_id 
@@ -631,7 +711,8 @@ pub async fn sync_from_gcal(
                          g.scheduled_timestamp = $scheduled_timestamp,
                          g.duration = $duration,
                          g.gcal_last_sync = $sync_time,
-                         g.updated_at = $sync_time",
+                         g.updated_at = $sync_time,
+                         g.last_modified = timestamp()",
                 )
                 .param("user_id", user_id)
                 .param("gcal_event_id", gcal_event.id.clone())
@@ -794,10 +875,16 @@ pub async fn sync_to_gcal(
                 Ok(_) => {
                     updated_events += 1;
                     // Update sync timestamp
-                    let update_sync_query =
-                        query("MATCH (g:Goal) WHERE id(g) = $id SET g.gcal_last_sync = $sync_time")
-                            .param("id", goal.id.unwrap_or(0))
-                            .param("sync_time", Utc::now().timestamp_millis());
+                    let update_sync_query = query(
+                        "MATCH (g:Goal) WHERE id(g) = $id
+                         SET g.gcal_last_sync = $sync_time,
+                             g.gcal_last_sync_attempt = $sync_time,
+                             g.gcal_sync_status = 'synced',
+                             g.last_modified = timestamp()
+                         REMOVE g.gcal_sync_error",
+                    )
+                    .param("id", goal.id.unwrap_or(0))
+                    .param("sync_time", Utc::now().timestamp_millis());
 
                     let _ = graph.run(update_sync_query).await;
                     eprintln!(
@@ -806,6 +893,7 @@ pub async fn sync_to_gcal(
                     );
                 }
                 Err(e) => {
+                    record_sync_failure(&graph, goal.id.unwrap_or(0), &e.to_string()).await;
                     errors.push(format!("Failed to update event {}: {}", goal.name, e));
                     eprintln!(
                         "❌ [GCAL→] Failed to update goal id={:?} ('{}'): {}",
@@ -828,7 +916,11 @@ pub async fn sync_to_gcal(
                         "MATCH (g:Goal) WHERE id(g) = $id 
                          SET g.gcal_event_id = $gcal_event_id,
                              g.gcal_calendar_id = $gcal_calendar_id,
-                             g.gcal_last_sync = $sync_time",
+                             g.gcal_last_sync = $sync_time,
+                             g.gcal_last_sync_attempt = $sync_time,
+                             g.gcal_sync_status = 'synced',
+                             g.last_modified = timestamp()
+                         REMOVE g.gcal_sync_error",
                     )
                     .param("id", goal.id.unwrap_or(0))
                     .param("gcal_event_id", gcal_event_id)
@@ -842,6 +934,7 @@ pub async fn sync_to_gcal(
                     );
                 }
                 Err(e) => {
+                    record_sync_failure(&graph, goal.id.unwrap_or(0), &e.to_string()).await;
                     errors.push(format!("Failed to create event {}: {}", goal.name, e));
                     eprintln!(
                         "❌ [GCAL→] Failed to create GCal event for goal id={:?} ('{}'): {}",
@@ -875,6 +968,133 @@ pub async fn sync_to_gcal(
     }))
 }
 
+/// Mark a failed push so it shows up in the sync status report. Best effort: a failure
+/// here shouldn't mask the original sync error.
+async fn record_sync_failure(graph: &Graph, goal_id: i64, error: &str) {
+    let failure_query = query(
+        "MATCH (g:Goal) WHERE id(g) = $id
+         SET g.gcal_sync_status = 'failed',
+             g.gcal_last_sync_attempt = $attempt_time,
+             g.gcal_sync_error = $error,
+             g.last_modified = timestamp()",
+    )
+    .param("id", goal_id)
+    .param("attempt_time", Utc::now().timestamp_millis())
+    .param("error", error);
+
+    if let Err(e) = graph.run(failure_query).await {
+        eprintln!(
+            "⚠️ [GCAL→] Could not record sync failure for goal id={}: {}",
+            goal_id, e
+        );
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GCalSyncFailure {
+    pub event_id: i64,
+    pub name: String,
+    pub scheduled_timestamp: Option<i64>,
+    pub last_attempt: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GCalSyncStatus {
+    pub synced: i64,
+    pub pending: i64,
+    pub failed: i64,
+    pub failed_events: Vec<GCalSyncFailure>, // most recent attempts first, capped
+}
+
+const SYNC_FAILURE_SAMPLE_SIZE: i64 = 20;
+
+/// Summarize push-sync state for a user's sync-enabled events. Events that have never
+/// been attempted count as pending.
+pub async fn get_gcal_sync_status_handler(
+    graph: Graph,
+    user_id: i64,
+) -> Result<Json<GCalSyncStatus>, (StatusCode, String)> {
+    let counts_query = query(
+        "MATCH (g:Goal)
+         WHERE g.user_id = $user_id
+         AND g.goal_type = 'event'
+         AND g.gcal_sync_enabled = true
+         AND coalesce(g.is_deleted, false) <> true
+         WITH coalesce(g.gcal_sync_status, 'pending') as status
+         RETURN
+            count(CASE WHEN status = 'synced' THEN 1 END) as synced,
+            count(CASE WHEN status = 'pending' THEN 1 END) as pending,
+            count(CASE WHEN status = 'failed' THEN 1 END) as failed",
+    )
+    .param("user_id", user_id);
+
+    let mut result = graph.execute(counts_query).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch sync status: {}", e),
+        )
+    })?;
+
+    let (synced, pending, failed) = match result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        Some(row) => (
+            row.get::<i64>("synced").unwrap_or(0),
+            row.get::<i64>("pending").unwrap_or(0),
+            row.get::<i64>("failed").unwrap_or(0),
+        ),
+        None => (0, 0, 0),
+    };
+
+    let failures_query = query(
+        "MATCH (g:Goal)
+         WHERE g.user_id = $user_id
+         AND g.goal_type = 'event'
+         AND g.gcal_sync_status = 'failed'
+         AND coalesce(g.is_deleted, false) <> true
+         RETURN id(g) as event_id, g.name as name,
+                g.scheduled_timestamp as scheduled_timestamp,
+                g.gcal_last_sync_attempt as last_attempt,
+                g.gcal_sync_error as error
+         ORDER BY g.gcal_last_sync_attempt DESC
+         LIMIT $limit",
+    )
+    .param("user_id", user_id)
+    .param("limit", SYNC_FAILURE_SAMPLE_SIZE);
+
+    let mut result = graph.execute(failures_query).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch failed events: {}", e),
+        )
+    })?;
+
+    let mut failed_events = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        failed_events.push(GCalSyncFailure {
+            event_id: row.get("event_id").unwrap_or(0),
+            name: row.get("name").unwrap_or_default(),
+            scheduled_timestamp: row.get("scheduled_timestamp").ok(),
+            last_attempt: row.get("last_attempt").ok(),
+            error: row.get("error").ok(),
+        });
+    }
+
+    Ok(Json(GCalSyncStatus {
+        synced,
+        pending,
+        failed,
+        failed_events,
+    }))
+}
+
 /// Delete a Google Calendar event
 pub async fn delete_gcal_event_handler(
     graph: Graph,
@@ -923,6 +1143,7 @@ pub async fn delete_gcal_event_handler(
             // Remove GCal-related properties from the goal node
             let update_goal_query = query(
                 "MATCH (g:Goal) WHERE id(g) = $id 
+                 SET g.last_modified = timestamp()
                  REMOVE g.gcal_event_id, g.gcal_calendar_id, g.gcal_sync_enabled, 
                         g.gcal_last_sync, g.is_gcal_imported, g.gcal_sync_direction",
             )
@@ -1008,7 +1229,7 @@ pub async fn resolve_conflict_handler(
                 let sync_time = Utc::now().timestamp_millis();
                 let update_query = query(
                     "MATCH (g:Goal) WHERE id(g) = $goal_id 
-                     SET g.gcal_last_sync = $sync_time, g.updated_at = $sync_time",
+                     SET g.gcal_last_sync = $sync_time, g.updated_at = $sync_time, g.last_modified = timestamp()",
                 )
                 .param("goal_id", request.goal_id)
                 .param("sync_time", sync_time);
@@ -1104,7 +1325,8 @@ pub async fn resolve_conflict_handler(
                      g.scheduled_timestamp = $scheduled_timestamp,
                      g.duration = $duration,
                      g.gcal_last_sync = $sync_time,
-                     g.updated_at = $sync_time",
+                     g.updated_at = $sync_time,
+                     g.last_modified = timestamp()",
             )
             .param("goal_id", request.goal_id)
             .param("name", gcal_event.summary)
@@ -6,7 +6,188 @@ use axum::{http::StatusCode, Json};
 use neo4rs::{query, Graph};
 use serde::{Deserialize, Serialize};
 
+use crate::tools::achievements;
+
 pub const DEBUG_PRINTS: bool = false;
+pub const ENERGY_LEVELS: [&str; 3] = ["low", "medium", "high"];
+pub const RESET_PERIODS: [&str; 2] = ["yearly", "monthly"];
+// Highest first; anything else is weighted like "medium" in stats
+pub const PRIORITIES: [&str; 4] = ["high", "medium", "low", "none"];
+// Units of a routine frequency "{multiplier}{unit}[:days]", as stepped by the generator
+pub const FREQUENCY_UNITS: [&str; 4] = ["D", "W", "M", "Y"];
+
+/// A routine frequency broken into its parts; see `parse_frequency`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedFrequency {
+    pub multiplier: u32,
+    pub unit: String,   // one of FREQUENCY_UNITS
+    pub days: Vec<u32>, // weekly only: weekdays 0-6 from Sunday; empty means any day
+}
+
+/// Parse a "{multiplier}{unit}[:days]" frequency, e.g. "1D", "2W:1,3" (weekdays 0-6 from
+/// Sunday) or "1M". The generator falls back to daily steps on anything else, so catch it here.
+pub fn parse_frequency(frequency: &str) -> Result<ParsedFrequency, String> {
+    let invalid = || {
+        format!(
+            "Invalid frequency '{}'; expected e.g. '1D', '2W:1,3' or '1M'",
+            frequency
+        )
+    };
+    let (step, days) = match frequency.split_once(':') {
+        Some((step, days)) => (step, Some(days)),
+        None => (frequency, None),
+    };
+    let unit_pos = step
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (multiplier, unit) = step.split_at(unit_pos);
+    let unit = unit.to_ascii_uppercase();
+    let multiplier = multiplier
+        .parse::<u32>()
+        .ok()
+        .filter(|&m| m >= 1)
+        .ok_or_else(invalid)?;
+    if !FREQUENCY_UNITS.contains(&unit.as_str()) {
+        return Err(invalid());
+    }
+    let days = match days {
+        Some(days) => {
+            if unit != "W" {
+                return Err(invalid());
+            }
+            days.split(',')
+                .map(|d| d.parse::<u32>().ok().filter(|&d| d <= 6))
+                .collect::<Option<Vec<u32>>>()
+                .ok_or_else(invalid)?
+        }
+        None => Vec::new(),
+    };
+    Ok(ParsedFrequency {
+        multiplier,
+        unit,
+        days,
+    })
+}
+
+pub fn validate_frequency(frequency: &str) -> Result<(), String> {
+    parse_frequency(frequency).map(|_| ())
+}
+
+const WEEKDAY_LABELS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Human-readable form of a frequency, e.g. "Every day", "Every Mon/Wed/Fri" or
+/// "Every 2 weeks on Tue", so clients don't each re-parse the string. None if it doesn't parse.
+pub fn frequency_label(frequency: &str) -> Option<String> {
+    let parsed = parse_frequency(frequency).ok()?;
+    let unit = match parsed.unit.as_str() {
+        "D" => "day",
+        "W" => "week",
+        "M" => "month",
+        _ => "year",
+    };
+    let every = match parsed.multiplier {
+        1 => format!("Every {}", unit),
+        n => format!("Every {} {}s", n, unit),
+    };
+    if parsed.days.is_empty() {
+        return Some(every);
+    }
+    let days = parsed
+        .days
+        .iter()
+        .map(|&d| WEEKDAY_LABELS[d as usize])
+        .collect::<Vec<_>>()
+        .join("/");
+    Some(match parsed.multiplier {
+        1 => format!("Every {}", days),
+        _ => format!("{} on {}", every, days),
+    })
+}
+
+/// Rotate a weekly pattern by `days`, e.g. "1W:1,3,5" moved one day later is "1W:2,4,6".
+/// Frequencies without a weekday list step from the last event, so they come back unchanged.
+pub fn shift_frequency_days(frequency: &str, days: i64) -> Result<String, String> {
+    let parsed = parse_frequency(frequency)?;
+    if parsed.days.is_empty() {
+        return Ok(frequency.to_string());
+    }
+    let mut shifted: Vec<u32> = parsed
+        .days
+        .iter()
+        .map(|&d| (d as i64 + days).rem_euclid(7) as u32)
+        .collect();
+    shifted.sort_unstable();
+    shifted.dedup();
+    Ok(format!(
+        "{}{}:{}",
+        parsed.multiplier,
+        parsed.unit,
+        shifted
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    ))
+}
+
+/// Event links must be absolute http(s) URLs so the calendar can open them directly.
+pub fn validate_links(links: &[String]) -> Result<(), String> {
+    for link in links {
+        let parsed = reqwest::Url::parse(link).map_err(|_| format!("Invalid link '{}'", link))?;
+        if !matches!(parsed.scheme(), "http" | "https") || !parsed.has_host() {
+            return Err(format!("Link '{}' must be an http(s) URL", link));
+        }
+    }
+    Ok(())
+}
+
+/// Routine targets use the same 0–1 scale as `RoutineStats::completion_rate`.
+pub fn validate_target_completion_rate(rate: f64) -> Result<(), String> {
+    if !(rate > 0.0 && rate <= 1.0) {
+        return Err(
+            "target_completion_rate must be between 0 and 1 (e.g. 0.8 for 80%)".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Read the goal in `column`, or log and return None when the node can't be deserialized
+/// (e.g. a half-migrated node with an unknown goal_type), so list views can skip it rather
+/// than failing the whole response.
+pub fn goal_from_row_lenient(row: &neo4rs::Row, column: &str) -> Option<Goal> {
+    match row.get::<Goal>(column) {
+        Ok(goal) => Some(goal),
+        Err(e) => {
+            let id = row
+                .get::<serde_json::Value>(column)
+                .ok()
+                .and_then(|value| value.get("id").cloned());
+            eprintln!("⚠️ Skipping malformed goal {:?}: {:?}", id, e);
+            None
+        }
+    }
+}
+
+/// Trim and lowercase a priority, rejecting anything outside `PRIORITIES` so typos like "hi"
+/// aren't silently weighted as medium.
+pub fn normalize_priority(raw: &str) -> Result<String, String> {
+    let normalized = raw.trim().to_lowercase();
+    if !PRIORITIES.contains(&normalized.as_str()) {
+        return Err(format!(
+            "Unknown priority '{}'; expected one of: {}",
+            raw,
+            PRIORITIES.join(", ")
+        ));
+    }
+    Ok(normalized)
+}
+
+/// Colors are stored as "#RRGGBB" hex codes.
+pub fn is_valid_hex_color(color: &str) -> bool {
+    color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
 
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -15,15 +196,28 @@ pub enum ResolutionStatus {
     Completed, // Successfully completed
     Failed,    // Explicitly marked as failed/abandoned
     Skipped,   // Intentionally skipped
+    Overdue,   // Deadline event elapsed without being resolved (set by the overdue job)
+    Cancelled, // Called off; kept for the record but excluded from completion rates
 }
 
 impl ResolutionStatus {
+    pub const ALL: [ResolutionStatus; 6] = [
+        ResolutionStatus::Pending,
+        ResolutionStatus::Completed,
+        ResolutionStatus::Failed,
+        ResolutionStatus::Skipped,
+        ResolutionStatus::Overdue,
+        ResolutionStatus::Cancelled,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             ResolutionStatus::Pending => "pending",
             ResolutionStatus::Completed => "completed",
             ResolutionStatus::Failed => "failed",
             ResolutionStatus::Skipped => "skipped",
+            ResolutionStatus::Overdue => "overdue",
+            ResolutionStatus::Cancelled => "cancelled",
         }
     }
 
@@ -34,6 +228,8 @@ impl ResolutionStatus {
             "completed" => Some(ResolutionStatus::Completed),
             "failed" => Some(ResolutionStatus::Failed),
             "skipped" => Some(ResolutionStatus::Skipped),
+            "overdue" => Some(ResolutionStatus::Overdue),
+            "cancelled" => Some(ResolutionStatus::Cancelled),
             _ => None,
         }
     }
@@ -51,7 +247,7 @@ fn normalize_resolution_status(status_raw: &str) -> Result<String, (StatusCode,
         return Err((
             StatusCode::BAD_REQUEST,
             format!(
-                "Invalid resolution_status: {}. Must be one of: pending, completed, failed, skipped",
+                "Invalid resolution_status: {}. Must be one of: pending, completed, failed, skipped, overdue, cancelled",
                 status_raw
             ),
         ));
@@ -86,7 +282,7 @@ pub struct Goal {
     pub priority: Option<String>,
     pub start_timestamp: Option<i64>,
     pub end_timestamp: Option<i64>,
-    pub resolution_status: Option<String>, // "pending", "completed", "failed", "skipped"
+    pub resolution_status: Option<String>, // "pending", "completed", "failed", "skipped", "overdue", "cancelled"
     pub resolved_at: Option<i64>,          // Timestamp when resolution was set
     pub next_timestamp: Option<i64>,
     //pub previous_timestamp: Option<i64>,
@@ -118,6 +314,27 @@ pub struct Goal {
 
     // Modification tracking for conflict detection
     pub updated_at: Option<i64>, // Track local modifications timestamp
+    pub version: Option<i64>,    // Incremented on each write (optimistic concurrency for events)
+    pub energy_required: Option<String>, // "low", "medium" or "high"; used by the schedule suggester
+    pub gcal_sync_status: Option<String>, // "synced", "pending" or "failed" (push to Google Calendar)
+    pub gcal_last_sync_attempt: Option<i64>, // Last push attempt, success or not
+    pub gcal_sync_error: Option<String>, // Error from the last failed push
+    pub target_value: Option<f64>, // Achievements: amount needed to complete (e.g. 12 books)
+    pub current_value: Option<f64>, // Achievements: progress so far toward target_value
+    pub is_deadline: Option<bool>, // Events: hard deadline; flagged overdue once elapsed (if the user opts in)
+    pub skip_holidays: Option<bool>, // Routines: don't generate events on the user's holiday dates
+    pub reset_period: Option<String>, // Achievements: "yearly" or "monthly"; current_value is archived and zeroed each period
+    pub last_reset_at: Option<i64>, // Achievements: start of the current reset period
+    pub notes: Option<String>, // Events: per-occurrence note, never propagated to sibling events
+    pub color: Option<String>, // "#RRGGBB"; events inherit their parent's color at creation
+    pub duration_seconds: Option<i32>, // precise length for very short events; overrides duration for end-time math when set
+    pub recurrence: Option<String>, // Task events: repeat pattern in routine frequency format; occurrences are added under the same task
+    pub recurrence_source_id: Option<i64>, // Task events: the recurring event this occurrence was generated from
+    pub links: Option<Vec<String>>, // Events: attached http(s) URLs (docs, meeting links)
+    pub allow_overlap: Option<bool>, // Events: may be double-booked; ignored by conflict checks, still counted in stats
+    pub routine_times: Option<Vec<i64>>, // Routines: several times of day (same encoding as routine_time); one event each per qualifying day, overriding routine_time
+    pub last_modified: Option<i64>, // DB time of the last write of any kind (unlike updated_at, includes sync writes); drives delta sync
+    pub target_completion_rate: Option<f64>, // Routines: adherence goal as a fraction (0.8 = 80%), compared against routine stats
 }
 
 impl Default for Goal {
@@ -154,6 +371,27 @@ impl Default for Goal {
             gcal_sync_direction: None,
             is_gcal_imported: None,
             updated_at: None,
+            version: None,
+            energy_required: None,
+            gcal_sync_status: None,
+            gcal_last_sync_attempt: None,
+            gcal_sync_error: None,
+            target_value: None,
+            current_value: None,
+            is_deadline: None,
+            skip_holidays: None,
+            reset_period: None,
+            last_reset_at: None,
+            notes: None,
+            color: None,
+            duration_seconds: None,
+            recurrence: None,
+            recurrence_source_id: None,
+            links: None,
+            allow_overlap: None,
+            routine_times: None,
+            last_modified: None,
+            target_completion_rate: None,
         }
     }
 }
@@ -189,6 +427,27 @@ pub const GOAL_RETURN_QUERY: &str = "RETURN {
                     gcal_sync_direction: g.gcal_sync_direction,
                     is_gcal_imported: g.is_gcal_imported,
                     updated_at: g.updated_at,
+                    version: g.version,
+                    energy_required: g.energy_required,
+                    gcal_sync_status: g.gcal_sync_status,
+                    gcal_last_sync_attempt: g.gcal_last_sync_attempt,
+                    gcal_sync_error: g.gcal_sync_error,
+                    target_value: g.target_value,
+                    current_value: g.current_value,
+                    is_deadline: g.is_deadline,
+                    skip_holidays: g.skip_holidays,
+                    reset_period: g.reset_period,
+                    last_reset_at: g.last_reset_at,
+                    notes: g.notes,
+                    color: g.color,
+                    duration_seconds: g.duration_seconds,
+                    recurrence: g.recurrence,
+                    recurrence_source_id: g.recurrence_source_id,
+                    links: g.links,
+                    allow_overlap: g.allow_overlap,
+                    routine_times: g.routine_times,
+                    last_modified: g.last_modified,
+                    target_completion_rate: g.target_completion_rate,
                     id: id(g)
                  } as g";
 
@@ -204,6 +463,15 @@ pub enum GoalType {
 }
 
 impl GoalType {
+    pub const ALL: [GoalType; 6] = [
+        GoalType::Directive,
+        GoalType::Project,
+        GoalType::Achievement,
+        GoalType::Routine,
+        GoalType::Task,
+        GoalType::Event,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             GoalType::Directive => "directive",
@@ -229,6 +497,26 @@ impl std::fmt::Display for GoalType {
     }
 }
 
+// Canonical values for goal fields, so clients don't hard-code their own copies
+#[derive(Debug, Serialize)]
+pub struct GoalMetadata {
+    pub goal_types: Vec<&'static str>,
+    pub resolution_statuses: Vec<&'static str>,
+    pub priorities: Vec<&'static str>,
+    pub frequency_units: Vec<&'static str>,
+    pub energy_levels: Vec<&'static str>,
+    pub reset_periods: Vec<&'static str>,
+}
+
+// Goal creation payload: the goal itself plus, for routines, dates to skip from the start
+#[derive(Debug, Deserialize)]
+pub struct CreateGoalRequest {
+    #[serde(flatten)]
+    pub goal: Goal,
+    #[serde(default)]
+    pub exceptions: Vec<i64>, // any timestamp within each UTC date to skip
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Relationship {
     pub from_id: i64,
@@ -250,6 +538,11 @@ pub struct ResolveGoalResponse {
     pub resolved_at: Option<i64>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SyncEventsResponse {
+    pub updated: i64,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ExpandTaskDateRangeRequest {
     pub task_id: i64,
@@ -267,16 +560,19 @@ pub struct DuplicateOptions {
 
 pub async fn delete_relationship_handler(
     graph: Graph,
+    user_id: i64,
     from_id: i64,
     to_id: i64,
 ) -> Result<StatusCode, (StatusCode, String)> {
     let query = query(
         "MATCH (from:Goal)-[r]->(to:Goal) 
          WHERE id(from) = $from_id AND id(to) = $to_id 
+         AND from.user_id = $user_id AND to.user_id = $user_id
          DELETE r",
     )
     .param("from_id", from_id)
-    .param("to_id", to_id);
+    .param("to_id", to_id)
+    .param("user_id", user_id);
 
     match graph.run(query).await {
         Ok(_) => Ok(StatusCode::OK),
@@ -290,14 +586,27 @@ pub async fn delete_relationship_handler(
     }
 }
 
+/// Fetch a single goal owned by `user_id`. Soft-deleted goals are treated as missing.
 pub async fn get_goal_handler(
     graph: Graph,
-    id: i64,
+    user_id: i64,
+    goal_id: i64,
 ) -> Result<(StatusCode, Json<Goal>), (StatusCode, String)> {
-    let query = format!("MATCH (g:Goal) WHERE g.id = $id {}", GOAL_RETURN_QUERY);
+    let query = format!(
+        "MATCH (g:Goal)
+         WHERE id(g) = $goal_id
+         AND g.user_id = $user_id
+         AND coalesce(g.is_deleted, false) <> true
+         {}",
+        GOAL_RETURN_QUERY
+    );
 
     let mut result = graph
-        .execute(neo4rs::query(&query).param("id", id))
+        .execute(
+            neo4rs::query(&query)
+                .param("goal_id", goal_id)
+                .param("user_id", user_id),
+        )
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
@@ -306,14 +615,7 @@ pub async fn get_goal_handler(
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
     {
-        let goal_data: serde_json::Value = row.get("goal").map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Error getting goal data: {}", e),
-            )
-        })?;
-
-        let goal: Goal = serde_json::from_value(goal_data).map_err(|e| {
+        let goal: Goal = row.get("g").map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Error parsing goal data: {}", e),
@@ -322,7 +624,10 @@ pub async fn get_goal_handler(
 
         Ok((StatusCode::OK, Json(goal)))
     } else {
-        Err((StatusCode::NOT_FOUND, "Goal not found".to_string()))
+        Err((
+            StatusCode::NOT_FOUND,
+            format!("Goal with id {} not found", goal_id),
+        ))
     }
 }
 
@@ -365,6 +670,23 @@ pub async fn create_goal_handler(
             "is_deleted",
             "due_date",
             "start_date",
+            "version",
+            "energy_required",
+            "target_value",
+            "current_value",
+            "is_deadline",
+            "skip_holidays",
+            "reset_period",
+            "last_reset_at",
+            "notes",
+            "color",
+            "duration_seconds",
+            "recurrence",
+            "recurrence_source_id",
+            "links",
+            "allow_overlap",
+            "routine_times",
+            "target_completion_rate",
         ];
 
         let unknown_fields: Vec<String> = map
@@ -384,6 +706,10 @@ pub async fn create_goal_handler(
     // Create a mutable copy of the goal with the user_id and start_timestamp
     let goal = Goal {
         user_id: Some(user_id),
+        priority: match goal.priority.as_deref().map(normalize_priority) {
+            Some(Ok(priority)) => Some(priority),
+            _ => goal.priority, // rejected by goal_validation_errors below
+        },
         ..goal
     };
 
@@ -391,21 +717,52 @@ pub async fn create_goal_handler(
         println!("Processed goal creation request: {:?}", goal);
     }
 
+    let validation_errors = goal_validation_errors(&goal);
+    if !validation_errors.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Validation failed:\n- {}", validation_errors.join("\n- ")),
+        ));
+    }
+
+    match goal.create_goal(&graph).await {
+        Ok(created_goal) => {
+            println!("Successfully created goal: {:?}", created_goal);
+
+            Ok((StatusCode::CREATED, Json(created_goal)))
+        }
+        Err(e) => {
+            eprintln!("Error creating goal: {:?}", e);
+            eprintln!("Goal data that caused error: {:?}", goal);
+            eprintln!("Error details: {:#?}", e);
+
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!(
+                    "Failed to create goal. Error: {}. Please check server logs for more details.",
+                    e
+                ),
+            ))
+        }
+    }
+}
+
+/// Validation shared by goal creation and the CSV import; empty when the goal is valid.
+pub fn goal_validation_errors(goal: &Goal) -> Vec<String> {
     let mut validation_errors = Vec::new();
     if goal.name.trim().is_empty() {
-        validation_errors.push("Name is required");
+        validation_errors.push("Name is required".to_string());
     }
-    let error_msg = format!("Invalid user_id {}", goal.user_id.unwrap_or(0));
     if goal.user_id.unwrap_or(0) < 0 {
-        validation_errors.push(&error_msg);
+        validation_errors.push(format!("Invalid user_id {}", goal.user_id.unwrap_or(0)));
     }
     match goal.goal_type {
         GoalType::Routine => {
             if goal.frequency.is_none() {
-                validation_errors.push("Frequency is required for routine goals");
+                validation_errors.push("Frequency is required for routine goals".to_string());
             }
             if goal.start_timestamp.is_none() {
-                validation_errors.push("Start timestamp is required for routine goals");
+                validation_errors.push("Start timestamp is required for routine goals".to_string());
             }
         }
         GoalType::Task => {
@@ -413,57 +770,83 @@ pub async fn create_goal_handler(
         }
         GoalType::Event => {
             if goal.parent_id.is_none() {
-                validation_errors.push("Events must have a parent task or routine");
+                validation_errors.push("Events must have a parent task or routine".to_string());
             }
             if goal.scheduled_timestamp.is_none() {
-                validation_errors.push("Events must have a scheduled time");
+                validation_errors.push("Events must have a scheduled time".to_string());
             }
             if goal.duration.is_none() {
-                validation_errors.push("Events must have a duration");
+                validation_errors.push("Events must have a duration".to_string());
             }
         }
-        GoalType::Project | GoalType::Achievement => {
-            if goal.start_timestamp.is_none() {
-                validation_errors
-                    .push("Start timestamp is required for project and achievement goals");
-            }
+        GoalType::Project | GoalType::Achievement if goal.start_timestamp.is_none() => {
+            validation_errors
+                .push("Start timestamp is required for project and achievement goals".to_string());
         }
         _ => {}
     }
-    if !validation_errors.is_empty() {
-        return Err((
-            StatusCode::UNPROCESSABLE_ENTITY,
-            format!("Validation failed:\n- {}", validation_errors.join("\n- ")),
-        ));
+    if let Some(energy) = &goal.energy_required {
+        if !ENERGY_LEVELS.contains(&energy.as_str()) {
+            validation_errors.push("energy_required must be 'low', 'medium' or 'high'".to_string());
+        }
     }
-
-    match goal.create_goal(&graph).await {
-        Ok(created_goal) => {
-            println!("Successfully created goal: {:?}", created_goal);
-
-            Ok((StatusCode::CREATED, Json(created_goal)))
+    if goal.target_value.is_some_and(|t| t <= 0.0) {
+        validation_errors.push("target_value must be positive".to_string());
+    }
+    if let Some(period) = &goal.reset_period {
+        if goal.goal_type != GoalType::Achievement {
+            validation_errors.push("reset_period is only supported on achievements".to_string());
+        } else if !RESET_PERIODS.contains(&period.as_str()) {
+            validation_errors.push("reset_period must be 'yearly' or 'monthly'".to_string());
         }
-        Err(e) => {
-            eprintln!("Error creating goal: {:?}", e);
-            eprintln!("Goal data that caused error: {:?}", goal);
-            eprintln!("Error details: {:#?}", e);
-
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!(
-                    "Failed to create goal. Error: {}. Please check server logs for more details.",
-                    e
-                ),
-            ))
+    }
+    if let Some(Err(e)) = goal.priority.as_deref().map(normalize_priority) {
+        validation_errors.push(e);
+    }
+    if goal
+        .color
+        .as_deref()
+        .is_some_and(|c| !is_valid_hex_color(c))
+    {
+        validation_errors.push("color must be a hex code like '#1a2b3c'".to_string());
+    }
+    if goal.duration_seconds.is_some_and(|s| s <= 0) {
+        validation_errors.push("duration_seconds must be positive".to_string());
+    }
+    if let Some(Err(e)) = goal.links.as_deref().map(validate_links) {
+        validation_errors.push(e);
+    }
+    if let Some(rate) = goal.target_completion_rate {
+        if goal.goal_type != GoalType::Routine {
+            validation_errors
+                .push("target_completion_rate is only supported on routines".to_string());
+        } else if let Err(e) = validate_target_completion_rate(rate) {
+            validation_errors.push(e);
         }
     }
+    validation_errors
+}
+
+pub fn get_goal_metadata_handler() -> Json<GoalMetadata> {
+    Json(GoalMetadata {
+        goal_types: GoalType::ALL.iter().map(GoalType::as_str).collect(),
+        resolution_statuses: ResolutionStatus::ALL
+            .iter()
+            .map(ResolutionStatus::as_str)
+            .collect(),
+        priorities: PRIORITIES.to_vec(),
+        frequency_units: FREQUENCY_UNITS.to_vec(),
+        energy_levels: ENERGY_LEVELS.to_vec(),
+        reset_periods: RESET_PERIODS.to_vec(),
+    })
 }
 
 pub async fn create_relationship_handler(
     graph: Graph,
+    user_id: i64,
     relationship: Relationship,
 ) -> Result<(StatusCode, &'static str), (StatusCode, String)> {
-    match Goal::create_relationship(&graph, &relationship).await {
+    match Goal::create_relationship(&graph, user_id, &relationship).await {
         Ok(_) => Ok((StatusCode::CREATED, "Relationship created")),
         Err(e) => {
             eprintln!("Error creating relationship: {}", e);
@@ -477,17 +860,25 @@ pub async fn create_relationship_handler(
 
 pub async fn update_goal_handler(
     graph: Graph,
+    user_id: i64,
     id: i64,
     goal: Goal,
 ) -> Result<(StatusCode, Json<Goal>), (StatusCode, String)> {
     // Build the SET clause dynamically based on provided fields
     // Always set updated_at on any update for conflict detection
-    let mut set_clauses = vec!["g.name = $name", "g.goal_type = $goal_type", "g.updated_at = timestamp()"];
+    let mut set_clauses = vec![
+        "g.name = $name",
+        "g.goal_type = $goal_type",
+        "g.updated_at = timestamp()",
+        "g.last_modified = timestamp()",
+        "g.version = coalesce(g.version, 0) + 1",
+    ];
     let mut params = vec![
         (
             "id",
             neo4rs::BoltType::Integer(neo4rs::BoltInteger { value: id }),
         ),
+        ("user_id", user_id.into()),
         ("name", goal.name.into()),
         ("goal_type", goal.goal_type.as_str().into()),
     ];
@@ -500,10 +891,11 @@ pub async fn update_goal_handler(
         // Validate goal type before applying resolution status updates.
         let check_query = query(
             "MATCH (g:Goal)
-             WHERE id(g) = $id
+             WHERE id(g) = $id AND g.user_id = $user_id
              RETURN g.goal_type as goal_type",
         )
-        .param("id", id);
+        .param("id", id)
+        .param("user_id", user_id);
 
         let mut check_result = graph.execute(check_query).await.map_err(|e| {
             (
@@ -550,8 +942,98 @@ pub async fn update_goal_handler(
         params.push(("description", desc.clone().into()));
     }
     if let Some(priority) = &goal.priority {
+        let priority = normalize_priority(priority).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
         set_clauses.push("g.priority = $priority");
-        params.push(("priority", priority.clone().into()));
+        params.push(("priority", priority.into()));
+    }
+    if let Some(energy) = &goal.energy_required {
+        if !ENERGY_LEVELS.contains(&energy.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "energy_required must be 'low', 'medium' or 'high'".to_string(),
+            ));
+        }
+        set_clauses.push("g.energy_required = $energy_required");
+        params.push(("energy_required", energy.clone().into()));
+    }
+    if let Some(target) = goal.target_value {
+        if target <= 0.0 {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "target_value must be positive".to_string(),
+            ));
+        }
+        set_clauses.push("g.target_value = $target_value");
+        params.push(("target_value", target.into()));
+    }
+    if let Some(rate) = goal.target_completion_rate {
+        validate_target_completion_rate(rate).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        set_clauses.push("g.target_completion_rate = $target_completion_rate");
+        params.push(("target_completion_rate", rate.into()));
+    }
+    if let Some(current) = goal.current_value {
+        set_clauses.push("g.current_value = $current_value");
+        params.push(("current_value", current.into()));
+    }
+    if let Some(is_deadline) = goal.is_deadline {
+        set_clauses.push("g.is_deadline = $is_deadline");
+        params.push(("is_deadline", is_deadline.into()));
+    }
+    if let Some(allow_overlap) = goal.allow_overlap {
+        set_clauses.push("g.allow_overlap = $allow_overlap");
+        params.push(("allow_overlap", allow_overlap.into()));
+    }
+    if let Some(skip_holidays) = goal.skip_holidays {
+        set_clauses.push("g.skip_holidays = $skip_holidays");
+        params.push(("skip_holidays", skip_holidays.into()));
+    }
+    if let Some(period) = &goal.reset_period {
+        let Some(period_start) = achievements::reset_period_start(period, chrono::Utc::now())
+        else {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "reset_period must be 'yearly' or 'monthly'".to_string(),
+            ));
+        };
+        set_clauses.push("g.reset_period = $reset_period");
+        params.push(("reset_period", period.clone().into()));
+        // Progress made before the period was set up counts toward the current period
+        set_clauses.push("g.last_reset_at = coalesce(g.last_reset_at, $last_reset_at)");
+        params.push(("last_reset_at", period_start.into()));
+    }
+    if let Some(notes) = &goal.notes {
+        set_clauses.push("g.notes = $notes");
+        params.push(("notes", notes.clone().into()));
+    }
+    if let Some(color) = &goal.color {
+        if !is_valid_hex_color(color) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "color must be a hex code like '#1a2b3c'".to_string(),
+            ));
+        }
+        set_clauses.push("g.color = $color");
+        params.push(("color", color.clone().into()));
+    }
+    if let Some(seconds) = goal.duration_seconds {
+        if seconds <= 0 {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "duration_seconds must be positive".to_string(),
+            ));
+        }
+        set_clauses.push("g.duration_seconds = $duration_seconds");
+        params.push(("duration_seconds", seconds.into()));
+    }
+    if let Some(recurrence) = &goal.recurrence {
+        validate_frequency(recurrence).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        set_clauses.push("g.recurrence = $recurrence");
+        params.push(("recurrence", recurrence.clone().into()));
+    }
+    if let Some(links) = &goal.links {
+        validate_links(links).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        set_clauses.push("g.links = $links");
+        params.push(("links", links.clone().into()));
     }
     if let Some(start) = goal.start_timestamp {
         set_clauses.push("g.start_timestamp = $start_timestamp");
@@ -585,6 +1067,14 @@ pub async fn update_goal_handler(
         set_clauses.push("g.routine_time = $routine_time");
         params.push(("routine_time", routine_time.into()));
     }
+    if let Some(routine_times) = &goal.routine_times {
+        if routine_times.is_empty() {
+            set_clauses.push("g.routine_times = null");
+        } else {
+            set_clauses.push("g.routine_times = $routine_times");
+            params.push(("routine_times", routine_times.clone().into()));
+        }
+    }
     if let Some(x) = goal.position_x {
         set_clauses.push("g.position_x = $position_x");
         params.push(("position_x", x.into()));
@@ -688,7 +1178,7 @@ pub async fn update_goal_handler(
     }
 
     let query_str = format!(
-        "MATCH (g:Goal) WHERE id(g) = $id SET {} {}",
+        "MATCH (g:Goal) WHERE id(g) = $id AND g.user_id = $user_id SET {} {}",
         set_clauses.join(", "),
         GOAL_RETURN_QUERY
     );
@@ -731,86 +1221,1020 @@ pub async fn update_goal_handler(
     }
 }
 
+/// Push a task's or routine's current name, priority and description onto its live
+/// events. Events copy these from the parent when they are created, so a rename would
+/// otherwise leave every existing occurrence with the old values. Completed, past and
+/// deleted events are history and are left untouched.
+pub async fn sync_events_to_parent_handler(
+    graph: Graph,
+    user_id: i64,
+    goal_id: i64,
+) -> Result<Json<SyncEventsResponse>, (StatusCode, String)> {
+    let sync_query = query(
+        "MATCH (g:Goal)
+         WHERE id(g) = $goal_id AND g.user_id = $user_id
+         OPTIONAL MATCH (g)-[:HAS_EVENT]->(e:Goal)
+         WHERE coalesce(e.is_deleted, false) <> true
+           AND coalesce(e.resolution_status, 'pending') <> 'completed'
+           AND e.scheduled_timestamp >= $now
+         SET e.name = g.name,
+             e.priority = g.priority,
+             e.description = g.description,
+             e.updated_at = timestamp(),
+             e.last_modified = timestamp()
+         RETURN count(e) AS updated",
+    )
+    .param("goal_id", goal_id)
+    .param("user_id", user_id)
+    .param("now", chrono::Utc::now().timestamp_millis());
+
+    let mut result = graph.execute(sync_query).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Error syncing events to parent: {}", e),
+        )
+    })?;
+
+    let row = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Goal not found".to_string()))?;
+    let updated: i64 = row.get("updated").unwrap_or(0);
+
+    Ok(Json(SyncEventsResponse { updated }))
+}
+
+/// Soft-delete a goal. The goal, its CHILD descendants and their not-yet-deleted events are
+/// flagged with `is_deleted` so they drop out of active views but can be brought back with
+/// `restore_goal_handler` until the purge job removes them for good. A descendant that is
+/// also linked under a live goal outside the deleted tree stays live. The cascade runs in
+/// one transaction, so a failure can't leave the tree half-deleted.
 pub async fn delete_goal_handler(
     graph: Graph,
+    user_id: i64,
     id: i64,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    // First check if this is a routine goal, and if so, delete all its events
-    let check_routine_query = query(
-        "MATCH (g:Goal) WHERE id(g) = $id 
-         RETURN g.goal_type as goal_type",
+    let now = chrono::Utc::now().timestamp_millis();
+
+    // Descendants are tagged with the goal that deleted them, so a restore only brings
+    // back what this deletion removed
+    let delete_descendants_query = query(
+        "MATCH (g:Goal) WHERE id(g) = $id AND g.user_id = $user_id
+         MATCH (g)-[:CHILD*1..]->(d:Goal)
+         WHERE d.user_id = $user_id AND d.goal_type <> 'event'
+         WITH g, collect(DISTINCT d) as descendants
+         WITH descendants, [g] + descendants as subtree
+         UNWIND descendants as d
+         WITH d, subtree
+         WHERE coalesce(d.is_deleted, false) <> true
+         AND NOT EXISTS {
+             MATCH (p:Goal)-[:CHILD]->(d)
+             WHERE NOT p IN subtree AND coalesce(p.is_deleted, false) <> true
+         }
+         SET d.is_deleted = true,
+             d.deleted_at = $now,
+             d.deleted_by_parent = $id,
+             d.last_modified = timestamp()",
+    )
+    .param("id", id)
+    .param("user_id", user_id)
+    .param("now", now);
+
+    // Then the events of the goal and of every descendant deleted with it. A routine keeps
+    // its past and completed occurrences as history and only drops upcoming ones; any
+    // other goal takes all of its events. Each event is tagged with its own parent, so
+    // restoring that parent (on its own or as part of the tree) brings them back. Events
+    // deleted by hand aren't touched, so a restore doesn't bring them back.
+    let delete_events_query = query(
+        "MATCH (p:Goal)-[:HAS_EVENT]->(e:Goal)
+         WHERE p.user_id = $user_id
+         AND (id(p) = $id OR (p.deleted_by_parent = $id AND p.deleted_at = $now))
+         AND e.user_id = $user_id
+         AND e.goal_type = 'event'
+         AND coalesce(e.is_deleted, false) <> true
+         AND (p.goal_type <> 'routine'
+              OR (coalesce(e.scheduled_timestamp, 0) >= $now
+                  AND coalesce(e.resolution_status, 'pending') <> 'completed'))
+         SET e.is_deleted = true,
+             e.deleted_at = $now,
+             e.deleted_by_parent = id(p),
+             e.last_modified = timestamp()",
+    )
+    .param("id", id)
+    .param("user_id", user_id)
+    .param("now", now);
+
+    let delete_query = query(
+        "MATCH (g:Goal) WHERE id(g) = $id AND g.user_id = $user_id
+         SET g.is_deleted = true,
+             g.deleted_at = $now,
+             g.last_modified = timestamp()
+         RETURN id(g) as id",
+    )
+    .param("id", id)
+    .param("user_id", user_id)
+    .param("now", now);
+
+    let mut txn = graph
+        .start_txn()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let outcome: Result<bool, neo4rs::Error> = async {
+        txn.run(delete_descendants_query).await?;
+        txn.run(delete_events_query).await?;
+        let mut result = txn.execute(delete_query).await?;
+        Ok(result.next(txn.handle()).await?.is_some())
+    }
+    .await;
+
+    match outcome {
+        Ok(true) => {
+            txn.commit()
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            Ok(StatusCode::OK)
+        }
+        Ok(false) => {
+            let _ = txn.rollback().await;
+            Err((StatusCode::NOT_FOUND, "Goal not found".to_string()))
+        }
+        Err(e) => {
+            let _ = txn.rollback().await;
+            eprintln!("Error deleting goal: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Error deleting goal: {}", e),
+            ))
+        }
+    }
+}
+
+/// Undo a soft delete, bringing back the descendants that were removed along with the
+/// goal and the events of every goal restored. Only the user's own nodes reached from the
+/// goal are restored, all in one transaction.
+pub async fn restore_goal_handler(
+    graph: Graph,
+    user_id: i64,
+    id: i64,
+) -> Result<Json<Goal>, (StatusCode, String)> {
+    // Events first, while the descendants still carry their deleted_by_parent tag. Events
+    // are tagged with their own parent, so a descendant restored by itself gets its
+    // events back too.
+    let restore_events_query = query(
+        "MATCH (g:Goal)
+         WHERE id(g) = $id AND g.user_id = $user_id
+         AND g.goal_type <> 'event' AND g.is_deleted = true
+         OPTIONAL MATCH (g)-[:CHILD*1..]->(d:Goal)
+         WHERE d.deleted_by_parent = $id
+         AND d.user_id = $user_id AND d.goal_type <> 'event'
+         WITH g, collect(DISTINCT d) as descendants
+         UNWIND [g] + descendants as p
+         MATCH (p)-[:HAS_EVENT]->(e:Goal)
+         WHERE e.deleted_by_parent = id(p)
+         AND e.user_id = $user_id AND e.goal_type = 'event'
+         SET e.is_deleted = false,
+             e.last_modified = timestamp()
+         REMOVE e.deleted_at, e.deleted_by_parent",
+    )
+    .param("id", id)
+    .param("user_id", user_id);
+
+    let restore_descendants_query = query(
+        "MATCH (g:Goal)
+         WHERE id(g) = $id AND g.user_id = $user_id
+         AND g.goal_type <> 'event' AND g.is_deleted = true
+         MATCH (g)-[:CHILD*1..]->(d:Goal)
+         WHERE d.deleted_by_parent = $id
+         AND d.user_id = $user_id AND d.goal_type <> 'event'
+         WITH DISTINCT d
+         SET d.is_deleted = false,
+             d.last_modified = timestamp()
+         REMOVE d.deleted_at, d.deleted_by_parent",
     )
-    .param("id", id);
+    .param("id", id)
+    .param("user_id", user_id);
+
+    let restore_query = query(&format!(
+        "MATCH (g:Goal) WHERE id(g) = $id AND g.user_id = $user_id
+         AND g.goal_type <> 'event' AND g.is_deleted = true
+         SET g.is_deleted = false,
+             g.last_modified = timestamp()
+         REMOVE g.deleted_at, g.deleted_by_parent
+         {}",
+        GOAL_RETURN_QUERY
+    ))
+    .param("id", id)
+    .param("user_id", user_id);
+
+    let mut txn = graph
+        .start_txn()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let outcome: Result<Option<neo4rs::Row>, neo4rs::Error> = async {
+        txn.run(restore_events_query).await?;
+        txn.run(restore_descendants_query).await?;
+        let mut result = txn.execute(restore_query).await?;
+        result.next(txn.handle()).await
+    }
+    .await;
+
+    match outcome {
+        Ok(Some(row)) => match row.get::<Goal>("g") {
+            Ok(goal) => {
+                txn.commit()
+                    .await
+                    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                Ok(Json(goal))
+            }
+            Err(e) => {
+                let _ = txn.rollback().await;
+                Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+            }
+        },
+        Ok(None) => {
+            let _ = txn.rollback().await;
+            Err((
+                StatusCode::NOT_FOUND,
+                "Deleted goal not found".to_string(),
+            ))
+        }
+        Err(e) => {
+            let _ = txn.rollback().await;
+            eprintln!("Error restoring goal: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Error restoring goal: {}", e),
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeGoalsResponse {
+    pub goal: Goal,
+    pub moved_events: i64,
+    pub moved_children: i64,
+    pub moved_parents: i64,
+    pub event_count: i64, // the target's live events after the merge
+    pub child_count: i64,
+}
+
+/// The rules `merge_goals_handler` applies once both goals are loaded: same type, not
+/// events, and not separated by intermediate goals on a CHILD path (`nested`).
+pub fn check_merge(
+    source_type: &str,
+    target_type: &str,
+    nested: bool,
+) -> Result<(), (StatusCode, String)> {
+    if source_type != target_type {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Can't merge a {} into a {}; both goals must have the same type",
+                source_type, target_type
+            ),
+        ));
+    }
+    if source_type == GoalType::Event.as_str() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Events can't be merged".to_string(),
+        ));
+    }
+    if nested {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "One goal is nested under the other; move it out before merging".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Fold a duplicate goal into another of the same type: the source's events, children and
+/// parents move onto the target and the source is soft-deleted. Goals on the same CHILD
+/// path can only be merged when directly linked, since anything in between would end up
+/// in a cycle. A task target widens its dates to cover the events it takes over.
+pub async fn merge_goals_handler(
+    graph: Graph,
+    user_id: i64,
+    source_id: i64,
+    target_id: i64,
+) -> Result<Json<MergeGoalsResponse>, (StatusCode, String)> {
+    if source_id == target_id {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "A goal can't be merged into itself".to_string(),
+        ));
+    }
+
+    let mut check = graph
+        .execute(
+            query(
+                "MATCH (s:Goal), (t:Goal)
+                 WHERE id(s) = $source_id AND id(t) = $target_id
+                 AND s.user_id = $user_id AND t.user_id = $user_id
+                 AND coalesce(s.is_deleted, false) <> true
+                 AND coalesce(t.is_deleted, false) <> true
+                 RETURN s.goal_type as source_type,
+                        t.goal_type as target_type,
+                        EXISTS { MATCH (s)-[:CHILD*2..]->(t) }
+                        OR EXISTS { MATCH (t)-[:CHILD*2..]->(s) } as nested",
+            )
+            .param("source_id", source_id)
+            .param("target_id", target_id)
+            .param("user_id", user_id),
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let row = check
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Goal not found".to_string()))?;
+
+    let source_type: String = row.get("source_type").unwrap_or_default();
+    let target_type: String = row.get("target_type").unwrap_or_default();
+    check_merge(
+        &source_type,
+        &target_type,
+        row.get::<bool>("nested").unwrap_or(false),
+    )?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut txn = graph
+        .start_txn()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let outcome: Result<(i64, i64, i64), neo4rs::Error> = async {
+        let moved_events = count_in_txn(
+            &mut txn,
+            query(
+                "MATCH (s:Goal)-[r:HAS_EVENT]->(e:Goal), (t:Goal)
+                 WHERE id(s) = $source_id AND id(t) = $target_id
+                 CREATE (t)-[:HAS_EVENT]->(e)
+                 SET e.parent_id = id(t),
+                     e.updated_at = timestamp(),
+                     e.last_modified = timestamp()
+                 DELETE r
+                 RETURN count(e) as moved",
+            )
+            .param("source_id", source_id)
+            .param("target_id", target_id),
+        )
+        .await?;
+
+        let moved_children = count_in_txn(
+            &mut txn,
+            query(
+                "MATCH (s:Goal)-[r:CHILD]->(c:Goal), (t:Goal)
+                 WHERE id(s) = $source_id AND id(t) = $target_id AND id(c) <> $target_id
+                 MERGE (t)-[:CHILD]->(c)
+                 DELETE r
+                 RETURN count(c) as moved",
+            )
+            .param("source_id", source_id)
+            .param("target_id", target_id),
+        )
+        .await?;
+
+        let moved_parents = count_in_txn(
+            &mut txn,
+            query(
+                "MATCH (p:Goal)-[r:CHILD]->(s:Goal), (t:Goal)
+                 WHERE id(s) = $source_id AND id(t) = $target_id AND id(p) <> $target_id
+                 MERGE (p)-[:CHILD]->(t)
+                 DELETE r
+                 RETURN count(p) as moved",
+            )
+            .param("source_id", source_id)
+            .param("target_id", target_id),
+        )
+        .await?;
+
+        // Whatever is left links the two goals directly and would become a self-loop
+        txn.run(
+            query(
+                "MATCH (s:Goal)-[r:CHILD]-(t:Goal)
+                 WHERE id(s) = $source_id AND id(t) = $target_id
+                 DELETE r",
+            )
+            .param("source_id", source_id)
+            .param("target_id", target_id),
+        )
+        .await?;
+
+        txn.run(
+            query(
+                "MATCH (t:Goal)-[:HAS_EVENT]->(e:Goal)
+                 WHERE id(t) = $target_id AND t.goal_type = 'task'
+                 AND coalesce(e.is_deleted, false) <> true
+                 WITH t, min(e.scheduled_timestamp) as first, max(e.scheduled_timestamp) as last
+                 SET t.start_timestamp = CASE WHEN t.start_timestamp > first
+                                              THEN first ELSE t.start_timestamp END,
+                     t.end_timestamp = CASE WHEN t.end_timestamp < last
+                                            THEN last ELSE t.end_timestamp END,
+                     t.last_modified = timestamp()",
+            )
+            .param("target_id", target_id),
+        )
+        .await?;
+
+        txn.run(
+            query(
+                "MATCH (s:Goal) WHERE id(s) = $source_id
+                 SET s.is_deleted = true,
+                     s.deleted_at = $now,
+                     s.last_modified = timestamp()",
+            )
+            .param("source_id", source_id)
+            .param("now", now),
+        )
+        .await?;
+
+        Ok((moved_events, moved_children, moved_parents))
+    }
+    .await;
+
+    let (moved_events, moved_children, moved_parents) = match outcome {
+        Ok(moved) => {
+            txn.commit()
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            moved
+        }
+        Err(e) => {
+            let _ = txn.rollback().await;
+            eprintln!("Error merging goal {} into {}: {}", source_id, target_id, e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to merge goals: {}", e),
+            ));
+        }
+    };
+
+    let mut result = graph
+        .execute(
+            query(&format!(
+                "MATCH (g:Goal) WHERE id(g) = $target_id
+                 OPTIONAL MATCH (g)-[:HAS_EVENT]->(e:Goal)
+                 WHERE coalesce(e.is_deleted, false) <> true
+                 WITH g, count(e) as event_count
+                 OPTIONAL MATCH (g)-[:CHILD]->(c:Goal)
+                 WHERE coalesce(c.is_deleted, false) <> true
+                 WITH g, event_count, count(c) as child_count
+                 {}, event_count, child_count",
+                GOAL_RETURN_QUERY
+            ))
+            .param("target_id", target_id),
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let row = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Goal not found".to_string()))?;
+
+    Ok(Json(MergeGoalsResponse {
+        goal: row
+            .get("g")
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+        moved_events,
+        moved_children,
+        moved_parents,
+        event_count: row.get("event_count").unwrap_or(0),
+        child_count: row.get("child_count").unwrap_or(0),
+    }))
+}
+
+/// Run a write query inside `txn` and read back the single count it returns as `moved`.
+async fn count_in_txn(txn: &mut neo4rs::Txn, q: neo4rs::Query) -> Result<i64, neo4rs::Error> {
+    let mut result = txn.execute(q).await?;
+    Ok(match result.next(txn.handle()).await? {
+        Some(row) => row.get::<i64>("moved").unwrap_or(0),
+        None => 0,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeGoalTypeRequest {
+    pub goal_type: GoalType,
+    // Fields the new type needs that the goal may not have yet
+    pub frequency: Option<String>,
+    pub routine_time: Option<i64>,
+    pub start_timestamp: Option<i64>,
+    pub target_value: Option<f64>,
+    pub generate_events: Option<bool>, // task -> routine: fill in the routine's upcoming events
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChangeGoalTypeResponse {
+    pub goal: Goal,
+    pub previous_type: GoalType,
+    pub event_count: i64, // live events kept under the goal
+}
 
-    let mut check_result = graph.execute(check_routine_query).await.map_err(|e| {
-        eprintln!("Error checking goal type: {}", e);
+/// The goal as it would look after switching to `request.goal_type`, or every reason the
+/// switch isn't allowed. Fields that only mean something for the old type are dropped, the
+/// request's fields are filled in, and the result has to pass the normal goal validation.
+/// Events can't change type in either direction, and directives and projects can't take
+/// over a goal that still has events.
+pub fn apply_goal_type_change(
+    current: &Goal,
+    request: &ChangeGoalTypeRequest,
+    event_count: i64,
+) -> Result<Goal, Vec<String>> {
+    let new_type = request.goal_type;
+    if current.goal_type == new_type {
+        return Err(vec![format!("Goal is already a {}", new_type)]);
+    }
+    if current.goal_type == GoalType::Event || new_type == GoalType::Event {
+        return Err(vec![
+            "Events can't change type; create the event under a task or routine instead"
+                .to_string(),
+        ]);
+    }
+
+    let mut goal = current.clone();
+    goal.goal_type = new_type;
+    if current.goal_type == GoalType::Routine {
+        goal.frequency = None;
+        goal.routine_time = None;
+        goal.routine_type = None;
+        goal.skip_holidays = None;
+        goal.target_completion_rate = None;
+    }
+    if current.goal_type == GoalType::Achievement {
+        goal.target_value = None;
+        goal.current_value = None;
+        goal.reset_period = None;
+        goal.last_reset_at = None;
+    }
+    if new_type == GoalType::Routine {
+        goal.frequency = request.frequency.clone().or(goal.frequency);
+        goal.routine_time = request.routine_time.or(goal.routine_time);
+    }
+    if new_type == GoalType::Achievement {
+        goal.target_value = request.target_value.or(goal.target_value);
+    }
+    goal.start_timestamp = request.start_timestamp.or(goal.start_timestamp);
+
+    let mut errors = goal_validation_errors(&goal);
+    if let (GoalType::Routine, Some(frequency)) = (new_type, goal.frequency.as_deref()) {
+        if let Err(e) = validate_frequency(frequency) {
+            errors.push(e);
+        }
+    }
+    if matches!(new_type, GoalType::Directive | GoalType::Project) && event_count > 0 {
+        errors.push(format!(
+            "A {} can't hold events; move or delete this goal's {} event(s) first",
+            new_type, event_count
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(goal)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Convert a goal to another type in place, keeping its id, events and relationships, so
+/// history survives where deleting and recreating would lose it. Events are relabelled
+/// with the new parent type.
+pub async fn change_goal_type_handler(
+    graph: Graph,
+    user_id: i64,
+    goal_id: i64,
+    request: ChangeGoalTypeRequest,
+) -> Result<Json<ChangeGoalTypeResponse>, (StatusCode, String)> {
+    let mut result = graph
+        .execute(
+            query(&format!(
+                "MATCH (g:Goal)
+                 WHERE id(g) = $goal_id AND g.user_id = $user_id
+                 AND coalesce(g.is_deleted, false) <> true
+                 OPTIONAL MATCH (g)-[:HAS_EVENT]->(e:Goal)
+                 WHERE coalesce(e.is_deleted, false) <> true
+                 WITH g, count(e) as event_count
+                 {}, event_count",
+                GOAL_RETURN_QUERY
+            ))
+            .param("goal_id", goal_id)
+            .param("user_id", user_id),
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let row = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Goal not found".to_string()))?;
+    let current: Goal = row
+        .get("g")
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let event_count: i64 = row.get("event_count").unwrap_or(0);
+
+    let goal = apply_goal_type_change(&current, &request, event_count).map_err(|errors| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Error checking goal type: {}", e),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "Can't change this {} to a {}:\n- {}",
+                current.goal_type,
+                request.goal_type,
+                errors.join("\n- ")
+            ),
         )
     })?;
 
-    if let Some(row) = check_result.next().await.map_err(|e| {
-        eprintln!("Error fetching goal type: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Error fetching goal type: {}", e),
+    let mut txn = graph
+        .start_txn()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let outcome: Result<Option<neo4rs::Row>, neo4rs::Error> = async {
+        txn.run(
+            query(
+                "MATCH (g:Goal)-[:HAS_EVENT]->(e:Goal)
+                 WHERE id(g) = $goal_id
+                 SET e.parent_type = $goal_type,
+                     e.last_modified = timestamp()",
+            )
+            .param("goal_id", goal_id)
+            .param("goal_type", goal.goal_type.as_str()),
         )
-    })? {
-        let goal_type: String = row.get("goal_type").unwrap_or_default();
-
-        if goal_type == "routine" {
-            // Delete all events belonging to this routine first
-            let delete_events_query = query(
-                "MATCH (r:Goal)-[:HAS_EVENT]->(e:Goal)
-                 WHERE id(r) = $id
-                 DETACH DELETE e",
+        .await?;
+
+        let mut result = txn
+            .execute(
+                query(&format!(
+                    "MATCH (g:Goal) WHERE id(g) = $goal_id
+                 SET g.goal_type = $goal_type,
+                     g.start_timestamp = $start_timestamp,
+                     g.frequency = $frequency,
+                     g.routine_time = $routine_time,
+                     g.routine_type = $routine_type,
+                     g.skip_holidays = $skip_holidays,
+                     g.target_completion_rate = $target_completion_rate,
+                     g.target_value = $target_value,
+                     g.current_value = $current_value,
+                     g.reset_period = $reset_period,
+                     g.last_reset_at = $last_reset_at,
+                     g.updated_at = timestamp(),
+                     g.last_modified = timestamp(),
+                     g.version = coalesce(g.version, 0) + 1
+                 WITH g
+                 {}",
+                    GOAL_RETURN_QUERY
+                ))
+                .param("goal_id", goal_id)
+                .param("goal_type", goal.goal_type.as_str())
+                .param("start_timestamp", goal.start_timestamp)
+                .param("frequency", goal.frequency.clone())
+                .param("routine_time", goal.routine_time)
+                .param("routine_type", goal.routine_type.clone())
+                .param("skip_holidays", goal.skip_holidays)
+                .param("target_completion_rate", goal.target_completion_rate)
+                .param("target_value", goal.target_value)
+                .param("current_value", goal.current_value)
+                .param("reset_period", goal.reset_period.clone())
+                .param("last_reset_at", goal.last_reset_at),
             )
-            .param("id", id);
+            .await?;
+        result.next(txn.handle()).await
+    }
+    .await;
 
-            graph.run(delete_events_query).await.map_err(|e| {
-                eprintln!("Error deleting routine events: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Error deleting routine events: {}", e),
-                )
-            })?;
+    let row = match outcome {
+        Ok(row) => {
+            txn.commit()
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            row.ok_or((StatusCode::NOT_FOUND, "Goal not found".to_string()))?
+        }
+        Err(e) => {
+            let _ = txn.rollback().await;
+            eprintln!("Error changing type of goal {}: {}", goal_id, e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to change goal type: {}", e),
+            ));
+        }
+    };
+
+    Ok(Json(ChangeGoalTypeResponse {
+        goal: row
+            .get("g")
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+        previous_type: current.goal_type,
+        event_count,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DuplicateRoutineRequest {
+    pub time_offset_ms: Option<i64>, // shift applied to the time(s) of day; negative is earlier
+    pub name_suffix: Option<String>, // appended to the name as-is, e.g. " (evening)"
+    pub generate_events: Option<bool>, // fill in the new routine's upcoming events
+}
 
-            // Delete all routine exceptions so we don't leave orphaned tombstones behind
-            let delete_exceptions_query = query(
-                "MATCH (r:Goal)-[:HAS_EXCEPTION]->(x:RoutineException)
-                 WHERE id(r) = $id
-                 DETACH DELETE x",
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// A new routine with `source`'s schedule and settings, its time(s) of day moved by
+/// `time_offset_ms` (wrapping past midnight). Nothing tied to the original's history,
+/// sync state or events is carried over.
+pub fn duplicate_routine_config(
+    source: &Goal,
+    time_offset_ms: i64,
+    name_suffix: &str,
+) -> Result<Goal, String> {
+    if source.goal_type != GoalType::Routine {
+        return Err(format!(
+            "Only routines can be duplicated, not a {}",
+            source.goal_type
+        ));
+    }
+    if time_offset_ms.abs() >= DAY_MS {
+        return Err("time_offset_ms must be less than a day".to_string());
+    }
+    if time_offset_ms != 0 && source.routine_time.is_none() && source.routine_times.is_none() {
+        return Err("Routine has no time of day to shift".to_string());
+    }
+
+    let shift = |time: i64| (time + time_offset_ms).rem_euclid(DAY_MS);
+    let routine_times = source.routine_times.as_ref().map(|times| {
+        let mut shifted: Vec<i64> = times.iter().copied().map(shift).collect();
+        shifted.sort_unstable();
+        shifted
+    });
+
+    Ok(Goal {
+        name: format!("{}{}", source.name, name_suffix),
+        goal_type: GoalType::Routine,
+        description: source.description.clone(),
+        user_id: source.user_id,
+        priority: source.priority.clone(),
+        start_timestamp: source.start_timestamp,
+        end_timestamp: source.end_timestamp,
+        duration: source.duration,
+        duration_seconds: source.duration_seconds,
+        frequency: source.frequency.clone(),
+        routine_type: source.routine_type.clone(),
+        routine_time: source.routine_time.map(shift),
+        routine_times,
+        skip_holidays: source.skip_holidays,
+        energy_required: source.energy_required.clone(),
+        color: source.color.clone(),
+        target_completion_rate: source.target_completion_rate,
+        ..Default::default()
+    })
+}
+
+/// Create a copy of a routine with its schedule shifted, e.g. an evening version of a
+/// morning routine, under the same parents. The original's events stay with it; the copy
+/// starts with none.
+pub async fn duplicate_routine_handler(
+    graph: Graph,
+    user_id: i64,
+    routine_id: i64,
+    time_offset_ms: i64,
+    name_suffix: &str,
+) -> Result<(StatusCode, Json<Goal>), (StatusCode, String)> {
+    let (_, Json(source)) = get_goal_handler(graph.clone(), user_id, routine_id).await?;
+    let duplicate = duplicate_routine_config(&source, time_offset_ms, name_suffix)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let (status, Json(created)) = create_goal_handler(graph.clone(), user_id, duplicate).await?;
+
+    let new_id = created.id.ok_or((
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Duplicated routine has no id".to_string(),
+    ))?;
+    graph
+        .run(
+            query(
+                "MATCH (p:Goal)-[:CHILD]->(o:Goal) WHERE id(o) = $old_id
+                 WITH p
+                 MATCH (n:Goal) WHERE id(n) = $new_id
+                 MERGE (p)-[:CHILD]->(n)",
             )
-            .param("id", id);
+            .param("old_id", routine_id)
+            .param("new_id", new_id),
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-            graph.run(delete_exceptions_query).await.map_err(|e| {
-                eprintln!("Error deleting routine exceptions: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Error deleting routine exceptions: {}", e),
-                )
-            })?;
+    Ok((status, Json(created)))
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeletedGoal {
+    #[serde(flatten)]
+    pub goal: Goal,
+    pub deleted_at: Option<i64>,
+}
+
+/// List the user's soft-deleted (non-event) goals, most recently deleted first.
+pub async fn list_deleted_goals(
+    graph: Graph,
+    user_id: i64,
+) -> Result<Json<Vec<DeletedGoal>>, (StatusCode, String)> {
+    let query_str = format!(
+        "MATCH (g:Goal)
+         WHERE g.user_id = $user_id
+         AND g.goal_type <> 'event'
+         AND g.is_deleted = true
+         WITH g ORDER BY g.deleted_at DESC
+         {}, g.deleted_at as deleted_at",
+        GOAL_RETURN_QUERY
+    );
+
+    let mut result = graph
+        .execute(query(&query_str).param("user_id", user_id))
+        .await
+        .map_err(|e| {
+            eprintln!("Error fetching deleted goals: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Error fetching deleted goals: {}", e),
+            )
+        })?;
+
+    let mut goals = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        if let Ok(goal) = row.get::<Goal>("g") {
+            goals.push(DeletedGoal {
+                goal,
+                deleted_at: row.get::<Option<i64>>("deleted_at").unwrap_or(None),
+            });
         }
     }
 
-    // Now delete the goal itself
-    let query = query(
-        "MATCH (g:Goal) WHERE id(g) = $id 
-         DETACH DELETE g",
-    )
-    .param("id", id);
+    Ok(Json(goals))
+}
 
-    match graph.run(query).await {
-        Ok(_) => Ok(StatusCode::OK),
-        Err(e) => {
-            eprintln!("Error deleting goal: {}", e);
-            Err((
+#[derive(Debug, Serialize)]
+pub struct GoalTombstone {
+    pub id: i64,
+    pub deleted_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalsSinceResponse {
+    pub goals: Vec<Goal>, // created or changed since the cursor, events included
+    pub tombstones: Vec<GoalTombstone>, // soft-deleted since the cursor
+    pub synced_at: i64,   // pass back as `since` on the next call
+}
+
+/// Soft-deleted goals are purged after the retention window, so a cursor older than that
+/// could silently miss deletions; those clients have to refetch everything.
+pub fn sync_cursor_expired(since: i64, now: i64) -> bool {
+    let retention =
+        chrono::Duration::days(crate::jobs::deleted_goal_purger::DELETED_GOAL_RETENTION_DAYS);
+    since < now - retention.num_milliseconds()
+}
+
+/// Goals and events written at or after `since` (a previous `synced_at`), for incremental
+/// sync. Hard-deleted events (bulk delete with `hard`) leave no tombstone.
+pub async fn get_goals_since_handler(
+    graph: Graph,
+    user_id: i64,
+    since: i64,
+) -> Result<Json<GoalsSinceResponse>, (StatusCode, String)> {
+    if sync_cursor_expired(since, chrono::Utc::now().timestamp_millis()) {
+        return Err((
+            StatusCode::GONE,
+            "Sync cursor is older than the deleted-goal retention window; refetch all goals"
+                .to_string(),
+        ));
+    }
+
+    // Read the cursor from the database clock (what last_modified is written with) before
+    // the changes, so a write racing this request is sent again next time rather than lost
+    let mut now_result = graph
+        .execute(query("RETURN timestamp() as now"))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let synced_at: i64 = now_result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .and_then(|row| row.get("now").ok())
+        .ok_or((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to read database time".to_string(),
+        ))?;
+
+    let query_str = format!(
+        "MATCH (g:Goal)
+         WHERE g.user_id = $user_id
+         AND g.last_modified >= $since
+         WITH g ORDER BY g.last_modified
+         {}, coalesce(g.is_deleted, false) as is_deleted, g.deleted_at as deleted_at",
+        GOAL_RETURN_QUERY
+    );
+
+    let mut result = graph
+        .execute(
+            query(&query_str)
+                .param("user_id", user_id)
+                .param("since", since),
+        )
+        .await
+        .map_err(|e| {
+            eprintln!("Error fetching changed goals: {}", e);
+            (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Error deleting goal: {}", e),
-            ))
+                format!("Error fetching changed goals: {}", e),
+            )
+        })?;
+
+    let mut goals = Vec::new();
+    let mut tombstones = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        let Some(goal) = goal_from_row_lenient(&row, "g") else {
+            continue;
+        };
+        if row.get::<bool>("is_deleted").unwrap_or(false) {
+            if let Some(id) = goal.id {
+                tombstones.push(GoalTombstone {
+                    id,
+                    deleted_at: row.get::<Option<i64>>("deleted_at").unwrap_or(None),
+                });
+            }
+        } else {
+            goals.push(goal);
+        }
+    }
+
+    Ok(Json(GoalsSinceResponse {
+        goals,
+        tombstones,
+        synced_at,
+    }))
+}
+
+/// Incomplete tasks and achievements that have no upcoming events scheduled, so the
+/// user can be nudged to put them on the calendar. Soonest deadline first.
+pub async fn get_unscheduled_goals_handler(
+    graph: Graph,
+    user_id: i64,
+) -> Result<Json<Vec<Goal>>, (StatusCode, String)> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let query_str = format!(
+        "MATCH (g:Goal)
+         WHERE g.user_id = $user_id
+         AND g.goal_type IN ['task', 'achievement']
+         AND (g.is_deleted IS NULL OR g.is_deleted = false)
+         AND COALESCE(g.resolution_status, 'pending') = 'pending'
+         AND NOT EXISTS {{
+             MATCH (g)-[:HAS_EVENT]->(e:Goal)
+             WHERE e.scheduled_timestamp >= $now
+             AND (e.is_deleted IS NULL OR e.is_deleted = false)
+         }}
+         WITH g ORDER BY COALESCE(g.due_date, g.end_timestamp) ASC, g.name ASC
+         {}",
+        GOAL_RETURN_QUERY
+    );
+
+    let mut result = graph
+        .execute(
+            query(&query_str)
+                .param("user_id", user_id)
+                .param("now", now),
+        )
+        .await
+        .map_err(|e| {
+            eprintln!("Error fetching unscheduled goals: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Error fetching unscheduled goals: {}", e),
+            )
+        })?;
+
+    let mut goals = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        if let Ok(goal) = row.get::<Goal>("g") {
+            goals.push(goal);
         }
     }
+
+    Ok(Json(goals))
 }
 
 /// Computes the display status for a goal based on resolution status and dates
@@ -825,7 +2249,12 @@ pub fn get_display_status(
 ) -> String {
     // For routines, if now > end_timestamp, it is considered completed even if pending
     if let (Some("routine"), Some(end), status) = (goal_type, end_timestamp, resolution_status) {
-        if now > end && status != Some("completed") && status != Some("failed") && status != Some("skipped") {
+        if now > end
+            && status != Some("completed")
+            && status != Some("failed")
+            && status != Some("skipped")
+            && status != Some("cancelled")
+        {
             return "completed".to_string();
         }
     }
@@ -843,6 +2272,7 @@ pub fn get_display_status(
         }
         Some("failed") => "failed".to_string(),
         Some("skipped") => "skipped".to_string(),
+        Some("cancelled") => "cancelled".to_string(),
         _ => {
             // Pending - compute temporal state from dates
             let start = start_timestamp;
@@ -906,6 +2336,7 @@ pub async fn resolve_goal_handler(
 
         let now = chrono::Utc::now().timestamp_millis();
         let mut set_clauses = resolution_set_clauses().to_vec();
+        set_clauses.push("g.last_modified = timestamp()");
         // If routine is being completed, also set end_timestamp to now
         if status == "completed" && goal_type == "routine" {
             set_clauses.push("g.end_timestamp = $now_end_timestamp");
@@ -1036,6 +2467,7 @@ pub async fn expand_task_date_range_handler(
         ));
     }
 
+    set_clauses.push("t.last_modified = timestamp()");
     let update_query = format!(
         "MATCH (t:Goal)
          WHERE id(t) = $task_id
@@ -1173,6 +2605,18 @@ pub async fn duplicate_goal_handler(
 
 impl Goal {
     pub async fn create_goal(&self, graph: &Graph) -> Result<Goal, neo4rs::Error> {
+        let mut result = graph.execute(self.create_query()).await?;
+        self.with_created_id(result.next().await?)
+    }
+
+    /// Same as `create_goal`, but runs inside an open transaction so callers can create
+    /// several nodes atomically.
+    pub async fn create_goal_in_txn(&self, txn: &mut neo4rs::Txn) -> Result<Goal, neo4rs::Error> {
+        let mut result = txn.execute(self.create_query()).await?;
+        self.with_created_id(result.next(txn.handle()).await?)
+    }
+
+    fn create_query(&self) -> neo4rs::Query {
         // Enhanced logging for routine_time specifically
         println!(
             "[goal.rs] create_goal - Attempting to create goal. Received routine_time: {:?}",
@@ -1209,6 +2653,49 @@ impl Goal {
                 self.description.as_ref().map(|v| v.clone().into()),
             ),
             ("priority", self.priority.as_ref().map(|v| v.clone().into())),
+            (
+                "energy_required",
+                self.energy_required.as_ref().map(|v| v.clone().into()),
+            ),
+            ("target_value", self.target_value.map(|v| v.into())),
+            ("current_value", self.current_value.map(|v| v.into())),
+            ("is_deadline", self.is_deadline.map(|v| v.into())),
+            ("skip_holidays", self.skip_holidays.map(|v| v.into())),
+            (
+                "reset_period",
+                self.reset_period.as_ref().map(|v| v.clone().into()),
+            ),
+            (
+                "last_reset_at",
+                self.last_reset_at
+                    .or_else(|| {
+                        self.reset_period
+                            .as_deref()
+                            .and_then(|p| achievements::reset_period_start(p, chrono::Utc::now()))
+                    })
+                    .map(|ts| neo4rs::BoltType::Integer(neo4rs::BoltInteger { value: ts })),
+            ),
+            ("notes", self.notes.as_ref().map(|v| v.clone().into())),
+            ("color", self.color.as_ref().map(|v| v.clone().into())),
+            ("duration_seconds", self.duration_seconds.map(|v| v.into())),
+            (
+                "recurrence",
+                self.recurrence.as_ref().map(|v| v.clone().into()),
+            ),
+            (
+                "recurrence_source_id",
+                self.recurrence_source_id.map(|v| v.into()),
+            ),
+            ("links", self.links.as_ref().map(|v| v.clone().into())),
+            ("allow_overlap", self.allow_overlap.map(|v| v.into())),
+            (
+                "routine_times",
+                self.routine_times.as_ref().map(|v| v.clone().into()),
+            ),
+            (
+                "target_completion_rate",
+                self.target_completion_rate.map(|v| v.into()),
+            ),
             (
                 "start_timestamp",
                 self.start_timestamp
@@ -1319,6 +2806,18 @@ impl Goal {
                     value: chrono::Utc::now().timestamp_millis(),
                 })),
             ),
+            // Events start at version 0; every later write bumps it
+            (
+                "version",
+                match self.goal_type {
+                    GoalType::Event => Some(neo4rs::BoltType::Integer(neo4rs::BoltInteger {
+                        value: self.version.unwrap_or(0),
+                    })),
+                    _ => self
+                        .version
+                        .map(|v| neo4rs::BoltType::Integer(neo4rs::BoltInteger { value: v })),
+                },
+            ),
         ];
 
         // Build query properties and parameters in one pass
@@ -1333,7 +2832,7 @@ impl Goal {
         }
 
         let query_str = format!(
-            "CREATE (g:Goal {{ {} }}) RETURN g, id(g) as id",
+            "CREATE (g:Goal {{ {}, last_modified: timestamp() }}) RETURN g, id(g) as id",
             properties.join(", ")
         );
 
@@ -1342,10 +2841,11 @@ impl Goal {
             println!("Final params: {:?}", params);
         }
 
-        // Execute query with parameters
-        let mut result = graph.execute(query(&query_str).params(params)).await?;
+        query(&query_str).params(params)
+    }
 
-        if let Some(row) = result.next().await? {
+    fn with_created_id(&self, row: Option<neo4rs::Row>) -> Result<Goal, neo4rs::Error> {
+        if let Some(row) = row {
             // Handle the error conversion manually
             let id: i64 = row.get("id").map_err(|_| neo4rs::Error::ConversionError)?;
 
@@ -1364,6 +2864,7 @@ impl Goal {
 
     pub async fn create_relationship(
         graph: &Graph,
+        user_id: i64,
         relationship: &Relationship,
     ) -> Result<(), neo4rs::Error> {
         // Only CHILD relationships are supported
@@ -1375,10 +2876,12 @@ impl Goal {
         let type_query = neo4rs::query(
             "MATCH (from:Goal), (to:Goal) 
              WHERE id(from) = $from_id AND id(to) = $to_id 
+             AND from.user_id = $user_id AND to.user_id = $user_id
              RETURN from.goal_type as from_type, to.goal_type as to_type",
         )
         .param("from_id", relationship.from_id)
-        .param("to_id", relationship.to_id);
+        .param("to_id", relationship.to_id)
+        .param("user_id", user_id);
 
         let mut result = graph.execute(type_query).await?;
 
@@ -1432,6 +2935,7 @@ impl Goal {
             let query_string = format!(
                 "MATCH (from:Goal), (to:Goal) 
                      WHERE id(from) = $from_id AND id(to) = $to_id 
+                     AND from.user_id = $user_id AND to.user_id = $user_id
                      CREATE (from)-[:{}]->(to)",
                 relationship.relationship_type.to_uppercase()
             );
@@ -1441,7 +2945,8 @@ impl Goal {
             }
             let create_query = neo4rs::query(&query_string)
                 .param("from_id", relationship.from_id)
-                .param("to_id", relationship.to_id);
+                .param("to_id", relationship.to_id)
+                .param("user_id", user_id);
 
             graph.run(create_query).await?;
             Ok(())
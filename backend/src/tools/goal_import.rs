@@ -0,0 +1,287 @@
+/*
+bulk import of goals from a CSV list, for onboarding from another tool
+rows are parsed and validated one by one so a bad row is reported back instead of failing the
+whole file; every valid row is then created in a single transaction
+*/
+use axum::{http::StatusCode, Json};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use neo4rs::Graph;
+use serde::Serialize;
+
+use crate::tools::goal::{self, Goal, GoalType};
+
+pub const CSV_COLUMNS: [&str; 5] = ["name", "type", "priority", "deadline", "description"];
+pub const MAX_IMPORT_ROWS: usize = 1000;
+
+#[derive(Debug, Serialize)]
+pub struct CsvImportRow {
+    pub row: usize, // line number in the uploaded file, header is line 1
+    pub success: bool,
+    pub goal: Option<Goal>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CsvImportResponse {
+    pub imported: usize,
+    pub failed: usize,
+    pub rows: Vec<CsvImportRow>,
+}
+
+/// Split one CSV line into fields. Fields may be wrapped in double quotes to hold commas,
+/// with `""` standing for a literal quote; unquoted fields are trimmed.
+pub fn parse_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            ',' => {
+                fields.push(finish_field(&field, quoted));
+                field.clear();
+                quoted = false;
+            }
+            '"' if field.trim().is_empty() && !quoted => {
+                field.clear();
+                quoted = true;
+                in_quotes = true;
+            }
+            '"' => return Err("Unexpected quote inside an unquoted field".to_string()),
+            _ if quoted => {
+                if !c.is_whitespace() {
+                    return Err("Unexpected text after a closing quote".to_string());
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("Unterminated quoted field".to_string());
+    }
+    fields.push(finish_field(&field, quoted));
+    Ok(fields)
+}
+
+fn finish_field(field: &str, quoted: bool) -> String {
+    if quoted {
+        field.to_string()
+    } else {
+        field.trim().to_string()
+    }
+}
+
+/// Normalize the header row. Columns may come in any order but must be known and unique;
+/// only `name` is required.
+pub fn parse_csv_header(line: &str) -> Result<Vec<String>, String> {
+    let columns: Vec<String> = parse_csv_line(line)?
+        .into_iter()
+        .map(|c| c.to_lowercase())
+        .collect();
+
+    for (i, column) in columns.iter().enumerate() {
+        if !CSV_COLUMNS.contains(&column.as_str()) {
+            return Err(format!(
+                "Unknown column '{}'; expected any of: {}",
+                column,
+                CSV_COLUMNS.join(", ")
+            ));
+        }
+        if columns[..i].contains(column) {
+            return Err(format!("Duplicate column '{}'", column));
+        }
+    }
+    if !columns.iter().any(|c| c == "name") {
+        return Err("Missing required column 'name'".to_string());
+    }
+    Ok(columns)
+}
+
+/// Parse a deadline as either a plain date, taken as the end of that day in `tz`, or an
+/// RFC 3339 timestamp. Returns milliseconds since the epoch.
+pub fn parse_deadline(raw: &str, tz: Tz) -> Result<i64, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        let next_midnight = (date + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap();
+        return tz
+            .from_local_datetime(&next_midnight)
+            .earliest()
+            .map(|dt| dt.timestamp_millis() - 1)
+            .ok_or_else(|| format!("Deadline '{}' does not exist in {}", raw, tz));
+    }
+    DateTime::parse_from_rfc3339(raw)
+        .map(|dt| dt.timestamp_millis())
+        .map_err(|_| {
+            format!(
+                "Unparseable deadline '{}'; expected YYYY-MM-DD or an RFC 3339 timestamp",
+                raw
+            )
+        })
+}
+
+/// Build the goal for one data row. Imported goals start at `now_ms`; routines and events
+/// need a frequency or a parent that a flat list can't carry, so they are rejected.
+pub fn goal_from_csv_row(
+    columns: &[String],
+    fields: &[String],
+    user_id: i64,
+    tz: Tz,
+    now_ms: i64,
+) -> Result<Goal, String> {
+    if fields.len() != columns.len() {
+        return Err(format!(
+            "Expected {} columns, found {}",
+            columns.len(),
+            fields.len()
+        ));
+    }
+    let value = |column: &str| {
+        columns
+            .iter()
+            .position(|c| c == column)
+            .map(|i| fields[i].as_str())
+            .filter(|v| !v.is_empty())
+    };
+
+    let goal_type = match value("type") {
+        None => GoalType::Task,
+        Some(raw) => GoalType::ALL
+            .into_iter()
+            .find(|t| t.as_str().eq_ignore_ascii_case(raw))
+            .ok_or_else(|| format!("Unknown type '{}'", raw))?,
+    };
+    if matches!(goal_type, GoalType::Routine | GoalType::Event) {
+        return Err(format!(
+            "Goals of type '{}' can't be imported from CSV",
+            goal_type
+        ));
+    }
+
+    let priority = value("priority")
+        .map(goal::normalize_priority)
+        .transpose()?;
+
+    let end_timestamp = value("deadline")
+        .map(|raw| parse_deadline(raw, tz))
+        .transpose()?;
+
+    let goal = Goal {
+        name: value("name").unwrap_or_default().to_string(),
+        goal_type,
+        description: value("description").map(str::to_string),
+        user_id: Some(user_id),
+        priority,
+        start_timestamp: Some(now_ms),
+        end_timestamp,
+        ..Default::default()
+    };
+
+    let errors = goal::goal_validation_errors(&goal);
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+    Ok(goal)
+}
+
+pub async fn import_goals_csv_handler(
+    graph: Graph,
+    user_id: i64,
+    csv_body: String,
+    tz: Tz,
+) -> Result<Json<CsvImportResponse>, (StatusCode, String)> {
+    let mut lines = csv_body
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, header) = lines
+        .next()
+        .ok_or((StatusCode::BAD_REQUEST, "CSV body is empty".to_string()))?;
+    let columns = parse_csv_header(header.trim_start_matches('\u{feff}'))
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid header: {}", e)))?;
+
+    let data_rows: Vec<(usize, &str)> = lines.collect();
+    if data_rows.len() > MAX_IMPORT_ROWS {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "CSV has {} rows; at most {} can be imported at once",
+                data_rows.len(),
+                MAX_IMPORT_ROWS
+            ),
+        ));
+    }
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let parsed: Vec<(usize, Result<Goal, String>)> = data_rows
+        .into_iter()
+        .map(|(row, line)| {
+            let goal = parse_csv_line(line)
+                .and_then(|fields| goal_from_csv_row(&columns, &fields, user_id, tz, now_ms));
+            (row, goal)
+        })
+        .collect();
+
+    let mut txn = graph
+        .start_txn()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let outcome: Result<Vec<CsvImportRow>, neo4rs::Error> = async {
+        let mut rows = Vec::with_capacity(parsed.len());
+        for (row, goal) in parsed {
+            rows.push(match goal {
+                Ok(goal) => CsvImportRow {
+                    row,
+                    success: true,
+                    goal: Some(goal.create_goal_in_txn(&mut txn).await?),
+                    error: None,
+                },
+                Err(error) => CsvImportRow {
+                    row,
+                    success: false,
+                    goal: None,
+                    error: Some(error),
+                },
+            });
+        }
+        Ok(rows)
+    }
+    .await;
+
+    match outcome {
+        Ok(rows) => {
+            txn.commit()
+                .await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            let imported = rows.iter().filter(|r| r.success).count();
+            Ok(Json(CsvImportResponse {
+                imported,
+                failed: rows.len() - imported,
+                rows,
+            }))
+        }
+        Err(e) => {
+            let _ = txn.rollback().await;
+            eprintln!("Error importing goals from CSV: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to import goals: {}", e),
+            ))
+        }
+    }
+}
@@ -2,7 +2,7 @@ use axum::{http::StatusCode, Json};
 use neo4rs::{query, Graph};
 use serde_json::Value;
 
-use crate::tools::goal::GOAL_RETURN_QUERY;
+use crate::tools::goal::{frequency_label, GOAL_RETURN_QUERY};
 
 pub async fn get_list_data(
     graph: Graph,
@@ -11,6 +11,7 @@ pub async fn get_list_data(
     let query_str = format!(
         "MATCH (g:Goal) 
          WHERE g.user_id = $user_id
+         AND coalesce(g.is_deleted, false) <> true
          {}",
         GOAL_RETURN_QUERY
     );
@@ -21,7 +22,8 @@ pub async fn get_list_data(
         Ok(mut result) => {
             let mut goals: Vec<Value> = Vec::new();
             while let Ok(Some(row)) = result.next().await {
-                if let Ok(goal) = row.get::<Value>("g") {
+                if let Ok(mut goal) = row.get::<Value>("g") {
+                    add_frequency_label(&mut goal);
                     goals.push(goal);
                 }
             }
@@ -36,3 +38,17 @@ pub async fn get_list_data(
         }
     }
 }
+
+/// Routines carry a display label for their frequency alongside the raw string.
+fn add_frequency_label(goal: &mut Value) {
+    if goal.get("goal_type").and_then(Value::as_str) != Some("routine") {
+        return;
+    }
+    let label = goal
+        .get("frequency")
+        .and_then(Value::as_str)
+        .and_then(frequency_label);
+    if let (Some(label), Some(fields)) = (label, goal.as_object_mut()) {
+        fields.insert("frequency_label".to_string(), Value::String(label));
+    }
+}
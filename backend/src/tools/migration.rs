@@ -1,6 +1,220 @@
-use crate::tools::goal::Goal;
+use crate::tools::event_settings::DEFAULT_EVENT_DURATION_MINUTES;
+use crate::tools::goal::{Goal, PRIORITIES};
 use chrono::{Datelike, TimeZone, Utc};
 use neo4rs::{query, Graph};
+use serde::Serialize;
+
+// Ledger entries for each migration; versions are applied in ascending order
+pub const EVENT_SYSTEM_MIGRATION: &str = "event_system_migration";
+pub const RESOLUTION_STATUS_MIGRATION: &str = "resolution_status_migration";
+pub const EVENT_VERSION_BACKFILL: &str = "event_version_backfill";
+pub const LAST_MODIFIED_BACKFILL: &str = "last_modified_backfill";
+pub const PRIORITY_NORMALIZATION: &str = "priority_normalization";
+
+pub const MIGRATIONS: [(&str, i64); 5] = [
+    (EVENT_SYSTEM_MIGRATION, 1),
+    (RESOLUTION_STATUS_MIGRATION, 2),
+    (EVENT_VERSION_BACKFILL, 3),
+    (LAST_MODIFIED_BACKFILL, 4),
+    (PRIORITY_NORMALIZATION, 5),
+];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MigrationRecord {
+    pub name: String,
+    pub version: i64,
+    pub applied: bool,
+    pub applied_at: Option<i64>,
+}
+
+fn migration_version(name: &str) -> i64 {
+    MIGRATIONS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, v)| *v)
+        .unwrap_or(0)
+}
+
+/// Look up a migration in the ledger. Also honours the legacy `MigrationStatus` node
+/// written before the ledger existed.
+async fn find_migration(graph: &Graph, name: &str) -> Result<Option<i64>, String> {
+    let mut result = graph
+        .execute(
+            query(
+                "OPTIONAL MATCH (m:Migration {name: $name})
+                 WHERE m.applied_at IS NOT NULL
+                 OPTIONAL MATCH (l:MigrationStatus {migration_name: $name})
+                 WHERE l.completed = true
+                 RETURN m IS NOT NULL OR l IS NOT NULL as applied,
+                        COALESCE(m.applied_at, l.completed_at) as applied_at",
+            )
+            .param("name", name),
+        )
+        .await
+        .map_err(|e| format!("Failed to check migration '{}': {}", name, e))?;
+
+    match result.next().await.map_err(|e| e.to_string())? {
+        Some(row) if row.get::<bool>("applied").unwrap_or(false) => {
+            Ok(Some(row.get::<i64>("applied_at").unwrap_or(0)))
+        }
+        _ => Ok(None),
+    }
+}
+
+pub async fn is_migration_applied(graph: &Graph, name: &str) -> Result<bool, String> {
+    Ok(find_migration(graph, name).await?.is_some())
+}
+
+/// Record (or re-record, after a forced run) a migration in the ledger.
+pub async fn record_migration(graph: &Graph, name: &str) -> Result<(), String> {
+    graph
+        .run(
+            query(
+                "MERGE (m:Migration {name: $name})
+                 SET m.version = $version,
+                     m.applied_at = $applied_at",
+            )
+            .param("name", name)
+            .param("version", migration_version(name))
+            .param("applied_at", Utc::now().timestamp_millis()),
+        )
+        .await
+        .map_err(|e| format!("Failed to record migration '{}': {}", name, e))
+}
+
+pub const DEFAULT_MIGRATION_BATCH_SIZE: i64 = 1000;
+
+/// Progress of an interrupted batched migration: the step in flight and the last goal id
+/// it finished.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationCheckpoint {
+    pub step: i64,
+    pub last_id: i64,
+}
+
+async fn load_checkpoint(graph: &Graph, name: &str) -> Result<Option<MigrationCheckpoint>, String> {
+    let mut result = graph
+        .execute(
+            query(
+                "MATCH (m:Migration {name: $name})
+                 WHERE m.checkpoint_step IS NOT NULL
+                 RETURN m.checkpoint_step as step, m.checkpoint_id as last_id",
+            )
+            .param("name", name),
+        )
+        .await
+        .map_err(|e| format!("Failed to load checkpoint for '{}': {}", name, e))?;
+
+    Ok(result
+        .next()
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|row| MigrationCheckpoint {
+            step: row.get("step").unwrap_or(0),
+            last_id: row.get("last_id").unwrap_or(-1),
+        }))
+}
+
+async fn save_checkpoint(
+    graph: &Graph,
+    name: &str,
+    checkpoint: &MigrationCheckpoint,
+) -> Result<(), String> {
+    graph
+        .run(
+            query(
+                "MERGE (m:Migration {name: $name})
+                 SET m.checkpoint_step = $step,
+                     m.checkpoint_id = $last_id,
+                     m.checkpoint_at = $now",
+            )
+            .param("name", name)
+            .param("step", checkpoint.step)
+            .param("last_id", checkpoint.last_id)
+            .param("now", Utc::now().timestamp_millis()),
+        )
+        .await
+        .map_err(|e| format!("Failed to save checkpoint for '{}': {}", name, e))
+}
+
+async fn clear_checkpoint(graph: &Graph, name: &str) -> Result<(), String> {
+    graph
+        .run(
+            query(
+                "MATCH (m:Migration {name: $name})
+                 REMOVE m.checkpoint_step, m.checkpoint_id, m.checkpoint_at",
+            )
+            .param("name", name),
+        )
+        .await
+        .map_err(|e| format!("Failed to clear checkpoint for '{}': {}", name, e))
+}
+
+/// Apply `update` to goals matching `filter` in ascending id order, `batch_size` at a time,
+/// saving a checkpoint after every batch. Returns the number of goals updated.
+async fn run_batched_step(
+    graph: &Graph,
+    name: &str,
+    step: i64,
+    filter: &str,
+    update: &str,
+    start_after: i64,
+    batch_size: i64,
+) -> Result<i64, String> {
+    let batch_query = format!(
+        "MATCH (g:Goal)
+         WHERE id(g) > $after AND ({})
+         WITH g ORDER BY id(g) LIMIT $batch_size
+         {}
+         RETURN count(g) as updated, max(id(g)) as last_id",
+        filter, update
+    );
+
+    let mut last_id = start_after;
+    let mut total = 0;
+    loop {
+        let mut result = graph
+            .execute(
+                query(&batch_query)
+                    .param("after", last_id)
+                    .param("batch_size", batch_size.max(1)),
+            )
+            .await
+            .map_err(|e| format!("Migration step {} failed after id {}: {}", step, last_id, e))?;
+
+        let (updated, batch_last_id) = match result.next().await.map_err(|e| e.to_string())? {
+            Some(row) => (
+                row.get::<i64>("updated").unwrap_or(0),
+                row.get::<i64>("last_id").ok(),
+            ),
+            None => (0, None),
+        };
+        let Some(batch_last_id) = batch_last_id.filter(|_| updated > 0) else {
+            break;
+        };
+
+        total += updated;
+        last_id = batch_last_id;
+        save_checkpoint(graph, name, &MigrationCheckpoint { step, last_id }).await?;
+    }
+
+    Ok(total)
+}
+
+/// Every known migration with whether and when it was applied, in version order.
+pub async fn migration_status(graph: &Graph) -> Result<Vec<MigrationRecord>, String> {
+    let mut records = Vec::with_capacity(MIGRATIONS.len());
+    for (name, version) in MIGRATIONS {
+        let applied_at = find_migration(graph, name).await?;
+        records.push(MigrationRecord {
+            name: name.to_string(),
+            version,
+            applied: applied_at.is_some(),
+            applied_at,
+        });
+    }
+    Ok(records)
+}
 
 // Migration state tracking
 #[derive(Debug)]
@@ -165,31 +379,14 @@ pub async fn migrate_to_events_force(graph: &Graph) -> Result<(), String> {
 async fn check_migration_already_run(graph: &Graph) -> Result<bool, String> {
     println!("Checking if migration has already been run...");
 
-    let check_query = "
-        MATCH (m:MigrationStatus {migration_name: 'event_system_migration'})
-        WHERE m.completed = true
-        RETURN count(m) as migration_count, m.completed_at as completed_at
-    ";
-
-    let mut result = graph
-        .execute(query(check_query))
-        .await
-        .map_err(|e| format!("Failed to check migration status: {}", e))?;
-
-    if let Some(row) = result.next().await.map_err(|e| e.to_string())? {
-        let migration_count: i64 = row.get("migration_count").unwrap_or(0);
-        if migration_count > 0 {
-            let completed_at: Option<i64> = row.get("completed_at").ok();
-            if let Some(timestamp) = completed_at {
-                let datetime = chrono::DateTime::from_timestamp(timestamp / 1000, 0)
-                    .unwrap_or_else(chrono::Utc::now);
-                println!(
-                    "Migration was already completed on: {}",
-                    datetime.format("%Y-%m-%d %H:%M:%S UTC")
-                );
-            }
-            return Ok(true);
-        }
+    if let Some(timestamp) = find_migration(graph, EVENT_SYSTEM_MIGRATION).await? {
+        let datetime =
+            chrono::DateTime::from_timestamp(timestamp / 1000, 0).unwrap_or_else(chrono::Utc::now);
+        println!(
+            "Migration was already completed on: {}",
+            datetime.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        return Ok(true);
     }
 
     println!("No previous migration found, proceeding...");
@@ -199,29 +396,8 @@ async fn check_migration_already_run(graph: &Graph) -> Result<bool, String> {
 async fn mark_migration_completed(graph: &Graph) -> Result<(), String> {
     println!("Marking migration as completed...");
 
-    let completed_timestamp = chrono::Utc::now().timestamp_millis();
-
-    let mark_query = query(
-        "MERGE (m:MigrationStatus {migration_name: 'event_system_migration'})
-         SET m.completed = true,
-             m.completed_at = $timestamp,
-             m.version = '1.0.0'
-         RETURN m.migration_name as name",
-    )
-    .param("timestamp", completed_timestamp);
-
-    let mut result = graph
-        .execute(mark_query)
-        .await
-        .map_err(|e| format!("Failed to mark migration as completed: {}", e))?;
-
-    if let Some(row) = result.next().await.map_err(|e| e.to_string())? {
-        let name: String = row.get("name").unwrap_or_default();
-        println!(
-            "Migration '{}' marked as completed at timestamp {}",
-            name, completed_timestamp
-        );
-    }
+    record_migration(graph, EVENT_SYSTEM_MIGRATION).await?;
+    println!("Migration '{}' marked as completed", EVENT_SYSTEM_MIGRATION);
 
     Ok(())
 }
@@ -232,13 +408,15 @@ pub async fn reset_migration_status(graph: &Graph) -> Result<(), String> {
     println!("Resetting migration status...");
 
     let reset_query = "
-        MATCH (m:MigrationStatus {migration_name: 'event_system_migration'})
+        MATCH (m)
+        WHERE (m:MigrationStatus AND m.migration_name = $name)
+           OR (m:Migration AND m.name = $name)
         DELETE m
         RETURN count(m) as deleted_count
     ";
 
     let mut result = graph
-        .execute(query(reset_query))
+        .execute(query(reset_query).param("name", EVENT_SYSTEM_MIGRATION))
         .await
         .map_err(|e| format!("Failed to reset migration status: {}", e))?;
 
@@ -476,7 +654,7 @@ async fn migrate_scheduled_tasks(graph: &Graph, state: &mut MigrationState) -> R
             name: t.name,
             goal_type: 'event',
             scheduled_timestamp: t.scheduled_timestamp,
-            duration: coalesce(t.duration, 60),  // Default to 60 minutes if no duration
+            duration: coalesce(t.duration, $default_duration),
             completed: coalesce(t.completed, false),
             parent_id: id(t),
             parent_type: 'task',
@@ -495,7 +673,9 @@ async fn migrate_scheduled_tasks(graph: &Graph, state: &mut MigrationState) -> R
     ";
 
     let mut result = graph
-        .execute(query(query_str))
+        .execute(
+            query(query_str).param("default_duration", DEFAULT_EVENT_DURATION_MINUTES as i64),
+        )
         .await
         .map_err(|e| format!("Failed to migrate scheduled tasks: {}", e))?;
 
@@ -1297,7 +1477,19 @@ pub async fn remove_queue_relationships(graph: &Graph) -> Result<(), String> {
 /// 1. Renames completion_date to resolved_at
 /// 2. Converts completed boolean to resolution_status enum string
 /// 3. Removes the old completed field
-pub async fn migrate_to_resolution_status(graph: &Graph) -> Result<serde_json::Value, String> {
+///
+/// Skipped if already recorded in the ledger unless `force` is set. Updates run in
+/// batches of `batch_size` goals and resume from the last checkpoint after a failure.
+pub async fn migrate_to_resolution_status(
+    graph: &Graph,
+    force: bool,
+    batch_size: i64,
+) -> Result<serde_json::Value, String> {
+    if !force && is_migration_applied(graph, RESOLUTION_STATUS_MIGRATION).await? {
+        println!("resolution_status migration already applied; use --force to re-run");
+        return Ok(serde_json::json!({ "skipped": true }));
+    }
+
     println!("Starting migration to resolution_status system...");
 
     let mut results = serde_json::Map::new();
@@ -1337,104 +1529,72 @@ pub async fn migrate_to_resolution_status(graph: &Graph) -> Result<serde_json::V
         }
     }
 
-    // Step 2: Rename completion_date to resolved_at (for goals that have it)
-    println!("Step 1: Renaming completion_date to resolved_at...");
-    {
-        let rename_query = "
-            MATCH (g:Goal)
-            WHERE g.completion_date IS NOT NULL AND g.resolved_at IS NULL
-            SET g.resolved_at = g.completion_date
-            REMOVE g.completion_date
-            RETURN count(g) as migrated
-        ";
-
-        let mut rename_result = graph
-            .execute(query(rename_query))
-            .await
-            .map_err(|e| format!("Failed to rename completion_date: {}", e))?;
-
-        let renamed_count: i64 = if let Some(row) = rename_result.next().await.map_err(|e| e.to_string())? {
-            row.get("migrated").unwrap_or(0)
-        } else {
-            0
-        };
-        println!("  - Renamed {} completion_date fields to resolved_at", renamed_count);
-        results.insert("renamed_completion_date".to_string(), renamed_count.into());
-    }
-
-    // Step 3: Migrate completed=true to resolution_status='completed'
-    println!("Step 2: Migrating completed=true to resolution_status='completed'...");
-    {
-        let completed_true_query = "
-            MATCH (g:Goal)
-            WHERE g.completed = true AND (g.resolution_status IS NULL OR g.resolution_status = 'pending')
-            SET g.resolution_status = 'completed',
-                g.resolved_at = COALESCE(g.resolved_at, timestamp())
-            REMOVE g.completed
-            RETURN count(g) as migrated
-        ";
-
-        let mut completed_true_result = graph
-            .execute(query(completed_true_query))
-            .await
-            .map_err(|e| format!("Failed to migrate completed=true: {}", e))?;
-
-        let completed_true_count: i64 = if let Some(row) = completed_true_result.next().await.map_err(|e| e.to_string())? {
-            row.get("migrated").unwrap_or(0)
-        } else {
-            0
-        };
-        println!("  - Migrated {} goals from completed=true to resolution_status='completed'", completed_true_count);
-        results.insert("migrated_completed_true".to_string(), completed_true_count.into());
+    // Steps 2-5 update goals in id-ordered batches, checkpointing after each one so an
+    // interrupted run resumes where it stopped instead of starting over
+    let resume = if force {
+        clear_checkpoint(graph, RESOLUTION_STATUS_MIGRATION).await?;
+        None
+    } else {
+        load_checkpoint(graph, RESOLUTION_STATUS_MIGRATION).await?
+    };
+    if let Some(checkpoint) = &resume {
+        println!(
+            "Resuming from checkpoint: step {} after goal id {}",
+            checkpoint.step, checkpoint.last_id
+        );
     }
 
-    // Step 4: Migrate completed=false or completed IS NULL to resolution_status='pending'
-    println!("Step 3: Migrating remaining goals to resolution_status='pending'...");
-    {
-        let pending_query = "
-            MATCH (g:Goal)
-            WHERE (g.completed = false OR g.completed IS NULL) AND g.resolution_status IS NULL
-            SET g.resolution_status = 'pending'
-            REMOVE g.completed
-            RETURN count(g) as migrated
-        ";
-
-        let mut pending_result = graph
-            .execute(query(pending_query))
-            .await
-            .map_err(|e| format!("Failed to migrate pending goals: {}", e))?;
+    let steps: [(&str, &str, &str, &str); 4] = [
+        (
+            "Renaming completion_date to resolved_at",
+            "g.completion_date IS NOT NULL AND g.resolved_at IS NULL",
+            "SET g.resolved_at = g.completion_date REMOVE g.completion_date",
+            "renamed_completion_date",
+        ),
+        (
+            "Migrating completed=true to resolution_status='completed'",
+            "g.completed = true AND (g.resolution_status IS NULL OR g.resolution_status = 'pending')",
+            "SET g.resolution_status = 'completed', g.resolved_at = COALESCE(g.resolved_at, timestamp()) REMOVE g.completed",
+            "migrated_completed_true",
+        ),
+        (
+            "Migrating remaining goals to resolution_status='pending'",
+            "(g.completed = false OR g.completed IS NULL) AND g.resolution_status IS NULL",
+            "SET g.resolution_status = 'pending' REMOVE g.completed",
+            "migrated_pending",
+        ),
+        (
+            "Cleaning up remaining completed fields",
+            "g.completed IS NOT NULL",
+            "REMOVE g.completed",
+            "cleanup_completed",
+        ),
+    ];
 
-        let pending_count: i64 = if let Some(row) = pending_result.next().await.map_err(|e| e.to_string())? {
-            row.get("migrated").unwrap_or(0)
-        } else {
-            0
+    for (index, (label, filter, update, result_key)) in steps.into_iter().enumerate() {
+        let step = index as i64 + 1;
+        let start_after = match &resume {
+            Some(checkpoint) if checkpoint.step > step => {
+                println!("Step {}: {} (already done, skipping)", step, label);
+                continue;
+            }
+            Some(checkpoint) if checkpoint.step == step => checkpoint.last_id,
+            _ => -1,
         };
-        println!("  - Set {} goals to resolution_status='pending'", pending_count);
-        results.insert("migrated_pending".to_string(), pending_count.into());
-    }
-
-    // Step 5: Clean up any remaining completed fields
-    println!("Step 4: Cleaning up remaining completed fields...");
-    {
-        let cleanup_query = "
-            MATCH (g:Goal)
-            WHERE g.completed IS NOT NULL
-            REMOVE g.completed
-            RETURN count(g) as cleaned
-        ";
 
-        let mut cleanup_result = graph
-            .execute(query(cleanup_query))
-            .await
-            .map_err(|e| format!("Failed to cleanup completed field: {}", e))?;
-
-        let cleanup_count: i64 = if let Some(row) = cleanup_result.next().await.map_err(|e| e.to_string())? {
-            row.get("cleaned").unwrap_or(0)
-        } else {
-            0
-        };
-        println!("  - Cleaned up {} remaining completed fields", cleanup_count);
-        results.insert("cleanup_completed".to_string(), cleanup_count.into());
+        println!("Step {}: {}...", step, label);
+        let count = run_batched_step(
+            graph,
+            RESOLUTION_STATUS_MIGRATION,
+            step,
+            filter,
+            update,
+            start_after,
+            batch_size,
+        )
+        .await?;
+        println!("  - Updated {} goals", count);
+        results.insert(result_key.to_string(), count.into());
     }
 
     // Step 6: Verify final state
@@ -1484,6 +1644,9 @@ pub async fn migrate_to_resolution_status(graph: &Graph) -> Result<serde_json::V
 
     results.insert("timestamp".to_string(), Utc::now().timestamp().into());
 
+    record_migration(graph, RESOLUTION_STATUS_MIGRATION).await?;
+    clear_checkpoint(graph, RESOLUTION_STATUS_MIGRATION).await?;
+
     Ok(serde_json::Value::Object(results))
 }
 
@@ -1519,11 +1682,16 @@ pub async fn needs_resolution_status_migration(graph: &Graph) -> Result<bool, St
 /// Returns Ok(true) if migration was run, Ok(false) if it was already done.
 pub async fn run_resolution_status_migration_if_needed(graph: &Graph) -> Result<bool, String> {
     println!("🔍 Checking if resolution_status migration is needed...");
-    
+
+    if is_migration_applied(graph, RESOLUTION_STATUS_MIGRATION).await? {
+        println!("✅ Resolution status migration already recorded - no action needed");
+        return Ok(false);
+    }
+
     match needs_resolution_status_migration(graph).await {
         Ok(true) => {
             println!("📦 Migration needed - running resolution_status migration...");
-            match migrate_to_resolution_status(graph).await {
+            match migrate_to_resolution_status(graph, false, DEFAULT_MIGRATION_BATCH_SIZE).await {
                 Ok(result) => {
                     println!("✅ Resolution status migration completed successfully!");
                     // Log key results
@@ -1545,6 +1713,7 @@ pub async fn run_resolution_status_migration_if_needed(graph: &Graph) -> Result<
         }
         Ok(false) => {
             println!("✅ Resolution status migration already complete - no action needed");
+            record_migration(graph, RESOLUTION_STATUS_MIGRATION).await?;
             Ok(false)
         }
         Err(e) => {
@@ -1553,3 +1722,109 @@ pub async fn run_resolution_status_migration_if_needed(graph: &Graph) -> Result<
         }
     }
 }
+
+/// Give events created before optimistic concurrency a starting version of 0.
+/// Idempotent; returns the number of events that were backfilled (0 if already recorded).
+pub async fn backfill_event_versions(graph: &Graph) -> Result<i64, String> {
+    if is_migration_applied(graph, EVENT_VERSION_BACKFILL).await? {
+        return Ok(0);
+    }
+
+    let mut result = graph
+        .execute(query(
+            "MATCH (e:Goal)
+             WHERE e.goal_type = 'event' AND e.version IS NULL
+             SET e.version = 0
+             RETURN count(e) as backfilled",
+        ))
+        .await
+        .map_err(|e| format!("Failed to backfill event versions: {}", e))?;
+
+    let backfilled = match result.next().await.map_err(|e| e.to_string())? {
+        Some(row) => row.get("backfilled").unwrap_or(0),
+        None => 0,
+    };
+
+    record_migration(graph, EVENT_VERSION_BACKFILL).await?;
+    Ok(backfilled)
+}
+
+/// Give goals written before delta sync a `last_modified` (their last local edit when
+/// known, otherwise now) and index it. Idempotent; returns the number of goals backfilled
+/// (0 if already recorded).
+pub async fn backfill_last_modified(graph: &Graph) -> Result<i64, String> {
+    if is_migration_applied(graph, LAST_MODIFIED_BACKFILL).await? {
+        return Ok(0);
+    }
+
+    graph
+        .run(query(
+            "CREATE INDEX goal_last_modified IF NOT EXISTS FOR (g:Goal) ON (g.last_modified)",
+        ))
+        .await
+        .map_err(|e| format!("Failed to create last_modified index: {}", e))?;
+
+    let backfilled = run_batched_step(
+        graph,
+        LAST_MODIFIED_BACKFILL,
+        1,
+        "g.last_modified IS NULL",
+        "SET g.last_modified = coalesce(g.updated_at, timestamp())",
+        -1,
+        DEFAULT_MIGRATION_BATCH_SIZE,
+    )
+    .await?;
+
+    clear_checkpoint(graph, LAST_MODIFIED_BACKFILL).await?;
+    record_migration(graph, LAST_MODIFIED_BACKFILL).await?;
+    Ok(backfilled)
+}
+
+/// Bring stored priorities in line with what writes now accept: trim and lowercase them,
+/// clear blank ones, and set anything still unrecognized to "medium" (how stats already
+/// weighted it). Idempotent; returns the number of goals changed (0 if already recorded).
+pub async fn normalize_priorities(graph: &Graph) -> Result<i64, String> {
+    if is_migration_applied(graph, PRIORITY_NORMALIZATION).await? {
+        return Ok(0);
+    }
+
+    let mut normalized = run_batched_step(
+        graph,
+        PRIORITY_NORMALIZATION,
+        1,
+        "g.priority IS NOT NULL AND g.priority <> toLower(trim(g.priority))",
+        "SET g.priority = toLower(trim(g.priority)), g.last_modified = timestamp()",
+        -1,
+        DEFAULT_MIGRATION_BATCH_SIZE,
+    )
+    .await?;
+    normalized += run_batched_step(
+        graph,
+        PRIORITY_NORMALIZATION,
+        2,
+        "g.priority = ''",
+        "SET g.priority = null, g.last_modified = timestamp()",
+        -1,
+        DEFAULT_MIGRATION_BATCH_SIZE,
+    )
+    .await?;
+    let known = PRIORITIES
+        .iter()
+        .map(|p| format!("'{}'", p))
+        .collect::<Vec<_>>()
+        .join(", ");
+    normalized += run_batched_step(
+        graph,
+        PRIORITY_NORMALIZATION,
+        3,
+        &format!("g.priority IS NOT NULL AND NOT g.priority IN [{}]", known),
+        "SET g.priority = 'medium', g.last_modified = timestamp()",
+        -1,
+        DEFAULT_MIGRATION_BATCH_SIZE,
+    )
+    .await?;
+
+    clear_checkpoint(graph, PRIORITY_NORMALIZATION).await?;
+    record_migration(graph, PRIORITY_NORMALIZATION).await?;
+    Ok(normalized)
+}
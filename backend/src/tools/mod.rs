@@ -1,14 +1,18 @@
 pub mod achievements;
+pub mod admin;
 pub mod autofill;
 pub mod calendar;
 pub mod day;
 pub mod event;
+pub mod event_settings;
 pub mod gcal_client;
 pub mod goal;
+pub mod goal_import;
 pub mod list;
 pub mod migration;
 pub mod network;
 pub mod notification_settings;
+pub mod quick_add;
 pub mod relations;
 pub mod routine;
 pub mod routine_exceptions;
@@ -3,12 +3,13 @@ use axum::{http::StatusCode, Json};
 use neo4rs::{query, Graph};
 use serde::{Deserialize, Serialize};
 
-use crate::tools::goal::Goal;
+use crate::tools::goal::{goal_from_row_lenient, Goal};
 
 #[derive(Debug, Serialize)]
 pub struct NetworkData {
     nodes: Vec<NetworkNode>,
     edges: Vec<NetworkEdge>,
+    skipped_goals: usize, // malformed nodes left out of the graph
 }
 
 #[derive(Debug, Serialize)]
@@ -40,6 +41,19 @@ pub struct PositionUpdate {
     pub y: f64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GoalPosition {
+    pub id: i64,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PositionsUpdateResult {
+    pub updated: i64,
+    pub ignored: i64, // missing or owned by another user
+}
+
 pub async fn get_network_data(
     graph: Graph,
     user_id: i64,
@@ -51,9 +65,11 @@ pub async fn get_network_data(
         "MATCH (g:Goal) 
          WHERE g.user_id = $user_id
          AND g.goal_type <> 'event'
+         AND coalesce(g.is_deleted, false) <> true
          OPTIONAL MATCH (g)-[r]->(g2:Goal)
          WHERE g2.user_id = $user_id
          AND g2.goal_type <> 'event'
+         AND coalesce(g2.is_deleted, false) <> true
          {}, 
          collect(DISTINCT CASE
              WHEN r IS NOT NULL THEN {{
@@ -77,6 +93,7 @@ pub async fn get_network_data(
 
     let mut nodes = Vec::new();
     let mut edges = Vec::new();
+    let mut skipped_goals = 0;
 
     while let Some(row) = result.next().await.map_err(|e| {
         eprintln!("Error fetching row: {:?}", e);
@@ -85,14 +102,10 @@ pub async fn get_network_data(
             format!("Error fetching row: {}", e),
         )
     })? {
-        let goal: Goal = row.get("g").map_err(|e| {
-            eprintln!("Error deserializing goal: {:?}", e);
-            eprintln!("Row data: {:?}", row);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Error deserializing goal: {}", e),
-            )
-        })?;
+        let Some(goal) = goal_from_row_lenient(&row, "g") else {
+            skipped_goals += 1;
+            continue;
+        };
 
         let goal_id = goal.id.unwrap_or_else(|| {
             eprintln!("Warning: Goal without ID found: {:?}", goal);
@@ -125,17 +138,26 @@ pub async fn get_network_data(
         }
     }
 
-    Ok(Json(NetworkData { nodes, edges }))
+    Ok(Json(NetworkData {
+        nodes,
+        edges,
+        skipped_goals,
+    }))
 }
 
 pub async fn update_node_position(
     graph: Graph,
+    user_id: i64,
     id: i64,
     x: f64,
     y: f64,
 ) -> Result<StatusCode, (StatusCode, String)> {
-    let query_str = "MATCH (g:Goal) WHERE id(g) = $id SET g.position_x = $x, g.position_y = $y";
-    let query = query(query_str).param("id", id).param("x", x).param("y", y);
+    let query_str = "MATCH (g:Goal) WHERE id(g) = $id AND g.user_id = $user_id SET g.position_x = $x, g.position_y = $y, g.last_modified = timestamp()";
+    let query = query(query_str)
+        .param("id", id)
+        .param("user_id", user_id)
+        .param("x", x)
+        .param("y", y);
 
     match graph.run(query).await {
         Ok(_) => Ok(StatusCode::OK),
@@ -148,3 +170,72 @@ pub async fn update_node_position(
         }
     }
 }
+
+/// Save layout positions for many nodes at once after a drag in the network view. Only the
+/// position properties are written (no version bump or sync), and goals the user doesn't
+/// own are skipped.
+pub async fn update_goal_positions_handler(
+    graph: Graph,
+    user_id: i64,
+    positions: Vec<GoalPosition>,
+) -> Result<Json<PositionsUpdateResult>, (StatusCode, String)> {
+    if let Some(bad) = positions
+        .iter()
+        .find(|p| !p.x.is_finite() || !p.y.is_finite())
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid position for goal {}", bad.id),
+        ));
+    }
+    if positions.is_empty() {
+        return Ok(Json(PositionsUpdateResult {
+            updated: 0,
+            ignored: 0,
+        }));
+    }
+
+    let ids: Vec<i64> = positions.iter().map(|p| p.id).collect();
+    let xs: Vec<f64> = positions.iter().map(|p| p.x).collect();
+    let ys: Vec<f64> = positions.iter().map(|p| p.y).collect();
+
+    // A single UNWIND statement, so the batch is applied atomically
+    let query_str = "
+        UNWIND range(0, size($ids) - 1) AS i
+        MATCH (g:Goal)
+        WHERE id(g) = $ids[i] AND g.user_id = $user_id
+        SET g.position_x = $xs[i], g.position_y = $ys[i], g.last_modified = timestamp()
+        RETURN count(g) as updated
+    ";
+
+    let mut result = graph
+        .execute(
+            query(query_str)
+                .param("ids", ids)
+                .param("xs", xs)
+                .param("ys", ys)
+                .param("user_id", user_id),
+        )
+        .await
+        .map_err(|e| {
+            eprintln!("Error updating node positions: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Error updating node positions: {}", e),
+            )
+        })?;
+
+    let updated = match result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        Some(row) => row.get::<i64>("updated").unwrap_or(0),
+        None => 0,
+    };
+
+    Ok(Json(PositionsUpdateResult {
+        updated,
+        ignored: positions.len() as i64 - updated,
+    }))
+}
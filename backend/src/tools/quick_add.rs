@@ -0,0 +1,393 @@
+/*
+deterministic "quick add" parsing of short phrases like "Gym tomorrow 7am 45m" into a task + event
+this is the cheap path for the common case; anything it can't parse is reported back so the
+client can fall back to the chat assistant
+*/
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use neo4rs::Graph;
+use serde::{Deserialize, Serialize};
+
+use crate::tools::event::{self, CreateTaskWithEventsRequest, TaskEventSpec};
+use crate::tools::goal::{Goal, GoalType};
+
+#[derive(Debug, Deserialize)]
+pub struct QuickAddRequest {
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuickAddResponse {
+    pub task: Goal,
+    pub event: Goal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedQuickAdd {
+    pub name: String,
+    pub date: NaiveDate,
+    pub time: NaiveTime,
+    pub duration_minutes: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuickAddParseError {
+    pub error_type: String, // "quick_add_parse_error"
+    pub part: String,       // "name", "date", "time", "duration" or "unknown"
+    pub token: Option<String>,
+    pub message: String,
+}
+
+impl QuickAddParseError {
+    fn new(part: &str, token: Option<&str>, message: &str) -> Self {
+        Self {
+            error_type: "quick_add_parse_error".to_string(),
+            part: part.to_string(),
+            token: token.map(|t| t.to_string()),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Errors from [`quick_add_handler`]. Parse failures go back as JSON so the client can
+/// point at the part that failed; anything else is the usual status and plain-text message.
+#[derive(Debug)]
+pub enum QuickAddError {
+    Parse(QuickAddParseError),
+    Other(StatusCode, String),
+}
+
+impl From<(StatusCode, String)> for QuickAddError {
+    fn from((status, message): (StatusCode, String)) -> Self {
+        QuickAddError::Other(status, message)
+    }
+}
+
+impl From<QuickAddParseError> for QuickAddError {
+    fn from(err: QuickAddParseError) -> Self {
+        QuickAddError::Parse(err)
+    }
+}
+
+impl IntoResponse for QuickAddError {
+    fn into_response(self) -> Response {
+        match self {
+            QuickAddError::Parse(err) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, Json(err)).into_response()
+            }
+            QuickAddError::Other(status, message) => (status, message).into_response(),
+        }
+    }
+}
+
+enum Token {
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Duration(i32),
+}
+
+/// Parse a quick-add phrase. The leading words up to the first date, time or duration
+/// token form the name; everything after must be one of those tokens.
+///
+/// Grammar (case-insensitive):
+/// - date: `today`, `tomorrow`, a weekday (`mon`/`monday`, optionally preceded by `next`),
+///   `YYYY-MM-DD` or `M/D`
+/// - time: `7am`, `7:30pm`, `19:00`, `noon`, or `at` followed by one of those
+/// - duration: `45m`, `45min`, `2h`, `1.5h`, `1h30m`
+pub fn parse_quick_add(text: &str, today: NaiveDate) -> Result<ParsedQuickAdd, QuickAddParseError> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    let mut name_words = Vec::new();
+    let mut date = None;
+    let mut time = None;
+    let mut duration = None;
+    let mut in_name = true;
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        let lower = word.to_lowercase();
+
+        // Connectors only make sense before a token; "at 7pm", "next monday", "for 30m"
+        if matches!(lower.as_str(), "at" | "on" | "for" | "next") && i + 1 < words.len() {
+            let next = words[i + 1].to_lowercase();
+            let next = if lower == "next" {
+                format!("next {}", next)
+            } else {
+                next
+            };
+            if let Some(token) = parse_token(&next, today) {
+                apply_token(token, &mut date, &mut time, &mut duration, words[i + 1])?;
+                in_name = false;
+                i += 2;
+                continue;
+            }
+        }
+
+        match parse_token(&lower, today) {
+            Some(token) => {
+                apply_token(token, &mut date, &mut time, &mut duration, word)?;
+                in_name = false;
+            }
+            None if in_name => name_words.push(word),
+            None => {
+                return Err(QuickAddParseError::new(
+                    "unknown",
+                    Some(word),
+                    "Unrecognized word after the date/time; expected a date, time or duration",
+                ))
+            }
+        }
+        i += 1;
+    }
+
+    if name_words.is_empty() {
+        return Err(QuickAddParseError::new(
+            "name",
+            None,
+            "Start the text with a name for the event",
+        ));
+    }
+
+    let time = time.ok_or_else(|| {
+        QuickAddParseError::new("time", None, "Add a time such as 7am, 7:30pm or 19:00")
+    })?;
+
+    Ok(ParsedQuickAdd {
+        name: name_words.join(" "),
+        date: date.unwrap_or(today),
+        time,
+        duration_minutes: duration,
+    })
+}
+
+fn apply_token(
+    token: Token,
+    date: &mut Option<NaiveDate>,
+    time: &mut Option<NaiveTime>,
+    duration: &mut Option<i32>,
+    raw: &str,
+) -> Result<(), QuickAddParseError> {
+    let (slot_filled, part) = match token {
+        Token::Date(d) => (date.replace(d).is_some(), "date"),
+        Token::Time(t) => (time.replace(t).is_some(), "time"),
+        Token::Duration(m) => (duration.replace(m).is_some(), "duration"),
+    };
+    if slot_filled {
+        return Err(QuickAddParseError::new(
+            part,
+            Some(raw),
+            &format!("More than one {} was given", part),
+        ));
+    }
+    Ok(())
+}
+
+fn parse_token(word: &str, today: NaiveDate) -> Option<Token> {
+    parse_date(word, today)
+        .map(Token::Date)
+        .or_else(|| parse_time(word).map(Token::Time))
+        .or_else(|| parse_duration(word).map(Token::Duration))
+}
+
+fn parse_date(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match word {
+        "today" => return Some(today),
+        "tomorrow" | "tmrw" => return Some(today + Duration::days(1)),
+        _ => {}
+    }
+
+    let (skip_week, day) = match word.strip_prefix("next ") {
+        Some(rest) => (true, rest),
+        None => (false, word),
+    };
+    if let Some(weekday) = parse_weekday(day) {
+        // Next occurrence strictly after today; "next" pushes it a further week out
+        let mut days_ahead = (weekday.num_days_from_monday() as i64
+            - today.weekday().num_days_from_monday() as i64
+            + 7)
+            % 7;
+        if days_ahead == 0 {
+            days_ahead = 7;
+        }
+        if skip_week {
+            days_ahead += 7;
+        }
+        return Some(today + Duration::days(days_ahead));
+    }
+    if skip_week {
+        return None;
+    }
+
+    if let Ok(d) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+        return Some(d);
+    }
+
+    // M/D, rolling over to next year if that date has already passed
+    let (m, d) = word.split_once('/')?;
+    let month: u32 = m.parse().ok()?;
+    let day: u32 = d.parse().ok()?;
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if this_year < today {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    } else {
+        Some(this_year)
+    }
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    match word {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+
+    let (clock, meridiem) = if let Some(rest) = word.strip_suffix("am") {
+        (rest, Some(false))
+    } else if let Some(rest) = word.strip_suffix("pm") {
+        (rest, Some(true))
+    } else {
+        (word, None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        Some(_) => return None,
+        // A bare number is only a time with am/pm ("7" alone is ambiguous)
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+
+    let hour = match meridiem {
+        Some(is_pm) => {
+            if hour == 0 || hour > 12 {
+                return None;
+            }
+            match (hour, is_pm) {
+                (12, false) => 0,
+                (12, true) => 12,
+                (h, true) => h + 12,
+                (h, false) => h,
+            }
+        }
+        None => hour,
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn parse_duration(word: &str) -> Option<i32> {
+    // 1h30m
+    if let Some((h, m)) = word.split_once('h') {
+        if let Some(m) = m.strip_suffix('m') {
+            if !m.is_empty() {
+                let hours: i32 = h.parse().ok()?;
+                let minutes: i32 = m.parse().ok()?;
+                return hours
+                    .checked_mul(60)
+                    .and_then(|h| h.checked_add(minutes))
+                    .and_then(positive);
+            }
+        }
+    }
+
+    for suffix in ["minutes", "minute", "mins", "min", "m"] {
+        if let Some(n) = word.strip_suffix(suffix) {
+            return n.parse::<i32>().ok().and_then(positive);
+        }
+    }
+    for suffix in ["hours", "hour", "hrs", "hr", "h"] {
+        if let Some(n) = word.strip_suffix(suffix) {
+            let hours: f64 = n.parse().ok().filter(|h: &f64| h.is_finite())?;
+            let minutes = (hours * 60.0).round();
+            if minutes > i32::MAX as f64 {
+                return None;
+            }
+            return positive(minutes as i32);
+        }
+    }
+    None
+}
+
+fn positive(minutes: i32) -> Option<i32> {
+    (minutes > 0).then_some(minutes)
+}
+
+/// Parse `text` and create the task and its event together; if the event can't be
+/// written the task is rolled back with it.
+pub async fn quick_add_handler(
+    graph: Graph,
+    user_id: i64,
+    text: String,
+    tz: String,
+) -> Result<(StatusCode, Json<QuickAddResponse>), QuickAddError> {
+    let tz: Tz = tz.parse().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid timezone '{}'", tz),
+        )
+    })?;
+    let today = Utc::now().with_timezone(&tz).date_naive();
+
+    let parsed = parse_quick_add(&text, today)?;
+
+    let local = parsed.date.and_time(parsed.time);
+    let scheduled: DateTime<Tz> = tz.from_local_datetime(&local).earliest().ok_or_else(|| {
+        QuickAddParseError::new(
+            "time",
+            None,
+            "That time doesn't exist in your timezone (daylight saving change)",
+        )
+    })?;
+
+    let task = Goal {
+        name: parsed.name,
+        goal_type: GoalType::Task,
+        user_id: Some(user_id),
+        priority: Some("medium".to_string()),
+        resolution_status: Some("pending".to_string()),
+        ..Default::default()
+    };
+    let (_, Json(created)) = event::create_task_with_events_handler(
+        graph,
+        user_id,
+        CreateTaskWithEventsRequest {
+            task,
+            events: vec![TaskEventSpec {
+                scheduled_timestamp: scheduled.with_timezone(&Utc).timestamp_millis(),
+                duration: parsed.duration_minutes,
+            }],
+        },
+    )
+    .await?;
+    let event = created.events.into_iter().next().ok_or((
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Created task has no event".to_string(),
+    ))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(QuickAddResponse {
+            task: created.task,
+            event,
+        }),
+    ))
+}
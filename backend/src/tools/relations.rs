@@ -2,7 +2,7 @@ use axum::{http::StatusCode, Json};
 use neo4rs::{query, Graph};
 use serde::Serialize;
 
-use crate::tools::goal::{Goal, GoalType, GOAL_RETURN_QUERY};
+use crate::tools::goal::{self, Goal, GoalType, GOAL_RETURN_QUERY};
 
 #[derive(Debug, Serialize)]
 pub struct GoalRelationsResponse {
@@ -33,45 +33,7 @@ pub async fn get_goal_relations(
     goal_id: i64,
 ) -> Result<Json<GoalRelationsResponse>, (StatusCode, String)> {
     // First, fetch the goal to determine its type
-    let goal_query = query(&format!(
-        "MATCH (g:Goal) 
-         WHERE id(g) = $goal_id AND g.user_id = $user_id
-         {}",
-        GOAL_RETURN_QUERY
-    ))
-    .param("goal_id", goal_id)
-    .param("user_id", user_id);
-
-    let mut goal_result = graph.execute(goal_query).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Error fetching goal: {}", e),
-        )
-    })?;
-
-    let goal_row = goal_result.next().await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Error fetching goal row: {}", e),
-        )
-    })?;
-
-    let goal: Goal = match goal_row {
-        Some(row) => {
-            row.get("g").map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Error deserializing goal: {}", e),
-                )
-            })?
-        },
-        None => {
-            return Err((
-                StatusCode::NOT_FOUND,
-                format!("Goal with id {} not found", goal_id),
-            ));
-        }
-    };
+    let (_, Json(goal)) = goal::get_goal_handler(graph.clone(), user_id, goal_id).await?;
 
     let mut parents = Vec::new();
     let mut children = Vec::new();
@@ -114,6 +76,27 @@ pub async fn get_goal_relations(
                     gcal_sync_direction: p.gcal_sync_direction,
                     is_gcal_imported: p.is_gcal_imported,
                     updated_at: p.updated_at,
+                    version: p.version,
+                    energy_required: p.energy_required,
+                    gcal_sync_status: p.gcal_sync_status,
+                    gcal_last_sync_attempt: p.gcal_last_sync_attempt,
+                    gcal_sync_error: p.gcal_sync_error,
+                    target_value: p.target_value,
+                    current_value: p.current_value,
+                    is_deadline: p.is_deadline,
+                    skip_holidays: p.skip_holidays,
+                    reset_period: p.reset_period,
+                    last_reset_at: p.last_reset_at,
+                    notes: p.notes,
+                    color: p.color,
+                    duration_seconds: p.duration_seconds,
+                    recurrence: p.recurrence,
+                    recurrence_source_id: p.recurrence_source_id,
+                    links: p.links,
+                    allow_overlap: p.allow_overlap,
+                    routine_times: p.routine_times,
+                    last_modified: p.last_modified,
+                    target_completion_rate: p.target_completion_rate,
                     id: id(p)
                  } as p".to_string();
         let has_event_query = query(&has_event_query_str)
@@ -172,6 +155,27 @@ pub async fn get_goal_relations(
                     gcal_sync_direction: parent.gcal_sync_direction,
                     is_gcal_imported: parent.is_gcal_imported,
                     updated_at: parent.updated_at,
+                    version: parent.version,
+                    energy_required: parent.energy_required,
+                    gcal_sync_status: parent.gcal_sync_status,
+                    gcal_last_sync_attempt: parent.gcal_last_sync_attempt,
+                    gcal_sync_error: parent.gcal_sync_error,
+                    target_value: parent.target_value,
+                    current_value: parent.current_value,
+                    is_deadline: parent.is_deadline,
+                    skip_holidays: parent.skip_holidays,
+                    reset_period: parent.reset_period,
+                    last_reset_at: parent.last_reset_at,
+                    notes: parent.notes,
+                    color: parent.color,
+                    duration_seconds: parent.duration_seconds,
+                    recurrence: parent.recurrence,
+                    recurrence_source_id: parent.recurrence_source_id,
+                    links: parent.links,
+                    allow_overlap: parent.allow_overlap,
+                    routine_times: parent.routine_times,
+                    last_modified: parent.last_modified,
+                    target_completion_rate: parent.target_completion_rate,
                     id: id(parent)
                  } as parent".to_string();
                 eprintln!("[relations] Executing parent_id query for parent_id={}", parent_id);
@@ -230,6 +234,27 @@ pub async fn get_goal_relations(
                     gcal_sync_direction: parent.gcal_sync_direction,
                     is_gcal_imported: parent.is_gcal_imported,
                     updated_at: parent.updated_at,
+                    version: parent.version,
+                    energy_required: parent.energy_required,
+                    gcal_sync_status: parent.gcal_sync_status,
+                    gcal_last_sync_attempt: parent.gcal_last_sync_attempt,
+                    gcal_sync_error: parent.gcal_sync_error,
+                    target_value: parent.target_value,
+                    current_value: parent.current_value,
+                    is_deadline: parent.is_deadline,
+                    skip_holidays: parent.skip_holidays,
+                    reset_period: parent.reset_period,
+                    last_reset_at: parent.last_reset_at,
+                    notes: parent.notes,
+                    color: parent.color,
+                    duration_seconds: parent.duration_seconds,
+                    recurrence: parent.recurrence,
+                    recurrence_source_id: parent.recurrence_source_id,
+                    links: parent.links,
+                    allow_overlap: parent.allow_overlap,
+                    routine_times: parent.routine_times,
+                    last_modified: parent.last_modified,
+                    target_completion_rate: parent.target_completion_rate,
                     id: id(parent)
                  } as parent".to_string();
         let parents_query = query(&parents_query_str)
@@ -285,6 +310,27 @@ pub async fn get_goal_relations(
                     gcal_sync_direction: child.gcal_sync_direction,
                     is_gcal_imported: child.is_gcal_imported,
                     updated_at: child.updated_at,
+                    version: child.version,
+                    energy_required: child.energy_required,
+                    gcal_sync_status: child.gcal_sync_status,
+                    gcal_last_sync_attempt: child.gcal_last_sync_attempt,
+                    gcal_sync_error: child.gcal_sync_error,
+                    target_value: child.target_value,
+                    current_value: child.current_value,
+                    is_deadline: child.is_deadline,
+                    skip_holidays: child.skip_holidays,
+                    reset_period: child.reset_period,
+                    last_reset_at: child.last_reset_at,
+                    notes: child.notes,
+                    color: child.color,
+                    duration_seconds: child.duration_seconds,
+                    recurrence: child.recurrence,
+                    recurrence_source_id: child.recurrence_source_id,
+                    links: child.links,
+                    allow_overlap: child.allow_overlap,
+                    routine_times: child.routine_times,
+                    last_modified: child.last_modified,
+                    target_completion_rate: child.target_completion_rate,
                     id: id(child)
                  } as child".to_string();
             let children_query = query(&children_query_str)
@@ -389,6 +435,27 @@ pub async fn get_goal_subgraph(
                     gcal_sync_direction: node.gcal_sync_direction,
                     is_gcal_imported: node.is_gcal_imported,
                     updated_at: node.updated_at,
+                    version: node.version,
+                    energy_required: node.energy_required,
+                    gcal_sync_status: node.gcal_sync_status,
+                    gcal_last_sync_attempt: node.gcal_last_sync_attempt,
+                    gcal_sync_error: node.gcal_sync_error,
+                    target_value: node.target_value,
+                    current_value: node.current_value,
+                    is_deadline: node.is_deadline,
+                    skip_holidays: node.skip_holidays,
+                    reset_period: node.reset_period,
+                    last_reset_at: node.last_reset_at,
+                    notes: node.notes,
+                    color: node.color,
+                    duration_seconds: node.duration_seconds,
+                    recurrence: node.recurrence,
+                    recurrence_source_id: node.recurrence_source_id,
+                    links: node.links,
+                    allow_overlap: node.allow_overlap,
+                    routine_times: node.routine_times,
+                    last_modified: node.last_modified,
+                    target_completion_rate: node.target_completion_rate,
                     id: id(node)
                  }} as node",
         MAX_DEPTH
@@ -454,6 +521,27 @@ pub async fn get_goal_subgraph(
                     gcal_sync_direction: node.gcal_sync_direction,
                     is_gcal_imported: node.is_gcal_imported,
                     updated_at: node.updated_at,
+                    version: node.version,
+                    energy_required: node.energy_required,
+                    gcal_sync_status: node.gcal_sync_status,
+                    gcal_last_sync_attempt: node.gcal_last_sync_attempt,
+                    gcal_sync_error: node.gcal_sync_error,
+                    target_value: node.target_value,
+                    current_value: node.current_value,
+                    is_deadline: node.is_deadline,
+                    skip_holidays: node.skip_holidays,
+                    reset_period: node.reset_period,
+                    last_reset_at: node.last_reset_at,
+                    notes: node.notes,
+                    color: node.color,
+                    duration_seconds: node.duration_seconds,
+                    recurrence: node.recurrence,
+                    recurrence_source_id: node.recurrence_source_id,
+                    links: node.links,
+                    allow_overlap: node.allow_overlap,
+                    routine_times: node.routine_times,
+                    last_modified: node.last_modified,
+                    target_completion_rate: node.target_completion_rate,
                     id: id(node)
                  }} as node",
         MAX_DEPTH
@@ -1,4 +1,4 @@
-use neo4rs::{query, Graph};
+use neo4rs::{query, Graph, Query};
 
 /// Create (or keep) a skip exception for a given routine occurrence timestamp (ms).
 /// This is used to prevent the routine generator from backfilling a user-deleted/moved occurrence.
@@ -8,25 +8,28 @@ pub async fn create_skip_exception(
     routine_id: i64,
     timestamp: i64,
 ) -> Result<(), neo4rs::Error> {
-    let now = chrono::Utc::now().timestamp_millis();
     graph
-        .run(
-            query(
-                "MATCH (r:Goal)
-                 WHERE id(r) = $routine_id AND r.goal_type = 'routine'
-                 MERGE (r)-[:HAS_EXCEPTION]->(x:RoutineException { timestamp: $timestamp, kind: 'skip' })
-                 ON CREATE SET x.created_at = $now, x.user_id = $user_id
-                 RETURN id(x) as id",
-            )
-            .param("routine_id", routine_id)
-            .param("timestamp", timestamp)
-            .param("now", now)
-            .param("user_id", user_id),
-        )
+        .run(skip_exception_query(user_id, routine_id, timestamp))
         .await?;
     Ok(())
 }
 
+/// The `MERGE` behind `create_skip_exception`, for callers that need it inside their own transaction.
+pub fn skip_exception_query(user_id: i64, routine_id: i64, timestamp: i64) -> Query {
+    let now = chrono::Utc::now().timestamp_millis();
+    query(
+        "MATCH (r:Goal)
+         WHERE id(r) = $routine_id AND r.goal_type = 'routine'
+         MERGE (r)-[:HAS_EXCEPTION]->(x:RoutineException { timestamp: $timestamp, kind: 'skip' })
+         ON CREATE SET x.created_at = $now, x.user_id = $user_id
+         RETURN id(x) as id",
+    )
+    .param("routine_id", routine_id)
+    .param("timestamp", timestamp)
+    .param("now", now)
+    .param("user_id", user_id)
+}
+
 /// Fetch all skip exception timestamps in a given range (inclusive).
 pub async fn get_skip_exception_timestamps_in_range(
     graph: &Graph,
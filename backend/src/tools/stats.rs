@@ -3,9 +3,13 @@ use chrono::{Datelike, Duration, LocalResult, NaiveDate, TimeZone, Utc};
 use chrono_tz::Tz;
 use neo4rs::{query, Graph};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::future::Future;
 
-fn priority_to_weight(priority: &str) -> f64 {
+use crate::tools::event_settings;
+
+pub fn priority_to_weight(priority: &str) -> f64 {
     match priority {
         "none" => 0.0,
         "low" => 1.0,
@@ -15,6 +19,83 @@ fn priority_to_weight(priority: &str) -> f64 {
     }
 }
 
+pub const DEFAULT_MAX_TRAVERSAL_DEPTH: usize = 10;
+const MAX_TRAVERSAL_DEPTH_LIMIT: usize = 50;
+// A timed-out query's connection goes back to the pool, which resets it before reuse and
+// so stops the query on the server; one heavy hierarchy can't hold a connection for good
+const STATS_QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Parse a STATS_MAX_DEPTH value; unset or blank means the default.
+pub fn parse_max_traversal_depth(raw: Option<&str>) -> Result<usize, String> {
+    match raw.map(str::trim).filter(|s| !s.is_empty()) {
+        None => Ok(DEFAULT_MAX_TRAVERSAL_DEPTH),
+        Some(value) => value
+            .parse::<usize>()
+            .ok()
+            .filter(|depth| (1..=MAX_TRAVERSAL_DEPTH_LIMIT).contains(depth))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid STATS_MAX_DEPTH '{}'. Expected a number of levels from 1 to {}.",
+                    value, MAX_TRAVERSAL_DEPTH_LIMIT
+                )
+            }),
+    }
+}
+
+/// How many CHILD levels below a goal the stats roll up. Startup rejects invalid values,
+/// so the fallback here only covers tests and tools that skip that check.
+pub fn max_traversal_depth() -> usize {
+    parse_max_traversal_depth(env::var("STATS_MAX_DEPTH").ok().as_deref())
+        .unwrap_or(DEFAULT_MAX_TRAVERSAL_DEPTH)
+}
+
+pub const DEFAULT_GRACE_PERIOD_MINUTES: i64 = 120;
+const MAX_GRACE_PERIOD_MINUTES: i64 = 24 * 60;
+
+/// Parse a grace period in minutes, from STATS_GRACE_MINUTES or a `grace_minutes` query
+/// parameter; unset or blank means `fallback`.
+pub fn parse_grace_minutes(raw: Option<&str>, fallback: i64) -> Result<i64, String> {
+    match raw.map(str::trim).filter(|s| !s.is_empty()) {
+        None => Ok(fallback),
+        Some(value) => value
+            .parse::<i64>()
+            .ok()
+            .filter(|minutes| (0..=MAX_GRACE_PERIOD_MINUTES).contains(minutes))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid grace period '{}'. Expected minutes from 0 to {}.",
+                    value, MAX_GRACE_PERIOD_MINUTES
+                )
+            }),
+    }
+}
+
+/// How long after an event ends before a pending event counts as missed in the stats,
+/// so there's time to mark it done. Completed events count as soon as they end.
+pub fn default_grace_minutes() -> i64 {
+    parse_grace_minutes(
+        env::var("STATS_GRACE_MINUTES").ok().as_deref(),
+        DEFAULT_GRACE_PERIOD_MINUTES,
+    )
+    .unwrap_or(DEFAULT_GRACE_PERIOD_MINUTES)
+}
+
+/// Give up on a stats query that runs past STATS_QUERY_TIMEOUT.
+async fn with_stats_timeout<T>(
+    what: &str,
+    query_future: impl Future<Output = T>,
+) -> Result<T, (StatusCode, String)> {
+    tokio::time::timeout(STATS_QUERY_TIMEOUT, query_future)
+        .await
+        .map_err(|_| {
+            eprintln!("Timed out fetching {}", what);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("Fetching {} took too long; try a shorter range", what),
+            )
+        })
+}
+
 #[derive(Debug, Clone)]
 struct RawGoalData {
     name: String,
@@ -27,6 +108,7 @@ struct RawGoalData {
 #[derive(Debug, Clone)]
 struct RawEventData {
     completed: bool,
+    moved: bool, // rescheduled at least once before it was resolved
     priority: String,
     duration: f64,
     date: String,
@@ -39,7 +121,9 @@ struct RecursiveStats {
     total_duration_minutes: f64,
     weighted_completion_rate: f64,
     children_count: i32,
+    moved_before_completion: i32,
     daily_stats: HashMap<String, DailyRecursiveStats>,
+    truncated: bool, // some descendants were past the depth limit and left out
 }
 
 #[derive(Debug, Clone, Default)]
@@ -51,11 +135,14 @@ struct DailyRecursiveStats {
     total_count: i32,
 }
 
+/// Roll a goal's events up with its descendants', at most `depth_remaining` levels down.
+/// Only complete results are cached, since a truncated one depends on where it was reached.
 fn calculate_recursive_stats_internal(
     goal_id: i64,
     goals_map: &HashMap<i64, RawGoalData>,
     cache: &mut HashMap<i64, RecursiveStats>,
     visited: &mut HashSet<i64>,
+    depth_remaining: usize,
 ) -> RecursiveStats {
     if let Some(cached) = cache.get(&goal_id) {
         return cached.clone();
@@ -68,7 +155,9 @@ fn calculate_recursive_stats_internal(
             total_duration_minutes: 0.0,
             weighted_completion_rate: 0.0,
             children_count: 0,
+            moved_before_completion: 0,
             daily_stats: HashMap::new(),
+            truncated: false,
         };
     }
     visited.insert(goal_id);
@@ -82,6 +171,7 @@ fn calculate_recursive_stats_internal(
     let mut total_weight = 0.0;
     let mut completed_weight = 0.0;
     let mut children_count = 0;
+    let mut moved_before_completion = 0;
     let mut daily_stats: HashMap<String, DailyRecursiveStats> = HashMap::new();
 
     for event in &goal.events {
@@ -92,6 +182,9 @@ fn calculate_recursive_stats_internal(
             completed_events += 1;
             completed_weight += weight;
             total_duration_minutes += event.duration;
+            if event.moved {
+                moved_before_completion += 1;
+            }
         }
 
         let day = daily_stats.entry(event.date.clone()).or_default();
@@ -112,15 +205,26 @@ fn calculate_recursive_stats_internal(
     // Map of date -> (sum(child_rate * child_weight), sum(child_weight))
     let mut daily_child_agg: HashMap<String, (f64, f64)> = HashMap::new();
 
-    for &child_id in &goal.child_ids {
+    let mut truncated = depth_remaining == 0 && !goal.child_ids.is_empty();
+    let child_ids: &[i64] = if truncated { &[] } else { &goal.child_ids };
+
+    for &child_id in child_ids {
         if let Some(child_raw) = goals_map.get(&child_id) {
-            let child_stats = calculate_recursive_stats_internal(child_id, goals_map, cache, visited);
+            let child_stats = calculate_recursive_stats_internal(
+                child_id,
+                goals_map,
+                cache,
+                visited,
+                depth_remaining - 1,
+            );
+            truncated |= child_stats.truncated;
             
             // Flat aggregates
             total_events += child_stats.total_events;
             completed_events += child_stats.completed_events;
             total_duration_minutes += child_stats.total_duration_minutes;
             children_count += 1 + child_stats.children_count;
+            moved_before_completion += child_stats.moved_before_completion;
 
             // Weighted completion for parent
             let weight = priority_to_weight(&child_raw.priority);
@@ -179,17 +283,23 @@ fn calculate_recursive_stats_internal(
         total_duration_minutes,
         weighted_completion_rate,
         children_count,
+        moved_before_completion,
         daily_stats,
+        truncated,
     };
 
     visited.remove(&goal_id);
-    cache.insert(goal_id, stats.clone());
+    if !truncated {
+        cache.insert(goal_id, stats.clone());
+    }
     stats
 }
 
 fn normalize_tz(tz: &str) -> Result<String, (StatusCode, String)> {
     let tz = tz.trim();
-    let tz = if tz.is_empty() { "UTC" } else { tz };
+    if tz.is_empty() {
+        return Ok(event_settings::default_timezone().to_string());
+    }
     let tz = if tz.eq_ignore_ascii_case("utc") {
         "UTC"
     } else {
@@ -254,6 +364,19 @@ pub struct YearStats {
     pub daily_stats: Vec<DailyStats>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RangeStats {
+    pub start_date: String, // "YYYY-MM-DD", local to the requested timezone
+    pub end_date: String,
+    pub daily_stats: Vec<DailyStats>,
+    pub weekly_stats: Vec<PeriodStats>,
+    pub monthly_stats: Vec<PeriodStats>,
+    pub totals: PeriodStats,
+}
+
+// Upper bound on custom stats ranges so a typo can't scan the whole history day by day
+pub const MAX_RANGE_DAYS: i64 = 5 * 366;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PeriodStats {
     pub period: String,       // "2024-W01", "2024-01", "2024"
@@ -283,6 +406,30 @@ pub struct RoutineStats {
     pub total_events: i32,
     pub completed_events: i32,
     pub smoothed_completion: Vec<SmoothedPoint>,
+    pub weekday_completion: Vec<WeekdayCompletion>, // Monday first, always 7 entries
+    pub target_completion_rate: Option<f64>,
+    pub meets_target: Option<bool>, // None when the routine has no target
+    pub target_delta: Option<f64>,  // completion_rate - target; negative means behind
+}
+
+/// Compare a routine's completion rate with its target, returning (meets_target, delta).
+/// Both are None when no target is set.
+pub fn target_adherence(completion_rate: f64, target: Option<f64>) -> (Option<bool>, Option<f64>) {
+    match target {
+        Some(target) => (
+            Some(completion_rate >= target),
+            Some(completion_rate - target),
+        ),
+        None => (None, None),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WeekdayCompletion {
+    pub weekday: String, // "Mon" .. "Sun", in the user's timezone
+    pub total_events: i32,
+    pub completed_events: i32,
+    pub completion_rate: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -339,6 +486,14 @@ pub struct EventAnalytics {
     pub duration_stats: Vec<DurationStats>,
     pub priority_stats: Vec<PriorityStats>,
     pub source_stats: SourceStats,
+    pub cancellation_stats: CancellationStats,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancellationStats {
+    pub total_events: i32, // resolved-or-elapsed events including cancelled ones
+    pub cancelled_events: i32,
+    pub cancellation_rate: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -382,6 +537,8 @@ pub struct EffortStat {
     pub total_duration_minutes: f64,
     pub weighted_completion_rate: f64,
     pub children_count: i32,
+    pub moved_before_completion: i32, // completed events that were rescheduled first
+    pub truncated: bool,              // the hierarchy goes deeper than the stats depth limit
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -394,6 +551,8 @@ pub struct ChildEffortTimeSeries {
     pub total_duration_minutes: f64,
     pub weighted_completion_rate: f64,
     pub children_count: i32,
+    pub moved_before_completion: i32,
+    pub truncated: bool,
     pub daily_stats: Vec<DailyEffortPoint>,
 }
 
@@ -406,11 +565,126 @@ pub struct DailyEffortPoint {
     pub weighted_score: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoalProgress {
+    pub goal_id: i64,
+    pub total_events: i32,
+    pub completed_events: i32,
+    pub completion_rate: f64,          // completed / total
+    pub weighted_completion_rate: f64, // priority-weighted, 0.0 to 1.0
+    pub truncated: bool,               // the hierarchy goes deeper than the stats depth limit
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriorityCount {
+    pub priority: String, // "high", "medium", "low" or "none"
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriorityDistribution {
+    pub total: i64,
+    pub by_priority: Vec<PriorityCount>, // high -> low, then anything unrecognized
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct TrendBucket {
+    pub period_start: String, // YYYY-MM-DD, first day of the bucket
+    pub total_events: i32,
+    pub completed_events: i32,
+    pub weighted_completion_rate: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoalTrend {
+    pub goal_id: i64,
+    pub bucket: String,
+    pub buckets: Vec<TrendBucket>, // oldest first; buckets without events are omitted
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct FocusTimeBucket {
+    pub period_start: String, // YYYY-MM-DD, first day of the bucket
+    pub scheduled_minutes: f64,
+    pub completed_minutes: f64,
+    pub event_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FocusTimeStats {
+    pub bucket: String,
+    pub buckets: Vec<FocusTimeBucket>, // oldest first; buckets without events are omitted
+    pub total_scheduled_minutes: f64,
+    pub total_completed_minutes: f64,
+    pub excluded_events: i32, // all-day or implausibly long events left out of the totals
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BreakdownGroup {
+    pub key: String, // the goal type or priority the events share
+    pub total_events: i32,
+    pub completed_events: i32,
+    pub completion_rate: f64,
+    pub weighted_completion_rate: f64, // completed events weighted by priority_to_weight
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatsBreakdown {
+    pub group_by: String,
+    pub groups: Vec<BreakdownGroup>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoalCompletionCount {
+    pub goal_id: i64,
+    pub goal_name: String,
+    pub completed_events: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventRescheduleCount {
+    pub event_id: i64,
+    pub event_name: String,
+    pub reschedule_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompletionLatencyStat {
+    pub goal_id: i64,
+    pub goal_name: String,
+    pub goal_type: String,
+    pub completed_events: i32,
+    pub average_latency_minutes: f64, // resolved_at minus scheduled time; negative = done early
+    pub median_latency_minutes: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WeekReview {
+    pub week_start: String, // YYYY-MM-DD, local to the requested timezone
+    pub week_end: String,   // last day of the week, inclusive
+    pub completion_rate: f64,
+    pub total_events: i32,
+    pub completed_events: i32,
+    pub most_completed_goal: Option<GoalCompletionCount>,
+    pub most_rescheduled_event: Option<EventRescheduleCount>,
+    pub focused_minutes: f64, // summed duration of the week's completed events
+    pub current_streak_days: i32, // days in a row with a completion, up to the latest elapsed day
+    pub longest_streak_days: i32,
+}
+
+/// Daily stats for a local calendar year, served from a `CachedYearStats` node while the
+/// year's event fingerprint is unchanged. `force` skips the cache and recomputes.
+/// `include_routines: false` leaves routine events out, so only task and achievement
+/// work is scored.
 pub async fn get_year_stats(
     graph: Graph,
     user_id: i64,
     year: Option<i32>,
     tz: String,
+    force: bool,
+    grace_minutes: i64,
+    include_routines: bool,
 ) -> Result<Json<YearStats>, (StatusCode, String)> {
     let target_year = year.unwrap_or_else(|| Utc::now().year());
     let tz = normalize_tz(&tz)?;
@@ -418,30 +692,293 @@ pub async fn get_year_stats(
         .parse()
         .expect("normalize_tz validated timezone; parse should not fail");
 
-    // Get start and end timestamps for the year
-    let start_date = NaiveDate::from_ymd_opt(target_year, 1, 1).unwrap();
-    let end_date = NaiveDate::from_ymd_opt(target_year, 12, 31).unwrap();
-
     // Use the user's timezone for year boundaries so "year" matches their local calendar.
     let (start_timestamp, end_timestamp) = tz_year_range_utc_millis(target_year, &tz_parsed);
+    let grace_ms = grace_minutes * 60 * 1000;
+    // The routine toggle is part of the fingerprint so the two views never share a cache entry
+    let scope = if include_routines {
+        "all"
+    } else {
+        "no_routines"
+    };
+    let fingerprint = format!(
+        "{}:{}",
+        scope,
+        year_stats_fingerprint(&graph, user_id, start_timestamp, end_timestamp, grace_ms).await?
+    );
 
-    // Query all events (Goal nodes with goal_type='event') linked to tasks, achievements, and routines for the year
-    // Only include events that have passed their scheduled time (scheduled_timestamp + duration <= current_time)
-    // Exclude skipped events from metrics entirely
+    if !force {
+        if let Some(cached) =
+            cached_year_stats(&graph, user_id, target_year, &tz, &fingerprint).await
+        {
+            return Ok(Json(cached));
+        }
+    }
+
+    let daily_stats = daily_stats_for_range(
+        &graph,
+        user_id,
+        start_timestamp,
+        end_timestamp,
+        &tz,
+        grace_ms,
+        include_routines,
+    )
+    .await?;
+    let stats = YearStats {
+        year: target_year,
+        daily_stats,
+    };
+
+    // A failed cache write only costs the next request a recompute
+    if let Err(e) = store_year_stats(&graph, user_id, &tz, &fingerprint, &stats).await {
+        eprintln!("Warning: Failed to cache year stats: {}", e);
+    }
+
+    Ok(Json(stats))
+}
+
+/// Summary of the events behind a year's stats. It changes when events are added, removed,
+/// moved, resolved or pass their end time; priority edits are only picked up through
+/// `invalidate_year_stats_cache` or a forced recompute.
+async fn year_stats_fingerprint(
+    graph: &Graph,
+    user_id: i64,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    grace_ms: i64,
+) -> Result<String, (StatusCode, String)> {
+    let default_duration = event_settings::default_event_duration(graph, user_id).await;
     let query_str = "
         MATCH (e:Goal)<-[:HAS_EVENT]-(g:Goal)
         WHERE e.goal_type = 'event'
         AND g.user_id = $user_id
-        AND (g.goal_type = 'task' OR g.goal_type = 'achievement' OR g.goal_type = 'routine')
+        AND e.scheduled_timestamp >= $start_timestamp
+        AND e.scheduled_timestamp <= $end_timestamp
+        AND (e.is_deleted IS NULL OR e.is_deleted = false)
+        WITH e, COALESCE(e.resolution_status, 'pending') as status,
+             (e.scheduled_timestamp + COALESCE(e.duration_seconds * 1000, COALESCE(e.duration_minutes, e.duration, $default_duration) * 60 * 1000)) as event_end_time
+        RETURN count(e) as total,
+               sum(CASE WHEN status = 'completed' THEN 1 ELSE 0 END) as completed,
+               sum(CASE WHEN status IN ['skipped', 'cancelled'] THEN 1 ELSE 0 END) as excluded,
+               sum(CASE WHEN event_end_time <= timestamp() - $grace_ms
+                          OR (status = 'completed' AND event_end_time <= timestamp())
+                        THEN 1 ELSE 0 END) as passed,
+               sum(e.scheduled_timestamp % 86400000) as time_of_day_sum,
+               max(coalesce(e.resolved_at, 0)) as last_resolved
+    ";
+
+    let mut result = graph
+        .execute(
+            query(query_str)
+                .param("user_id", user_id)
+                .param("start_timestamp", start_timestamp)
+                .param("end_timestamp", end_timestamp)
+                .param("default_duration", default_duration as i64)
+                .param("grace_ms", grace_ms),
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    else {
+        return Ok(String::new());
+    };
+    let field = |name: &str| row.get::<i64>(name).unwrap_or(0);
+    Ok(format!(
+        "{}:{}:{}:{}:{}:{}:{}",
+        grace_ms,
+        field("total"),
+        field("completed"),
+        field("excluded"),
+        field("passed"),
+        field("time_of_day_sum"),
+        field("last_resolved")
+    ))
+}
+
+async fn cached_year_stats(
+    graph: &Graph,
+    user_id: i64,
+    year: i32,
+    tz: &str,
+    fingerprint: &str,
+) -> Option<YearStats> {
+    let mut result = graph
+        .execute(
+            query(
+                "MATCH (c:CachedYearStats {user_id: $user_id, year: $year, tz: $tz})
+                 WHERE c.fingerprint = $fingerprint
+                 RETURN c.payload as payload",
+            )
+            .param("user_id", user_id)
+            .param("year", year as i64)
+            .param("tz", tz)
+            .param("fingerprint", fingerprint),
+        )
+        .await
+        .ok()?;
+    let payload = result.next().await.ok()??.get::<String>("payload").ok()?;
+    serde_json::from_str(&payload).ok()
+}
+
+async fn store_year_stats(
+    graph: &Graph,
+    user_id: i64,
+    tz: &str,
+    fingerprint: &str,
+    stats: &YearStats,
+) -> Result<(), String> {
+    let payload = serde_json::to_string(stats).map_err(|e| e.to_string())?;
+    graph
+        .run(
+            query(
+                "MERGE (c:CachedYearStats {user_id: $user_id, year: $year, tz: $tz})
+                 SET c.fingerprint = $fingerprint,
+                     c.payload = $payload,
+                     c.computed_at = timestamp()",
+            )
+            .param("user_id", user_id)
+            .param("year", stats.year as i64)
+            .param("tz", tz)
+            .param("fingerprint", fingerprint)
+            .param("payload", payload),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Calendar years a timestamp can fall in across all timezones (UTC-12 to UTC+14).
+pub fn years_touched(timestamp: i64) -> Vec<i32> {
+    let year_at = |offset_hours: i64| {
+        Utc.timestamp_millis_opt(timestamp + offset_hours * 60 * 60 * 1000)
+            .single()
+            .map(|dt| dt.year())
+    };
+    let mut years: Vec<i32> = [year_at(-12), year_at(14)].into_iter().flatten().collect();
+    years.dedup();
+    years
+}
+
+/// Drop cached year stats (in every timezone) for the years an event at `timestamp` touches.
+/// Failures are logged; the fingerprint check still catches most changes.
+pub async fn invalidate_year_stats_cache(graph: &Graph, user_id: i64, timestamp: i64) {
+    let years: Vec<i64> = years_touched(timestamp)
+        .into_iter()
+        .map(i64::from)
+        .collect();
+    let result = graph
+        .run(
+            query(
+                "MATCH (c:CachedYearStats)
+                 WHERE c.user_id = $user_id AND c.year IN $years
+                 DETACH DELETE c",
+            )
+            .param("user_id", user_id)
+            .param("years", years),
+        )
+        .await;
+    if let Err(e) = result {
+        eprintln!("Warning: Failed to invalidate year stats cache: {}", e);
+    }
+}
+
+/// Daily stats (plus weekly/monthly rollups) over an arbitrary range, e.g. "last 90 days"
+/// or a fiscal year. Days are bucketed in `tz`.
+pub async fn get_range_stats(
+    graph: Graph,
+    user_id: i64,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    tz: String,
+    monday_start: bool,
+) -> Result<Json<RangeStats>, (StatusCode, String)> {
+    if end_timestamp < start_timestamp {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "end_timestamp must not be before start_timestamp".to_string(),
+        ));
+    }
+    if end_timestamp - start_timestamp > MAX_RANGE_DAYS * 24 * 60 * 60 * 1000 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Stats ranges are limited to {} days", MAX_RANGE_DAYS),
+        ));
+    }
+    let tz = normalize_tz(&tz)?;
+    let tz_parsed: Tz = tz
+        .parse()
+        .expect("normalize_tz validated timezone; parse should not fail");
+
+    let grace_ms = default_grace_minutes() * 60 * 1000;
+    let daily_stats = daily_stats_for_range(
+        &graph,
+        user_id,
+        start_timestamp,
+        end_timestamp,
+        &tz,
+        grace_ms,
+        true,
+    )
+    .await?;
+    let start_date = local_date(&tz_parsed, start_timestamp);
+    let end_date = local_date(&tz_parsed, end_timestamp);
+
+    Ok(Json(RangeStats {
+        start_date: start_date.format("%Y-%m-%d").to_string(),
+        end_date: end_date.format("%Y-%m-%d").to_string(),
+        weekly_stats: aggregate_weekly_stats(&daily_stats, monday_start),
+        monthly_stats: aggregate_monthly_stats(&daily_stats),
+        totals: aggregate_period_stats(&daily_stats, format!("{}..{}", start_date, end_date)),
+        daily_stats,
+    }))
+}
+
+fn local_date(tz: &Tz, timestamp: i64) -> NaiveDate {
+    tz.timestamp_millis_opt(timestamp)
+        .single()
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default()
+}
+
+/// Score every local day between the two timestamps (inclusive), including empty days.
+async fn daily_stats_for_range(
+    graph: &Graph,
+    user_id: i64,
+    start_timestamp: i64,
+    end_timestamp: i64,
+    tz: &str,
+    grace_ms: i64,
+    include_routines: bool,
+) -> Result<Vec<DailyStats>, (StatusCode, String)> {
+    let default_duration = event_settings::default_event_duration(graph, user_id).await;
+    let tz_parsed: Tz = tz
+        .parse()
+        .expect("normalize_tz validated timezone; parse should not fail");
+
+    // Query all events (Goal nodes with goal_type='event') linked to tasks, achievements, and (unless excluded) routines in the range
+    // Only include events that have passed their scheduled time (scheduled_timestamp + duration <= current_time);
+    // pending ones only once the grace period is over too
+    // Exclude skipped and cancelled events from metrics entirely
+    let query_str = "
+        MATCH (e:Goal)<-[:HAS_EVENT]-(g:Goal)
+        WHERE e.goal_type = 'event'
+        AND g.user_id = $user_id
+        AND (g.goal_type = 'task' OR g.goal_type = 'achievement'
+             OR ($include_routines AND g.goal_type = 'routine'))
         AND e.scheduled_timestamp >= $start_timestamp
         AND e.scheduled_timestamp <= $end_timestamp
         AND (e.is_deleted IS NULL OR e.is_deleted = false)
         WITH e, g, 
-             (e.scheduled_timestamp + COALESCE(e.duration_minutes, e.duration, 60) * 60 * 1000) as event_end_time,
+             (e.scheduled_timestamp + COALESCE(e.duration_seconds * 1000, COALESCE(e.duration_minutes, e.duration, $default_duration) * 60 * 1000)) as event_end_time,
              timestamp() as current_time,
              COALESCE(e.resolution_status, 'pending') as status
-        WHERE event_end_time <= current_time
-        AND status <> 'skipped'
+        WHERE (event_end_time <= current_time - $grace_ms
+               OR (status = 'completed' AND event_end_time <= current_time))
+        AND NOT status IN ['skipped', 'cancelled']
         WITH e, g, status,
              datetime({epochMillis: e.scheduled_timestamp, timezone: $tz}) as dt
         RETURN toString(date(dt)) as date,
@@ -453,7 +990,10 @@ pub async fn get_year_stats(
         .param("user_id", user_id)
         .param("start_timestamp", start_timestamp)
         .param("end_timestamp", end_timestamp)
-        .param("tz", tz);
+        .param("tz", tz)
+        .param("default_duration", default_duration as i64)
+        .param("grace_ms", grace_ms)
+        .param("include_routines", include_routines);
 
     match graph.execute(query).await {
         Ok(mut result) => {
@@ -472,68 +1012,91 @@ pub async fn get_year_stats(
                     .push((completed, priority));
             }
 
-            // Calculate daily stats for each day of the year
-            let mut daily_stats = Vec::new();
-            let mut current_date = start_date;
-
-            while current_date <= end_date {
-                let date_str = current_date.format("%Y-%m-%d").to_string();
-                let events = daily_events.get(&date_str).cloned().unwrap_or_default();
-
-                let mut total_events = 0;
-                let mut completed_events = 0;
-                let mut weighted_total = 0.0;
-                let mut weighted_completed = 0.0;
-
-                for (completed, priority) in events {
-                    let weight = match priority.as_str() {
-                        "none" => 0.0,
-                        "low" => 1.0,
-                        "medium" => 2.0,
-                        "high" => 3.0,
-                        _ => 2.0, // default to medium
-                    };
+            Ok(build_daily_stats(
+                &daily_events,
+                local_date(&tz_parsed, start_timestamp),
+                local_date(&tz_parsed, end_timestamp),
+            ))
+        }
+        Err(e) => {
+            eprintln!("Error fetching range stats: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch stats: {}", e),
+            ))
+        }
+    }
+}
 
-                    total_events += 1;
-                    weighted_total += weight;
+/// Turn per-day `(completed, priority)` events into one `DailyStats` per day from
+/// `start_date` through `end_date`.
+pub fn build_daily_stats(
+    daily_events: &HashMap<String, Vec<(bool, String)>>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Vec<DailyStats> {
+    let mut daily_stats = Vec::new();
+    let mut current_date = start_date;
+
+    while current_date <= end_date {
+        let date_str = current_date.format("%Y-%m-%d").to_string();
+        let events = daily_events.get(&date_str).cloned().unwrap_or_default();
 
-                    if completed {
-                        completed_events += 1;
-                        weighted_completed += weight;
-                    }
-                }
+        let mut total_events = 0;
+        let mut completed_events = 0;
+        let mut weighted_total = 0.0;
+        let mut weighted_completed = 0.0;
 
-                let score = if weighted_total > 0.0 {
-                    weighted_completed / weighted_total
-                } else {
-                    0.0
-                };
+        for (completed, priority) in events {
+            let weight = priority_to_weight(&priority);
 
-                daily_stats.push(DailyStats {
-                    date: date_str,
-                    score,
-                    total_events,
-                    completed_events,
-                    weighted_total,
-                    weighted_completed,
-                });
+            total_events += 1;
+            weighted_total += weight;
 
-                current_date += Duration::days(1);
+            if completed {
+                completed_events += 1;
+                weighted_completed += weight;
             }
-
-            Ok(Json(YearStats {
-                year: target_year,
-                daily_stats,
-            }))
-        }
-        Err(e) => {
-            eprintln!("Error fetching year stats: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to fetch year stats: {}", e),
-            ))
         }
+
+        let score = if weighted_total > 0.0 {
+            weighted_completed / weighted_total
+        } else {
+            0.0
+        };
+
+        daily_stats.push(DailyStats {
+            date: date_str,
+            score,
+            total_events,
+            completed_events,
+            weighted_total,
+            weighted_completed,
+        });
+
+        current_date += Duration::days(1);
     }
+
+    daily_stats
+}
+
+/// Lower bound for a "2w"/"1m"/"3m"/"6m"/"1y"/"5y" range (months and years approximated in
+/// days), anchored to local midnight so it matches the user's calendar. Anything else is unbounded.
+fn range_start_timestamp(range: Option<&str>, tz: &Tz) -> Option<i64> {
+    let days = match range {
+        Some("5y") => 5 * 365,
+        Some("1y") => 365,
+        Some("6m") => 182,
+        Some("3m") => 91,
+        Some("1m") => 30,
+        Some("2w") => 14,
+        _ => return None,
+    };
+    let today_local = Utc::now().with_timezone(tz).date_naive();
+    Some(tz_midnight_utc_millis(
+        tz,
+        today_local - Duration::days(days),
+    ))
 }
 
 pub async fn get_effort_stats(
@@ -542,54 +1105,27 @@ pub async fn get_effort_stats(
     range: Option<String>,
     tz: String,
 ) -> Result<Json<Vec<EffortStat>>, (StatusCode, String)> {
-    // Determine lower bound start timestamp from range (approximate months/years using days),
-    // anchored to the user's local midnight to match their calendar expectations.
+    let default_duration = event_settings::default_event_duration(&graph, user_id).await;
     let tz_parsed: Tz = normalize_tz(&tz)?
         .parse()
         .expect("normalize_tz validated timezone; parse should not fail");
-    let today_local = Utc::now().with_timezone(&tz_parsed).date_naive();
 
-    let start_timestamp_opt: Option<i64> = match range.as_deref() {
-        Some("5y") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(5 * 365),
-        )),
-        Some("1y") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(365),
-        )),
-        Some("6m") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(182),
-        )),
-        Some("3m") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(91),
-        )),
-        Some("1m") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(30),
-        )),
-        Some("2w") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(14),
-        )),
-        Some("all") | None | Some("") => None,
-        Some(_) => None,
-    };
+    let start_timestamp_opt = range_start_timestamp(range.as_deref(), &tz_parsed);
 
     // Fetch all non-event goals and their relationships for the user
     let tree_query_str = r#"
         MATCH (g:Goal)
         WHERE g.user_id = $user_id AND g.goal_type <> 'event'
+          AND coalesce(g.is_deleted, false) <> true
         OPTIONAL MATCH (g)-[:CHILD]->(child:Goal)
         WHERE child.user_id = $user_id AND child.goal_type <> 'event'
+          AND coalesce(child.is_deleted, false) <> true
         OPTIONAL MATCH (g)-[:HAS_EVENT]->(e:Goal)
         WHERE e.goal_type = 'event'
           AND (e.is_deleted IS NULL OR e.is_deleted = false)
           AND e.scheduled_timestamp < timestamp()
           AND ($start_timestamp IS NULL OR e.scheduled_timestamp >= $start_timestamp)
-          AND COALESCE(e.resolution_status, 'pending') <> 'skipped'
+          AND NOT COALESCE(e.resolution_status, 'pending') IN ['skipped', 'cancelled']
         RETURN id(g) AS id,
                g.name AS name,
                g.goal_type AS goal_type,
@@ -601,15 +1137,22 @@ pub async fn get_effort_stats(
                    duration: CASE
                       WHEN e.end_timestamp IS NOT NULL AND e.end_timestamp > e.scheduled_timestamp
                         THEN toFloat(e.end_timestamp - e.scheduled_timestamp) / (1000.0*60.0)
-                      ELSE toFloat(COALESCE(e.duration_minutes, e.duration, 60))
+                      ELSE toFloat(COALESCE(e.duration_seconds / 60.0, e.duration_minutes, e.duration, $default_duration))
                     END,
-                   date: toString(date(datetime({epochMillis: e.scheduled_timestamp, timezone: $tz})))
+                   date: toString(date(datetime({epochMillis: e.scheduled_timestamp, timezone: $tz}))),
+                   moved: EXISTS {
+                       MATCH (em:EventMove)
+                       WHERE em.event_id = id(e)
+                         AND em.move_type = 'reschedule'
+                         AND em.move_timestamp <= COALESCE(e.resolved_at, timestamp())
+                   }
                }) AS events
     "#;
 
     let mut q = query(tree_query_str)
         .param("user_id", user_id)
-        .param("tz", tz_parsed.to_string());
+        .param("tz", tz_parsed.to_string())
+        .param("default_duration", default_duration as i64);
 
     if let Some(start) = start_timestamp_opt {
         q = q.param("start_timestamp", start);
@@ -617,7 +1160,8 @@ pub async fn get_effort_stats(
         q = q.param("start_timestamp", Option::<i64>::None);
     }
 
-    match graph.execute(q).await {
+    let max_depth = max_traversal_depth();
+    match with_stats_timeout("effort stats", graph.execute(q)).await? {
         Ok(mut result) => {
             let mut goals_map = HashMap::new();
             let mut goal_ids = Vec::new();
@@ -635,6 +1179,7 @@ pub async fn get_effort_stats(
                     if let Some(status) = ev.get("status").and_then(|v| v.as_str()) {
                         events.push(RawEventData {
                             completed: status == "completed",
+                            moved: ev.get("moved").and_then(|v| v.as_bool()).unwrap_or(false),
                             priority: ev.get("priority").and_then(|v| v.as_str()).unwrap_or("medium").to_string(),
                             duration: ev.get("duration").and_then(|v| v.as_f64()).unwrap_or(60.0),
                             date: ev.get("date").and_then(|v| v.as_str()).unwrap_or("").to_string(),
@@ -658,7 +1203,13 @@ pub async fn get_effort_stats(
 
             for id in goal_ids {
                 let mut visited = HashSet::new();
-                let res = calculate_recursive_stats_internal(id, &goals_map, &mut cache, &mut visited);
+                let res = calculate_recursive_stats_internal(
+                    id,
+                    &goals_map,
+                    &mut cache,
+                    &mut visited,
+                    max_depth,
+                );
                 
                 let goal = &goals_map[&id];
                 stats.push(EffortStat {
@@ -670,6 +1221,8 @@ pub async fn get_effort_stats(
                     total_duration_minutes: res.total_duration_minutes,
                     weighted_completion_rate: res.weighted_completion_rate,
                     children_count: res.children_count,
+                    moved_before_completion: res.moved_before_completion,
+                    truncated: res.truncated,
                 });
             }
 
@@ -688,6 +1241,607 @@ pub async fn get_effort_stats(
     }
 }
 
+/// Average and median of completion latencies given in milliseconds, in minutes. None when
+/// there are no latencies.
+pub fn latency_summary_minutes(latencies_ms: &[i64]) -> Option<(f64, f64)> {
+    if latencies_ms.is_empty() {
+        return None;
+    }
+    let mut sorted = latencies_ms.to_vec();
+    sorted.sort_unstable();
+    let to_minutes = |ms: f64| ms / (60.0 * 1000.0);
+
+    let average = sorted.iter().map(|&ms| ms as f64).sum::<f64>() / sorted.len() as f64;
+    let mid = sorted.len() / 2;
+    let median = if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] as f64 + sorted[mid] as f64) / 2.0
+    } else {
+        sorted[mid] as f64
+    };
+    Some((to_minutes(average), to_minutes(median)))
+}
+
+/// Per goal, how long after their scheduled time the goal's events actually get completed,
+/// over completed events in `range` (see `range_start_timestamp`; unbounded otherwise).
+/// Chronically late goals come first.
+pub async fn get_completion_latency_stats(
+    graph: Graph,
+    user_id: i64,
+    range: Option<String>,
+    tz: String,
+) -> Result<Json<Vec<CompletionLatencyStat>>, (StatusCode, String)> {
+    let tz_parsed: Tz = normalize_tz(&tz)?
+        .parse()
+        .expect("normalize_tz validated timezone; parse should not fail");
+    let start_timestamp = range_start_timestamp(range.as_deref(), &tz_parsed);
+
+    let latency_query = query(
+        "MATCH (g:Goal)-[:HAS_EVENT]->(e:Goal)
+         WHERE g.user_id = $user_id
+           AND e.goal_type = 'event'
+           AND (e.is_deleted IS NULL OR e.is_deleted = false)
+           AND e.resolution_status = 'completed'
+           AND e.resolved_at IS NOT NULL
+           AND e.scheduled_timestamp IS NOT NULL
+           AND ($start_timestamp IS NULL OR e.scheduled_timestamp >= $start_timestamp)
+         RETURN id(g) AS id,
+                g.name AS name,
+                g.goal_type AS goal_type,
+                collect(e.resolved_at - e.scheduled_timestamp) AS latencies",
+    )
+    .param("user_id", user_id)
+    .param("start_timestamp", start_timestamp);
+
+    let mut result = with_stats_timeout("completion latency", graph.execute(latency_query))
+        .await?
+        .map_err(|e| {
+            eprintln!("Error fetching completion latency stats: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch completion latency stats: {}", e),
+            )
+        })?;
+
+    let mut stats = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        let latencies = row.get::<Vec<i64>>("latencies").unwrap_or_default();
+        let Some((average, median)) = latency_summary_minutes(&latencies) else {
+            continue;
+        };
+        stats.push(CompletionLatencyStat {
+            goal_id: row.get::<i64>("id").unwrap_or(0),
+            goal_name: row.get::<String>("name").unwrap_or_default(),
+            goal_type: row.get::<String>("goal_type").unwrap_or_default(),
+            completed_events: latencies.len() as i32,
+            average_latency_minutes: average,
+            median_latency_minutes: median,
+        });
+    }
+
+    stats.sort_by(|a, b| {
+        b.average_latency_minutes
+            .partial_cmp(&a.average_latency_minutes)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    Ok(Json(stats))
+}
+
+/// Check the goal belongs to the user and report whether its hierarchy goes deeper than
+/// `max_depth` levels, i.e. whether a subtree rollup capped there leaves goals out.
+async fn verify_goal_depth(
+    graph: &Graph,
+    user_id: i64,
+    goal_id: i64,
+    max_depth: usize,
+) -> Result<bool, (StatusCode, String)> {
+    let verify_query = query(&format!(
+        "MATCH (g:Goal)
+         WHERE id(g) = $goal_id AND g.user_id = $user_id
+         RETURN EXISTS {{ MATCH (g)-[:CHILD*{}]->(:Goal) }} as truncated",
+        max_depth + 1
+    ))
+    .param("goal_id", goal_id)
+    .param("user_id", user_id);
+
+    let mut verify_result = with_stats_timeout("goal depth", graph.execute(verify_query))
+        .await?
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let row = verify_result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Goal with id {} not found", goal_id),
+        ))?;
+    Ok(row.get::<bool>("truncated").unwrap_or(false))
+}
+
+/// Aggregate completion progress over every event belonging to a goal or any of its
+/// descendants, optionally restricted to events scheduled within [start, end].
+pub async fn get_goal_progress(
+    graph: Graph,
+    user_id: i64,
+    goal_id: i64,
+    start_timestamp: Option<i64>,
+    end_timestamp: Option<i64>,
+) -> Result<Json<GoalProgress>, (StatusCode, String)> {
+    let max_depth = max_traversal_depth();
+    let truncated = verify_goal_depth(&graph, user_id, goal_id, max_depth).await?;
+
+    // A descendant reachable through several CHILD paths must only be counted once,
+    // so collapse both the descendants and their events with DISTINCT.
+    let query_str = format!(
+        "
+        MATCH (root:Goal)-[:CHILD*0..{}]->(d:Goal)
+        WHERE id(root) = $goal_id
+          AND d.user_id = $user_id
+          AND coalesce(d.is_deleted, false) <> true
+        WITH DISTINCT d
+        MATCH (d)-[:HAS_EVENT]->(e:Goal)
+        WHERE e.goal_type = 'event'
+          AND (e.is_deleted IS NULL OR e.is_deleted = false)
+          AND NOT COALESCE(e.resolution_status, 'pending') IN ['skipped', 'cancelled']
+          AND ($start_timestamp IS NULL OR e.scheduled_timestamp >= $start_timestamp)
+          AND ($end_timestamp IS NULL OR e.scheduled_timestamp <= $end_timestamp)
+        WITH DISTINCT e, COALESCE(e.priority, d.priority, 'medium') as priority
+        RETURN COALESCE(e.resolution_status, 'pending') = 'completed' as completed,
+               priority
+    ",
+        max_depth
+    );
+
+    let mut result = with_stats_timeout(
+        "goal progress",
+        graph.execute(
+            query(&query_str)
+                .param("goal_id", goal_id)
+                .param("user_id", user_id)
+                .param("start_timestamp", start_timestamp)
+                .param("end_timestamp", end_timestamp),
+        ),
+    )
+    .await?
+    .map_err(|e| {
+        eprintln!("Error fetching goal progress: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch goal progress: {}", e),
+        )
+    })?;
+
+    let mut total_events = 0;
+    let mut completed_events = 0;
+    let mut weighted_total = 0.0;
+    let mut weighted_completed = 0.0;
+
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        let completed = row.get::<bool>("completed").unwrap_or(false);
+        let priority = row
+            .get::<String>("priority")
+            .unwrap_or_else(|_| "medium".to_string());
+        let weight = priority_to_weight(&priority);
+
+        total_events += 1;
+        weighted_total += weight;
+        if completed {
+            completed_events += 1;
+            weighted_completed += weight;
+        }
+    }
+
+    let completion_rate = if total_events > 0 {
+        completed_events as f64 / total_events as f64
+    } else {
+        0.0
+    };
+    let weighted_completion_rate = if weighted_total > 0.0 {
+        weighted_completed / weighted_total
+    } else {
+        0.0
+    };
+
+    Ok(Json(GoalProgress {
+        goal_id,
+        total_events,
+        completed_events,
+        completion_rate,
+        weighted_completion_rate,
+        truncated,
+    }))
+}
+
+/// First day of the "day", "week" (Monday-based) or "month" bucket containing `date`.
+pub fn trend_bucket_start(date: NaiveDate, bucket: &str) -> Option<NaiveDate> {
+    match bucket {
+        "day" => Some(date),
+        "week" => Some(date - Duration::days(date.weekday().num_days_from_monday() as i64)),
+        "month" => date.with_day(1),
+        _ => None,
+    }
+}
+
+/// Group (date, completed, priority) events into buckets with a priority-weighted completion
+/// rate per bucket, oldest first.
+pub fn build_trend_buckets(events: &[(NaiveDate, bool, String)], bucket: &str) -> Vec<TrendBucket> {
+    // (total, completed, weighted_total, weighted_completed)
+    let mut grouped: BTreeMap<NaiveDate, (i32, i32, f64, f64)> = BTreeMap::new();
+    for (date, completed, priority) in events {
+        let Some(start) = trend_bucket_start(*date, bucket) else {
+            continue;
+        };
+        let weight = priority_to_weight(priority);
+        let entry = grouped.entry(start).or_default();
+        entry.0 += 1;
+        entry.2 += weight;
+        if *completed {
+            entry.1 += 1;
+            entry.3 += weight;
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(
+            |(start, (total, completed, weighted_total, weighted_completed))| TrendBucket {
+                period_start: start.format("%Y-%m-%d").to_string(),
+                total_events: total,
+                completed_events: completed,
+                weighted_completion_rate: if weighted_total > 0.0 {
+                    weighted_completed / weighted_total
+                } else {
+                    0.0
+                },
+            },
+        )
+        .collect()
+}
+
+/// Weighted completion over a goal's whole subtree, bucketed over time, to show whether a
+/// project is accelerating or stalling. Only past events count, as in `get_effort_stats`.
+pub async fn get_goal_trend(
+    graph: Graph,
+    user_id: i64,
+    goal_id: i64,
+    bucket: String,
+    range: Option<String>,
+    tz: String,
+) -> Result<Json<GoalTrend>, (StatusCode, String)> {
+    if trend_bucket_start(NaiveDate::default(), &bucket).is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "bucket must be 'day', 'week' or 'month'".to_string(),
+        ));
+    }
+    let tz_parsed: Tz = normalize_tz(&tz)?
+        .parse()
+        .expect("normalize_tz validated timezone; parse should not fail");
+    let start_timestamp = range_start_timestamp(range.as_deref(), &tz_parsed);
+
+    let max_depth = max_traversal_depth();
+    let truncated = verify_goal_depth(&graph, user_id, goal_id, max_depth).await?;
+
+    // Same DISTINCT collapsing as get_goal_progress so shared descendants count once
+    let query_str = format!(
+        "
+        MATCH (root:Goal)-[:CHILD*0..{}]->(d:Goal)
+        WHERE id(root) = $goal_id
+          AND d.user_id = $user_id
+          AND coalesce(d.is_deleted, false) <> true
+        WITH DISTINCT d
+        MATCH (d)-[:HAS_EVENT]->(e:Goal)
+        WHERE e.goal_type = 'event'
+          AND (e.is_deleted IS NULL OR e.is_deleted = false)
+          AND e.scheduled_timestamp < timestamp()
+          AND ($start_timestamp IS NULL OR e.scheduled_timestamp >= $start_timestamp)
+          AND NOT COALESCE(e.resolution_status, 'pending') IN ['skipped', 'cancelled']
+        WITH DISTINCT e, COALESCE(e.priority, d.priority, 'medium') as priority
+        RETURN e.scheduled_timestamp as scheduled_timestamp,
+               COALESCE(e.resolution_status, 'pending') = 'completed' as completed,
+               priority
+    ",
+        max_depth
+    );
+
+    let mut result = with_stats_timeout(
+        "goal trend",
+        graph.execute(
+            query(&query_str)
+                .param("goal_id", goal_id)
+                .param("user_id", user_id)
+                .param("start_timestamp", start_timestamp),
+        ),
+    )
+    .await?
+    .map_err(|e| {
+        eprintln!("Error fetching goal trend: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch goal trend: {}", e),
+        )
+    })?;
+
+    let mut events = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        let Ok(timestamp) = row.get::<i64>("scheduled_timestamp") else {
+            continue;
+        };
+        let completed = row.get::<bool>("completed").unwrap_or(false);
+        let priority = row
+            .get::<String>("priority")
+            .unwrap_or_else(|_| "medium".to_string());
+        events.push((local_date(&tz_parsed, timestamp), completed, priority));
+    }
+
+    Ok(Json(GoalTrend {
+        goal_id,
+        buckets: build_trend_buckets(&events, &bucket),
+        bucket,
+        truncated,
+    }))
+}
+
+/// All-day events are stored with this duration; anything this long isn't focused work.
+pub const ALL_DAY_EVENT_MINUTES: f64 = 1440.0;
+
+/// Minutes an event contributes to focus time: its duration, or None for all-day and
+/// longer events (and non-positive durations), which would swamp the totals.
+pub fn focus_minutes(duration_minutes: f64) -> Option<f64> {
+    (duration_minutes > 0.0 && duration_minutes < ALL_DAY_EVENT_MINUTES).then_some(duration_minutes)
+}
+
+/// Sum (date, duration minutes, completed) events into scheduled/completed minutes per
+/// bucket, oldest first. Events excluded by `focus_minutes` are skipped.
+pub fn build_focus_buckets(
+    events: &[(NaiveDate, f64, bool)],
+    bucket: &str,
+) -> Vec<FocusTimeBucket> {
+    // (scheduled, completed, count)
+    let mut grouped: BTreeMap<NaiveDate, (f64, f64, i32)> = BTreeMap::new();
+    for (date, duration, completed) in events {
+        let (Some(start), Some(minutes)) =
+            (trend_bucket_start(*date, bucket), focus_minutes(*duration))
+        else {
+            continue;
+        };
+        let entry = grouped.entry(start).or_default();
+        entry.0 += minutes;
+        entry.2 += 1;
+        if *completed {
+            entry.1 += minutes;
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(|(start, (scheduled, completed, count))| FocusTimeBucket {
+            period_start: start.format("%Y-%m-%d").to_string(),
+            scheduled_minutes: scheduled,
+            completed_minutes: completed,
+            event_count: count,
+        })
+        .collect()
+}
+
+/// Scheduled vs. completed event minutes per bucket: a volume view to sit alongside the
+/// completion-rate stats. Past events only, skipped/cancelled excluded, as in `get_goal_trend`.
+pub async fn get_focus_time_stats(
+    graph: Graph,
+    user_id: i64,
+    range: Option<String>,
+    bucket: String,
+    tz: String,
+) -> Result<Json<FocusTimeStats>, (StatusCode, String)> {
+    if trend_bucket_start(NaiveDate::default(), &bucket).is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "bucket must be 'day', 'week' or 'month'".to_string(),
+        ));
+    }
+    let tz_parsed: Tz = normalize_tz(&tz)?
+        .parse()
+        .expect("normalize_tz validated timezone; parse should not fail");
+    let start_timestamp = range_start_timestamp(range.as_deref(), &tz_parsed);
+    let default_duration = event_settings::default_event_duration(&graph, user_id).await;
+
+    let query_str = "
+        MATCH (e:Goal)
+        WHERE e.user_id = $user_id
+          AND e.goal_type = 'event'
+          AND (e.is_deleted IS NULL OR e.is_deleted = false)
+          AND e.scheduled_timestamp < timestamp()
+          AND ($start_timestamp IS NULL OR e.scheduled_timestamp >= $start_timestamp)
+          AND NOT COALESCE(e.resolution_status, 'pending') IN ['skipped', 'cancelled']
+        RETURN e.scheduled_timestamp as scheduled_timestamp,
+               toFloat(COALESCE(e.duration_seconds / 60.0, e.duration, $default_duration)) as duration,
+               COALESCE(e.resolution_status, 'pending') = 'completed' as completed
+    ";
+
+    let mut result = with_stats_timeout(
+        "focus time",
+        graph.execute(
+            query(query_str)
+                .param("user_id", user_id)
+                .param("start_timestamp", start_timestamp)
+                .param("default_duration", default_duration as i64),
+        ),
+    )
+    .await?
+    .map_err(|e| {
+        eprintln!("Error fetching focus time: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch focus time: {}", e),
+        )
+    })?;
+
+    let mut events = Vec::new();
+    let mut excluded_events = 0;
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        let Ok(timestamp) = row.get::<i64>("scheduled_timestamp") else {
+            continue;
+        };
+        let duration = row.get::<f64>("duration").unwrap_or(0.0);
+        if focus_minutes(duration).is_none() {
+            excluded_events += 1;
+            continue;
+        }
+        let completed = row.get::<bool>("completed").unwrap_or(false);
+        events.push((local_date(&tz_parsed, timestamp), duration, completed));
+    }
+
+    let buckets = build_focus_buckets(&events, &bucket);
+    Ok(Json(FocusTimeStats {
+        total_scheduled_minutes: buckets.iter().map(|b| b.scheduled_minutes).sum(),
+        total_completed_minutes: buckets.iter().map(|b| b.completed_minutes).sum(),
+        bucket,
+        buckets,
+        excluded_events,
+    }))
+}
+
+/// Completion counts and rates per group for (group key, priority, completed) events.
+/// Priority groups keep the none/low/medium/high order; anything else sorts by key.
+pub fn build_breakdown_groups(
+    events: &[(String, String, bool)],
+    group_by: &str,
+) -> Vec<BreakdownGroup> {
+    // total, completed, total weight, completed weight
+    let mut totals: HashMap<&str, (i32, i32, f64, f64)> = HashMap::new();
+    for (key, priority, completed) in events {
+        let weight = priority_to_weight(priority);
+        let entry = totals.entry(key.as_str()).or_insert((0, 0, 0.0, 0.0));
+        entry.0 += 1;
+        entry.2 += weight;
+        if *completed {
+            entry.1 += 1;
+            entry.3 += weight;
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (key, (total, completed, weight, completed_weight)) in totals {
+        groups.push(BreakdownGroup {
+            key: key.to_string(),
+            total_events: total,
+            completed_events: completed,
+            completion_rate: completed as f64 / total as f64,
+            weighted_completion_rate: if weight > 0.0 {
+                completed_weight / weight
+            } else {
+                0.0
+            },
+        });
+    }
+
+    if group_by == "priority" {
+        let order = ["none", "low", "medium", "high"];
+        let rank = |key: &str| order.iter().position(|&p| p == key).unwrap_or(order.len());
+        groups.sort_by_key(|g| (rank(&g.key), g.key.clone()));
+    } else {
+        groups.sort_by(|a, b| a.key.cmp(&b.key));
+    }
+    groups
+}
+
+/// Completion rates for elapsed events in the range, grouped by the parent's goal type or
+/// by priority. Skipped and cancelled events don't count either way.
+pub async fn get_stats_breakdown(
+    graph: Graph,
+    user_id: i64,
+    range: Option<String>,
+    group_by: String,
+    tz: String,
+) -> Result<Json<StatsBreakdown>, (StatusCode, String)> {
+    let key_expr = match group_by.as_str() {
+        "goal_type" => "coalesce(p.goal_type, e.parent_type, 'none')",
+        "priority" => "priority",
+        "tag" => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Goals don't have tags yet; group by 'goal_type' or 'priority'".to_string(),
+            ))
+        }
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "group_by must be 'goal_type' or 'priority'".to_string(),
+            ))
+        }
+    };
+    let tz_parsed: Tz = normalize_tz(&tz)?
+        .parse()
+        .expect("normalize_tz validated timezone; parse should not fail");
+    let start_timestamp = range_start_timestamp(range.as_deref(), &tz_parsed);
+
+    let query_str = format!(
+        "MATCH (e:Goal)
+        WHERE e.user_id = $user_id
+          AND e.goal_type = 'event'
+          AND (e.is_deleted IS NULL OR e.is_deleted = false)
+          AND e.scheduled_timestamp < timestamp()
+          AND ($start_timestamp IS NULL OR e.scheduled_timestamp >= $start_timestamp)
+          AND NOT COALESCE(e.resolution_status, 'pending') IN ['skipped', 'cancelled']
+        OPTIONAL MATCH (p:Goal)-[:HAS_EVENT]->(e)
+        WITH e, head(collect(p)) as p
+        WITH e, p, toLower(COALESCE(e.priority, p.priority, 'medium')) as priority
+        RETURN {} as key,
+               priority,
+               COALESCE(e.resolution_status, 'pending') = 'completed' as completed",
+        key_expr
+    );
+
+    let mut result = with_stats_timeout(
+        "stats breakdown",
+        graph.execute(
+            query(&query_str)
+                .param("user_id", user_id)
+                .param("start_timestamp", start_timestamp),
+        ),
+    )
+    .await?
+    .map_err(|e| {
+        eprintln!("Error fetching stats breakdown: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch stats breakdown: {}", e),
+        )
+    })?;
+
+    let mut events = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        let key: String = row.get("key").unwrap_or_else(|_| "none".to_string());
+        let priority: String = row.get("priority").unwrap_or_else(|_| "medium".to_string());
+        events.push((key, priority, row.get("completed").unwrap_or(false)));
+    }
+
+    Ok(Json(StatsBreakdown {
+        groups: build_breakdown_groups(&events, &group_by),
+        group_by,
+    }))
+}
+
 pub async fn get_goal_children_effort(
     graph: Graph,
     user_id: i64,
@@ -695,53 +1849,27 @@ pub async fn get_goal_children_effort(
     range: Option<String>,
     tz: String,
 ) -> Result<Json<Vec<ChildEffortTimeSeries>>, (StatusCode, String)> {
+    let default_duration = event_settings::default_event_duration(&graph, user_id).await;
     let tz = normalize_tz(&tz)?;
     let tz_parsed: Tz = tz
         .parse()
         .expect("normalize_tz validated timezone; parse should not fail");
-    // Determine lower bound start timestamp from range
-    let today_local = Utc::now().with_timezone(&tz_parsed).date_naive();
-    let start_timestamp_opt: Option<i64> = match range.as_deref() {
-        Some("5y") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(5 * 365),
-        )),
-        Some("1y") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(365),
-        )),
-        Some("6m") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(182),
-        )),
-        Some("3m") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(91),
-        )),
-        Some("1m") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(30),
-        )),
-        Some("2w") => Some(tz_midnight_utc_millis(
-            &tz_parsed,
-            today_local - Duration::days(14),
-        )),
-        Some("all") | None | Some("") => None,
-        Some(_) => None,
-    };
+    let start_timestamp_opt = range_start_timestamp(range.as_deref(), &tz_parsed);
 
     // Fetch all non-event goals and their relationships for the user
     let tree_query_str = r#"
         MATCH (g:Goal)
         WHERE g.user_id = $user_id AND g.goal_type <> 'event'
+          AND coalesce(g.is_deleted, false) <> true
         OPTIONAL MATCH (g)-[:CHILD]->(child:Goal)
         WHERE child.user_id = $user_id AND child.goal_type <> 'event'
+          AND coalesce(child.is_deleted, false) <> true
         OPTIONAL MATCH (g)-[:HAS_EVENT]->(e:Goal)
         WHERE e.goal_type = 'event'
           AND (e.is_deleted IS NULL OR e.is_deleted = false)
           AND e.scheduled_timestamp < timestamp()
           AND ($start_timestamp IS NULL OR e.scheduled_timestamp >= $start_timestamp)
-          AND COALESCE(e.resolution_status, 'pending') <> 'skipped'
+          AND NOT COALESCE(e.resolution_status, 'pending') IN ['skipped', 'cancelled']
         RETURN id(g) AS id,
                g.name AS name,
                g.goal_type AS goal_type,
@@ -753,15 +1881,22 @@ pub async fn get_goal_children_effort(
                    duration: CASE
                       WHEN e.end_timestamp IS NOT NULL AND e.end_timestamp > e.scheduled_timestamp
                         THEN toFloat(e.end_timestamp - e.scheduled_timestamp) / (1000.0*60.0)
-                      ELSE toFloat(COALESCE(e.duration_minutes, e.duration, 60))
+                      ELSE toFloat(COALESCE(e.duration_seconds / 60.0, e.duration_minutes, e.duration, $default_duration))
                     END,
-                   date: toString(date(datetime({epochMillis: e.scheduled_timestamp, timezone: $tz})))
+                   date: toString(date(datetime({epochMillis: e.scheduled_timestamp, timezone: $tz}))),
+                   moved: EXISTS {
+                       MATCH (em:EventMove)
+                       WHERE em.event_id = id(e)
+                         AND em.move_type = 'reschedule'
+                         AND em.move_timestamp <= COALESCE(e.resolved_at, timestamp())
+                   }
                }) AS events
     "#;
 
     let mut q = query(tree_query_str)
         .param("user_id", user_id)
-        .param("tz", tz_parsed.to_string());
+        .param("tz", tz_parsed.to_string())
+        .param("default_duration", default_duration as i64);
 
     if let Some(start) = start_timestamp_opt {
         q = q.param("start_timestamp", start);
@@ -769,7 +1904,9 @@ pub async fn get_goal_children_effort(
         q = q.param("start_timestamp", Option::<i64>::None);
     }
 
-    match graph.execute(q).await {
+    // The children already sit one level below the requested goal
+    let max_depth = max_traversal_depth() - 1;
+    match with_stats_timeout("goal children effort", graph.execute(q)).await? {
         Ok(mut result) => {
             let mut goals_map = HashMap::new();
             let mut root_goal_child_ids = Vec::new();
@@ -788,6 +1925,7 @@ pub async fn get_goal_children_effort(
                     if let Some(status) = ev.get("status").and_then(|v| v.as_str()) {
                         events.push(RawEventData {
                             completed: status == "completed",
+                            moved: ev.get("moved").and_then(|v| v.as_bool()).unwrap_or(false),
                             priority: ev.get("priority").and_then(|v| v.as_str()).unwrap_or("medium").to_string(),
                             duration: ev.get("duration").and_then(|v| v.as_f64()).unwrap_or(60.0),
                             date: ev.get("date").and_then(|v| v.as_str()).unwrap_or("").to_string(),
@@ -815,7 +1953,13 @@ pub async fn get_goal_children_effort(
             for child_id in root_goal_child_ids {
                 if let Some(child_raw) = goals_map.get(&child_id) {
                     let mut visited = HashSet::new();
-                    let res = calculate_recursive_stats_internal(child_id, &goals_map, &mut cache, &mut visited);
+                    let res = calculate_recursive_stats_internal(
+                        child_id,
+                        &goals_map,
+                        &mut cache,
+                        &mut visited,
+                        max_depth,
+                    );
                     
                     // Convert daily stats to Vec<DailyEffortPoint>
                     let mut daily_stats: Vec<DailyEffortPoint> = res.daily_stats
@@ -843,6 +1987,8 @@ pub async fn get_goal_children_effort(
                         total_duration_minutes: res.total_duration_minutes,
                         weighted_completion_rate: res.weighted_completion_rate,
                         children_count: res.children_count,
+                        moved_before_completion: res.moved_before_completion,
+                        truncated: res.truncated,
                         daily_stats,
                     });
                 }
@@ -868,15 +2014,25 @@ pub async fn get_extended_stats(
     user_id: i64,
     year: Option<i32>,
     tz: String,
+    monday_start: bool,
 ) -> Result<Json<ExtendedStats>, (StatusCode, String)> {
     // First get the daily stats
-    let year_stats_result = get_year_stats(graph.clone(), user_id, year, tz.clone()).await?;
+    let year_stats_result = get_year_stats(
+        graph.clone(),
+        user_id,
+        year,
+        tz.clone(),
+        false,
+        default_grace_minutes(),
+        true,
+    )
+    .await?;
     let year_stats = year_stats_result.0;
 
     // Aggregate into weekly and monthly stats
-    let weekly_stats = aggregate_weekly_stats(&year_stats.daily_stats, year_stats.year);
-    let monthly_stats = aggregate_monthly_stats(&year_stats.daily_stats, year_stats.year);
-    let yearly_stats = aggregate_yearly_stats(&year_stats.daily_stats, year_stats.year);
+    let weekly_stats = aggregate_weekly_stats(&year_stats.daily_stats, monday_start);
+    let monthly_stats = aggregate_monthly_stats(&year_stats.daily_stats);
+    let yearly_stats = aggregate_period_stats(&year_stats.daily_stats, year_stats.year.to_string());
 
     Ok(Json(ExtendedStats {
         year: year_stats.year,
@@ -887,6 +2043,85 @@ pub async fn get_extended_stats(
     }))
 }
 
+/// Score and counts for a single local day (today by default), for the dashboard. Only that
+/// day's events are queried, instead of the whole year behind `get_year_stats`.
+pub async fn get_today_score_handler(
+    graph: Graph,
+    user_id: i64,
+    date: Option<NaiveDate>,
+    tz: String,
+) -> Result<Json<DailyStats>, (StatusCode, String)> {
+    let tz = normalize_tz(&tz)?;
+    let tz_parsed: Tz = tz
+        .parse()
+        .expect("normalize_tz validated timezone; parse should not fail");
+    let date = date.unwrap_or_else(|| Utc::now().with_timezone(&tz_parsed).date_naive());
+    let start_timestamp = tz_midnight_utc_millis(&tz_parsed, date);
+    let end_timestamp = tz_midnight_utc_millis(&tz_parsed, date + Duration::days(1)) - 1;
+
+    let grace_ms = default_grace_minutes() * 60 * 1000;
+    let daily_stats = daily_stats_for_range(
+        &graph,
+        user_id,
+        start_timestamp,
+        end_timestamp,
+        &tz,
+        grace_ms,
+        true,
+    )
+    .await?;
+
+    daily_stats.into_iter().next().map(Json).ok_or((
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Failed to compute the day's stats".to_string(),
+    ))
+}
+
+/// Count the user's active goals (unresolved, not deleted, excluding events) by priority.
+pub async fn get_priority_distribution(
+    graph: Graph,
+    user_id: i64,
+) -> Result<Json<PriorityDistribution>, (StatusCode, String)> {
+    let query_str = "
+        MATCH (g:Goal)
+        WHERE g.user_id = $user_id
+        AND g.goal_type <> 'event'
+        AND coalesce(g.is_deleted, false) <> true
+        AND coalesce(g.resolution_status, 'pending') = 'pending'
+        RETURN coalesce(toLower(g.priority), 'none') as priority, count(g) as count
+    ";
+
+    let mut result = graph
+        .execute(query(query_str).param("user_id", user_id))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // Always report the standard levels so the widget has a stable shape
+    let mut by_priority: Vec<PriorityCount> = ["high", "medium", "low"]
+        .iter()
+        .map(|p| PriorityCount {
+            priority: p.to_string(),
+            count: 0,
+        })
+        .collect();
+
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        let priority: String = row.get("priority").unwrap_or_else(|_| "none".to_string());
+        let count: i64 = row.get("count").unwrap_or(0);
+        match by_priority.iter_mut().find(|p| p.priority == priority) {
+            Some(existing) => existing.count += count,
+            None => by_priority.push(PriorityCount { priority, count }),
+        }
+    }
+
+    let total = by_priority.iter().map(|p| p.count).sum();
+    Ok(Json(PriorityDistribution { total, by_priority }))
+}
+
 pub async fn search_routines(
     graph: Graph,
     user_id: i64,
@@ -901,6 +2136,7 @@ pub async fn search_routines(
         MATCH (r:Goal)
         WHERE r.user_id = $user_id 
         AND r.goal_type = 'routine'
+        AND coalesce(r.is_deleted, false) <> true
         AND (toLower(r.name) CONTAINS toLower($search_term) 
              OR toLower(COALESCE(r.description, '')) CONTAINS toLower($search_term))
         RETURN id(r) as id, r.name as name, r.description as description
@@ -955,7 +2191,10 @@ pub async fn get_routine_stats(
     routine_ids: Vec<i64>,
     year: Option<i32>,
     tz: String,
+    grace_minutes: i64,
 ) -> Result<Json<Vec<RoutineStats>>, (StatusCode, String)> {
+    let default_duration = event_settings::default_event_duration(&graph, user_id).await;
+    let grace_ms = grace_minutes * 60 * 1000;
     let target_year = year.unwrap_or_else(|| Utc::now().year());
     let tz = normalize_tz(&tz)?;
     let tz_parsed: Tz = tz
@@ -982,7 +2221,7 @@ pub async fn get_routine_stats(
             WHERE id(r) = $routine_id
             OPTIONAL MATCH (r)-[:HAS_EVENT]->(e:Goal)
             WHERE e.goal_type = 'event'
-            AND COALESCE(e.resolution_status, 'pending') <> 'skipped'
+            AND NOT COALESCE(e.resolution_status, 'pending') IN ['skipped', 'cancelled']
             RETURN r.name as routine_name,
                    count(e) as total_events,
                    collect({
@@ -1071,7 +2310,8 @@ pub async fn get_routine_stats(
         }
 
         // Main query with time filtering
-        // Only include events that have passed their scheduled time (scheduled_timestamp + duration <= current_time)
+        // Only include events that have passed their scheduled time (scheduled_timestamp + duration <= current_time);
+        // pending ones only once the grace period is over too
         let query_str = "
             MATCH (r:Goal)-[:HAS_EVENT]->(e:Goal)
             WHERE id(r) = $routine_id
@@ -1080,16 +2320,18 @@ pub async fn get_routine_stats(
             AND e.scheduled_timestamp >= $start_timestamp
             AND e.scheduled_timestamp <= $end_timestamp
             AND (e.is_deleted IS NULL OR e.is_deleted = false)
-            AND COALESCE(e.resolution_status, 'pending') <> 'skipped'
+            AND NOT COALESCE(e.resolution_status, 'pending') IN ['skipped', 'cancelled']
             WITH r, e,
-                 (e.scheduled_timestamp + COALESCE(e.duration_minutes, e.duration, 60) * 60 * 1000) as event_end_time,
+                 (e.scheduled_timestamp + COALESCE(e.duration_seconds * 1000, COALESCE(e.duration_minutes, e.duration, $default_duration) * 60 * 1000)) as event_end_time,
                  timestamp() as current_time,
                  COALESCE(e.resolution_status, 'pending') as status
-            WHERE event_end_time <= current_time
+            WHERE event_end_time <= current_time - $grace_ms
+               OR (status = 'completed' AND event_end_time <= current_time)
             WITH r, e, status,
                  datetime({epochMillis: e.scheduled_timestamp, timezone: $tz}) as dt
             ORDER BY e.scheduled_timestamp
             RETURN r.name as routine_name,
+                   r.target_completion_rate as target_completion_rate,
                    collect({
                        date: toString(date(dt)),
                        completed: CASE WHEN status = 'completed' THEN true ELSE false END
@@ -1101,13 +2343,18 @@ pub async fn get_routine_stats(
             .param("user_id", user_id)
             .param("start_timestamp", start_timestamp)
             .param("end_timestamp", end_timestamp)
-            .param("tz", tz.clone());
+            .param("tz", tz.clone())
+            .param("default_duration", default_duration as i64)
+            .param("grace_ms", grace_ms);
 
         match graph.execute(query).await {
             Ok(mut result) => {
                 if let Ok(Some(row)) = result.next().await {
                     let routine_name = row.get::<String>("routine_name").unwrap_or_default();
                     let events: Vec<serde_json::Value> = row.get("events").unwrap_or_default();
+                    let target_completion_rate = row
+                        .get::<Option<f64>>("target_completion_rate")
+                        .unwrap_or(None);
 
                     eprintln!(
                         "🔍 [ROUTINE_STATS] Found routine: {}, events in time range: {}",
@@ -1118,6 +2365,8 @@ pub async fn get_routine_stats(
                     let mut total_events = 0;
                     let mut completed_events = 0;
                     let mut daily_completion: HashMap<String, (i32, i32)> = HashMap::new();
+                    // (total, completed) indexed by days from Monday
+                    let mut weekday_counts = [(0i32, 0i32); 7];
 
                     for event in events {
                         if let (Some(date_str), Some(completed)) = (
@@ -1129,6 +2378,15 @@ pub async fn get_routine_stats(
                                 completed_events += 1;
                             }
 
+                            if let Ok(day) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+                                let entry =
+                                    &mut weekday_counts[day.weekday().num_days_from_monday() as usize];
+                                entry.0 += 1;
+                                if completed {
+                                    entry.1 += 1;
+                                }
+                            }
+
                             // Group by date for smoothing (date is already a string from Cypher)
                             let date = date_str.to_string();
 
@@ -1155,6 +2413,24 @@ pub async fn get_routine_stats(
                     let smoothed_completion =
                         create_smoothed_completion(&daily_completion, &start_date, &end_date);
 
+                    let weekday_completion = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+                        .iter()
+                        .zip(weekday_counts.iter())
+                        .map(|(weekday, (total, completed))| WeekdayCompletion {
+                            weekday: weekday.to_string(),
+                            total_events: *total,
+                            completed_events: *completed,
+                            completion_rate: if *total > 0 {
+                                *completed as f64 / *total as f64
+                            } else {
+                                0.0
+                            },
+                        })
+                        .collect();
+
+                    let (meets_target, target_delta) =
+                        target_adherence(completion_rate, target_completion_rate);
+
                     routine_stats.push(RoutineStats {
                         routine_id,
                         routine_name,
@@ -1162,6 +2438,10 @@ pub async fn get_routine_stats(
                         total_events,
                         completed_events,
                         smoothed_completion,
+                        weekday_completion,
+                        target_completion_rate,
+                        meets_target,
+                        target_delta,
                     });
                 } else {
                     eprintln!(
@@ -1192,6 +2472,7 @@ pub async fn get_rescheduling_stats(
     year: Option<i32>,
     tz: String,
 ) -> Result<Json<EventReschedulingStats>, (StatusCode, String)> {
+    let default_duration = event_settings::default_event_duration(&graph, user_id).await;
     let target_year = year.unwrap_or_else(|| Utc::now().year());
     let tz_parsed: Tz = normalize_tz(&tz)?
         .parse()
@@ -1213,7 +2494,7 @@ pub async fn get_rescheduling_stats(
         AND (g.goal_type = 'task' OR g.goal_type = 'achievement' OR g.goal_type = 'routine')
         AND (e.is_deleted IS NULL OR e.is_deleted = false)
         WITH em, e, g,
-             (e.scheduled_timestamp + COALESCE(e.duration_minutes, e.duration, 60) * 60 * 1000) as event_end_time,
+             (e.scheduled_timestamp + COALESCE(e.duration_seconds * 1000, COALESCE(e.duration_minutes, e.duration, $default_duration) * 60 * 1000)) as event_end_time,
              timestamp() as current_time
         WHERE event_end_time <= current_time
         RETURN em.event_id as event_id,
@@ -1228,7 +2509,8 @@ pub async fn get_rescheduling_stats(
     let query = query(query_str)
         .param("user_id", user_id)
         .param("start_timestamp", start_timestamp)
-        .param("end_timestamp", end_timestamp);
+        .param("end_timestamp", end_timestamp)
+        .param("default_duration", default_duration as i64);
 
     match graph.execute(query).await {
         Ok(mut result) => {
@@ -1293,7 +2575,7 @@ pub async fn get_rescheduling_stats(
                     parent_type,
                 })
                 .collect();
-            most_rescheduled_events.sort_by(|a, b| b.reschedule_count.cmp(&a.reschedule_count));
+            most_rescheduled_events.sort_by_key(|e| std::cmp::Reverse(e.reschedule_count));
             most_rescheduled_events.truncate(10);
 
             Ok(Json(EventReschedulingStats {
@@ -1313,6 +2595,172 @@ pub async fn get_rescheduling_stats(
     }
 }
 
+/// Streaks of consecutive days with at least one completed event, as (current, longest).
+/// The current streak runs back from the last day; when that day is still in progress and
+/// has nothing completed yet it doesn't break the streak.
+pub fn completion_streaks(daily_stats: &[DailyStats], last_day_in_progress: bool) -> (i32, i32) {
+    let mut longest = 0;
+    let mut run = 0;
+    for day in daily_stats {
+        run = if day.completed_events > 0 { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+
+    let mut days = daily_stats.iter().rev().peekable();
+    if last_day_in_progress {
+        days.next_if(|day| day.completed_events == 0);
+    }
+    let current = days.take_while(|day| day.completed_events > 0).count() as i32;
+    (current, longest)
+}
+
+/// One payload for a weekly review card: completion rate, the goal with the most completed
+/// events, the most rescheduled event, focused minutes and completion streaks. Everything is
+/// clamped to the seven local days from `week_start` (default: the current week, following
+/// the user's week start day) and to what has already happened.
+pub async fn get_week_review(
+    graph: Graph,
+    user_id: i64,
+    week_start: Option<NaiveDate>,
+    tz: String,
+) -> Result<Json<WeekReview>, (StatusCode, String)> {
+    let tz = normalize_tz(&tz)?;
+    let tz_parsed: Tz = tz
+        .parse()
+        .expect("normalize_tz validated timezone; parse should not fail");
+    let now = Utc::now();
+    let today = now.with_timezone(&tz_parsed).date_naive();
+
+    let week_start = match week_start {
+        Some(date) => date,
+        None => {
+            let weekday = today.weekday();
+            let offset = if event_settings::week_starts_on_monday(&graph, user_id).await {
+                weekday.num_days_from_monday()
+            } else {
+                weekday.num_days_from_sunday()
+            };
+            today - Duration::days(offset as i64)
+        }
+    };
+    if week_start > today {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "week_start must not be in the future".to_string(),
+        ));
+    }
+    let week_end = week_start + Duration::days(6);
+    let start_timestamp = tz_midnight_utc_millis(&tz_parsed, week_start);
+    let end_timestamp = (tz_midnight_utc_millis(&tz_parsed, week_end + Duration::days(1)) - 1)
+        .min(now.timestamp_millis());
+
+    let grace_ms = default_grace_minutes() * 60 * 1000;
+    let daily_stats = daily_stats_for_range(
+        &graph,
+        user_id,
+        start_timestamp,
+        end_timestamp,
+        &tz,
+        grace_ms,
+        true,
+    )
+    .await?;
+    let totals = aggregate_period_stats(&daily_stats, format!("{}..{}", week_start, week_end));
+    let (current_streak_days, longest_streak_days) =
+        completion_streaks(&daily_stats, week_end >= today);
+
+    let default_duration = event_settings::default_event_duration(&graph, user_id).await;
+    let completed_query = query(
+        "MATCH (g:Goal)-[:HAS_EVENT]->(e:Goal)
+         WHERE g.user_id = $user_id
+         AND e.goal_type = 'event'
+         AND (e.is_deleted IS NULL OR e.is_deleted = false)
+         AND e.resolution_status = 'completed'
+         AND e.scheduled_timestamp >= $start_timestamp
+         AND e.scheduled_timestamp <= $end_timestamp
+         WITH g, count(e) as completed,
+              sum(COALESCE(e.duration_seconds / 60.0, e.duration_minutes, e.duration, $default_duration)) as minutes
+         RETURN id(g) as goal_id, g.name as goal_name, completed, minutes
+         ORDER BY completed DESC, goal_name ASC",
+    )
+    .param("user_id", user_id)
+    .param("start_timestamp", start_timestamp)
+    .param("end_timestamp", end_timestamp)
+    .param("default_duration", default_duration as i64);
+
+    let mut result = graph.execute(completed_query).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch completed events: {}", e),
+        )
+    })?;
+
+    let mut most_completed_goal = None;
+    let mut focused_minutes = 0.0;
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        focused_minutes += row.get::<f64>("minutes").unwrap_or(0.0);
+        if most_completed_goal.is_none() {
+            most_completed_goal = Some(GoalCompletionCount {
+                goal_id: row.get("goal_id").unwrap_or(0),
+                goal_name: row.get("goal_name").unwrap_or_default(),
+                completed_events: row.get::<i64>("completed").unwrap_or(0) as i32,
+            });
+        }
+    }
+
+    let rescheduled_query = query(
+        "MATCH (em:EventMove)
+         WHERE em.user_id = $user_id
+         AND em.move_type = 'reschedule'
+         AND em.move_timestamp >= $start_timestamp
+         AND em.move_timestamp <= $end_timestamp
+         MATCH (e:Goal)
+         WHERE id(e) = em.event_id
+         AND e.user_id = $user_id
+         AND (e.is_deleted IS NULL OR e.is_deleted = false)
+         WITH e, count(em) as moves
+         RETURN id(e) as event_id, e.name as event_name, moves
+         ORDER BY moves DESC, event_name ASC
+         LIMIT 1",
+    )
+    .param("user_id", user_id)
+    .param("start_timestamp", start_timestamp)
+    .param("end_timestamp", end_timestamp);
+
+    let mut result = graph.execute(rescheduled_query).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch rescheduled events: {}", e),
+        )
+    })?;
+    let most_rescheduled_event = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(|row| EventRescheduleCount {
+            event_id: row.get("event_id").unwrap_or(0),
+            event_name: row.get("event_name").unwrap_or_default(),
+            reschedule_count: row.get::<i64>("moves").unwrap_or(0) as i32,
+        });
+
+    Ok(Json(WeekReview {
+        week_start: week_start.format("%Y-%m-%d").to_string(),
+        week_end: week_end.format("%Y-%m-%d").to_string(),
+        completion_rate: totals.completion_rate,
+        total_events: totals.total_events,
+        completed_events: totals.completed_events,
+        most_completed_goal,
+        most_rescheduled_event,
+        focused_minutes,
+        current_streak_days,
+        longest_streak_days,
+    }))
+}
+
 pub async fn record_event_move(
     graph: Graph,
     event_move: EventMove,
@@ -1357,14 +2805,107 @@ pub async fn record_event_move(
     }
 }
 
+#[derive(Debug, Serialize)]
+pub struct EventMoveHistory {
+    pub event_id: i64,
+    pub event_name: String,
+    pub moves: Vec<EventMove>, // oldest first
+}
+
+/// Every recorded move of one event, oldest first, so the UI can show how it drifted.
+/// Soft-deleted events keep their history; another user's event is reported as not found.
+pub async fn get_event_move_history_handler(
+    graph: Graph,
+    user_id: i64,
+    event_id: i64,
+) -> Result<Json<EventMoveHistory>, (StatusCode, String)> {
+    let query = query(
+        "MATCH (e:Goal)
+         WHERE id(e) = $event_id
+         AND e.goal_type = 'event'
+         AND e.user_id = $user_id
+         OPTIONAL MATCH (em:EventMove)
+         WHERE em.event_id = $event_id
+         AND em.user_id = $user_id
+         RETURN e.name as event_name,
+                id(em) as id,
+                em.old_timestamp as old_timestamp,
+                em.new_timestamp as new_timestamp,
+                em.move_type as move_type,
+                em.move_timestamp as move_timestamp,
+                em.reason as reason
+         ORDER BY em.move_timestamp ASC",
+    )
+    .param("event_id", event_id)
+    .param("user_id", user_id);
+
+    let mut result = graph.execute(query).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch event moves: {}", e),
+        )
+    })?;
+
+    let mut event_name = None;
+    let mut moves = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        event_name.get_or_insert_with(|| row.get::<String>("event_name").unwrap_or_default());
+        // A single row with no move fields means the event has never been moved
+        let Ok(id) = row.get::<i64>("id") else {
+            continue;
+        };
+        moves.push(EventMove {
+            id: Some(id),
+            event_id,
+            user_id,
+            old_timestamp: row.get("old_timestamp").unwrap_or(0),
+            new_timestamp: row.get("new_timestamp").unwrap_or(0),
+            move_type: row.get("move_type").unwrap_or_default(),
+            move_timestamp: row.get("move_timestamp").unwrap_or(0),
+            reason: row.get::<String>("reason").ok(),
+        });
+    }
+
+    let event_name = event_name.ok_or((StatusCode::NOT_FOUND, "Event not found".to_string()))?;
+    Ok(Json(EventMoveHistory {
+        event_id,
+        event_name,
+        moves,
+    }))
+}
+
 // Helper functions
 
-fn aggregate_weekly_stats(daily_stats: &[DailyStats], _year: i32) -> Vec<PeriodStats> {
+/// Which weekday a stats week starts on: `"monday"` (ISO weeks, the default) or `"sunday"`.
+/// Returns true for Monday.
+pub fn parse_week_start(raw: Option<&str>) -> Result<bool, String> {
+    match raw.map(|s| s.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("monday") => Ok(true),
+        Some("sunday") => Ok(false),
+        Some(other) => Err(format!(
+            "Invalid week_start '{}'; expected 'monday' or 'sunday'",
+            other
+        )),
+    }
+}
+
+/// Roll daily stats up into weeks. Monday weeks are ISO weeks; Sunday weeks carry the
+/// label of the ISO week their Monday falls in, so a Sunday counts toward the week after.
+pub fn aggregate_weekly_stats(daily_stats: &[DailyStats], monday_start: bool) -> Vec<PeriodStats> {
     let mut weekly_stats = HashMap::new();
 
     for day_stat in daily_stats {
         if let Ok(date) = NaiveDate::parse_from_str(&day_stat.date, "%Y-%m-%d") {
-            let iso_week = date.iso_week();
+            let label_date = if !monday_start && date.weekday() == chrono::Weekday::Sun {
+                date + Duration::days(1)
+            } else {
+                date
+            };
+            let iso_week = label_date.iso_week();
             let week_key = format!("{}-W{:02}", iso_week.year(), iso_week.week());
 
             let entry = weekly_stats.entry(week_key.clone()).or_insert(PeriodStats {
@@ -1406,7 +2947,7 @@ fn aggregate_weekly_stats(daily_stats: &[DailyStats], _year: i32) -> Vec<PeriodS
     result
 }
 
-fn aggregate_monthly_stats(daily_stats: &[DailyStats], _year: i32) -> Vec<PeriodStats> {
+pub fn aggregate_monthly_stats(daily_stats: &[DailyStats]) -> Vec<PeriodStats> {
     let mut monthly_stats = HashMap::new();
 
     for day_stat in daily_stats {
@@ -1454,9 +2995,10 @@ fn aggregate_monthly_stats(daily_stats: &[DailyStats], _year: i32) -> Vec<Period
     result
 }
 
-fn aggregate_yearly_stats(daily_stats: &[DailyStats], year: i32) -> PeriodStats {
+/// Roll every day in `daily_stats` into a single period labelled `period`.
+pub fn aggregate_period_stats(daily_stats: &[DailyStats], period: String) -> PeriodStats {
     let mut yearly_stat = PeriodStats {
-        period: year.to_string(),
+        period,
         completion_rate: 0.0,
         total_events: 0,
         completed_events: 0,
@@ -1538,6 +3080,7 @@ pub async fn get_event_analytics(
     year: Option<i32>,
     tz: String,
 ) -> Result<Json<EventAnalytics>, (StatusCode, String)> {
+    let default_duration = event_settings::default_event_duration(&graph, user_id).await;
     let target_year = year.unwrap_or_else(|| Utc::now().year());
     let tz_parsed: Tz = normalize_tz(&tz)?
         .parse()
@@ -1558,13 +3101,14 @@ pub async fn get_event_analytics(
         AND (e.is_deleted IS NULL OR e.is_deleted = false)
         AND COALESCE(e.resolution_status, 'pending') <> 'skipped'
         WITH e, g,
-             (e.scheduled_timestamp + COALESCE(e.duration_minutes, e.duration, 60) * 60 * 1000) as event_end_time,
+             (e.scheduled_timestamp + COALESCE(e.duration_seconds * 1000, COALESCE(e.duration_minutes, e.duration, $default_duration) * 60 * 1000)) as event_end_time,
              timestamp() as current_time,
              COALESCE(e.resolution_status, 'pending') as status
         WHERE event_end_time <= current_time
         RETURN e.scheduled_timestamp as scheduled_timestamp,
-               COALESCE(e.end_timestamp, e.scheduled_timestamp + COALESCE(e.duration_minutes, 60) * 60 * 1000) as end_timestamp,
-               COALESCE(e.duration_minutes, 60) as duration_minutes,
+               status = 'cancelled' as cancelled,
+               COALESCE(e.end_timestamp, e.scheduled_timestamp + COALESCE(e.duration_seconds * 1000, COALESCE(e.duration_minutes, $default_duration) * 60 * 1000)) as end_timestamp,
+               COALESCE(e.duration_minutes, $default_duration) as duration_minutes,
                CASE WHEN status = 'completed' THEN true ELSE false END as completed,
                COALESCE(e.priority, g.priority, 'medium') as priority,
                g.goal_type as parent_type,
@@ -1574,16 +3118,23 @@ pub async fn get_event_analytics(
     let query = query(query_str)
         .param("user_id", user_id)
         .param("start_timestamp", start_timestamp)
-        .param("end_timestamp", end_timestamp);
+        .param("end_timestamp", end_timestamp)
+        .param("default_duration", default_duration as i64);
 
     match graph.execute(query).await {
         Ok(mut result) => {
             let mut events = Vec::new();
+            let mut cancelled_events = 0;
 
             while let Ok(Some(row)) = result.next().await {
+                // Cancelled events only feed the cancellation rate, not completion rates
+                if row.get::<bool>("cancelled").unwrap_or(false) {
+                    cancelled_events += 1;
+                    continue;
+                }
                 let scheduled_timestamp = row.get::<i64>("scheduled_timestamp").unwrap_or(0);
                 let end_timestamp = row.get::<i64>("end_timestamp").unwrap_or(0);
-                let duration_minutes = row.get::<i64>("duration_minutes").unwrap_or(60);
+                let duration_minutes = row.get::<i64>("duration_minutes").unwrap_or(default_duration as i64);
                 let completed = row.get::<bool>("completed").unwrap_or(false);
                 let priority = row
                     .get::<String>("priority")
@@ -1606,11 +3157,14 @@ pub async fn get_event_analytics(
             let duration_stats = calculate_duration_stats(&events);
             let priority_stats = calculate_priority_stats(&events);
             let source_stats = calculate_source_stats(&events);
+            let cancellation_stats =
+                calculate_cancellation_stats(events.len() as i32, cancelled_events);
 
             Ok(Json(EventAnalytics {
                 duration_stats,
                 priority_stats,
                 source_stats,
+                cancellation_stats,
             }))
         }
         Err(e) => {
@@ -1623,6 +3177,19 @@ pub async fn get_event_analytics(
     }
 }
 
+pub fn calculate_cancellation_stats(other_events: i32, cancelled_events: i32) -> CancellationStats {
+    let total_events = other_events + cancelled_events;
+    CancellationStats {
+        total_events,
+        cancelled_events,
+        cancellation_rate: if total_events > 0 {
+            cancelled_events as f64 / total_events as f64
+        } else {
+            0.0
+        },
+    }
+}
+
 fn calculate_duration_stats(events: &[(f64, bool, String, String)]) -> Vec<DurationStats> {
     let mut duration_buckets: HashMap<String, (i32, i32, f64)> = HashMap::new();
 
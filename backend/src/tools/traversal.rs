@@ -1,15 +1,22 @@
 use crate::tools::goal::Goal;
 use crate::tools::goal::GoalType;
+use crate::tools::goal::GOAL_RETURN_QUERY;
 use axum::{http::StatusCode, Json};
 use neo4rs::{query, Graph};
+use serde::Serialize;
 
-pub async fn query_hierarchy(graph: Graph, goal_id: i64) -> Result<Vec<Goal>, neo4rs::Error> {
+pub async fn query_hierarchy(
+    graph: Graph,
+    user_id: i64,
+    goal_id: i64,
+) -> Result<Vec<Goal>, neo4rs::Error> {
     let query = query(
         "MATCH (g:Goal)-[*]-(related:Goal) \
-         WHERE id(g) = $goal_id \
+         WHERE id(g) = $goal_id AND g.user_id = $user_id AND related.user_id = $user_id \
          RETURN DISTINCT related.name AS name, related.goal_type AS goal_type, id(related) AS id",
     )
-    .param("goal_id", goal_id);
+    .param("goal_id", goal_id)
+    .param("user_id", user_id);
 
     let mut result = graph.execute(query).await?;
     let mut hierarchy = Vec::new();
@@ -67,6 +74,27 @@ pub async fn query_hierarchy(graph: Graph, goal_id: i64) -> Result<Vec<Goal>, ne
             gcal_sync_direction: None,
             is_gcal_imported: None,
             updated_at: None,
+            version: None,
+            energy_required: None,
+            gcal_sync_status: None,
+            gcal_last_sync_attempt: None,
+            gcal_sync_error: None,
+            target_value: None,
+            current_value: None,
+            is_deadline: None,
+            skip_holidays: None,
+            reset_period: None,
+            last_reset_at: None,
+            notes: None,
+            color: None,
+            duration_seconds: None,
+            recurrence: None,
+            recurrence_source_id: None,
+            links: None,
+            allow_overlap: None,
+            routine_times: None,
+            last_modified: None,
+            target_completion_rate: None,
         });
     }
 
@@ -153,6 +181,27 @@ pub async fn query_parent_hierarchy(
             gcal_sync_direction: None,
             is_gcal_imported: None,
             updated_at: None,
+            version: None,
+            energy_required: None,
+            gcal_sync_status: None,
+            gcal_last_sync_attempt: None,
+            gcal_sync_error: None,
+            target_value: None,
+            current_value: None,
+            is_deadline: None,
+            skip_holidays: None,
+            reset_period: None,
+            last_reset_at: None,
+            notes: None,
+            color: None,
+            duration_seconds: None,
+            recurrence: None,
+            recurrence_source_id: None,
+            links: None,
+            allow_overlap: None,
+            routine_times: None,
+            last_modified: None,
+            target_completion_rate: None,
         });
     }
 
@@ -161,9 +210,10 @@ pub async fn query_parent_hierarchy(
 
 pub async fn query_hierarchy_handler(
     graph: Graph,
+    user_id: i64,
     goal_id: i64,
 ) -> Result<Json<Vec<Goal>>, (StatusCode, String)> {
-    match query_hierarchy(graph, goal_id).await {
+    match query_hierarchy(graph, user_id, goal_id).await {
         Ok(hierarchy) => Ok(Json(hierarchy)),
         Err(e) => {
             eprintln!("Error querying hierarchy: {}", e);
@@ -174,3 +224,156 @@ pub async fn query_hierarchy_handler(
         }
     }
 }
+
+const DEFAULT_TREE_DEPTH: i32 = 10;
+const MAX_TREE_DEPTH: i32 = 25;
+
+#[derive(Debug, Serialize)]
+pub struct GoalTreeEdge {
+    pub from: i64,
+    pub to: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalTreeResponse {
+    pub goal: Goal,
+    pub ancestors: Vec<Goal>,
+    pub descendants: Vec<Goal>,
+    pub edges: Vec<GoalTreeEdge>,
+    pub max_depth: i32,
+}
+
+/// Fetch every ancestor (up to the roots) and descendant (down to the leaves) of a goal,
+/// along with the CHILD edges connecting them. Each path must consist entirely of the
+/// requesting user's goals, so a stray edge can't pull in someone else's nodes.
+pub async fn get_goal_tree(
+    graph: Graph,
+    user_id: i64,
+    goal_id: i64,
+    max_depth: Option<i32>,
+) -> Result<Json<GoalTreeResponse>, (StatusCode, String)> {
+    let depth = max_depth
+        .unwrap_or(DEFAULT_TREE_DEPTH)
+        .clamp(1, MAX_TREE_DEPTH);
+
+    let center_query = query(&format!(
+        "MATCH (g:Goal)
+         WHERE id(g) = $goal_id AND g.user_id = $user_id
+         {}",
+        GOAL_RETURN_QUERY
+    ))
+    .param("goal_id", goal_id)
+    .param("user_id", user_id);
+
+    let mut center_result = graph
+        .execute(center_query)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let goal: Goal = center_result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("Goal with id {} not found", goal_id),
+        ))?
+        .get("g")
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let ancestors_pattern = format!("(:Goal)-[:CHILD*1..{}]->(center:Goal)", depth);
+    let descendants_pattern = format!("(center:Goal)-[:CHILD*1..{}]->(:Goal)", depth);
+
+    let ancestors = fetch_tree_nodes(&graph, user_id, goal_id, &ancestors_pattern).await?;
+    let descendants = fetch_tree_nodes(&graph, user_id, goal_id, &descendants_pattern).await?;
+
+    let mut edges = fetch_tree_edges(&graph, user_id, goal_id, &ancestors_pattern).await?;
+    edges.extend(fetch_tree_edges(&graph, user_id, goal_id, &descendants_pattern).await?);
+
+    Ok(Json(GoalTreeResponse {
+        goal,
+        ancestors,
+        descendants,
+        edges,
+        max_depth: depth,
+    }))
+}
+
+async fn fetch_tree_nodes(
+    graph: &Graph,
+    user_id: i64,
+    goal_id: i64,
+    path_pattern: &str,
+) -> Result<Vec<Goal>, (StatusCode, String)> {
+    let query_str = format!(
+        "MATCH path = {}
+         WHERE id(center) = $goal_id
+           AND all(n IN nodes(path) WHERE n.user_id = $user_id AND n.goal_type <> 'event')
+         UNWIND nodes(path) as g
+         WITH DISTINCT g
+         WHERE id(g) <> $goal_id
+         {}",
+        path_pattern, GOAL_RETURN_QUERY
+    );
+
+    let mut result = graph
+        .execute(
+            query(&query_str)
+                .param("goal_id", goal_id)
+                .param("user_id", user_id),
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut nodes = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        if let Ok(goal) = row.get::<Goal>("g") {
+            nodes.push(goal);
+        }
+    }
+
+    Ok(nodes)
+}
+
+async fn fetch_tree_edges(
+    graph: &Graph,
+    user_id: i64,
+    goal_id: i64,
+    path_pattern: &str,
+) -> Result<Vec<GoalTreeEdge>, (StatusCode, String)> {
+    let query_str = format!(
+        "MATCH path = {}
+         WHERE id(center) = $goal_id
+           AND all(n IN nodes(path) WHERE n.user_id = $user_id AND n.goal_type <> 'event')
+         UNWIND relationships(path) as r
+         WITH DISTINCT r
+         RETURN id(startNode(r)) as from_id, id(endNode(r)) as to_id",
+        path_pattern
+    );
+
+    let mut result = graph
+        .execute(
+            query(&query_str)
+                .param("goal_id", goal_id)
+                .param("user_id", user_id),
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let mut edges = Vec::new();
+    while let Some(row) = result
+        .next()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    {
+        if let (Ok(from), Ok(to)) = (row.get::<i64>("from_id"), row.get::<i64>("to_id")) {
+            edges.push(GoalTreeEdge { from, to });
+        }
+    }
+
+    Ok(edges)
+}
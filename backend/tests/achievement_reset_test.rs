@@ -0,0 +1,36 @@
+use chrono::{TimeZone, Utc};
+
+use backend::tools::achievements::reset_period_start;
+
+#[test]
+fn test_reset_period_start() {
+    let now = Utc.with_ymd_and_hms(2024, 7, 15, 13, 45, 0).unwrap();
+
+    let yearly = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        reset_period_start("yearly", now),
+        Some(yearly.timestamp_millis())
+    );
+
+    let monthly = Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        reset_period_start("monthly", now),
+        Some(monthly.timestamp_millis())
+    );
+
+    assert_eq!(reset_period_start("weekly", now), None);
+}
+
+#[test]
+fn test_reset_period_start_on_boundary() {
+    // The first instant of a period belongs to that period
+    let boundary = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        reset_period_start("yearly", boundary),
+        Some(boundary.timestamp_millis())
+    );
+    assert_eq!(
+        reset_period_start("monthly", boundary),
+        Some(boundary.timestamp_millis())
+    );
+}
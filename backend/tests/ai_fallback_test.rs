@@ -0,0 +1,83 @@
+use backend::ai::fallback::match_intent;
+use backend::ai::query::{offline_fallback_call, Message};
+use backend::ai::tool_registry::get_tools;
+use serde_json::json;
+
+fn message(role: &str, content: &str) -> Message {
+    Message {
+        role: role.to_string(),
+        content: content.to_string(),
+    }
+}
+
+#[test]
+fn list_commands_map_to_list_data() {
+    for query in ["list goals", "Show my goals", "  what are my goals? "] {
+        let call = match_intent(query).expect("should match a list command");
+        assert_eq!(call.tool_name, "get_list_data");
+    }
+}
+
+#[test]
+fn create_commands_keep_the_name_as_typed() {
+    let call = match_intent("Create goal Learn Rust").expect("should match a create command");
+    assert_eq!(call.tool_name, "create_goal");
+    assert_eq!(
+        call.args,
+        json!({ "goal": { "name": "Learn Rust", "goal_type": "task" } })
+    );
+
+    let call = match_intent("add task \"Call mom\"").unwrap();
+    assert_eq!(call.args["goal"]["name"], "Call mom");
+}
+
+#[test]
+fn anything_else_needs_the_model() {
+    for query in [
+        "create goal",
+        "what should I do today?",
+        "delete goal 4",
+        "",
+    ] {
+        assert!(
+            match_intent(query).is_none(),
+            "{:?} should not match",
+            query
+        );
+    }
+}
+
+#[test]
+fn the_latest_user_message_picks_the_command() {
+    let history = vec![
+        message("user", "create goal Old"),
+        message("model", "Done"),
+        message("user", "list my goals"),
+        message("model", "list goals"),
+    ];
+    let call = offline_fallback_call(&history).expect("latest user message is a list command");
+    assert_eq!(call.tool_name, "get_list_data");
+
+    let history = vec![
+        message("user", "list goals"),
+        message("user", "plan my week"),
+    ];
+    assert_eq!(offline_fallback_call(&history), None);
+}
+
+#[test]
+fn fallback_commands_use_registered_tools() {
+    let registered: Vec<String> = get_tools()
+        .into_iter()
+        .flat_map(|tool| tool.function_declarations)
+        .map(|declaration| declaration.name)
+        .collect();
+    for query in ["list goals", "create goal Learn Rust"] {
+        let call = match_intent(query).unwrap();
+        assert!(
+            registered.iter().any(|name| name == call.tool_name),
+            "{} is not a registered tool",
+            call.tool_name
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use backend::ai::generation::{GenerationOverrides, GenerationSettings};
+
+fn env_from(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn settings_from(pairs: &[(&str, &str)]) -> GenerationSettings {
+    let env = env_from(pairs);
+    GenerationSettings::from_lookup(|name| env.get(name).cloned())
+}
+
+fn overrides_from(pairs: &[(&str, &str)]) -> GenerationOverrides {
+    let env = env_from(pairs);
+    GenerationOverrides::from_lookup(|name| env.get(name).cloned())
+}
+
+#[test]
+fn test_generation_settings_defaults() {
+    assert_eq!(settings_from(&[]), GenerationSettings::default());
+}
+
+#[test]
+fn test_generation_settings_overrides() {
+    let settings = settings_from(&[
+        ("GOALS_AI_TEMPERATURE", "0.2"),
+        ("GOALS_AI_MAX_OUTPUT_TOKENS", "8192"),
+    ]);
+
+    assert_eq!(settings.temperature, 0.2);
+    assert_eq!(settings.max_output_tokens, 8192);
+    assert_eq!(settings.top_p, GenerationSettings::default().top_p);
+}
+
+#[test]
+fn test_generation_settings_ignore_invalid_values() {
+    let settings = settings_from(&[
+        ("GOALS_AI_TEMPERATURE", "hot"),
+        ("GOALS_AI_TOP_P", "1.5"),
+        ("GOALS_AI_MAX_OUTPUT_TOKENS", "0"),
+    ]);
+
+    assert_eq!(settings, GenerationSettings::default());
+}
+
+#[test]
+fn test_generation_overrides_only_include_explicit_values() {
+    assert_eq!(overrides_from(&[]), GenerationOverrides::default());
+
+    let overrides = overrides_from(&[("GOALS_AI_TEMPERATURE", "0.2"), ("GOALS_AI_TOP_K", "0")]);
+    assert_eq!(overrides.temperature, Some(0.2));
+    assert_eq!(overrides.top_k, None);
+    assert_eq!(overrides.top_p, None);
+    assert_eq!(overrides.max_output_tokens, None);
+}
@@ -0,0 +1,38 @@
+use axum::http::{header, HeaderMap, HeaderValue};
+
+use backend::ai::messages::{message, Locale, MessageKey};
+
+#[test]
+fn test_locale_takes_first_supported_language() {
+    assert_eq!(
+        Locale::from_accept_language("fr-CA,fr;q=0.9,en;q=0.8"),
+        Locale::Fr
+    );
+    assert_eq!(Locale::from_accept_language("ja,de;q=0.8"), Locale::De);
+    assert_eq!(Locale::from_accept_language("es_MX"), Locale::Es);
+    assert_eq!(Locale::from_accept_language("ja, zh"), Locale::En);
+    assert_eq!(Locale::from_accept_language(""), Locale::En);
+}
+
+#[test]
+fn test_locale_from_headers_defaults_to_english() {
+    assert_eq!(Locale::from_headers(&HeaderMap::new()), Locale::En);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("de-DE"));
+    assert_eq!(Locale::from_headers(&headers), Locale::De);
+}
+
+#[test]
+fn test_messages_are_translated() {
+    let english = message(Locale::En, MessageKey::ProcessingFailed);
+    for locale in [Locale::Es, Locale::Fr, Locale::De] {
+        let translated = message(locale, MessageKey::ProcessingFailed);
+        assert!(!translated.is_empty());
+        assert_ne!(
+            translated, english,
+            "{:?} should not fall back to English",
+            locale
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use serde_json::json;
+
+use axum::http::{header, StatusCode};
+
+use backend::ai::messages::{self, Locale, MessageKey};
+use backend::ai::query::{
+    busy_response, gemini_max_concurrent, validate_user_query, WsQueryMessage,
+    DEFAULT_GEMINI_MAX_CONCURRENT, GEMINI_BUSY_RETRY_AFTER_SECS, MAX_HISTORY_MESSAGES,
+    MAX_QUERY_CHARS, MAX_WS_MESSAGE_BYTES,
+};
+
+#[test]
+fn test_user_query_debug_defaults_to_off() {
+    let msg: WsQueryMessage = serde_json::from_value(
+        json!({"type": "UserQuery", "content": "hi", "conversation_id": null}),
+    )
+    .unwrap();
+    assert!(matches!(
+        msg,
+        WsQueryMessage::UserQuery { debug: false, .. }
+    ));
+
+    let msg: WsQueryMessage = serde_json::from_value(
+        json!({"type": "UserQuery", "content": "hi", "conversation_id": null, "debug": true}),
+    )
+    .unwrap();
+    assert!(matches!(msg, WsQueryMessage::UserQuery { debug: true, .. }));
+}
+
+#[test]
+fn test_tool_trace_reports_the_call_and_result() {
+    let args = json!({"goal_id": 3});
+    let result = json!({"success": true, "data": "ok"});
+    let trace = WsQueryMessage::tool_trace("get_goal", &args, result.clone(), false);
+
+    assert_eq!(
+        serde_json::to_value(&trace).unwrap(),
+        json!({
+            "type": "DebugTrace",
+            "tool_name": "get_goal",
+            "args": args,
+            "tool_result": result,
+            "fallback_used": false,
+        })
+    );
+}
+
+#[test]
+fn test_empty_response_trace_marks_the_fallback() {
+    assert_eq!(
+        serde_json::to_value(WsQueryMessage::empty_response_trace()).unwrap(),
+        json!({
+            "type": "DebugTrace",
+            "tool_name": null,
+            "args": null,
+            "tool_result": null,
+            "fallback_used": true,
+        })
+    );
+}
+
+#[test]
+fn test_query_length_limit_counts_characters() {
+    let at_limit = "a".repeat(MAX_QUERY_CHARS);
+    assert_eq!(validate_user_query(&at_limit, 0), Ok(()));
+
+    let over_limit = "a".repeat(MAX_QUERY_CHARS + 1);
+    assert_eq!(
+        validate_user_query(&over_limit, 0),
+        Err(MessageKey::QueryTooLong)
+    );
+
+    // Multi-byte characters count once each, not per byte
+    let accented = "é".repeat(MAX_QUERY_CHARS);
+    assert_eq!(validate_user_query(&accented, 0), Ok(()));
+}
+
+#[test]
+fn test_history_limit() {
+    assert_eq!(validate_user_query("hi", MAX_HISTORY_MESSAGES - 1), Ok(()));
+    assert_eq!(
+        validate_user_query("hi", MAX_HISTORY_MESSAGES),
+        Err(MessageKey::HistoryTooLong)
+    );
+}
+
+#[test]
+fn test_longest_allowed_query_fits_in_one_ws_message() {
+    // Otherwise the websocket layer would drop queries that validation accepts
+    let msg = WsQueryMessage::UserQuery {
+        content: "\u{1F600}".repeat(MAX_QUERY_CHARS),
+        conversation_id: Some(uuid::Uuid::new_v4().to_string()),
+        debug: true,
+    };
+    assert!(serde_json::to_string(&msg).unwrap().len() <= MAX_WS_MESSAGE_BYTES);
+}
+
+fn max_concurrent_from(value: Option<&str>) -> usize {
+    gemini_max_concurrent(|name| {
+        assert_eq!(name, "GOALS_GEMINI_MAX_CONCURRENT");
+        value.map(str::to_string)
+    })
+}
+
+#[test]
+fn test_gemini_max_concurrent() {
+    assert_eq!(max_concurrent_from(None), DEFAULT_GEMINI_MAX_CONCURRENT);
+    assert_eq!(max_concurrent_from(Some("4")), 4);
+    assert_eq!(
+        max_concurrent_from(Some("lots")),
+        DEFAULT_GEMINI_MAX_CONCURRENT
+    );
+    // A zero-sized pool would reject every query
+    assert_eq!(max_concurrent_from(Some("0")), 1);
+}
+
+#[tokio::test]
+async fn test_busy_response_asks_the_client_to_retry() {
+    let response = busy_response(Locale::Es);
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        response.headers()[header::RETRY_AFTER],
+        GEMINI_BUSY_RETRY_AFTER_SECS.to_string()
+    );
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, messages::message(Locale::Es, MessageKey::Busy));
+}
@@ -0,0 +1,48 @@
+use serde_json::json;
+
+use backend::ai::tool_result::format_tool_result;
+
+#[test]
+fn test_day_tasks_result_is_readable() {
+    // Shape returned by get_day_tasks
+    let events = json!([
+        {
+            "id": 12,
+            "name": "Write report",
+            "goal_type": "event",
+            "priority": "high",
+            "resolution_status": "completed",
+            "scheduled_timestamp": 1_709_802_000_000_i64, // 2024-03-07 09:00 UTC
+            "duration": 45,
+            "parent_id": 3,
+            "parent_goal_type": "task"
+        },
+        {
+            "id": 13,
+            "name": "Stretch",
+            "goal_type": "event",
+            "resolution_status": "pending",
+            "scheduled_timestamp": 1_709_830_800_000_i64, // 17:00 UTC
+            "duration": null,
+            "parent_id": 4,
+            "parent_goal_type": "routine"
+        }
+    ]);
+
+    assert_eq!(
+        format_tool_result("get_day_tasks", &events),
+        "- 09:00 UTC Write report (id 12, 45 min, completed)\n\
+         - 17:00 UTC Stretch (id 13, pending)"
+    );
+    assert_eq!(
+        format_tool_result("get_day_tasks", &json!([])),
+        "No events scheduled."
+    );
+}
+
+#[test]
+fn test_other_results_keep_real_field_names() {
+    let data = json!({"nodes": [{"name": "Learn Rust", "goal_type": "directive"}]});
+    let text = format_tool_result("get_network_data", &data);
+    assert!(text.contains("\"name\":\"Learn Rust\""));
+}
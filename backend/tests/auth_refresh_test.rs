@@ -0,0 +1,140 @@
+mod common;
+
+use axum::http::StatusCode;
+use neo4rs::{query, Graph};
+use std::env;
+
+use backend::server::auth::{issue_refresh_token, refresh_token_handler, revoke_refresh_token};
+use common::unreachable_graph;
+
+async fn create_test_graph() -> Graph {
+    let uri = env::var("NEO4J_TEST_URI").unwrap_or_else(|_| "bolt://localhost:7688".to_string());
+    let username = env::var("NEO4J_TEST_USERNAME").unwrap_or_else(|_| "neo4j".to_string());
+    let password = env::var("NEO4J_TEST_PASSWORD").unwrap_or_else(|_| "password123".to_string());
+
+    let config = neo4rs::ConfigBuilder::default()
+        .uri(&uri)
+        .user(&username)
+        .password(&password)
+        .build()
+        .unwrap();
+
+    Graph::connect(config)
+        .await
+        .expect("Failed to create test database connection")
+}
+
+async fn create_user(graph: &Graph, username: &str) -> i64 {
+    let mut result = graph
+        .execute(
+            query("CREATE (u:User {username: $username}) RETURN id(u) as id")
+                .param("username", username),
+        )
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("id").unwrap()
+}
+
+async fn delete_user(graph: &Graph, user_id: i64) {
+    graph
+        .run(
+            query("MATCH (u:User) WHERE id(u) = $user_id DETACH DELETE u")
+                .param("user_id", user_id),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_refresh_rejects_malformed_token() {
+    for token in ["", "not-a-token", "abc.def"] {
+        let graph = unreachable_graph().await;
+        let result = refresh_token_handler(graph, token.to_string()).await;
+
+        let (status, _) = result.expect_err("malformed refresh token should be rejected");
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_refresh_rotates_the_token() {
+    let graph = create_test_graph().await;
+    let user_id = create_user(&graph, "refresh_rotation_user").await;
+
+    let original = issue_refresh_token(&graph, user_id).await.unwrap();
+    let refreshed = refresh_token_handler(graph.clone(), original.clone())
+        .await
+        .expect("a freshly issued token should refresh");
+    let rotated = refreshed
+        .0
+        .refresh_token
+        .clone()
+        .expect("a new refresh token");
+    assert_ne!(rotated, original);
+    assert!(!refreshed.0.token.is_empty());
+
+    // The presented token is consumed, so replaying it fails
+    let (status, _) = refresh_token_handler(graph.clone(), original)
+        .await
+        .expect_err("a consumed token should not refresh again");
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let again = refresh_token_handler(graph.clone(), rotated)
+        .await
+        .expect("the rotated token should refresh");
+    assert!(again.0.refresh_token.is_some());
+
+    delete_user(&graph, user_id).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_refresh_rejects_expired_token() {
+    let graph = create_test_graph().await;
+    let user_id = create_user(&graph, "refresh_expiry_user").await;
+
+    let token = issue_refresh_token(&graph, user_id).await.unwrap();
+    // Backdate the stored expiry instead of waiting out the configured lifetime
+    graph
+        .run(
+            query(
+                "MATCH (u:User) WHERE id(u) = $user_id
+                 SET u.refresh_tokens = [t IN u.refresh_tokens | split(t, ':')[0] + ':1']",
+            )
+            .param("user_id", user_id),
+        )
+        .await
+        .unwrap();
+
+    let (status, _) = refresh_token_handler(graph.clone(), token)
+        .await
+        .expect_err("an expired token should be rejected");
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    delete_user(&graph, user_id).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_revoked_token_cannot_refresh() {
+    let graph = create_test_graph().await;
+    let user_id = create_user(&graph, "refresh_revoke_user").await;
+
+    let revoked = issue_refresh_token(&graph, user_id).await.unwrap();
+    let kept = issue_refresh_token(&graph, user_id).await.unwrap();
+    revoke_refresh_token(&graph, &revoked).await.unwrap();
+
+    let (status, _) = refresh_token_handler(graph.clone(), revoked)
+        .await
+        .expect_err("a token revoked on logout should be rejected");
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Logging out one session leaves the user's other sessions alone
+    let still_valid = refresh_token_handler(graph.clone(), kept)
+        .await
+        .expect("other refresh tokens should still work");
+    assert!(still_valid.0.refresh_token.is_some());
+
+    delete_user(&graph, user_id).await;
+}
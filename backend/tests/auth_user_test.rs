@@ -0,0 +1,59 @@
+use axum::extract::FromRequestParts;
+use axum::http::{Request, StatusCode};
+
+use backend::server::auth::{parse_admin_user_ids, Claims};
+use backend::server::middleware::{AdminUser, AuthUser};
+
+#[tokio::test]
+async fn test_auth_user_reads_middleware_extension() {
+    let (mut parts, _) = Request::builder().body(()).unwrap().into_parts();
+    parts.extensions.insert(AuthUser(42));
+
+    let user = AuthUser::from_request_parts(&mut parts, &()).await.unwrap();
+    assert_eq!(user, AuthUser(42));
+}
+
+#[tokio::test]
+async fn test_auth_user_rejects_unauthenticated_request() {
+    // A bare i64 extension is not enough; only the middleware inserts AuthUser
+    let (mut parts, _) = Request::builder().body(()).unwrap().into_parts();
+    parts.extensions.insert(42_i64);
+
+    let (status, _) = AuthUser::from_request_parts(&mut parts, &())
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_admin_user_requires_admin_claim() {
+    // The middleware only inserts AdminUser for tokens carrying is_admin
+    let (mut parts, _) = Request::builder().body(()).unwrap().into_parts();
+    parts.extensions.insert(AuthUser(42));
+
+    let (status, _) = AdminUser::from_request_parts(&mut parts, &())
+        .await
+        .unwrap_err();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    parts.extensions.insert(AdminUser(42));
+    let admin = AdminUser::from_request_parts(&mut parts, &())
+        .await
+        .unwrap();
+    assert_eq!(admin, AdminUser(42));
+}
+
+#[test]
+fn test_parse_admin_user_ids() {
+    let ids = parse_admin_user_ids(" 1, 42,,abc, 7 ");
+    assert_eq!(ids.len(), 3);
+    assert!(ids.contains(&1) && ids.contains(&42) && ids.contains(&7));
+    assert!(parse_admin_user_ids("").is_empty());
+}
+
+#[test]
+fn test_tokens_without_admin_claim_decode_as_non_admin() {
+    let claims: Claims =
+        serde_json::from_str(r#"{"user_id": 1, "username": "a", "exp": 0}"#).unwrap();
+    assert!(!claims.is_admin);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use axum::http::StatusCode;
+use backend::tools::event::{
+    auto_reschedule_handler, pick_reschedule_slot, resolve_conflicts_handler, RescheduleSuggestion,
+    MAX_CONFLICT_BATCH,
+};
+use common::unreachable_graph;
+
+#[tokio::test]
+async fn rejects_non_positive_look_ahead_before_querying() {
+    let graph = unreachable_graph().await;
+
+    for days in [0, -3] {
+        let err = auto_reschedule_handler(graph.clone(), 1, 42, days)
+            .await
+            .expect_err("non-positive window should be rejected");
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn resolve_conflicts_validates_before_querying() {
+    let graph = unreachable_graph().await;
+
+    let err = resolve_conflicts_handler(graph.clone(), 1, vec![], 7)
+        .await
+        .expect_err("an empty batch should be rejected");
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+
+    let err = resolve_conflicts_handler(graph.clone(), 1, vec![1, 2], 0)
+        .await
+        .expect_err("non-positive window should be rejected");
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+
+    let too_many: Vec<i64> = (0..=MAX_CONFLICT_BATCH as i64).collect();
+    let err = resolve_conflicts_handler(graph, 1, too_many, 7)
+        .await
+        .expect_err("oversized batch should be rejected");
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+}
+
+fn suggestion(timestamp: i64, score: f64) -> RescheduleSuggestion {
+    RescheduleSuggestion {
+        timestamp,
+        reason: String::new(),
+        score,
+    }
+}
+
+#[test]
+fn picks_the_top_scored_slot() {
+    let best = pick_reschedule_slot(
+        vec![
+            suggestion(100, 0.4),
+            suggestion(300, 0.9),
+            suggestion(200, 0.7),
+        ],
+        7,
+    )
+    .unwrap();
+    assert_eq!(best.timestamp, 300);
+}
+
+#[test]
+fn breaks_score_ties_with_the_earliest_slot() {
+    let best = pick_reschedule_slot(
+        vec![
+            suggestion(500, 0.8),
+            suggestion(200, 0.8),
+            suggestion(900, 0.1),
+        ],
+        7,
+    )
+    .unwrap();
+    assert_eq!(best.timestamp, 200);
+}
+
+#[test]
+fn no_slot_in_window_is_a_conflict() {
+    let err = pick_reschedule_slot(Vec::new(), 3).expect_err("nothing to pick");
+    assert_eq!(err.0, StatusCode::CONFLICT);
+    assert!(err.1.contains("3 days"));
+}
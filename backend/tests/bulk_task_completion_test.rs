@@ -0,0 +1,156 @@
+mod common;
+
+use axum::http::StatusCode;
+use backend::tools::event::{bulk_set_completion_handler, TaskCompletionUpdate};
+use common::unreachable_graph;
+use neo4rs::{query, Graph};
+use std::env;
+
+// Separate from the other database suites' users so they can't clobber each other
+const TEST_USER_ID: i64 = 994;
+const OTHER_USER_ID: i64 = 993;
+
+async fn create_test_graph() -> Graph {
+    let uri = env::var("NEO4J_TEST_URI").unwrap_or_else(|_| "bolt://localhost:7688".to_string());
+    let username = env::var("NEO4J_TEST_USERNAME").unwrap_or_else(|_| "neo4j".to_string());
+    let password = env::var("NEO4J_TEST_PASSWORD").unwrap_or_else(|_| "password123".to_string());
+
+    let config = neo4rs::ConfigBuilder::default()
+        .uri(&uri)
+        .user(&username)
+        .password(&password)
+        .build()
+        .unwrap();
+
+    Graph::connect(config)
+        .await
+        .expect("Failed to create test database connection")
+}
+
+/// Creates a pending task owned by `user_id` with `event_count` pending events under it.
+async fn create_task_with_events(graph: &Graph, user_id: i64, event_count: i64) -> i64 {
+    let mut result = graph
+        .execute(
+            query(
+                "CREATE (t:Goal {name: 'Bulk task', goal_type: 'task', user_id: $user_id,
+                                 resolution_status: 'pending'})
+                 WITH t
+                 UNWIND range(1, $event_count) AS i
+                 CREATE (t)-[:HAS_EVENT]->(:Goal {name: 'Bulk event', goal_type: 'event',
+                                                  user_id: $user_id, is_deleted: false,
+                                                  resolution_status: 'pending'})
+                 RETURN DISTINCT id(t) as id",
+            )
+            .param("user_id", user_id)
+            .param("event_count", event_count),
+        )
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("id").unwrap()
+}
+
+/// Resolution status of the task and of each of its events.
+async fn statuses(graph: &Graph, task_id: i64) -> (String, Vec<String>) {
+    let mut result = graph
+        .execute(
+            query(
+                "MATCH (t:Goal)-[:HAS_EVENT]->(e:Goal) WHERE id(t) = $id
+                 RETURN t.resolution_status as task, collect(e.resolution_status) as events",
+            )
+            .param("id", task_id),
+        )
+        .await
+        .unwrap();
+    let row = result.next().await.unwrap().unwrap();
+    (row.get("task").unwrap(), row.get("events").unwrap())
+}
+
+#[tokio::test]
+async fn rejects_an_empty_batch_before_querying() {
+    let err = bulk_set_completion_handler(unreachable_graph().await, 1, Vec::new())
+        .await
+        .expect_err("empty batch should be rejected");
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn rejects_a_goal_listed_twice_before_querying() {
+    let updates = vec![
+        TaskCompletionUpdate {
+            goal_id: 7,
+            completed: true,
+        },
+        TaskCompletionUpdate {
+            goal_id: 9,
+            completed: true,
+        },
+        TaskCompletionUpdate {
+            goal_id: 7,
+            completed: false,
+        },
+    ];
+
+    let err = bulk_set_completion_handler(unreachable_graph().await, 1, updates)
+        .await
+        .expect_err("conflicting updates for one goal should be rejected");
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    assert!(err.1.contains("goal_id 7"));
+}
+
+#[tokio::test]
+#[ignore]
+async fn completes_owned_tasks_and_their_events() {
+    let graph = create_test_graph().await;
+    let first = create_task_with_events(&graph, TEST_USER_ID, 2).await;
+    let second = create_task_with_events(&graph, TEST_USER_ID, 1).await;
+    let foreign = create_task_with_events(&graph, OTHER_USER_ID, 1).await;
+
+    let updates = [first, second, foreign]
+        .into_iter()
+        .map(|goal_id| TaskCompletionUpdate {
+            goal_id,
+            completed: true,
+        })
+        .collect();
+    let results = bulk_set_completion_handler(graph.clone(), TEST_USER_ID, updates)
+        .await
+        .unwrap()
+        .0;
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].success);
+    assert_eq!(results[0].affected_events, 2);
+    assert!(results[1].success);
+    assert_eq!(results[1].affected_events, 1);
+    assert!(
+        !results[2].success,
+        "another user's task must not be touched"
+    );
+
+    let completed = "completed".to_string();
+    assert_eq!(
+        statuses(&graph, first).await,
+        (completed.clone(), vec![completed.clone(); 2])
+    );
+    assert_eq!(
+        statuses(&graph, second).await,
+        (completed.clone(), vec![completed])
+    );
+    let pending = "pending".to_string();
+    assert_eq!(
+        statuses(&graph, foreign).await,
+        (pending.clone(), vec![pending])
+    );
+
+    graph
+        .run(
+            query(
+                "MATCH (t:Goal) WHERE id(t) IN $ids
+                 OPTIONAL MATCH (t)-[:HAS_EVENT]->(e:Goal)
+                 DETACH DELETE t, e",
+            )
+            .param("ids", vec![first, second, foreign]),
+        )
+        .await
+        .unwrap();
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use axum::http::StatusCode;
+
+use backend::tools::calendar::{get_events_in_range_handler, MAX_EVENT_RANGE_DAYS};
+use common::unreachable_graph;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[tokio::test]
+async fn test_events_in_range_rejects_inverted_range() {
+    for (start, end) in [(1_000, 1_000), (2_000, 1_000)] {
+        let graph = unreachable_graph().await;
+        let result = get_events_in_range_handler(graph, 1, start, end, false).await;
+
+        let (status, _) = result.expect_err("start must come before end");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_events_in_range_rejects_oversized_range() {
+    let graph = unreachable_graph().await;
+    let end = (MAX_EVENT_RANGE_DAYS + 1) * DAY_MS;
+    let result = get_events_in_range_handler(graph, 1, 0, end, true).await;
+
+    let (status, message) = result.expect_err("range over the cap should be rejected");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains(&MAX_EVENT_RANGE_DAYS.to_string()));
+}
@@ -0,0 +1,9 @@
+use neo4rs::Graph;
+
+/// A pool pointed at a closed port. Pool creation is lazy, so handlers that reject their
+/// input before the first query can be tested without a database.
+pub async fn unreachable_graph() -> Graph {
+    Graph::new("bolt://127.0.0.1:1", "neo4j", "password")
+        .await
+        .expect("pool creation is lazy and should not connect")
+}
@@ -0,0 +1,51 @@
+use chrono::{TimeZone, Utc};
+
+use backend::jobs::routine_generator::advance_generation_start;
+use backend::tools::event::should_prompt_parent_completion;
+use backend::tools::goal::{Goal, GoalType};
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+fn ts(y: i32, m: u32, d: u32) -> i64 {
+    Utc.with_ymd_and_hms(y, m, d, 0, 0, 0)
+        .unwrap()
+        .timestamp_millis()
+}
+
+fn daily_routine() -> Goal {
+    Goal {
+        name: "Stretch".to_string(),
+        goal_type: GoalType::Routine,
+        frequency: Some("1D".to_string()),
+        start_timestamp: Some(ts(2024, 1, 1)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_advance_continues_after_a_future_last_event() {
+    let now = ts(2024, 3, 10) + 12 * 60 * 60 * 1000;
+    let last = ts(2024, 3, 11);
+
+    let start = advance_generation_start(&daily_routine(), Some(last), now).unwrap();
+    assert_eq!(start, last + DAY_MS);
+}
+
+#[test]
+fn test_advance_skips_past_a_stale_last_event() {
+    // The generator stalled a week ago: resume from now rather than backfilling the gap
+    let now = ts(2024, 3, 10) + 12 * 60 * 60 * 1000;
+    let stale = ts(2024, 3, 3);
+
+    let start = advance_generation_start(&daily_routine(), Some(stale), now).unwrap();
+    assert_eq!(start, ts(2024, 3, 11));
+}
+
+#[test]
+fn test_last_routine_event_never_prompts_completion() {
+    assert!(!should_prompt_parent_completion(GoalType::Routine, 0));
+    assert!(!should_prompt_parent_completion(GoalType::Routine, 3));
+    // Tasks are prompted only once nothing is left scheduled
+    assert!(should_prompt_parent_completion(GoalType::Task, 0));
+    assert!(!should_prompt_parent_completion(GoalType::Task, 1));
+}
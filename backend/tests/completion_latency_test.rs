@@ -0,0 +1,23 @@
+use backend::tools::stats::latency_summary_minutes;
+
+const MINUTE_MS: i64 = 60 * 1000;
+
+#[test]
+fn test_latency_summary_average_and_median() {
+    // Completed 10 and 30 minutes late, and one 2 hours late skewing the average
+    let (average, median) =
+        latency_summary_minutes(&[30 * MINUTE_MS, 120 * MINUTE_MS, 10 * MINUTE_MS]).unwrap();
+    assert!((average - 160.0 / 3.0).abs() < 1e-9);
+    assert_eq!(median, 30.0);
+}
+
+#[test]
+fn test_latency_summary_even_count_and_early_completions() {
+    // Finishing early gives a negative latency
+    let (average, median) =
+        latency_summary_minutes(&[-20 * MINUTE_MS, 0, 10 * MINUTE_MS, 50 * MINUTE_MS]).unwrap();
+    assert_eq!(average, 10.0);
+    assert_eq!(median, 5.0);
+
+    assert!(latency_summary_minutes(&[]).is_none());
+}
@@ -0,0 +1,42 @@
+use backend::tools::day::local_day_bounds;
+use backend::tools::event_settings::parse_default_timezone;
+use chrono::{TimeZone, Utc};
+use chrono_tz::Tz;
+
+#[test]
+fn unset_or_blank_default_timezone_is_utc() {
+    assert_eq!(parse_default_timezone(None), Ok(Tz::UTC));
+    assert_eq!(parse_default_timezone(Some("  ")), Ok(Tz::UTC));
+    assert_eq!(parse_default_timezone(Some("utc")), Ok(Tz::UTC));
+}
+
+#[test]
+fn iana_names_parse_and_garbage_is_rejected() {
+    assert_eq!(
+        parse_default_timezone(Some(" Europe/Berlin ")),
+        Ok(Tz::Europe__Berlin)
+    );
+    assert!(parse_default_timezone(Some("Mars/Olympus")).is_err());
+}
+
+#[test]
+fn day_bounds_follow_the_local_calendar_day() {
+    let tz: Tz = "America/New_York".parse().unwrap();
+    // 02:00 UTC on the 11th is still the evening of the 10th in New York
+    let now = Utc.with_ymd_and_hms(2025, 3, 11, 2, 0, 0).unwrap();
+
+    let (start, end) = local_day_bounds(tz, now);
+
+    assert_eq!(
+        start,
+        tz.with_ymd_and_hms(2025, 3, 10, 0, 0, 0)
+            .unwrap()
+            .timestamp_millis()
+    );
+    assert_eq!(
+        end + 1,
+        tz.with_ymd_and_hms(2025, 3, 11, 0, 0, 0)
+            .unwrap()
+            .timestamp_millis()
+    );
+}
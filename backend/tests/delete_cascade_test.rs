@@ -0,0 +1,234 @@
+use chrono::Utc;
+use neo4rs::{query, Graph};
+use std::env;
+
+use backend::tools::goal::{delete_goal_handler, restore_goal_handler, Goal, GoalType};
+
+// Separate from the routine integration tests' user so the suites can't clobber each other
+const TEST_USER_ID: i64 = 997;
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+async fn create_test_graph() -> Graph {
+    let uri = env::var("NEO4J_TEST_URI").unwrap_or_else(|_| "bolt://localhost:7688".to_string());
+    let username = env::var("NEO4J_TEST_USERNAME").unwrap_or_else(|_| "neo4j".to_string());
+    let password = env::var("NEO4J_TEST_PASSWORD").unwrap_or_else(|_| "password123".to_string());
+
+    let config = neo4rs::ConfigBuilder::default()
+        .uri(&uri)
+        .user(&username)
+        .password(&password)
+        .build()
+        .unwrap();
+
+    Graph::connect(config)
+        .await
+        .expect("Failed to create test database connection")
+}
+
+async fn clear_test_data(graph: &Graph) {
+    graph
+        .run(
+            query("MATCH (g:Goal) WHERE g.user_id = $user_id DETACH DELETE g")
+                .param("user_id", TEST_USER_ID),
+        )
+        .await
+        .expect("Failed to clear test data");
+}
+
+async fn create_parent(graph: &Graph, goal_type: GoalType) -> i64 {
+    let goal = Goal {
+        name: "Cascade parent".to_string(),
+        goal_type,
+        user_id: Some(TEST_USER_ID),
+        start_timestamp: Some(Utc::now().timestamp_millis() - 30 * DAY_MS),
+        frequency: Some("1D".to_string()),
+        ..Default::default()
+    };
+    goal.create_goal(graph).await.unwrap().id.unwrap()
+}
+
+async fn create_event(graph: &Graph, parent_id: i64, timestamp: i64, status: &str) -> i64 {
+    let event = Goal {
+        name: "Cascade event".to_string(),
+        goal_type: GoalType::Event,
+        user_id: Some(TEST_USER_ID),
+        parent_id: Some(parent_id),
+        scheduled_timestamp: Some(timestamp),
+        duration: Some(30),
+        resolution_status: Some(status.to_string()),
+        is_deleted: Some(false),
+        ..Default::default()
+    };
+    let event_id = event.create_goal(graph).await.unwrap().id.unwrap();
+    graph
+        .run(
+            query(
+                "MATCH (p:Goal), (e:Goal) WHERE id(p) = $parent_id AND id(e) = $event_id
+                 CREATE (p)-[:HAS_EVENT]->(e)",
+            )
+            .param("parent_id", parent_id)
+            .param("event_id", event_id),
+        )
+        .await
+        .unwrap();
+    event_id
+}
+
+/// Ids of events under `parent_id` that are still live, in scheduled order.
+async fn live_event_ids(graph: &Graph, parent_id: i64) -> Vec<i64> {
+    let mut result = graph
+        .execute(
+            query(
+                "MATCH (p:Goal)-[:HAS_EVENT]->(e:Goal)
+                 WHERE id(p) = $parent_id AND coalesce(e.is_deleted, false) <> true
+                 RETURN id(e) as id ORDER BY e.scheduled_timestamp",
+            )
+            .param("parent_id", parent_id),
+        )
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    while let Some(row) = result.next().await.unwrap() {
+        ids.push(row.get::<i64>("id").unwrap());
+    }
+    ids
+}
+
+#[tokio::test]
+async fn test_deleting_task_removes_all_its_events() {
+    let graph = create_test_graph().await;
+    clear_test_data(&graph).await;
+    let now = Utc::now().timestamp_millis();
+
+    let task_id = create_parent(&graph, GoalType::Task).await;
+    create_event(&graph, task_id, now - 2 * DAY_MS, "completed").await;
+    create_event(&graph, task_id, now - DAY_MS, "pending").await;
+    create_event(&graph, task_id, now + DAY_MS, "pending").await;
+
+    delete_goal_handler(graph.clone(), TEST_USER_ID, task_id)
+        .await
+        .unwrap();
+    assert!(live_event_ids(&graph, task_id).await.is_empty());
+
+    // Restoring brings all of them back
+    let restored = restore_goal_handler(graph.clone(), TEST_USER_ID, task_id)
+        .await
+        .unwrap();
+    assert_eq!(restored.0.name, "Cascade parent");
+    assert_eq!(live_event_ids(&graph, task_id).await.len(), 3);
+
+    clear_test_data(&graph).await;
+}
+
+#[tokio::test]
+async fn test_deleting_routine_keeps_history_and_removes_upcoming_events() {
+    let graph = create_test_graph().await;
+    clear_test_data(&graph).await;
+    let now = Utc::now().timestamp_millis();
+
+    let routine_id = create_parent(&graph, GoalType::Routine).await;
+    let past = create_event(&graph, routine_id, now - DAY_MS, "pending").await;
+    let completed_early = create_event(&graph, routine_id, now + DAY_MS, "completed").await;
+    create_event(&graph, routine_id, now + 2 * DAY_MS, "pending").await;
+    create_event(&graph, routine_id, now + 3 * DAY_MS, "skipped").await;
+
+    delete_goal_handler(graph.clone(), TEST_USER_ID, routine_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        live_event_ids(&graph, routine_id).await,
+        vec![past, completed_early]
+    );
+
+    clear_test_data(&graph).await;
+}
+
+async fn link_child(graph: &Graph, parent_id: i64, child_id: i64) {
+    graph
+        .run(
+            query(
+                "MATCH (p:Goal), (c:Goal) WHERE id(p) = $parent_id AND id(c) = $child_id
+                 CREATE (p)-[:CHILD]->(c)",
+            )
+            .param("parent_id", parent_id)
+            .param("child_id", child_id),
+        )
+        .await
+        .unwrap();
+}
+
+async fn is_deleted(graph: &Graph, goal_id: i64) -> bool {
+    let mut result = graph
+        .execute(
+            query("MATCH (g:Goal) WHERE id(g) = $id RETURN coalesce(g.is_deleted, false) as d")
+                .param("id", goal_id),
+        )
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("d").unwrap()
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_deleting_project_cascades_to_descendants_and_restores_them() {
+    let graph = create_test_graph().await;
+    clear_test_data(&graph).await;
+    let now = Utc::now().timestamp_millis();
+
+    let project = create_parent(&graph, GoalType::Project).await;
+    let subproject = create_parent(&graph, GoalType::Project).await;
+    let task = create_parent(&graph, GoalType::Task).await;
+    let shared = create_parent(&graph, GoalType::Task).await;
+    let other_project = create_parent(&graph, GoalType::Project).await;
+    link_child(&graph, project, subproject).await;
+    link_child(&graph, subproject, task).await;
+    link_child(&graph, subproject, shared).await;
+    link_child(&graph, other_project, shared).await;
+    create_event(&graph, task, now + DAY_MS, "pending").await;
+
+    delete_goal_handler(graph.clone(), TEST_USER_ID, project)
+        .await
+        .unwrap();
+    assert!(is_deleted(&graph, subproject).await);
+    assert!(is_deleted(&graph, task).await);
+    assert!(live_event_ids(&graph, task).await.is_empty());
+    // Still linked under a live project, so it isn't orphaned and stays
+    assert!(!is_deleted(&graph, shared).await);
+
+    let restored = restore_goal_handler(graph.clone(), TEST_USER_ID, project)
+        .await
+        .unwrap();
+    assert_eq!(restored.0.id, Some(project));
+    assert!(!is_deleted(&graph, subproject).await);
+    assert!(!is_deleted(&graph, task).await);
+    assert_eq!(live_event_ids(&graph, task).await.len(), 1);
+
+    clear_test_data(&graph).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_restoring_a_descendant_alone_brings_back_its_events() {
+    let graph = create_test_graph().await;
+    clear_test_data(&graph).await;
+    let now = Utc::now().timestamp_millis();
+
+    let project = create_parent(&graph, GoalType::Project).await;
+    let task = create_parent(&graph, GoalType::Task).await;
+    link_child(&graph, project, task).await;
+    create_event(&graph, task, now + DAY_MS, "pending").await;
+
+    delete_goal_handler(graph.clone(), TEST_USER_ID, project)
+        .await
+        .unwrap();
+    assert!(live_event_ids(&graph, task).await.is_empty());
+
+    let restored = restore_goal_handler(graph.clone(), TEST_USER_ID, task)
+        .await
+        .unwrap();
+    assert_eq!(restored.0.id, Some(task));
+    assert!(is_deleted(&graph, project).await);
+    assert_eq!(live_event_ids(&graph, task).await.len(), 1);
+
+    clear_test_data(&graph).await;
+}
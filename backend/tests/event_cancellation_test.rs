@@ -0,0 +1,26 @@
+use backend::tools::goal::{get_display_status, ResolutionStatus};
+use backend::tools::stats::calculate_cancellation_stats;
+
+#[test]
+fn test_cancelled_is_a_resolution_status() {
+    assert_eq!(
+        ResolutionStatus::from_str("cancelled"),
+        Some(ResolutionStatus::Cancelled)
+    );
+    assert_eq!(ResolutionStatus::Cancelled.as_str(), "cancelled");
+    assert_eq!(
+        get_display_status(Some("event"), Some("cancelled"), None, None, None, None, 0),
+        "cancelled"
+    );
+}
+
+#[test]
+fn test_cancellation_rate_counts_cancelled_in_denominator() {
+    let stats = calculate_cancellation_stats(6, 2);
+    assert_eq!(stats.total_events, 8);
+    assert_eq!(stats.cancelled_events, 2);
+    assert!((stats.cancellation_rate - 0.25).abs() < f64::EPSILON);
+
+    let empty = calculate_cancellation_stats(0, 0);
+    assert_eq!(empty.cancellation_rate, 0.0);
+}
@@ -0,0 +1,32 @@
+use backend::jobs::event_move_pruner::{
+    parse_event_move_retention_days, DEFAULT_EVENT_MOVE_RETENTION_DAYS,
+};
+
+#[test]
+fn retention_defaults_when_unset_or_blank() {
+    assert_eq!(
+        parse_event_move_retention_days(None),
+        Ok(DEFAULT_EVENT_MOVE_RETENTION_DAYS)
+    );
+    assert_eq!(
+        parse_event_move_retention_days(Some("  ")),
+        Ok(DEFAULT_EVENT_MOVE_RETENTION_DAYS)
+    );
+}
+
+#[test]
+fn retention_accepts_whole_days() {
+    assert_eq!(parse_event_move_retention_days(Some("90")), Ok(90));
+    assert_eq!(parse_event_move_retention_days(Some(" 730 ")), Ok(730));
+}
+
+#[test]
+fn retention_rejects_invalid_values() {
+    for bad in ["0", "-5", "1.5", "forever", "100000"] {
+        assert!(
+            parse_event_move_retention_days(Some(bad)).is_err(),
+            "{} should be rejected",
+            bad
+        );
+    }
+}
@@ -0,0 +1,287 @@
+mod common;
+
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+
+use backend::tools::event::{
+    bulk_delete_events_handler, create_event_handler, event_duration_ms, split_across_working_days,
+    update_event_handler, update_routine_event_handler, validate_event_duration,
+    CreateEventRequest, UpdateEventError, UpdateEventRequest, UpdateRoutineEventRequest,
+    VersionConflictError,
+};
+use backend::tools::goal::{validate_links, Goal, GoalType};
+use common::unreachable_graph;
+
+#[test]
+fn test_validate_event_duration() {
+    assert!(validate_event_duration(30).is_ok());
+    // Very long events are allowed, only logged
+    assert!(validate_event_duration(36 * 60).is_ok());
+
+    for bad in [0, -1, -60] {
+        let (status, _) = validate_event_duration(bad).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_create_event_rejects_negative_duration() {
+    let graph = unreachable_graph().await;
+
+    let result = create_event_handler(
+        graph,
+        1,
+        CreateEventRequest {
+            parent_id: 1,
+            parent_type: "task".to_string(),
+            scheduled_timestamp: 1_700_000_000_000,
+            duration: Some(-30),
+            priority: None,
+            is_deadline: None,
+            duration_seconds: None,
+            recurrence: None,
+            allow_overlap: None,
+            max_daily_minutes: None,
+        },
+    )
+    .await;
+
+    let (status, message) = result.expect_err("negative duration should be rejected");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("positive"));
+}
+
+#[tokio::test]
+async fn test_create_event_rejects_zero_duration_seconds() {
+    let graph = unreachable_graph().await;
+
+    let result = create_event_handler(
+        graph,
+        1,
+        CreateEventRequest {
+            parent_id: 1,
+            parent_type: "routine".to_string(),
+            scheduled_timestamp: 1_700_000_000_000,
+            duration: Some(1),
+            priority: None,
+            is_deadline: None,
+            duration_seconds: Some(0),
+            recurrence: None,
+            allow_overlap: None,
+            max_daily_minutes: None,
+        },
+    )
+    .await;
+
+    let (status, message) = result.expect_err("zero seconds should be rejected");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("duration_seconds"));
+}
+
+#[tokio::test]
+async fn test_create_event_recurrence_is_task_only() {
+    let graph = unreachable_graph().await;
+    let request = |parent_type: &str, recurrence: &str| CreateEventRequest {
+        parent_id: 1,
+        parent_type: parent_type.to_string(),
+        scheduled_timestamp: 1_700_000_000_000,
+        duration: Some(30),
+        priority: None,
+        is_deadline: None,
+        duration_seconds: None,
+        recurrence: Some(recurrence.to_string()),
+        allow_overlap: None,
+        max_daily_minutes: None,
+    };
+
+    let (status, message) = create_event_handler(graph.clone(), 1, request("routine", "1D"))
+        .await
+        .expect_err("routine events repeat through the routine");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("task events"));
+
+    let (status, _) = create_event_handler(graph, 1, request("task", "every day"))
+        .await
+        .expect_err("malformed pattern should be rejected");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_duration_seconds_override_minutes() {
+    assert_eq!(event_duration_ms(30, None), 30 * 60 * 1000);
+    assert_eq!(event_duration_ms(1, Some(20)), 20 * 1000);
+    assert_eq!(event_duration_ms(2, Some(90)), 90 * 1000);
+}
+
+#[tokio::test]
+async fn test_update_event_rejects_zero_duration() {
+    let graph = unreachable_graph().await;
+
+    let result = update_event_handler(
+        graph,
+        1,
+        1,
+        UpdateEventRequest {
+            scheduled_timestamp: None,
+            duration: Some(0),
+            resolution_status: None,
+            completed: None,
+            move_reason: None,
+            version: None,
+            notes: None,
+            links: None,
+            allow_overlap: None,
+        },
+    )
+    .await;
+
+    let status = result
+        .expect_err("zero duration should be rejected")
+        .status();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_validate_links() {
+    assert!(validate_links(&["https://meet.example.com/abc".to_string()]).is_ok());
+    assert!(validate_links(&[]).is_ok());
+
+    for bad in [
+        "not a url",
+        "ftp://files.example.com",
+        "mailto:me@example.com",
+    ] {
+        assert!(validate_links(&[bad.to_string()]).is_err(), "{}", bad);
+    }
+}
+
+#[tokio::test]
+async fn test_update_event_rejects_malformed_link() {
+    let graph = unreachable_graph().await;
+
+    let result = update_event_handler(
+        graph,
+        1,
+        1,
+        UpdateEventRequest {
+            scheduled_timestamp: None,
+            duration: None,
+            resolution_status: None,
+            completed: None,
+            move_reason: None,
+            version: None,
+            notes: None,
+            links: Some(vec!["example.com/doc".to_string()]),
+            allow_overlap: None,
+        },
+    )
+    .await;
+
+    let status = result
+        .expect_err("malformed link should be rejected")
+        .status();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_bulk_delete_rejects_empty_ids() {
+    let graph = unreachable_graph().await;
+
+    let result = bulk_delete_events_handler(graph, 1, Vec::new(), false).await;
+
+    let (status, _) = result.expect_err("empty id list should be rejected");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_long_event_splits_across_weekdays() {
+    // Thursday 2024-03-07 09:00 UTC, 10 hours capped at 4 per day
+    let thursday = 1_709_802_000_000;
+    let day = 24 * 60 * 60 * 1000;
+
+    let chunks = split_across_working_days(thursday, 600, 240);
+    assert_eq!(
+        chunks,
+        vec![
+            (thursday, 240),
+            (thursday + day, 240),
+            (thursday + 4 * day, 120)
+        ],
+        "Friday follows Thursday, then the weekend is skipped"
+    );
+
+    // Under the cap nothing is split
+    assert_eq!(
+        split_across_working_days(thursday, 240, 240),
+        vec![(thursday, 240)]
+    );
+}
+
+#[tokio::test]
+async fn test_create_event_rejects_non_positive_daily_cap() {
+    let graph = unreachable_graph().await;
+
+    let (status, message) = create_event_handler(
+        graph,
+        1,
+        CreateEventRequest {
+            parent_id: 1,
+            parent_type: "task".to_string(),
+            scheduled_timestamp: 1_700_000_000_000,
+            duration: Some(600),
+            priority: None,
+            is_deadline: None,
+            duration_seconds: None,
+            recurrence: None,
+            allow_overlap: None,
+            max_daily_minutes: Some(0),
+        },
+    )
+    .await
+    .expect_err("a zero cap should be rejected");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("max_daily_minutes"));
+}
+
+#[test]
+fn test_version_conflict_is_sent_as_json() {
+    let conflict = UpdateEventError::Conflict(Box::new(VersionConflictError {
+        error_type: "version_conflict".to_string(),
+        message: "Event was modified elsewhere (current version 3)".to_string(),
+        current: Goal {
+            name: "Standup".to_string(),
+            goal_type: GoalType::Event,
+            version: Some(3),
+            ..Default::default()
+        },
+    }));
+    assert_eq!(conflict.status(), StatusCode::CONFLICT);
+
+    let response = conflict.into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/json"
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_shift_mode_is_rejected() {
+    let (status, message) = update_routine_event_handler(
+        unreachable_graph().await,
+        1,
+        1,
+        UpdateRoutineEventRequest {
+            new_timestamp: 0,
+            update_scope: "all".to_string(),
+            range_start: None,
+            range_end: None,
+            resolution_status: None,
+            shift_mode: Some("sideways".to_string()),
+        },
+    )
+    .await
+    .expect_err("an unknown shift_mode should be rejected");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("sideways"));
+}
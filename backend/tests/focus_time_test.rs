@@ -0,0 +1,35 @@
+use backend::tools::stats::{build_focus_buckets, focus_minutes};
+use chrono::NaiveDate;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn all_day_and_longer_events_are_not_focus_time() {
+    assert_eq!(focus_minutes(45.0), Some(45.0));
+    assert_eq!(focus_minutes(1439.0), Some(1439.0));
+    assert_eq!(focus_minutes(1440.0), None);
+    assert_eq!(focus_minutes(3000.0), None);
+    assert_eq!(focus_minutes(0.0), None);
+}
+
+#[test]
+fn focus_buckets_sum_scheduled_and_completed_minutes() {
+    // 2025-03-10 is a Monday
+    let events = vec![
+        (date(2025, 3, 10), 60.0, true),
+        (date(2025, 3, 12), 30.0, false),
+        (date(2025, 3, 12), 1440.0, true), // all-day, ignored
+        (date(2025, 3, 17), 90.0, true),
+    ];
+
+    let buckets = build_focus_buckets(&events, "week");
+    assert_eq!(buckets.len(), 2);
+    assert_eq!(buckets[0].period_start, "2025-03-10");
+    assert_eq!(buckets[0].scheduled_minutes, 90.0);
+    assert_eq!(buckets[0].completed_minutes, 60.0);
+    assert_eq!(buckets[0].event_count, 2);
+    assert_eq!(buckets[1].period_start, "2025-03-17");
+    assert_eq!(buckets[1].completed_minutes, 90.0);
+}
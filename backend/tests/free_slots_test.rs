@@ -0,0 +1,145 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+
+use backend::tools::event::{
+    compute_free_slots, find_tight_schedules_handler, get_free_slots_handler,
+    get_overlapping_events_handler, overlap_clusters, spaced_starts, tight_pairs, FreeSlot,
+};
+use common::unreachable_graph;
+
+const MINUTE_MS: i64 = 60 * 1000;
+const HOUR_MS: i64 = 60 * MINUTE_MS;
+
+fn slot(start: i64, end: i64) -> FreeSlot {
+    FreeSlot {
+        start_timestamp: start,
+        end_timestamp: end,
+        duration_minutes: (end - start) / MINUTE_MS,
+    }
+}
+
+#[test]
+fn test_free_slots_between_events() {
+    // Working window 8:00-18:00 on an arbitrary day
+    let (start, end) = (8 * HOUR_MS, 18 * HOUR_MS);
+    let busy = [
+        (9 * HOUR_MS, 60),                   // 9:00-10:00
+        (9 * HOUR_MS + 30 * MINUTE_MS, 60),  // overlaps, extends to 10:30
+        (10 * HOUR_MS + 45 * MINUTE_MS, 15), // 10:45-11:00, leaves a 15 minute gap
+        (17 * HOUR_MS, 120),                 // runs past the window
+    ];
+
+    let slots = compute_free_slots(&busy, start, end, 30);
+
+    assert_eq!(
+        slots,
+        vec![
+            slot(8 * HOUR_MS, 9 * HOUR_MS),
+            slot(11 * HOUR_MS, 17 * HOUR_MS),
+        ]
+    );
+}
+
+#[test]
+fn test_free_slots_handle_events_outside_window() {
+    let (start, end) = (8 * HOUR_MS, 12 * HOUR_MS);
+    // Late event from the previous evening spilling into the morning
+    let busy = [(-2 * HOUR_MS, 11 * 60), (20 * HOUR_MS, 60)];
+
+    assert_eq!(
+        compute_free_slots(&busy, start, end, 30),
+        vec![slot(9 * HOUR_MS, 12 * HOUR_MS)]
+    );
+    assert_eq!(
+        compute_free_slots(&[], start, end, 30),
+        vec![slot(start, end)]
+    );
+}
+
+#[tokio::test]
+async fn test_free_slots_rejects_inverted_working_hours() {
+    // Validation runs before any query, so the graph never needs to connect
+    let graph = unreachable_graph().await;
+    let date = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+
+    let (status, _) = get_free_slots_handler(graph, 1, date, 30, 18, 8)
+        .await
+        .expect_err("inverted hours should be rejected");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_overlap_clusters_group_chained_overlaps() {
+    let intervals = [
+        (13 * HOUR_MS, 14 * HOUR_MS),                  // 0: alone
+        (9 * HOUR_MS, 10 * HOUR_MS),                   // 1
+        (9 * HOUR_MS + 30 * MINUTE_MS, 11 * HOUR_MS),  // 2: overlaps 1
+        (10 * HOUR_MS + 45 * MINUTE_MS, 12 * HOUR_MS), // 3: overlaps 2 only
+        (12 * HOUR_MS, 13 * HOUR_MS),                  // 4: touches 3 and 0, no overlap
+    ];
+
+    assert_eq!(overlap_clusters(&intervals), vec![vec![1, 2, 3]]);
+    assert!(overlap_clusters(&[]).is_empty());
+}
+
+#[tokio::test]
+async fn test_overlapping_events_rejects_bad_ranges() {
+    let graph = unreachable_graph().await;
+
+    let reversed = get_overlapping_events_handler(graph.clone(), 1, 10 * HOUR_MS, HOUR_MS)
+        .await
+        .unwrap_err();
+    assert_eq!(reversed.0, StatusCode::BAD_REQUEST);
+
+    let too_long = get_overlapping_events_handler(graph, 1, 0, 400 * 24 * HOUR_MS)
+        .await
+        .unwrap_err();
+    assert_eq!(too_long.0, StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_tight_pairs_flag_gaps_below_minimum() {
+    let intervals = [
+        (9 * HOUR_MS, 10 * HOUR_MS),
+        (10 * HOUR_MS + 5 * MINUTE_MS, 11 * HOUR_MS), // 5 min after the first
+        (12 * HOUR_MS, 13 * HOUR_MS),                 // an hour of breathing room
+    ];
+    assert_eq!(tight_pairs(&intervals, 15 * MINUTE_MS), vec![(0, 1)]);
+    assert!(tight_pairs(&intervals, 5 * MINUTE_MS).is_empty());
+}
+
+#[test]
+fn test_spaced_starts_cascade_and_respect_fixed_events() {
+    let intervals = [
+        (9 * HOUR_MS, 10 * HOUR_MS),
+        (10 * HOUR_MS, 11 * HOUR_MS),
+        (11 * HOUR_MS + 10 * MINUTE_MS, 12 * HOUR_MS),
+    ];
+
+    // Pushing the second event forward squeezes the third, which moves too
+    assert_eq!(
+        spaced_starts(&intervals, &[true, true, true], 15 * MINUTE_MS),
+        vec![
+            (1, 10 * HOUR_MS + 15 * MINUTE_MS),
+            (2, 11 * HOUR_MS + 30 * MINUTE_MS),
+        ]
+    );
+
+    // A fixed middle event stays; the last is spaced from where it actually ends
+    assert_eq!(
+        spaced_starts(&intervals, &[true, false, true], 15 * MINUTE_MS),
+        vec![(2, 11 * HOUR_MS + 15 * MINUTE_MS)]
+    );
+}
+
+#[tokio::test]
+async fn test_tight_schedules_rejects_non_positive_gap() {
+    let graph = unreachable_graph().await;
+
+    let (status, _) = find_tight_schedules_handler(graph, 1, 0, 0, HOUR_MS, false)
+        .await
+        .expect_err("gap must be positive");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,58 @@
+use backend::tools::event_settings::{validate_gcal_conflict_policy, GCAL_CONFLICT_POLICIES};
+use backend::tools::gcal_client::{resolve_sync_conflict, ConflictWinner};
+
+// 2026-03-01T12:00:00Z
+const LOCAL_EDIT_MS: i64 = 1_772_366_400_000;
+
+#[test]
+fn fixed_policies_ignore_timestamps() {
+    let later = Some("2026-03-01T13:00:00Z");
+    let earlier = Some("2026-03-01T11:00:00Z");
+
+    assert_eq!(
+        resolve_sync_conflict("goals_wins", LOCAL_EDIT_MS, later),
+        ConflictWinner::Goals
+    );
+    assert_eq!(
+        resolve_sync_conflict("google_wins", LOCAL_EDIT_MS, earlier),
+        ConflictWinner::Google
+    );
+}
+
+#[test]
+fn newest_wins_compares_google_updated_with_local_edit() {
+    assert_eq!(
+        resolve_sync_conflict("newest_wins", LOCAL_EDIT_MS, Some("2026-03-01T12:00:01Z")),
+        ConflictWinner::Google
+    );
+    assert_eq!(
+        resolve_sync_conflict(
+            "newest_wins",
+            LOCAL_EDIT_MS,
+            Some("2026-03-01T13:59:00+02:00")
+        ),
+        ConflictWinner::Goals
+    );
+    // Ties and a missing or garbled Google timestamp keep the local edit
+    assert_eq!(
+        resolve_sync_conflict("newest_wins", LOCAL_EDIT_MS, Some("2026-03-01T12:00:00Z")),
+        ConflictWinner::Goals
+    );
+    assert_eq!(
+        resolve_sync_conflict("newest_wins", LOCAL_EDIT_MS, None),
+        ConflictWinner::Goals
+    );
+    assert_eq!(
+        resolve_sync_conflict("newest_wins", LOCAL_EDIT_MS, Some("yesterday")),
+        ConflictWinner::Goals
+    );
+}
+
+#[test]
+fn only_known_policies_are_accepted() {
+    for policy in GCAL_CONFLICT_POLICIES {
+        assert!(validate_gcal_conflict_policy(policy).is_ok());
+    }
+    assert!(validate_gcal_conflict_policy("last_writer_wins").is_err());
+    assert!(validate_gcal_conflict_policy("").is_err());
+}
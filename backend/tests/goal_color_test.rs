@@ -0,0 +1,22 @@
+use backend::tools::gcal_client::nearest_gcal_color_id;
+use backend::tools::goal::is_valid_hex_color;
+
+#[test]
+fn test_hex_color_validation() {
+    assert!(is_valid_hex_color("#1a2B3c"));
+    assert!(!is_valid_hex_color("1a2b3c"));
+    assert!(!is_valid_hex_color("#fff"));
+    assert!(!is_valid_hex_color("#12345g"));
+    assert!(!is_valid_hex_color("red"));
+}
+
+#[test]
+fn test_nearest_gcal_color() {
+    // Exact palette entries map to themselves
+    assert_eq!(nearest_gcal_color_id("#d50000"), Some("11"));
+    assert_eq!(nearest_gcal_color_id("#0B8043"), Some("10"));
+    // Close-enough colors snap to the nearest palette entry
+    assert_eq!(nearest_gcal_color_id("#ff0000"), Some("11"));
+    assert_eq!(nearest_gcal_color_id("#606060"), Some("8"));
+    assert_eq!(nearest_gcal_color_id("not-a-color"), None);
+}
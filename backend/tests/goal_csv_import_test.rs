@@ -0,0 +1,87 @@
+use backend::tools::goal::GoalType;
+use backend::tools::goal_import::{
+    goal_from_csv_row, parse_csv_header, parse_csv_line, parse_deadline,
+};
+use chrono_tz::Tz;
+
+const NOW_MS: i64 = 1_767_225_600_000; // 2026-01-01T00:00:00Z
+
+fn row(columns: &[String], line: &str) -> Result<backend::tools::goal::Goal, String> {
+    let fields = parse_csv_line(line)?;
+    goal_from_csv_row(columns, &fields, 7, Tz::UTC, NOW_MS)
+}
+
+#[test]
+fn quoted_fields_keep_commas_and_escaped_quotes() {
+    let fields = parse_csv_line(r#"Read, "Books, papers", "Say ""hi""" ,"#).unwrap();
+    assert_eq!(fields, vec!["Read", "Books, papers", r#"Say "hi""#, ""]);
+
+    assert!(parse_csv_line(r#"Read,"unterminated"#).is_err());
+    assert!(parse_csv_line(r#"Re"ad,task"#).is_err());
+}
+
+#[test]
+fn header_must_name_known_unique_columns() {
+    let columns = parse_csv_header("Name, TYPE ,deadline").unwrap();
+    assert_eq!(columns, vec!["name", "type", "deadline"]);
+
+    assert!(parse_csv_header("name,owner").is_err());
+    assert!(parse_csv_header("name,type,name").is_err());
+    assert!(parse_csv_header("type,priority").is_err());
+}
+
+#[test]
+fn rows_become_goals_with_defaults() {
+    let columns = parse_csv_header("name,type,priority,deadline,description").unwrap();
+
+    let task = row(&columns, "Write report,,High,2026-03-01,Quarterly numbers").unwrap();
+    assert_eq!(task.goal_type, GoalType::Task);
+    assert_eq!(task.priority.as_deref(), Some("high"));
+    assert_eq!(task.user_id, Some(7));
+    assert_eq!(task.start_timestamp, Some(NOW_MS));
+    assert_eq!(task.description.as_deref(), Some("Quarterly numbers"));
+    // A plain date is due by the end of that day
+    assert_eq!(task.end_timestamp, Some(1_772_409_600_000 - 1));
+
+    let project = row(&columns, "Learn piano,Project,,,").unwrap();
+    assert_eq!(project.goal_type, GoalType::Project);
+    assert_eq!(project.priority, None);
+    assert_eq!(project.end_timestamp, None);
+    assert_eq!(project.description, None);
+}
+
+#[test]
+fn malformed_rows_are_reported() {
+    let columns = parse_csv_header("name,type,priority,deadline").unwrap();
+
+    assert!(row(&columns, "Gym,chore,,")
+        .unwrap_err()
+        .contains("Unknown type"));
+    assert!(row(&columns, "Gym,routine,,")
+        .unwrap_err()
+        .contains("routine"));
+    assert!(row(&columns, "Gym,task,urgent,")
+        .unwrap_err()
+        .contains("priority"));
+    assert!(row(&columns, "Gym,task,,next friday")
+        .unwrap_err()
+        .contains("Unparseable deadline"));
+    assert!(row(&columns, ",task,,")
+        .unwrap_err()
+        .contains("Name is required"));
+    assert!(row(&columns, "Gym,task")
+        .unwrap_err()
+        .contains("Expected 4 columns"));
+}
+
+#[test]
+fn deadlines_accept_dates_in_the_timezone_and_rfc3339() {
+    let tz: Tz = "America/New_York".parse().unwrap();
+    // 2026-03-02T00:00:00-05:00
+    assert_eq!(parse_deadline("2026-03-01", tz), Ok(1_772_427_600_000 - 1));
+    assert_eq!(
+        parse_deadline("2026-03-01T09:30:00Z", tz),
+        Ok(1_772_357_400_000)
+    );
+    assert!(parse_deadline("03/01/2026", tz).is_err());
+}
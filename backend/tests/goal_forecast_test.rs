@@ -0,0 +1,43 @@
+use backend::tools::achievements::{forecast_progress, ForecastStatus};
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[test]
+fn test_forecast_on_track_when_pace_covers_remaining() {
+    // 6 of 12 done in 30 days, 60 days left: 0.2/day observed vs 0.1/day needed
+    let forecast = forecast_progress(6.0, 12.0, 0, 90 * DAY_MS, 30 * DAY_MS);
+
+    assert_eq!(forecast.status, ForecastStatus::OnTrack);
+    assert!((forecast.observed_pace_per_day - 0.2).abs() < 1e-9);
+    assert!((forecast.required_pace_per_day - 0.1).abs() < 1e-9);
+    assert_eq!(forecast.projected_completion, Some(60 * DAY_MS));
+}
+
+#[test]
+fn test_forecast_at_risk_and_behind() {
+    // 0.09/day observed against 0.1/day needed
+    let at_risk = forecast_progress(2.7, 8.7, 0, 90 * DAY_MS, 30 * DAY_MS);
+    assert_eq!(at_risk.status, ForecastStatus::AtRisk);
+
+    // 0.05/day observed against 0.175/day needed
+    let behind = forecast_progress(1.5, 12.0, 0, 90 * DAY_MS, 30 * DAY_MS);
+    assert_eq!(behind.status, ForecastStatus::Behind);
+    assert!(behind.projected_completion.unwrap() > 90 * DAY_MS);
+}
+
+#[test]
+fn test_forecast_without_progress_has_no_projection() {
+    let forecast = forecast_progress(0.0, 10.0, 0, 90 * DAY_MS, 30 * DAY_MS);
+    assert_eq!(forecast.status, ForecastStatus::Behind);
+    assert_eq!(forecast.projected_completion, None);
+}
+
+#[test]
+fn test_forecast_target_met_or_deadline_passed() {
+    let met = forecast_progress(12.0, 10.0, 0, 90 * DAY_MS, 30 * DAY_MS);
+    assert_eq!(met.status, ForecastStatus::OnTrack);
+    assert_eq!(met.required_pace_per_day, 0.0);
+
+    let missed = forecast_progress(5.0, 10.0, 0, 90 * DAY_MS, 100 * DAY_MS);
+    assert_eq!(missed.status, ForecastStatus::Behind);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use axum::http::StatusCode;
+
+use backend::tools::goal::{check_merge, merge_goals_handler};
+use common::unreachable_graph;
+
+#[tokio::test]
+async fn merging_a_goal_into_itself_is_rejected() {
+    let graph = unreachable_graph().await;
+
+    let (status, message) = merge_goals_handler(graph, 1, 42, 42)
+        .await
+        .expect_err("self-merge should be rejected");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("itself"));
+}
+
+#[test]
+fn goals_of_different_types_cannot_merge() {
+    let (status, message) = check_merge("task", "project", false).expect_err("type mismatch");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("same type"));
+}
+
+#[test]
+fn events_cannot_merge() {
+    let (status, message) = check_merge("event", "event", false).expect_err("events");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("Events"));
+}
+
+#[test]
+fn nested_goals_cannot_merge() {
+    let (status, message) = check_merge("project", "project", true).expect_err("nested");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("nested"));
+}
+
+#[test]
+fn goals_of_the_same_type_can_merge() {
+    for goal_type in ["project", "task", "routine", "achievement", "directive"] {
+        assert!(check_merge(goal_type, goal_type, false).is_ok());
+    }
+}
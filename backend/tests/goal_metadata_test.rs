@@ -0,0 +1,77 @@
+use backend::jobs::routine_generator::routine_occurrences;
+use backend::tools::goal::{
+    get_goal_metadata_handler, goal_validation_errors, normalize_priority, Goal, GoalType,
+    ResolutionStatus,
+};
+
+#[test]
+fn metadata_names_match_the_serialized_enums() {
+    let metadata = get_goal_metadata_handler().0;
+
+    let goal_types: Vec<String> = GoalType::ALL
+        .iter()
+        .map(|t| {
+            serde_json::to_value(t)
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(metadata.goal_types, goal_types);
+
+    let statuses: Vec<String> = ResolutionStatus::ALL
+        .iter()
+        .map(|s| {
+            serde_json::to_value(s)
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(metadata.resolution_statuses, statuses);
+}
+
+#[test]
+fn every_advertised_frequency_unit_is_understood_by_the_generator() {
+    let day_ms = 24 * 60 * 60 * 1000;
+    // 2024-01-01 through 2026-01-01 leaves room for a yearly step
+    let (start, end) = (1_704_067_200_000, 1_704_067_200_000 + 731 * day_ms);
+
+    for unit in get_goal_metadata_handler().0.frequency_units {
+        let occurrences = routine_occurrences(&format!("1{}", unit), start, end, None).unwrap();
+        assert!(
+            occurrences.len() >= 2 && occurrences[1] - occurrences[0] >= day_ms,
+            "unit {} should step forward",
+            unit
+        );
+    }
+}
+
+#[test]
+fn advertised_priorities_are_normalized_on_write() {
+    for priority in get_goal_metadata_handler().0.priorities {
+        assert_eq!(normalize_priority(priority).as_deref(), Ok(priority));
+    }
+    assert_eq!(normalize_priority(" High ").as_deref(), Ok("high"));
+    assert_eq!(normalize_priority("NONE").as_deref(), Ok("none"));
+
+    for typo in ["hi", "urgent", ""] {
+        assert!(
+            normalize_priority(typo).is_err(),
+            "{:?} should be rejected",
+            typo
+        );
+    }
+
+    let goal = Goal {
+        name: "Plan".to_string(),
+        goal_type: GoalType::Directive,
+        priority: Some("hi".to_string()),
+        ..Default::default()
+    };
+    assert!(goal_validation_errors(&goal)
+        .iter()
+        .any(|e| e.contains("Unknown priority")));
+}
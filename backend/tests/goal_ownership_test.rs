@@ -0,0 +1,185 @@
+use axum::http::StatusCode;
+use neo4rs::{query, Graph};
+use std::env;
+
+use backend::tools::day::toggle_complete_task;
+use backend::tools::goal::{
+    create_relationship_handler, delete_relationship_handler, update_goal_handler, Goal, GoalType,
+    Relationship,
+};
+use backend::tools::network::update_node_position;
+use backend::tools::traversal::query_hierarchy_handler;
+
+// Separate from the other database suites' users so they can't clobber each other
+const TEST_USER_ID: i64 = 992;
+const OTHER_USER_ID: i64 = 991;
+
+async fn create_test_graph() -> Graph {
+    let uri = env::var("NEO4J_TEST_URI").unwrap_or_else(|_| "bolt://localhost:7688".to_string());
+    let username = env::var("NEO4J_TEST_USERNAME").unwrap_or_else(|_| "neo4j".to_string());
+    let password = env::var("NEO4J_TEST_PASSWORD").unwrap_or_else(|_| "password123".to_string());
+
+    let config = neo4rs::ConfigBuilder::default()
+        .uri(&uri)
+        .user(&username)
+        .password(&password)
+        .build()
+        .unwrap();
+
+    Graph::connect(config)
+        .await
+        .expect("Failed to create test database connection")
+}
+
+async fn create_goal(graph: &Graph, user_id: i64, goal_type: &str) -> i64 {
+    let mut result = graph
+        .execute(
+            query(
+                "CREATE (g:Goal {name: 'Owned goal', goal_type: $goal_type, user_id: $user_id,
+                                 resolution_status: 'pending', scheduled_timestamp: timestamp()})
+                 RETURN id(g) as id",
+            )
+            .param("goal_type", goal_type)
+            .param("user_id", user_id),
+        )
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("id").unwrap()
+}
+
+async fn string_property(graph: &Graph, goal_id: i64, property: &str) -> Option<String> {
+    let mut result = graph
+        .execute(
+            query(&format!(
+                "MATCH (g:Goal) WHERE id(g) = $id RETURN g.{} as value",
+                property
+            ))
+            .param("id", goal_id),
+        )
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("value").ok()
+}
+
+async fn child_edge_count(graph: &Graph, from_id: i64, to_id: i64) -> i64 {
+    let mut result = graph
+        .execute(
+            query(
+                "MATCH (from:Goal)-[r:CHILD]->(to:Goal)
+                 WHERE id(from) = $from_id AND id(to) = $to_id
+                 RETURN count(r) as count",
+            )
+            .param("from_id", from_id)
+            .param("to_id", to_id),
+        )
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("count").unwrap()
+}
+
+async fn delete_goals(graph: &Graph, ids: Vec<i64>) {
+    graph
+        .run(query("MATCH (g:Goal) WHERE id(g) IN $ids DETACH DELETE g").param("ids", ids))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_update_goal_leaves_other_users_goals_alone() {
+    let graph = create_test_graph().await;
+    let foreign = create_goal(&graph, OTHER_USER_ID, "project").await;
+
+    let update = Goal {
+        name: "Renamed".to_string(),
+        goal_type: GoalType::Project,
+        ..Default::default()
+    };
+    let (status, _) = update_goal_handler(graph.clone(), TEST_USER_ID, foreign, update)
+        .await
+        .expect_err("another user's goal should not be updated");
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        string_property(&graph, foreign, "name").await.as_deref(),
+        Some("Owned goal")
+    );
+
+    delete_goals(&graph, vec![foreign]).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_relationships_require_both_goals_to_be_owned() {
+    let graph = create_test_graph().await;
+    let own = create_goal(&graph, TEST_USER_ID, "project").await;
+    let foreign = create_goal(&graph, OTHER_USER_ID, "task").await;
+
+    let relationship = Relationship {
+        from_id: own,
+        to_id: foreign,
+        relationship_type: "child".to_string(),
+    };
+    let (status, _) = create_relationship_handler(graph.clone(), TEST_USER_ID, relationship)
+        .await
+        .expect_err("linking to another user's goal should fail");
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(child_edge_count(&graph, own, foreign).await, 0);
+
+    // Neither user owns both ends, so neither may remove the edge
+    graph
+        .run(
+            query(
+                "MATCH (a:Goal), (b:Goal) WHERE id(a) = $a AND id(b) = $b CREATE (a)-[:CHILD]->(b)",
+            )
+            .param("a", own)
+            .param("b", foreign),
+        )
+        .await
+        .unwrap();
+    delete_relationship_handler(graph.clone(), OTHER_USER_ID, own, foreign)
+        .await
+        .unwrap();
+    assert_eq!(child_edge_count(&graph, own, foreign).await, 1);
+
+    let Ok(axum::Json(hierarchy)) = query_hierarchy_handler(graph.clone(), TEST_USER_ID, own).await
+    else {
+        panic!("hierarchy query failed");
+    };
+    assert!(hierarchy.iter().all(|goal| goal.id != Some(foreign)));
+
+    delete_goals(&graph, vec![own, foreign]).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_position_and_completion_skip_foreign_goals() {
+    let graph = create_test_graph().await;
+    let foreign_goal = create_goal(&graph, OTHER_USER_ID, "project").await;
+    let foreign_event = create_goal(&graph, OTHER_USER_ID, "event").await;
+
+    update_node_position(graph.clone(), TEST_USER_ID, foreign_goal, 10.0, 20.0)
+        .await
+        .unwrap();
+    let mut result = graph
+        .execute(
+            query("MATCH (g:Goal) WHERE id(g) = $id RETURN g.position_x as x")
+                .param("id", foreign_goal),
+        )
+        .await
+        .unwrap();
+    let row = result.next().await.unwrap().unwrap();
+    assert_eq!(row.get::<f64>("x").ok(), None);
+
+    let (status, _) = toggle_complete_task(graph.clone(), TEST_USER_ID, foreign_event)
+        .await
+        .expect_err("another user's event should not be toggled");
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        string_property(&graph, foreign_event, "resolution_status")
+            .await
+            .as_deref(),
+        Some("pending")
+    );
+
+    delete_goals(&graph, vec![foreign_goal, foreign_event]).await;
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use axum::http::StatusCode;
+use neo4rs::{query, Graph};
+use std::env;
+
+use backend::tools::network::{update_goal_positions_handler, GoalPosition};
+use common::unreachable_graph;
+
+// Separate from the other database suites' users so they can't clobber each other
+const TEST_USER_ID: i64 = 996;
+const OTHER_USER_ID: i64 = 995;
+
+async fn create_test_graph() -> Graph {
+    let uri = env::var("NEO4J_TEST_URI").unwrap_or_else(|_| "bolt://localhost:7688".to_string());
+    let username = env::var("NEO4J_TEST_USERNAME").unwrap_or_else(|_| "neo4j".to_string());
+    let password = env::var("NEO4J_TEST_PASSWORD").unwrap_or_else(|_| "password123".to_string());
+
+    let config = neo4rs::ConfigBuilder::default()
+        .uri(&uri)
+        .user(&username)
+        .password(&password)
+        .build()
+        .unwrap();
+
+    Graph::connect(config)
+        .await
+        .expect("Failed to create test database connection")
+}
+
+async fn create_goal(graph: &Graph, user_id: i64) -> i64 {
+    let mut result = graph
+        .execute(
+            query(
+                "CREATE (g:Goal {name: 'Positioned goal', goal_type: 'project', user_id: $user_id})
+                 RETURN id(g) as id",
+            )
+            .param("user_id", user_id),
+        )
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("id").unwrap()
+}
+
+async fn position_of(graph: &Graph, goal_id: i64) -> (Option<f64>, Option<f64>) {
+    let mut result = graph
+        .execute(
+            query("MATCH (g:Goal) WHERE id(g) = $id RETURN g.position_x as x, g.position_y as y")
+                .param("id", goal_id),
+        )
+        .await
+        .unwrap();
+    let row = result.next().await.unwrap().unwrap();
+    (row.get("x").ok(), row.get("y").ok())
+}
+
+#[tokio::test]
+async fn test_empty_batch_is_a_no_op() {
+    let result = update_goal_positions_handler(unreachable_graph().await, 1, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(result.updated, 0);
+    assert_eq!(result.ignored, 0);
+}
+
+#[tokio::test]
+async fn test_non_finite_positions_are_rejected() {
+    let positions = vec![
+        GoalPosition {
+            id: 1,
+            x: 10.0,
+            y: 20.0,
+        },
+        GoalPosition {
+            id: 2,
+            x: f64::NAN,
+            y: 0.0,
+        },
+    ];
+    let err = update_goal_positions_handler(unreachable_graph().await, 1, positions)
+        .await
+        .unwrap_err();
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    assert!(err.1.contains("goal 2"));
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_batch_writes_owned_positions_and_skips_foreign_goals() {
+    let graph = create_test_graph().await;
+    let first = create_goal(&graph, TEST_USER_ID).await;
+    let second = create_goal(&graph, TEST_USER_ID).await;
+    let foreign = create_goal(&graph, OTHER_USER_ID).await;
+
+    let positions = vec![
+        GoalPosition {
+            id: first,
+            x: 10.0,
+            y: 20.0,
+        },
+        GoalPosition {
+            id: second,
+            x: -5.5,
+            y: 0.0,
+        },
+        GoalPosition {
+            id: foreign,
+            x: 1.0,
+            y: 1.0,
+        },
+    ];
+    let result = update_goal_positions_handler(graph.clone(), TEST_USER_ID, positions)
+        .await
+        .unwrap();
+
+    assert_eq!(result.updated, 2);
+    assert_eq!(result.ignored, 1);
+    assert_eq!(position_of(&graph, first).await, (Some(10.0), Some(20.0)));
+    assert_eq!(position_of(&graph, second).await, (Some(-5.5), Some(0.0)));
+    assert_eq!(position_of(&graph, foreign).await, (None, None));
+
+    graph
+        .run(
+            query("MATCH (g:Goal) WHERE id(g) IN $ids DETACH DELETE g")
+                .param("ids", vec![first, second, foreign]),
+        )
+        .await
+        .unwrap();
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use axum::http::StatusCode;
+use backend::tools::stats::{build_trend_buckets, get_goal_trend, trend_bucket_start};
+use chrono::NaiveDate;
+use common::unreachable_graph;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn bucket_start_uses_monday_weeks_and_first_of_month() {
+    // 2025-03-13 is a Thursday
+    assert_eq!(
+        trend_bucket_start(date(2025, 3, 13), "day"),
+        Some(date(2025, 3, 13))
+    );
+    assert_eq!(
+        trend_bucket_start(date(2025, 3, 13), "week"),
+        Some(date(2025, 3, 10))
+    );
+    assert_eq!(
+        trend_bucket_start(date(2025, 3, 13), "month"),
+        Some(date(2025, 3, 1))
+    );
+    assert_eq!(trend_bucket_start(date(2025, 3, 13), "quarter"), None);
+}
+
+#[test]
+fn buckets_are_ordered_and_priority_weighted() {
+    let events = vec![
+        (date(2025, 3, 20), false, "low".to_string()),
+        (date(2025, 3, 11), true, "high".to_string()),
+        (date(2025, 3, 12), false, "low".to_string()),
+    ];
+
+    let buckets = build_trend_buckets(&events, "week");
+
+    assert_eq!(buckets.len(), 2);
+    assert_eq!(buckets[0].period_start, "2025-03-10");
+    assert_eq!(buckets[0].total_events, 2);
+    assert_eq!(buckets[0].completed_events, 1);
+    assert!((buckets[0].weighted_completion_rate - 0.75).abs() < 1e-9);
+    assert_eq!(buckets[1].period_start, "2025-03-17");
+    assert_eq!(buckets[1].weighted_completion_rate, 0.0);
+}
+
+#[tokio::test]
+async fn unknown_bucket_is_rejected_before_querying() {
+    let graph = unreachable_graph().await;
+
+    let err = get_goal_trend(graph, 1, 1, "quarter".to_string(), None, "UTC".to_string())
+        .await
+        .expect_err("unknown bucket should be rejected");
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+}
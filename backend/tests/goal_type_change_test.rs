@@ -0,0 +1,93 @@
+use backend::tools::goal::{apply_goal_type_change, ChangeGoalTypeRequest, Goal, GoalType};
+
+fn request(goal_type: GoalType) -> ChangeGoalTypeRequest {
+    ChangeGoalTypeRequest {
+        goal_type,
+        frequency: None,
+        routine_time: None,
+        start_timestamp: None,
+        target_value: None,
+        generate_events: None,
+    }
+}
+
+fn task() -> Goal {
+    Goal {
+        id: Some(1),
+        name: "Stretch".to_string(),
+        goal_type: GoalType::Task,
+        user_id: Some(7),
+        start_timestamp: Some(1_700_000_000_000),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn task_to_routine_requires_a_valid_frequency() {
+    let errors = apply_goal_type_change(&task(), &request(GoalType::Routine), 0).unwrap_err();
+    assert!(errors.iter().any(|e| e.contains("Frequency is required")));
+
+    let bad = ChangeGoalTypeRequest {
+        frequency: Some("1X".to_string()),
+        ..request(GoalType::Routine)
+    };
+    assert!(apply_goal_type_change(&task(), &bad, 0).is_err());
+
+    let good = ChangeGoalTypeRequest {
+        frequency: Some("1D".to_string()),
+        routine_time: Some(9 * 60 * 60 * 1000),
+        ..request(GoalType::Routine)
+    };
+    let routine = apply_goal_type_change(&task(), &good, 3).unwrap();
+    assert_eq!(routine.goal_type, GoalType::Routine);
+    assert_eq!(routine.frequency.as_deref(), Some("1D"));
+    assert_eq!(routine.routine_time, Some(9 * 60 * 60 * 1000));
+    assert_eq!(routine.id, Some(1));
+}
+
+#[test]
+fn leaving_a_type_drops_its_fields() {
+    let routine = Goal {
+        goal_type: GoalType::Routine,
+        frequency: Some("1W:1,3".to_string()),
+        routine_time: Some(0),
+        skip_holidays: Some(true),
+        ..task()
+    };
+    let converted = apply_goal_type_change(&routine, &request(GoalType::Task), 5).unwrap();
+    assert_eq!(converted.frequency, None);
+    assert_eq!(converted.routine_time, None);
+    assert_eq!(converted.skip_holidays, None);
+
+    let achievement = Goal {
+        goal_type: GoalType::Achievement,
+        target_value: Some(12.0),
+        current_value: Some(4.0),
+        reset_period: Some("yearly".to_string()),
+        ..task()
+    };
+    let converted = apply_goal_type_change(&achievement, &request(GoalType::Task), 0).unwrap();
+    assert_eq!(converted.target_value, None);
+    assert_eq!(converted.current_value, None);
+    assert_eq!(converted.reset_period, None);
+}
+
+#[test]
+fn events_and_event_holders_are_protected() {
+    let event = Goal {
+        goal_type: GoalType::Event,
+        ..task()
+    };
+    assert!(apply_goal_type_change(&event, &request(GoalType::Task), 0).is_err());
+    assert!(apply_goal_type_change(&task(), &request(GoalType::Event), 0).is_err());
+
+    // A project can't keep the task's events
+    let errors = apply_goal_type_change(&task(), &request(GoalType::Project), 2).unwrap_err();
+    assert!(errors.iter().any(|e| e.contains("2 event(s)")));
+    assert!(apply_goal_type_change(&task(), &request(GoalType::Project), 0).is_ok());
+
+    assert!(apply_goal_type_change(&task(), &request(GoalType::Task), 0)
+        .unwrap_err()
+        .iter()
+        .any(|e| e.contains("already a task")));
+}
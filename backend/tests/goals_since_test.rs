@@ -0,0 +1,29 @@
+mod common;
+
+use axum::http::StatusCode;
+
+use backend::jobs::deleted_goal_purger::DELETED_GOAL_RETENTION_DAYS;
+use backend::tools::goal::{get_goals_since_handler, sync_cursor_expired};
+use common::unreachable_graph;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[test]
+fn test_cursor_expires_after_retention_window() {
+    let now = 1_700_000_000_000;
+    let window = DELETED_GOAL_RETENTION_DAYS * DAY_MS;
+
+    assert!(!sync_cursor_expired(now - DAY_MS, now));
+    assert!(!sync_cursor_expired(now - window, now));
+    assert!(sync_cursor_expired(now - window - 1, now));
+}
+
+#[tokio::test]
+async fn test_expired_cursor_is_rejected_before_querying() {
+    let graph = unreachable_graph().await;
+
+    let (status, _) = get_goals_since_handler(graph, 1, 0)
+        .await
+        .expect_err("a cursor from 1970 should require a full refetch");
+    assert_eq!(status, StatusCode::GONE);
+}
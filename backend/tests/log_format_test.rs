@@ -0,0 +1,10 @@
+use backend::server::main::log_format_is_json;
+
+#[test]
+fn test_log_format_selection() {
+    assert!(log_format_is_json(Some("json")));
+    assert!(log_format_is_json(Some(" JSON ")));
+    assert!(!log_format_is_json(Some("text")));
+    assert!(!log_format_is_json(Some("")));
+    assert!(!log_format_is_json(None));
+}
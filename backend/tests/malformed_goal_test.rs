@@ -0,0 +1,44 @@
+use backend::tools::goal::{goal_from_row_lenient, GoalType};
+use neo4rs::{BoltList, BoltMap, BoltType, Row};
+
+fn goal_row(props: &[(&str, BoltType)]) -> Row {
+    let mut map = BoltMap::new();
+    for (key, value) in props {
+        map.put((*key).into(), value.clone());
+    }
+    Row::new(
+        BoltList::from(vec![BoltType::from("g")]),
+        BoltList::from(vec![BoltType::Map(map)]),
+    )
+}
+
+#[test]
+fn test_well_formed_goal_is_returned() {
+    let row = goal_row(&[
+        ("id", BoltType::from(7_i64)),
+        ("name", BoltType::from("Read")),
+        ("goal_type", BoltType::from("task")),
+    ]);
+
+    let goal = goal_from_row_lenient(&row, "g").expect("goal should deserialize");
+    assert_eq!(goal.id, Some(7));
+    assert_eq!(goal.goal_type, GoalType::Task);
+}
+
+#[test]
+fn test_malformed_goal_is_skipped() {
+    // Unknown goal_type, as left behind by a partial migration
+    let row = goal_row(&[
+        ("id", BoltType::from(8_i64)),
+        ("name", BoltType::from("Legacy")),
+        ("goal_type", BoltType::from("habit")),
+    ]);
+    assert!(goal_from_row_lenient(&row, "g").is_none());
+
+    // Missing required name
+    let row = goal_row(&[
+        ("id", BoltType::from(9_i64)),
+        ("goal_type", BoltType::from("task")),
+    ]);
+    assert!(goal_from_row_lenient(&row, "g").is_none());
+}
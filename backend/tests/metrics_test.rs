@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+use backend::server::metrics::{observe_routine_generator_query, render};
+
+#[test]
+fn test_routine_generator_query_latency_is_labelled_by_operation() {
+    observe_routine_generator_query("find_routines", Duration::from_millis(20));
+
+    let output = render();
+    assert!(output.contains("# TYPE routine_generator_query_duration_seconds histogram"));
+    assert!(output.contains(
+        "routine_generator_query_duration_seconds_bucket{operation=\"find_routines\",le=\"0.025\"} 1"
+    ));
+    assert!(!output.contains("neo4j_query_duration_seconds"));
+}
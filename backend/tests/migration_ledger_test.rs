@@ -0,0 +1,14 @@
+use std::collections::HashSet;
+
+use backend::tools::migration::MIGRATIONS;
+
+#[test]
+fn test_migration_ledger_versions_are_unique_and_ordered() {
+    let names: HashSet<&str> = MIGRATIONS.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names.len(), MIGRATIONS.len(), "migration names must be unique");
+
+    assert!(
+        MIGRATIONS.windows(2).all(|w| w[0].1 < w[1].1),
+        "migration versions must be strictly increasing"
+    );
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use std::collections::HashMap;
+
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+
+use backend::tools::stats::{
+    aggregate_monthly_stats, aggregate_period_stats, aggregate_weekly_stats, build_daily_stats,
+    get_range_stats, get_today_score_handler, parse_week_start, MAX_RANGE_DAYS,
+};
+use common::unreachable_graph;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn test_daily_stats_cover_every_day_in_range() {
+    let mut events = HashMap::new();
+    events.insert(
+        "2024-01-30".to_string(),
+        vec![(true, "high".to_string()), (false, "low".to_string())],
+    );
+    events.insert(
+        "2024-02-02".to_string(),
+        vec![(false, "medium".to_string())],
+    );
+
+    let daily = build_daily_stats(&events, date(2024, 1, 29), date(2024, 2, 4));
+    assert_eq!(daily.len(), 7);
+    assert_eq!(daily[0].date, "2024-01-29");
+    assert_eq!(daily[0].total_events, 0);
+
+    let jan_30 = &daily[1];
+    assert_eq!(jan_30.total_events, 2);
+    assert_eq!(jan_30.completed_events, 1);
+    assert!((jan_30.score - 0.75).abs() < 1e-9);
+
+    // Range crosses a month boundary but stays within one ISO week
+    let weekly = aggregate_weekly_stats(&daily, true);
+    assert_eq!(weekly.len(), 1);
+    assert_eq!(weekly[0].period, "2024-W05");
+    assert_eq!(weekly[0].total_events, 3);
+
+    let monthly = aggregate_monthly_stats(&daily);
+    assert_eq!(
+        monthly
+            .iter()
+            .map(|m| m.period.as_str())
+            .collect::<Vec<_>>(),
+        vec!["2024-01", "2024-02"]
+    );
+
+    let totals = aggregate_period_stats(&daily, "last-7-days".to_string());
+    assert_eq!(totals.period, "last-7-days");
+    assert_eq!(totals.completed_events, 1);
+    assert_eq!(totals.days_with_tasks, 2);
+    assert_eq!(totals.days_with_no_tasks_complete, 1);
+}
+
+#[test]
+fn test_sunday_weeks_pull_sunday_into_the_next_week() {
+    let mut events = HashMap::new();
+    // Saturday, Sunday and Monday around the W05/W06 boundary
+    for day in ["2024-02-03", "2024-02-04", "2024-02-05"] {
+        events.insert(day.to_string(), vec![(true, "medium".to_string())]);
+    }
+    let daily = build_daily_stats(&events, date(2024, 2, 3), date(2024, 2, 5));
+
+    let iso: Vec<(String, i32)> = aggregate_weekly_stats(&daily, true)
+        .into_iter()
+        .map(|w| (w.period, w.total_events))
+        .collect();
+    assert_eq!(
+        iso,
+        vec![("2024-W05".to_string(), 2), ("2024-W06".to_string(), 1)]
+    );
+
+    let sunday: Vec<(String, i32)> = aggregate_weekly_stats(&daily, false)
+        .into_iter()
+        .map(|w| (w.period, w.total_events))
+        .collect();
+    assert_eq!(
+        sunday,
+        vec![("2024-W05".to_string(), 1), ("2024-W06".to_string(), 2)]
+    );
+}
+
+#[test]
+fn test_parse_week_start() {
+    assert_eq!(parse_week_start(None), Ok(true));
+    assert_eq!(parse_week_start(Some("Monday")), Ok(true));
+    assert_eq!(parse_week_start(Some("sunday")), Ok(false));
+    assert!(parse_week_start(Some("friday")).is_err());
+}
+
+#[tokio::test]
+async fn test_range_stats_rejects_bad_ranges() {
+    // Validation runs before any query, so the graph never needs to connect
+    let graph = unreachable_graph().await;
+
+    let inverted = get_range_stats(
+        graph.clone(),
+        1,
+        10 * DAY_MS,
+        DAY_MS,
+        "UTC".to_string(),
+        true,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(inverted.0, StatusCode::BAD_REQUEST);
+
+    let too_long = get_range_stats(
+        graph.clone(),
+        1,
+        0,
+        (MAX_RANGE_DAYS + 1) * DAY_MS,
+        "UTC".to_string(),
+        true,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(too_long.0, StatusCode::BAD_REQUEST);
+
+    let bad_tz = get_range_stats(graph, 1, 0, DAY_MS, "Mars/Olympus".to_string(), true)
+        .await
+        .unwrap_err();
+    assert_eq!(bad_tz.0, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_today_score_rejects_bad_timezone() {
+    let graph = unreachable_graph().await;
+
+    let bad_tz = get_today_score_handler(graph, 1, None, "Mars/Olympus".to_string())
+        .await
+        .unwrap_err();
+    assert_eq!(bad_tz.0, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,70 @@
+use chrono::{TimeZone, Utc};
+
+use backend::jobs::routine_generator::creation_exception_occurrences;
+use backend::tools::goal::{CreateGoalRequest, Goal, GoalType};
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+fn ts(y: i32, m: u32, d: u32, h: u32) -> i64 {
+    Utc.with_ymd_and_hms(y, m, d, h, 0, 0)
+        .unwrap()
+        .timestamp_millis()
+}
+
+fn daily_routine() -> Goal {
+    Goal {
+        name: "Stretch".to_string(),
+        goal_type: GoalType::Routine,
+        frequency: Some("1D".to_string()),
+        start_timestamp: Some(ts(2024, 1, 1, 0)),
+        end_timestamp: Some(ts(2024, 1, 31, 23)),
+        routine_time: Some(9 * HOUR_MS),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn exception_dates_map_to_that_days_occurrence() {
+    // Any time on the day identifies it; the seeded exception is the 09:00 occurrence
+    let skipped = creation_exception_occurrences(
+        &daily_routine(),
+        &[ts(2024, 1, 10, 0), ts(2024, 1, 12, 22)],
+    )
+    .unwrap();
+    assert_eq!(skipped, vec![ts(2024, 1, 10, 9), ts(2024, 1, 12, 9)]);
+}
+
+#[test]
+fn exception_dates_outside_the_routine_window_are_ignored() {
+    let skipped =
+        creation_exception_occurrences(&daily_routine(), &[ts(2023, 12, 31, 9), ts(2024, 2, 5, 9)])
+            .unwrap();
+    assert!(skipped.is_empty());
+}
+
+#[test]
+fn exception_dates_without_an_occurrence_are_ignored() {
+    // Mondays, Wednesdays and Fridays only; 2024-01-02 is a Tuesday
+    let routine = Goal {
+        frequency: Some("1W:1,3,5".to_string()),
+        ..daily_routine()
+    };
+    let skipped =
+        creation_exception_occurrences(&routine, &[ts(2024, 1, 2, 9), ts(2024, 1, 3, 9)]).unwrap();
+    assert_eq!(skipped, vec![ts(2024, 1, 3, 9)]);
+}
+
+#[test]
+fn create_request_accepts_a_plain_goal_and_optional_exceptions() {
+    let plain: CreateGoalRequest =
+        serde_json::from_str(r#"{"name": "Read", "goal_type": "task"}"#).unwrap();
+    assert!(plain.exceptions.is_empty());
+    assert_eq!(plain.goal.name, "Read");
+
+    let with_exceptions: CreateGoalRequest = serde_json::from_str(
+        r#"{"name": "Run", "goal_type": "routine", "frequency": "1D", "exceptions": [1704844800000]}"#,
+    )
+    .unwrap();
+    assert_eq!(with_exceptions.exceptions, vec![1_704_844_800_000]);
+    assert_eq!(with_exceptions.goal.goal_type, GoalType::Routine);
+}
@@ -0,0 +1,69 @@
+use backend::tools::goal::{duplicate_routine_config, Goal, GoalType};
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+fn routine() -> Goal {
+    Goal {
+        id: Some(3),
+        name: "Stretch".to_string(),
+        goal_type: GoalType::Routine,
+        user_id: Some(7),
+        frequency: Some("1D".to_string()),
+        routine_time: Some(8 * HOUR_MS),
+        duration: Some(20),
+        start_timestamp: Some(1_700_000_000_000),
+        next_timestamp: Some(1_700_100_000_000),
+        gcal_event_id: Some("abc".to_string()),
+        version: Some(4),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn duplicate_shifts_time_and_keeps_schedule() {
+    let copy = duplicate_routine_config(&routine(), 12 * HOUR_MS, " (evening)").unwrap();
+
+    assert_eq!(copy.name, "Stretch (evening)");
+    assert_eq!(copy.goal_type, GoalType::Routine);
+    assert_eq!(copy.routine_time, Some(20 * HOUR_MS));
+    assert_eq!(copy.frequency.as_deref(), Some("1D"));
+    assert_eq!(copy.duration, Some(20));
+    assert_eq!(copy.start_timestamp, Some(1_700_000_000_000));
+
+    // Nothing tied to the original's identity or generation progress
+    assert_eq!(copy.id, None);
+    assert_eq!(copy.next_timestamp, None);
+    assert_eq!(copy.gcal_event_id, None);
+    assert_eq!(copy.version, None);
+}
+
+#[test]
+fn duplicate_wraps_times_past_midnight() {
+    let source = Goal {
+        routine_time: None,
+        routine_times: Some(vec![7 * HOUR_MS, 22 * HOUR_MS]),
+        ..routine()
+    };
+    let copy = duplicate_routine_config(&source, 3 * HOUR_MS, "").unwrap();
+    assert_eq!(copy.routine_times, Some(vec![HOUR_MS, 10 * HOUR_MS]));
+
+    let earlier = duplicate_routine_config(&routine(), -9 * HOUR_MS, "").unwrap();
+    assert_eq!(earlier.routine_time, Some(23 * HOUR_MS));
+}
+
+#[test]
+fn duplicate_rejects_invalid_requests() {
+    let task = Goal {
+        goal_type: GoalType::Task,
+        ..routine()
+    };
+    assert!(duplicate_routine_config(&task, 0, "").is_err());
+    assert!(duplicate_routine_config(&routine(), 24 * HOUR_MS, "").is_err());
+
+    let all_day = Goal {
+        routine_time: None,
+        ..routine()
+    };
+    assert!(duplicate_routine_config(&all_day, HOUR_MS, "").is_err());
+    assert!(duplicate_routine_config(&all_day, 0, " copy").is_ok());
+}
@@ -0,0 +1,43 @@
+use backend::jobs::routine_generator::{is_generation_stalled, ROUTINE_GAP_THRESHOLD_DAYS};
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const NOW: i64 = 1_700_000_000_000;
+
+#[test]
+fn test_routine_with_events_far_ahead_is_healthy() {
+    assert!(!is_generation_stalled(
+        None,
+        None,
+        Some(NOW + 170 * DAY_MS),
+        NOW
+    ));
+}
+
+#[test]
+fn test_routine_with_no_or_few_future_events_is_stalled() {
+    assert!(is_generation_stalled(None, None, None, NOW));
+    assert!(is_generation_stalled(
+        Some(NOW - 30 * DAY_MS),
+        None,
+        Some(NOW + (ROUTINE_GAP_THRESHOLD_DAYS - 1) * DAY_MS),
+        NOW
+    ));
+}
+
+#[test]
+fn test_routines_ending_or_starting_outside_threshold_are_not_flagged() {
+    // Ends in two days: running out of events is expected
+    assert!(!is_generation_stalled(
+        None,
+        Some(NOW + 2 * DAY_MS),
+        None,
+        NOW
+    ));
+    // Hasn't started yet
+    assert!(!is_generation_stalled(
+        Some(NOW + 30 * DAY_MS),
+        None,
+        None,
+        NOW
+    ));
+}
@@ -6,8 +6,10 @@ use std::env;
 // Import the modules we need for testing
 use backend::jobs::routine_generator::generate_future_routine_events;
 use backend::jobs::routine_generator::recompute_future_for_routine;
+use backend::jobs::routine_generator::{occurrences_for_routine, routine_occurrences};
 use backend::tools::goal::{Goal, GoalType};
-use backend::tools::event::{delete_event_handler, update_routine_event_handler, UpdateRoutineEventRequest};
+use backend::tools::stats::get_year_stats;
+use backend::tools::event::{complete_event_handler, delete_event_handler, get_event_with_context_handler, get_events_by_instance, update_routine_event_handler, UpdateRoutineEventRequest};
 
 /// Helper function to create a test database connection
 async fn create_test_graph() -> Result<Graph, neo4rs::Error> {
@@ -79,6 +81,27 @@ async fn create_test_routine(
         gcal_sync_direction: None,
         is_gcal_imported: None,
         updated_at: None,
+        version: None,
+        energy_required: None,
+        gcal_sync_status: None,
+        gcal_last_sync_attempt: None,
+        gcal_sync_error: None,
+        target_value: None,
+        current_value: None,
+        is_deadline: None,
+        skip_holidays: None,
+        reset_period: None,
+        last_reset_at: None,
+        notes: None,
+        color: None,
+        duration_seconds: None,
+        recurrence: None,
+        recurrence_source_id: None,
+        links: None,
+        allow_overlap: None,
+        routine_times: None,
+        last_modified: None,
+        target_completion_rate: None,
     };
 
     // Create the routine using the goal creation logic
@@ -128,68 +151,10 @@ async fn get_routine_events(graph: &Graph, routine_id: i64) -> Result<Vec<Goal>,
     Ok(events)
 }
 
-/// Helper function from routine_generator.rs for setting time of day
-fn set_time_of_day(base_timestamp: i64, time_of_day: i64) -> i64 {
-    let day_in_ms: i64 = 24 * 60 * 60 * 1000;
-    let start_of_day = (base_timestamp / day_in_ms) * day_in_ms;
-
-    // Extract just the minutes since midnight from the timestamp
-    let minutes_since_midnight = (time_of_day % day_in_ms) / (60 * 1000);
-    let time_of_day_ms = minutes_since_midnight * 60 * 1000;
-
-    start_of_day + time_of_day_ms
-}
-
-// Helper function to validate if a given timestamp matches the routine's frequency pattern
-fn is_valid_day_for_routine(timestamp: i64, frequency: &str) -> Result<bool, String> {
-    let current_dt = Utc
-        .timestamp_millis_opt(timestamp)
-        .earliest()
-        .ok_or("Invalid timestamp")?;
-
-    // frequency pattern: {multiplier}{unit}[:days]
-    let parts: Vec<&str> = frequency.split(':').collect();
-    let freq_part = parts[0];
-
-    if let Some(unit_pos) = freq_part.find(|c: char| !c.is_numeric()) {
-        let unit = &freq_part[unit_pos..];
-
-        match unit {
-            "W" => {
-                if let Some(days) = parts.get(1) {
-                    // Get selected days as numbers (0-6)
-                    let selected_days: Vec<u32> =
-                        days.split(',').filter_map(|d| d.parse().ok()).collect();
-
-                    if selected_days.is_empty() {
-                        // If no specific days are selected, all days are valid for weekly
-                        Ok(true)
-                    } else {
-                        // Check if current day is one of the selected days
-                        let current_weekday = current_dt.weekday().num_days_from_sunday();
-                        Ok(selected_days.contains(&current_weekday))
-                    }
-                } else {
-                    // Weekly without specific days - all days are valid
-                    Ok(true)
-                }
-            }
-            "D" | "M" | "Y" => {
-                // For daily, monthly, yearly - all days are valid (the frequency calculation handles the intervals)
-                Ok(true)
-            }
-            _ => {
-                // Unknown unit - assume valid
-                Ok(true)
-            }
-        }
-    } else {
-        // No unit found - assume daily, so all days are valid
-        Ok(true)
-    }
-}
-
-/// Helper function to generate events for a specific test routine (more controlled than the full generator)
+/// Helper function to materialize a test routine's occurrences in a window.
+///
+/// Occurrence timestamps come from the real `routine_occurrences`, so these DB-backed
+/// tests exercise the shipped algorithm rather than a copy of it.
 async fn generate_events_for_test_routine(
     graph: &Graph,
     routine_id: i64,
@@ -209,101 +174,60 @@ async fn generate_events_for_test_routine(
         .get("r")
         .map_err(|_| neo4rs::Error::ConversionError)?;
 
-    let frequency = routine
-        .frequency
-        .as_ref()
-        .ok_or(neo4rs::Error::ConversionError)?;
+    // The routine's own end date caps the window, as it does in the generator
+    let occurrences = occurrences_for_routine(&routine, start_timestamp, end_timestamp)
+        .map_err(|_| neo4rs::Error::ConversionError)?;
 
     let instance_id = format!("{}-{}", routine_id, Utc::now().timestamp_millis());
-    let mut current_time = start_timestamp;
     let mut event_count = 0;
 
-    while current_time <= end_timestamp {
-        // Check if this day is valid for the routine's frequency pattern
-        if !is_valid_day_for_routine(current_time, frequency)
-            .map_err(|_| neo4rs::Error::ConversionError)?
-        {
-            // Skip to next occurrence if this day doesn't match the pattern
-            current_time = match frequency.as_str() {
-                "1D" => current_time + (24 * 60 * 60 * 1000), // Daily
-                "1W" => current_time + (7 * 24 * 60 * 60 * 1000), // Weekly
-                "2D" => current_time + (2 * 24 * 60 * 60 * 1000), // Every 2 days
-                _ => current_time + (24 * 60 * 60 * 1000),    // Default daily
-            };
-            continue;
-        }
+    for scheduled_timestamp in occurrences {
+        // Check if an event already exists at this timestamp
+        let check_query = query(
+            "MATCH (r:Goal)-[:HAS_EVENT]->(e:Goal)
+             WHERE id(r) = $routine_id 
+             AND e.scheduled_timestamp = $timestamp
+             AND (e.is_deleted IS NULL OR e.is_deleted = false)
+             RETURN count(e) as existing_count",
+        )
+        .param("routine_id", routine_id)
+        .param("timestamp", scheduled_timestamp);
 
-        // Apply the routine's time of day
-        let scheduled_timestamp = if let Some(routine_time) = routine.routine_time {
-            set_time_of_day(current_time, routine_time)
+        let mut check_result = graph.execute(check_query).await?;
+        let existing_count: i64 = if let Some(row) = check_result.next().await? {
+            row.get("existing_count").unwrap_or(0)
         } else {
-            current_time
+            0
         };
-
-        // Ensure we only create events in or after the routine's start date
-        let routine_start = routine.start_timestamp.unwrap_or(start_timestamp);
-        if scheduled_timestamp >= routine_start {
-            // Check if an event already exists at this timestamp
-            let check_query = query(
-                "MATCH (r:Goal)-[:HAS_EVENT]->(e:Goal)
-                 WHERE id(r) = $routine_id 
-                 AND e.scheduled_timestamp = $timestamp
-                 AND (e.is_deleted IS NULL OR e.is_deleted = false)
-                 RETURN count(e) as existing_count",
-            )
-            .param("routine_id", routine_id)
-            .param("timestamp", scheduled_timestamp);
-
-            let mut check_result = graph.execute(check_query).await?;
-            let existing_count: i64 = if let Some(row) = check_result.next().await? {
-                row.get("existing_count").unwrap_or(0)
-            } else {
-                0
-            };
-
-            if existing_count == 0 {
-                if let Some(end_ts) = routine.end_timestamp {
-                    if scheduled_timestamp > end_ts {
-                        break;
-                    }
-                }
-
-                // Create the event since it doesn't exist yet.
-                let create_query = query(
-                    "MATCH (r:Goal) WHERE id(r) = $routine_id
-                     CREATE (e:Goal {
-                         name: r.name,
-                         goal_type: 'event',
-                         scheduled_timestamp: $timestamp,
-                         duration: r.duration,
-                         parent_id: id(r),
-                         parent_type: 'routine',
-                         routine_instance_id: $instance_id,
-                         user_id: r.user_id,
-                         priority: r.priority,
-                         description: r.description,
-                         resolution_status: 'pending',
-                         resolved_at: null,
-                         is_deleted: false
-                     })
-                     CREATE (r)-[:HAS_EVENT]->(e)",
-                )
-                .param("routine_id", routine_id)
-                .param("timestamp", scheduled_timestamp)
-                .param("instance_id", instance_id.clone());
-
-                graph.run(create_query).await?;
-                event_count += 1;
-            }
+        if existing_count > 0 {
+            continue;
         }
 
-        // Calculate next occurrence
-        current_time = match frequency.as_str() {
-            "1D" => current_time + (24 * 60 * 60 * 1000), // Daily
-            "1W" => current_time + (7 * 24 * 60 * 60 * 1000), // Weekly
-            "2D" => current_time + (2 * 24 * 60 * 60 * 1000), // Every 2 days
-            _ => current_time + (24 * 60 * 60 * 1000),    // Default daily
-        };
+        let create_query = query(
+            "MATCH (r:Goal) WHERE id(r) = $routine_id
+             CREATE (e:Goal {
+                 name: r.name,
+                 goal_type: 'event',
+                 scheduled_timestamp: $timestamp,
+                 duration: r.duration,
+                 parent_id: id(r),
+                 parent_type: 'routine',
+                 routine_instance_id: $instance_id,
+                 user_id: r.user_id,
+                 priority: r.priority,
+                 description: r.description,
+                 resolution_status: 'pending',
+                 resolved_at: null,
+                 is_deleted: false
+             })
+             CREATE (r)-[:HAS_EVENT]->(e)",
+        )
+        .param("routine_id", routine_id)
+        .param("timestamp", scheduled_timestamp)
+        .param("instance_id", instance_id.clone());
+
+        graph.run(create_query).await?;
+        event_count += 1;
     }
 
     if event_count > 0 {
@@ -316,6 +240,216 @@ async fn generate_events_for_test_routine(
     Ok(())
 }
 
+fn utc_ms(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0)
+        .unwrap()
+        .timestamp_millis()
+}
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+// Pure occurrence tests: no database needed, and they run against the shipped algorithm.
+mod occurrence_tests {
+    use super::*;
+
+    #[test]
+    fn test_daily_occurrences_apply_routine_time() {
+        let occurrences = routine_occurrences(
+            "1D",
+            utc_ms(2024, 3, 1, 0, 0),
+            utc_ms(2024, 3, 4, 23, 59),
+            Some(15 * HOUR_MS + 30 * 60 * 1000),
+        )
+        .unwrap();
+
+        assert_eq!(
+            occurrences,
+            vec![
+                utc_ms(2024, 3, 1, 15, 30),
+                utc_ms(2024, 3, 2, 15, 30),
+                utc_ms(2024, 3, 3, 15, 30),
+                utc_ms(2024, 3, 4, 15, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multiple_times_per_day_fire_on_each_day_in_order() {
+        let routine = Goal {
+            frequency: Some("1D".to_string()),
+            // Out of order on purpose; occurrences still come out chronologically
+            routine_times: Some(vec![20 * HOUR_MS, 8 * HOUR_MS, 14 * HOUR_MS]),
+            last_modified: None,
+            target_completion_rate: None,
+            routine_time: Some(12 * HOUR_MS),
+            ..Default::default()
+        };
+
+        let occurrences = occurrences_for_routine(
+            &routine,
+            utc_ms(2024, 3, 1, 0, 0),
+            utc_ms(2024, 3, 2, 23, 59),
+        )
+        .unwrap();
+
+        assert_eq!(
+            occurrences,
+            vec![
+                utc_ms(2024, 3, 1, 8, 0),
+                utc_ms(2024, 3, 1, 14, 0),
+                utc_ms(2024, 3, 1, 20, 0),
+                utc_ms(2024, 3, 2, 8, 0),
+                utc_ms(2024, 3, 2, 14, 0),
+                utc_ms(2024, 3, 2, 20, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_every_other_day_without_routine_time_keeps_start_time() {
+        let occurrences = routine_occurrences(
+            "2D",
+            utc_ms(2024, 3, 1, 8, 0),
+            utc_ms(2024, 3, 8, 0, 0),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            occurrences,
+            vec![
+                utc_ms(2024, 3, 1, 8, 0),
+                utc_ms(2024, 3, 3, 8, 0),
+                utc_ms(2024, 3, 5, 8, 0),
+                utc_ms(2024, 3, 7, 8, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekly_without_days_steps_whole_weeks() {
+        let occurrences = routine_occurrences(
+            "1W",
+            utc_ms(2024, 3, 6, 0, 0),
+            utc_ms(2024, 3, 27, 23, 0),
+            Some(14 * HOUR_MS),
+        )
+        .unwrap();
+
+        assert_eq!(
+            occurrences,
+            vec![
+                utc_ms(2024, 3, 6, 14, 0),
+                utc_ms(2024, 3, 13, 14, 0),
+                utc_ms(2024, 3, 20, 14, 0),
+                utc_ms(2024, 3, 27, 14, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekly_selected_days_skip_start_day() {
+        // Creating a Mon/Wed/Fri routine by clicking a Thursday must not put an event on Thursday
+        let thursday = utc_ms(2024, 3, 7, 0, 0);
+        let occurrences = routine_occurrences(
+            "1W:1,3,5",
+            thursday,
+            utc_ms(2024, 3, 20, 23, 0),
+            Some(10 * HOUR_MS),
+        )
+        .unwrap();
+
+        assert_eq!(
+            occurrences,
+            vec![
+                utc_ms(2024, 3, 8, 10, 0),
+                utc_ms(2024, 3, 11, 10, 0),
+                utc_ms(2024, 3, 13, 10, 0),
+                utc_ms(2024, 3, 15, 10, 0),
+                utc_ms(2024, 3, 18, 10, 0),
+                utc_ms(2024, 3, 20, 10, 0),
+            ]
+        );
+        for ts in occurrences {
+            let weekday = Utc
+                .timestamp_millis_opt(ts)
+                .unwrap()
+                .weekday()
+                .num_days_from_sunday();
+            assert!([1, 3, 5].contains(&weekday), "unexpected weekday {}", weekday);
+        }
+    }
+
+    #[test]
+    fn test_monthly_occurrences_clamp_to_month_end() {
+        let occurrences = routine_occurrences(
+            "1M",
+            utc_ms(2024, 1, 31, 0, 0),
+            utc_ms(2024, 3, 31, 23, 0),
+            Some(9 * HOUR_MS),
+        )
+        .unwrap();
+
+        // Leap-year February clamps to the 29th; the day of month then carries forward
+        assert_eq!(
+            occurrences,
+            vec![
+                utc_ms(2024, 1, 31, 9, 0),
+                utc_ms(2024, 2, 29, 9, 0),
+                utc_ms(2024, 3, 29, 9, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_yearly_occurrences() {
+        let occurrences = routine_occurrences(
+            "1Y",
+            utc_ms(2024, 6, 1, 0, 0),
+            utc_ms(2026, 6, 1, 23, 0),
+            Some(12 * HOUR_MS),
+        )
+        .unwrap();
+
+        assert_eq!(
+            occurrences,
+            vec![
+                utc_ms(2024, 6, 1, 12, 0),
+                utc_ms(2025, 6, 1, 12, 0),
+                utc_ms(2026, 6, 1, 12, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_occurrences_stop_at_end_even_within_last_day() {
+        // The last day is in range, but its routine_time falls after the end
+        let occurrences = routine_occurrences(
+            "1D",
+            utc_ms(2024, 3, 1, 0, 0),
+            utc_ms(2024, 3, 3, 12, 0),
+            Some(18 * HOUR_MS),
+        )
+        .unwrap();
+
+        assert_eq!(
+            occurrences,
+            vec![utc_ms(2024, 3, 1, 18, 0), utc_ms(2024, 3, 2, 18, 0)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_frequency_multiplier_is_an_error() {
+        let result = routine_occurrences(
+            "xD",
+            utc_ms(2024, 3, 1, 0, 0),
+            utc_ms(2024, 3, 5, 0, 0),
+            None,
+        );
+        assert!(result.is_err(), "expected an error, got {:?}", result);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -659,6 +793,64 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn test_three_times_daily_routine_generates_three_events_per_day() {
+        let graph = create_test_graph()
+            .await
+            .expect("Failed to create test database connection");
+
+        clear_test_data(&graph)
+            .await
+            .expect("Failed to clear test data");
+
+        let day_ms = 24 * 60 * 60 * 1000;
+        let start_of_today = (Utc::now().timestamp_millis() / day_ms) * day_ms;
+        let end_timestamp = start_of_today + 3 * day_ms - 1; // today plus two more days
+        let times = vec![8 * HOUR_MS, 14 * HOUR_MS, 20 * HOUR_MS];
+
+        let routine = Goal {
+            name: "Medication".to_string(),
+            goal_type: GoalType::Routine,
+            user_id: Some(999),
+            priority: Some("high".to_string()),
+            start_timestamp: Some(start_of_today),
+            end_timestamp: Some(end_timestamp),
+            duration: Some(5),
+            frequency: Some("1D".to_string()),
+            routine_times: Some(times.clone()),
+            last_modified: None,
+            target_completion_rate: None,
+            is_deleted: Some(false),
+            ..Default::default()
+        };
+        let routine_id = routine
+            .create_goal(&graph)
+            .await
+            .expect("Failed to create test routine")
+            .id
+            .unwrap();
+
+        generate_events_for_test_routine(&graph, routine_id, start_of_today, end_timestamp)
+            .await
+            .expect("Failed to generate routine events");
+
+        let events = get_routine_events(&graph, routine_id)
+            .await
+            .expect("Failed to retrieve routine events");
+        assert_eq!(events.len(), 9, "Expected three events on each of three days");
+
+        for (day, day_events) in events.chunks(3).enumerate() {
+            let day_start = start_of_today + day as i64 * day_ms;
+            let scheduled: Vec<i64> = day_events
+                .iter()
+                .map(|event| event.scheduled_timestamp.unwrap())
+                .collect();
+            let expected: Vec<i64> = times.iter().map(|time| day_start + time).collect();
+            assert_eq!(scheduled, expected, "Wrong times on day {}", day);
+        }
+    }
+
     #[tokio::test]
     async fn test_routine_event_relationship() {
         // Set up test database connection
@@ -1176,6 +1368,27 @@ mod tests {
             gcal_sync_direction: None,
             is_gcal_imported: None,
             updated_at: None,
+            version: None,
+            energy_required: None,
+            gcal_sync_status: None,
+            gcal_last_sync_attempt: None,
+            gcal_sync_error: None,
+            target_value: None,
+            current_value: None,
+            is_deadline: None,
+            skip_holidays: None,
+            reset_period: None,
+            last_reset_at: None,
+            notes: None,
+            color: None,
+            duration_seconds: None,
+            recurrence: None,
+            recurrence_source_id: None,
+            links: None,
+            allow_overlap: None,
+            routine_times: None,
+            last_modified: None,
+            target_completion_rate: None,
         };
 
         // Create the routine via API (like frontend does)
@@ -1343,6 +1556,27 @@ mod tests {
             gcal_sync_direction: None,
             is_gcal_imported: None,
             updated_at: None,
+            version: None,
+            energy_required: None,
+            gcal_sync_status: None,
+            gcal_last_sync_attempt: None,
+            gcal_sync_error: None,
+            target_value: None,
+            current_value: None,
+            is_deadline: None,
+            skip_holidays: None,
+            reset_period: None,
+            last_reset_at: None,
+            notes: None,
+            color: None,
+            duration_seconds: None,
+            recurrence: None,
+            recurrence_source_id: None,
+            links: None,
+            allow_overlap: None,
+            routine_times: None,
+            last_modified: None,
+            target_completion_rate: None,
         };
 
         // Create via Goal API (simulates what the frontend does)
@@ -1504,6 +1738,27 @@ mod tests {
             gcal_sync_direction: None,
             is_gcal_imported: None,
             updated_at: None,
+            version: None,
+            energy_required: None,
+            gcal_sync_status: None,
+            gcal_last_sync_attempt: None,
+            gcal_sync_error: None,
+            target_value: None,
+            current_value: None,
+            is_deadline: None,
+            skip_holidays: None,
+            reset_period: None,
+            last_reset_at: None,
+            notes: None,
+            color: None,
+            duration_seconds: None,
+            recurrence: None,
+            recurrence_source_id: None,
+            links: None,
+            allow_overlap: None,
+            routine_times: None,
+            last_modified: None,
+            target_completion_rate: None,
         };
 
         println!(
@@ -2208,6 +2463,8 @@ async fn test_routine_single_reschedule_creates_tombstone_for_old_slot() {
             update_scope: "single".to_string(),
             range_start: None,
             range_end: None,
+            resolution_status: None,
+            shift_mode: None,
         },
     )
     .await
@@ -2232,6 +2489,148 @@ async fn test_routine_single_reschedule_creates_tombstone_for_old_slot() {
     );
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_a_failing_routine_does_not_block_the_users_other_routines() {
+    let graph = create_test_graph()
+        .await
+        .expect("Failed to create test database connection");
+
+    clear_test_data(&graph)
+        .await
+        .expect("Failed to clear test data");
+
+    // A routine with live events but no frequency can't work out its next occurrence
+    let now = Utc::now().timestamp_millis();
+    graph
+        .run(
+            query(
+                "CREATE (r:Goal {name: 'Broken Routine', goal_type: 'routine', user_id: 999,
+                                 start_timestamp: $now, is_deleted: false})
+                 CREATE (r)-[:HAS_EVENT]->(:Goal {name: 'Broken Routine', goal_type: 'event',
+                                 user_id: 999, scheduled_timestamp: $now, parent_type: 'routine',
+                                 routine_instance_id: 'broken', is_deleted: false})",
+            )
+            .param("now", now),
+        )
+        .await
+        .unwrap();
+    let routine_id = create_test_routine(&graph, "Healthy Routine", "1D", now, None, Some(now), 30)
+        .await
+        .expect("Failed to create routine");
+
+    generate_future_routine_events(&graph)
+        .await
+        .expect("Failed to generate routine events");
+
+    let events = get_routine_events(&graph, routine_id)
+        .await
+        .expect("Failed to retrieve routine events");
+    assert!(
+        events.len() > 100,
+        "Expected the healthy routine to be generated despite its broken sibling"
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_absolute_offset_moves_a_weekday_routine_with_its_events() {
+    let graph = create_test_graph()
+        .await
+        .expect("Failed to create test database connection");
+
+    clear_test_data(&graph)
+        .await
+        .expect("Failed to clear test data");
+
+    let now = Utc::now().timestamp_millis();
+    let start = now - (now % 86_400_000);
+    let routine_id = create_test_routine(
+        &graph,
+        "Shifted Weekday Routine",
+        "1W:1,3,5",
+        start,
+        None,
+        Some(start + 9 * 60 * 60 * 1000),
+        30,
+    )
+    .await
+    .expect("Failed to create routine");
+
+    generate_future_routine_events(&graph)
+        .await
+        .expect("Failed to generate routine events");
+
+    let events = get_routine_events(&graph, routine_id)
+        .await
+        .expect("Failed to retrieve routine events");
+    assert!(
+        events.len() > 5,
+        "Expected routine to have >5 events generated"
+    );
+
+    // Monday/Wednesday/Friday at 9:00 becomes Tuesday/Thursday/Saturday at 10:00
+    let target = &events[0];
+    let old_ts = target.scheduled_timestamp.unwrap();
+    let new_ts = old_ts + 86_400_000 + 60 * 60 * 1000;
+    let shifted = update_routine_event_handler(
+        graph.clone(),
+        999,
+        target.id.unwrap(),
+        UpdateRoutineEventRequest {
+            new_timestamp: new_ts,
+            update_scope: "all".to_string(),
+            range_start: None,
+            range_end: None,
+            resolution_status: None,
+            shift_mode: Some("absolute_offset".to_string()),
+        },
+    )
+    .await
+    .expect("update_routine_event_handler failed");
+    assert_eq!(shifted.0.len(), events.len());
+    assert!(shifted
+        .0
+        .iter()
+        .any(|e| e.scheduled_timestamp == Some(new_ts)));
+
+    let mut result = graph
+        .execute(
+            query(
+                "MATCH (r:Goal) WHERE id(r) = $id
+                 RETURN r.frequency as frequency, r.start_timestamp as start_timestamp",
+            )
+            .param("id", routine_id),
+        )
+        .await
+        .unwrap();
+    let row = result.next().await.unwrap().unwrap();
+    assert_eq!(row.get::<String>("frequency").unwrap(), "1W:2,4,6");
+    assert_eq!(
+        row.get::<i64>("start_timestamp").unwrap(),
+        start + 86_400_000
+    );
+
+    // The generator tops the series up on the new weekdays rather than the old ones
+    generate_future_routine_events(&graph)
+        .await
+        .expect("Failed to generate routine events (second run)");
+    let events_after = get_routine_events(&graph, routine_id)
+        .await
+        .expect("Failed to retrieve routine events after");
+    assert!(events_after.len() >= events.len());
+    for event in &events_after {
+        let scheduled = Utc
+            .timestamp_millis_opt(event.scheduled_timestamp.unwrap())
+            .unwrap();
+        assert!(
+            [2, 4, 6].contains(&scheduled.weekday().num_days_from_sunday()),
+            "Event at {} fell back onto the old pattern",
+            scheduled
+        );
+    }
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_recompute_from_cutoff_clears_tombstones_and_recreates_future_occurrences() {
@@ -2298,3 +2697,410 @@ async fn test_recompute_from_cutoff_clears_tombstones_and_recreates_future_occur
         "Expected deleted future occurrence to be recreated after recompute clearing tombstones"
     );
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_routine_skip_holidays_leaves_holiday_dates_empty() {
+    let graph = create_test_graph()
+        .await
+        .expect("Failed to create test database connection");
+
+    // Holidays live on the User node, so this test needs a real user rather than user_id 999
+    let day_ms: i64 = 24 * 60 * 60 * 1000;
+    let start = (Utc::now().timestamp_millis() / day_ms + 1) * day_ms; // tomorrow, midnight UTC
+    let holiday_ts = start + 3 * day_ms;
+    let holiday = Utc
+        .timestamp_millis_opt(holiday_ts)
+        .unwrap()
+        .date_naive()
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let mut user_result = graph
+        .execute(
+            query("CREATE (u:User {username: 'holiday_test_user', holidays: [$holiday]}) RETURN id(u) as id")
+                .param("holiday", holiday.clone()),
+        )
+        .await
+        .expect("Failed to create test user");
+    let user_id: i64 = user_result
+        .next()
+        .await
+        .expect("Failed to read test user")
+        .expect("No test user returned")
+        .get("id")
+        .expect("Missing user id");
+
+    let routine = Goal {
+        name: "Holiday Routine".to_string(),
+        goal_type: GoalType::Routine,
+        user_id: Some(user_id),
+        priority: Some("medium".to_string()),
+        start_timestamp: Some(start),
+        end_timestamp: Some(start + 7 * day_ms),
+        duration: Some(30),
+        resolution_status: Some("pending".to_string()),
+        frequency: Some("1D".to_string()),
+        routine_time: Some(9 * 60 * 60 * 1000),
+        is_deleted: Some(false),
+        skip_holidays: Some(true),
+        ..Default::default()
+    };
+    let routine_id = routine
+        .create_goal(&graph)
+        .await
+        .expect("Failed to create routine")
+        .id
+        .unwrap();
+
+    generate_future_routine_events(&graph)
+        .await
+        .expect("Failed to generate routine events");
+
+    let events = get_routine_events(&graph, routine_id)
+        .await
+        .expect("Failed to retrieve routine events");
+
+    let dates: Vec<String> = events
+        .iter()
+        .filter_map(|e| e.scheduled_timestamp)
+        .map(|ts| {
+            Utc.timestamp_millis_opt(ts)
+                .unwrap()
+                .date_naive()
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .collect();
+
+    // Cleanup before asserting so a failure doesn't leave data behind
+    graph
+        .run(
+            query("MATCH (g:Goal) WHERE g.user_id = $user_id DETACH DELETE g")
+                .param("user_id", user_id),
+        )
+        .await
+        .expect("Failed to clean up goals");
+    graph
+        .run(query("MATCH (u:User) WHERE id(u) = $user_id DETACH DELETE u").param("user_id", user_id))
+        .await
+        .expect("Failed to clean up user");
+
+    assert!(!dates.is_empty(), "Expected the routine to generate events");
+    assert!(
+        !dates.contains(&holiday),
+        "Routine with skip_holidays generated an event on holiday {}: {:?}",
+        holiday,
+        dates
+    );
+    assert_eq!(dates.len(), 6, "Expected every non-holiday day to have an event");
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_generator_output_matches_routine_occurrences() {
+    let graph = create_test_graph()
+        .await
+        .expect("Failed to create test database connection");
+    clear_test_data(&graph)
+        .await
+        .expect("Failed to clear test data");
+
+    // Start tomorrow so every occurrence is in the future and none are skipped as past
+    let day_ms: i64 = 24 * 60 * 60 * 1000;
+    let start = (Utc::now().timestamp_millis() / day_ms + 1) * day_ms;
+    let end = start + 21 * day_ms;
+    let routine_time = Some(10 * HOUR_MS);
+
+    let routine_id = create_test_routine(
+        &graph,
+        "Generator Parity Routine",
+        "1W:1,3,5",
+        start,
+        Some(end),
+        routine_time,
+        60,
+    )
+    .await
+    .expect("Failed to create test routine");
+
+    generate_future_routine_events(&graph)
+        .await
+        .expect("Failed to generate routine events");
+
+    let generated: Vec<i64> = get_routine_events(&graph, routine_id)
+        .await
+        .expect("Failed to retrieve routine events")
+        .iter()
+        .filter_map(|e| e.scheduled_timestamp)
+        .collect();
+    let expected = routine_occurrences("1W:1,3,5", start, end, routine_time).unwrap();
+
+    assert_eq!(generated, expected);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_recompute_output_matches_routine_occurrences() {
+    let graph = create_test_graph()
+        .await
+        .expect("Failed to create test database connection");
+    clear_test_data(&graph)
+        .await
+        .expect("Failed to clear test data");
+
+    let day_ms: i64 = 24 * 60 * 60 * 1000;
+    let start = (Utc::now().timestamp_millis() / day_ms + 1) * day_ms;
+    let end = start + 14 * day_ms;
+    let routine_time = Some(7 * HOUR_MS);
+
+    let routine_id = create_test_routine(
+        &graph,
+        "Recompute Parity Routine",
+        "2D",
+        start,
+        Some(end),
+        routine_time,
+        30,
+    )
+    .await
+    .expect("Failed to create test routine");
+
+    generate_future_routine_events(&graph)
+        .await
+        .expect("Failed to generate routine events");
+
+    let cutoff = start + 5 * day_ms;
+    recompute_future_for_routine(&graph, 999, routine_id, Some(cutoff))
+        .await
+        .expect("recompute_future_for_routine failed");
+
+    let after_cutoff: Vec<i64> = get_routine_events(&graph, routine_id)
+        .await
+        .expect("Failed to retrieve routine events")
+        .iter()
+        .filter_map(|e| e.scheduled_timestamp)
+        .filter(|ts| *ts >= cutoff)
+        .collect();
+    let expected = routine_occurrences("2D", cutoff, end, routine_time).unwrap();
+
+    assert_eq!(after_cutoff, expected);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_events_by_instance_returns_one_generation_run_in_order() {
+    let graph = create_test_graph()
+        .await
+        .expect("Failed to create test database connection");
+    clear_test_data(&graph)
+        .await
+        .expect("Failed to clear test data");
+
+    let day_ms: i64 = 24 * 60 * 60 * 1000;
+    let start = (Utc::now().timestamp_millis() / day_ms + 1) * day_ms;
+    let routine_id = create_test_routine(
+        &graph,
+        "Instance Lookup Routine",
+        "1D",
+        start,
+        Some(start + 5 * day_ms),
+        Some(9 * HOUR_MS),
+        30,
+    )
+    .await
+    .expect("Failed to create test routine");
+
+    generate_future_routine_events(&graph)
+        .await
+        .expect("Failed to generate routine events");
+
+    let routine_events = get_routine_events(&graph, routine_id)
+        .await
+        .expect("Failed to retrieve routine events");
+    let instance_id = routine_events[0]
+        .routine_instance_id
+        .clone()
+        .expect("generated events carry a routine_instance_id");
+    let expected: Vec<Option<i64>> = routine_events
+        .iter()
+        .filter(|e| e.routine_instance_id.as_deref() == Some(instance_id.as_str()))
+        .map(|e| e.id)
+        .collect();
+
+    let events = get_events_by_instance(graph.clone(), 999, instance_id.clone())
+        .await
+        .expect("lookup by instance should succeed")
+        .0;
+    assert_eq!(events.iter().map(|e| e.id).collect::<Vec<_>>(), expected);
+    assert!(events
+        .windows(2)
+        .all(|w| w[0].scheduled_timestamp <= w[1].scheduled_timestamp));
+
+    // Another user's lookup of the same run comes back empty
+    let other = get_events_by_instance(graph, 1000, instance_id)
+        .await
+        .expect("lookup by instance should succeed")
+        .0;
+    assert!(other.is_empty());
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_year_stats_can_exclude_routine_events() {
+    let graph = create_test_graph()
+        .await
+        .expect("Failed to create test database connection");
+    clear_test_data(&graph)
+        .await
+        .expect("Failed to clear test data");
+
+    // One completed event under a task and one under a routine, both finished earlier today
+    let scheduled = Utc::now().timestamp_millis() - 2 * HOUR_MS;
+    for parent_type in ["task", "routine"] {
+        graph
+            .run(
+                query(
+                    "CREATE (g:Goal {name: $name, goal_type: $parent_type, user_id: 999})
+                     CREATE (g)-[:HAS_EVENT]->(:Goal {
+                         name: $name, goal_type: 'event', user_id: 999,
+                         scheduled_timestamp: $scheduled, duration: 30,
+                         resolution_status: 'completed', is_deleted: false
+                     })",
+                )
+                .param("name", format!("Year stats {}", parent_type))
+                .param("parent_type", parent_type)
+                .param("scheduled", scheduled),
+            )
+            .await
+            .expect("Failed to create test events");
+    }
+
+    let total_events = |include_routines: bool| {
+        let graph = graph.clone();
+        async move {
+            let stats = get_year_stats(
+                graph,
+                999,
+                None,
+                "UTC".to_string(),
+                false,
+                0,
+                include_routines,
+            )
+            .await
+            .expect("year stats should load")
+            .0;
+            stats
+                .daily_stats
+                .iter()
+                .map(|d| d.total_events)
+                .sum::<i32>()
+        }
+    };
+
+    assert_eq!(total_events(true).await, 2);
+    // The cached "all events" result must not be served for the routine-free view
+    assert_eq!(total_events(false).await, 1);
+    assert_eq!(total_events(true).await, 2);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_completing_last_routine_event_does_not_prompt_completion() {
+    let graph = create_test_graph()
+        .await
+        .expect("Failed to create test database connection");
+    clear_test_data(&graph)
+        .await
+        .expect("Failed to clear test data");
+
+    // A routine whose only remaining event is the one being completed
+    let mut result = graph
+        .execute(
+            query(
+                "CREATE (r:Goal {name: 'Last run', goal_type: 'routine', user_id: 999, frequency: '1D'})
+                 CREATE (r)-[:HAS_EVENT]->(e:Goal {
+                     name: 'Last run', goal_type: 'event', user_id: 999,
+                     scheduled_timestamp: $scheduled, duration: 30,
+                     resolution_status: 'pending', is_deleted: false
+                 })
+                 RETURN id(e) AS event_id",
+            )
+            .param("scheduled", Utc::now().timestamp_millis()),
+        )
+        .await
+        .expect("Failed to create test routine event");
+    let event_id: i64 = result
+        .next()
+        .await
+        .expect("Failed to read created event")
+        .expect("Created event row missing")
+        .get("event_id")
+        .expect("event_id missing");
+
+    let response = complete_event_handler(graph.clone(), 999, event_id)
+        .await
+        .expect("complete_event_handler failed")
+        .0;
+
+    assert!(response.event_completed);
+    assert!(!response.has_future_events);
+    assert!(
+        !response.should_prompt_task_completion,
+        "Routines must never be prompted for completion"
+    );
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_event_with_context_includes_routine_summary_and_checks_owner() {
+    let graph = create_test_graph()
+        .await
+        .expect("Failed to create test database connection");
+    clear_test_data(&graph)
+        .await
+        .expect("Failed to clear test data");
+
+    let mut result = graph
+        .execute(
+            query(
+                "CREATE (r:Goal {name: 'Stretch', goal_type: 'routine', user_id: 999,
+                                 frequency: '1W:1,3,5', start_timestamp: $scheduled})
+                 CREATE (r)-[:HAS_EVENT]->(e:Goal {
+                     name: 'Stretch', goal_type: 'event', user_id: 999,
+                     scheduled_timestamp: $scheduled, duration: 15,
+                     resolution_status: 'pending', is_deleted: false
+                 })
+                 RETURN id(r) AS routine_id, id(e) AS event_id",
+            )
+            .param("scheduled", Utc::now().timestamp_millis()),
+        )
+        .await
+        .expect("Failed to create test routine event");
+    let row = result
+        .next()
+        .await
+        .expect("Failed to read created event")
+        .expect("Created event row missing");
+    let routine_id: i64 = row.get("routine_id").expect("routine_id missing");
+    let event_id: i64 = row.get("event_id").expect("event_id missing");
+
+    let context = get_event_with_context_handler(graph.clone(), 999, event_id)
+        .await
+        .expect("get_event_with_context_handler failed")
+        .0;
+
+    assert_eq!(context.event.id, Some(event_id));
+    let parent = context.parent.expect("Routine parent should be included");
+    assert_eq!(parent.id, routine_id);
+    assert_eq!(parent.goal_type, GoalType::Routine);
+    assert_eq!(parent.frequency.as_deref(), Some("1W:1,3,5"));
+    assert!(parent.frequency_label.is_some());
+
+    let other_user = get_event_with_context_handler(graph.clone(), 1000, event_id).await;
+    assert_eq!(
+        other_user.err().map(|(status, _)| status),
+        Some(axum::http::StatusCode::NOT_FOUND)
+    );
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+
+use backend::jobs::routine_generator::{
+    generator_concurrency, preview_routine_events, RoutinePreviewRequest,
+};
+use common::unreachable_graph;
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+fn ts(y: i32, m: u32, d: u32, h: u32) -> i64 {
+    Utc.with_ymd_and_hms(y, m, d, h, 0, 0)
+        .unwrap()
+        .timestamp_millis()
+}
+
+#[tokio::test]
+async fn test_preview_weekly_selected_days() {
+    let graph = unreachable_graph().await;
+    // 2024-01-01 is a Monday
+    let request = RoutinePreviewRequest {
+        frequency: "1W:1,3,5".to_string(),
+        start_timestamp: ts(2024, 1, 1, 0),
+        end_timestamp: ts(2024, 1, 14, 23),
+        routine_time: Some(9 * HOUR_MS),
+        routine_times: None,
+        skip_holidays: None,
+    };
+
+    let preview = preview_routine_events(&graph, 1, &request).await.unwrap();
+
+    assert_eq!(
+        preview,
+        vec![
+            ts(2024, 1, 1, 9),
+            ts(2024, 1, 3, 9),
+            ts(2024, 1, 5, 9),
+            ts(2024, 1, 8, 9),
+            ts(2024, 1, 10, 9),
+            ts(2024, 1, 12, 9),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_preview_multiple_times_per_day() {
+    let graph = unreachable_graph().await;
+    let request = RoutinePreviewRequest {
+        frequency: "1D".to_string(),
+        start_timestamp: ts(2024, 1, 1, 0),
+        end_timestamp: ts(2024, 1, 1, 23),
+        routine_time: None,
+        routine_times: Some(vec![8 * HOUR_MS, 14 * HOUR_MS, 20 * HOUR_MS]),
+        skip_holidays: None,
+    };
+
+    let preview = preview_routine_events(&graph, 1, &request).await.unwrap();
+
+    assert_eq!(
+        preview,
+        vec![ts(2024, 1, 1, 8), ts(2024, 1, 1, 14), ts(2024, 1, 1, 20)]
+    );
+}
+
+#[tokio::test]
+async fn test_preview_rejects_bad_input() {
+    let graph = unreachable_graph().await;
+
+    let reversed = RoutinePreviewRequest {
+        frequency: "1D".to_string(),
+        start_timestamp: ts(2024, 2, 1, 0),
+        end_timestamp: ts(2024, 1, 1, 0),
+        routine_time: None,
+        routine_times: None,
+        skip_holidays: None,
+    };
+    assert!(preview_routine_events(&graph, 1, &reversed).await.is_err());
+
+    let too_long = RoutinePreviewRequest {
+        frequency: "1D".to_string(),
+        start_timestamp: ts(2024, 1, 1, 0),
+        end_timestamp: ts(2026, 1, 1, 0),
+        routine_time: None,
+        routine_times: None,
+        skip_holidays: None,
+    };
+    assert!(preview_routine_events(&graph, 1, &too_long).await.is_err());
+}
+
+#[test]
+fn test_generator_concurrency_parsing() {
+    assert_eq!(generator_concurrency(None), 4);
+    assert_eq!(generator_concurrency(Some("8")), 8);
+    assert_eq!(generator_concurrency(Some(" 2 ")), 2);
+    // Zero would stall the generator; garbage falls back to the default
+    assert_eq!(generator_concurrency(Some("0")), 4);
+    assert_eq!(generator_concurrency(Some("lots")), 4);
+}
@@ -0,0 +1,59 @@
+use backend::tools::goal::{
+    goal_validation_errors, validate_target_completion_rate, Goal, GoalType,
+};
+use backend::tools::stats::target_adherence;
+
+#[test]
+fn adherence_compares_rate_with_target() {
+    let (meets, delta) = target_adherence(0.85, Some(0.8));
+    assert_eq!(meets, Some(true));
+    assert!((delta.unwrap() - 0.05).abs() < 1e-9);
+
+    let (meets, delta) = target_adherence(0.6, Some(0.8));
+    assert_eq!(meets, Some(false));
+    assert!((delta.unwrap() + 0.2).abs() < 1e-9);
+
+    // Hitting the target exactly counts as on track
+    assert_eq!(target_adherence(0.8, Some(0.8)).0, Some(true));
+}
+
+#[test]
+fn adherence_is_absent_without_a_target() {
+    assert_eq!(target_adherence(0.5, None), (None, None));
+}
+
+#[test]
+fn target_rate_is_a_fraction() {
+    assert!(validate_target_completion_rate(0.8).is_ok());
+    assert!(validate_target_completion_rate(1.0).is_ok());
+    for bad in [0.0, -0.1, 1.5, 80.0, f64::NAN] {
+        assert!(
+            validate_target_completion_rate(bad).is_err(),
+            "{} should be rejected",
+            bad
+        );
+    }
+}
+
+#[test]
+fn target_rate_is_only_for_routines() {
+    let routine = Goal {
+        name: "Run".to_string(),
+        goal_type: GoalType::Routine,
+        frequency: Some("1D".to_string()),
+        start_timestamp: Some(0),
+        target_completion_rate: Some(0.8),
+        ..Default::default()
+    };
+    assert!(goal_validation_errors(&routine).is_empty());
+
+    let task = Goal {
+        name: "Read".to_string(),
+        goal_type: GoalType::Task,
+        target_completion_rate: Some(0.8),
+        ..Default::default()
+    };
+    assert!(goal_validation_errors(&task)
+        .iter()
+        .any(|e| e.contains("target_completion_rate")));
+}
@@ -0,0 +1,69 @@
+use backend::tools::event::{scheduling_hour_bounds, soon_bonus, MIN_PATTERN_EVENTS};
+use backend::tools::event_settings::skip_weekend_day;
+
+#[test]
+fn test_sparse_history_uses_default_window() {
+    // One or two early-morning events shouldn't pull the window down to 6 AM
+    assert_eq!(scheduling_hour_bounds(&[], None, None), (8, 18));
+    assert_eq!(scheduling_hour_bounds(&[5], None, None), (8, 18));
+    assert_eq!(scheduling_hour_bounds(&[5, 23], None, None), (8, 18));
+
+    let almost_enough = vec![5; MIN_PATTERN_EVENTS - 1];
+    assert_eq!(scheduling_hour_bounds(&almost_enough, None, None), (8, 18));
+}
+
+#[test]
+fn test_history_bounds_use_padded_quartiles() {
+    let hours = [10, 11, 12, 13, 14, 15, 16, 17];
+    // q25 = 12, q75 = 16 -> padded by an hour on each side
+    assert_eq!(scheduling_hour_bounds(&hours, None, None), (11, 17));
+}
+
+#[test]
+fn test_history_bounds_are_clamped() {
+    let hours = [2, 3, 3, 4, 23, 23, 23, 23];
+    assert_eq!(scheduling_hour_bounds(&hours, None, None), (6, 22));
+}
+
+#[test]
+fn test_preferences_override_history() {
+    let hours = [10, 11, 12, 13, 14, 15, 16, 17];
+    assert_eq!(scheduling_hour_bounds(&hours, Some(7), Some(9)), (7, 9));
+    assert_eq!(scheduling_hour_bounds(&[], Some(-1), Some(20)), (0, 20));
+}
+
+#[test]
+fn test_weekend_preference_overrides_history() {
+    // Plenty of weekend history, but the user opted out
+    assert!(skip_weekend_day(Some(false), 50, 100));
+    // No weekend history, but the user explicitly works weekends
+    assert!(!skip_weekend_day(Some(true), 0, 100));
+}
+
+#[test]
+fn test_weekend_heuristic_when_preference_unset() {
+    assert!(skip_weekend_day(None, 5, 100));
+    assert!(!skip_weekend_day(None, 10, 100));
+}
+
+#[test]
+fn test_soon_bonus_without_priority_is_unchanged() {
+    for days_ahead in 0..=3 {
+        assert_eq!(soon_bonus(days_ahead, None), 0.1);
+    }
+    assert_eq!(soon_bonus(4, None), 0.0);
+    // Medium carries the same weight as no priority at all
+    assert_eq!(soon_bonus(1, Some("medium")), 0.1);
+    assert_eq!(soon_bonus(3, Some("medium")), 0.1);
+}
+
+#[test]
+fn test_soon_bonus_scales_with_priority() {
+    // High priority leans hardest toward the next day
+    assert!(soon_bonus(1, Some("high")) > soon_bonus(2, Some("high")));
+    assert!(soon_bonus(2, Some("high")) > soon_bonus(2, None));
+    // Low priority barely prefers the near term, and "none" not at all
+    assert!(soon_bonus(1, Some("low")) < soon_bonus(1, None));
+    assert_eq!(soon_bonus(0, Some("none")), 0.0);
+    assert_eq!(soon_bonus(5, Some("high")), 0.0);
+}
@@ -0,0 +1,54 @@
+use backend::tools::event::snooze_timestamp;
+use chrono::TimeZone;
+use chrono_tz::Tz;
+
+fn ms(tz: Tz, y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+    tz.with_ymd_and_hms(y, m, d, h, min, 0)
+        .unwrap()
+        .timestamp_millis()
+}
+
+#[test]
+fn one_hour_preset_adds_an_hour() {
+    let start = ms(Tz::UTC, 2025, 3, 10, 9, 30);
+    assert_eq!(
+        snooze_timestamp(start, "1h", Tz::UTC).unwrap(),
+        ms(Tz::UTC, 2025, 3, 10, 10, 30)
+    );
+}
+
+#[test]
+fn tonight_preset_moves_to_six_pm_local() {
+    let tz: Tz = "America/New_York".parse().unwrap();
+    let start = ms(tz, 2025, 3, 10, 9, 30);
+    assert_eq!(
+        snooze_timestamp(start, "tonight", tz).unwrap(),
+        ms(tz, 2025, 3, 10, 18, 0)
+    );
+}
+
+#[test]
+fn tonight_preset_rejects_events_already_in_the_evening() {
+    let start = ms(Tz::UTC, 2025, 3, 10, 19, 0);
+    assert!(snooze_timestamp(start, "tonight", Tz::UTC).is_err());
+}
+
+#[test]
+fn tomorrow_and_next_week_keep_local_time_across_dst() {
+    let tz: Tz = "America/New_York".parse().unwrap();
+    // DST starts on 2025-03-09 in New York.
+    let start = ms(tz, 2025, 3, 8, 9, 0);
+    assert_eq!(
+        snooze_timestamp(start, "tomorrow", tz).unwrap(),
+        ms(tz, 2025, 3, 9, 9, 0)
+    );
+    assert_eq!(
+        snooze_timestamp(start, "next_week", tz).unwrap(),
+        ms(tz, 2025, 3, 15, 9, 0)
+    );
+}
+
+#[test]
+fn unknown_preset_is_rejected() {
+    assert!(snooze_timestamp(0, "later", Tz::UTC).is_err());
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use axum::http::StatusCode;
+
+use backend::tools::stats::{build_breakdown_groups, get_stats_breakdown};
+use common::unreachable_graph;
+
+fn event(key: &str, priority: &str, completed: bool) -> (String, String, bool) {
+    (key.to_string(), priority.to_string(), completed)
+}
+
+#[test]
+fn test_breakdown_counts_and_weights_per_group() {
+    let events = vec![
+        event("routine", "high", true),
+        event("routine", "low", false),
+        event("task", "medium", true),
+        event("task", "medium", true),
+    ];
+    let groups = build_breakdown_groups(&events, "goal_type");
+
+    assert_eq!(groups.len(), 2);
+    let routine = &groups[0];
+    assert_eq!(routine.key, "routine");
+    assert_eq!((routine.total_events, routine.completed_events), (2, 1));
+    assert!((routine.completion_rate - 0.5).abs() < 1e-9);
+    // high (3) completed out of high + low (3 + 1)
+    assert!((routine.weighted_completion_rate - 0.75).abs() < 1e-9);
+
+    let task = &groups[1];
+    assert_eq!(task.key, "task");
+    assert_eq!(task.completion_rate, 1.0);
+    assert_eq!(task.weighted_completion_rate, 1.0);
+}
+
+#[test]
+fn test_breakdown_keeps_priority_order() {
+    let events = vec![
+        event("high", "high", true),
+        event("none", "none", false),
+        event("urgent", "urgent", false),
+        event("low", "low", true),
+    ];
+    let keys: Vec<String> = build_breakdown_groups(&events, "priority")
+        .into_iter()
+        .map(|g| g.key)
+        .collect();
+    assert_eq!(keys, ["none", "low", "high", "urgent"]);
+
+    // Weightless groups report zero rather than dividing by zero
+    let none = build_breakdown_groups(&[event("none", "none", true)], "priority");
+    assert_eq!(none[0].weighted_completion_rate, 0.0);
+}
+
+// Bad groupings are rejected before any query, so the graph never needs to connect
+#[tokio::test]
+async fn test_breakdown_rejects_unknown_grouping() {
+    for group_by in ["tag", "color", ""] {
+        let graph = unreachable_graph().await;
+        let result =
+            get_stats_breakdown(graph, 1, None, group_by.to_string(), "UTC".to_string()).await;
+
+        let (status, _) = result.expect_err("unsupported grouping should be rejected");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,27 @@
+use backend::tools::stats::{parse_max_traversal_depth, DEFAULT_MAX_TRAVERSAL_DEPTH};
+
+#[test]
+fn unset_or_blank_depth_uses_the_default() {
+    assert_eq!(
+        parse_max_traversal_depth(None),
+        Ok(DEFAULT_MAX_TRAVERSAL_DEPTH)
+    );
+    assert_eq!(
+        parse_max_traversal_depth(Some(" ")),
+        Ok(DEFAULT_MAX_TRAVERSAL_DEPTH)
+    );
+}
+
+#[test]
+fn depth_must_be_a_bounded_level_count() {
+    assert_eq!(parse_max_traversal_depth(Some(" 4 ")), Ok(4));
+    assert_eq!(parse_max_traversal_depth(Some("50")), Ok(50));
+
+    for bad in ["0", "51", "-3", "deep"] {
+        assert!(
+            parse_max_traversal_depth(Some(bad)).is_err(),
+            "{} should be rejected",
+            bad
+        );
+    }
+}
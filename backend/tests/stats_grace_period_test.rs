@@ -0,0 +1,25 @@
+use backend::tools::stats::{parse_grace_minutes, DEFAULT_GRACE_PERIOD_MINUTES};
+
+#[test]
+fn unset_or_blank_grace_period_uses_the_fallback() {
+    assert_eq!(
+        parse_grace_minutes(None, DEFAULT_GRACE_PERIOD_MINUTES),
+        Ok(DEFAULT_GRACE_PERIOD_MINUTES)
+    );
+    assert_eq!(parse_grace_minutes(Some(""), 30), Ok(30));
+}
+
+#[test]
+fn grace_period_is_minutes_up_to_a_day() {
+    assert_eq!(parse_grace_minutes(Some("0"), 120), Ok(0));
+    assert_eq!(parse_grace_minutes(Some(" 45 "), 120), Ok(45));
+    assert_eq!(parse_grace_minutes(Some("1440"), 120), Ok(1440));
+
+    for bad in ["-1", "1441", "2h"] {
+        assert!(
+            parse_grace_minutes(Some(bad), 120).is_err(),
+            "{} should be rejected",
+            bad
+        );
+    }
+}
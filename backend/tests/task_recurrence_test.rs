@@ -0,0 +1,73 @@
+use backend::jobs::task_recurrence::next_task_occurrences;
+use backend::tools::goal::{frequency_label, shift_frequency_days, validate_frequency};
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const SOURCE: i64 = 1_767_258_000_000; // 2026-01-01T09:00:00Z, a Thursday
+
+#[test]
+fn recurrence_patterns_use_the_routine_frequency_format() {
+    for ok in ["1D", "2d", "1W", "1W:1,3,5", "1M", "1Y"] {
+        assert!(validate_frequency(ok).is_ok(), "{} should be valid", ok);
+    }
+    for bad in ["", "D", "0D", "xD", "1Q", "1D:1", "1W:7", "1W:"] {
+        assert!(
+            validate_frequency(bad).is_err(),
+            "{} should be invalid",
+            bad
+        );
+    }
+}
+
+#[test]
+fn frequency_labels_read_like_the_schedule() {
+    assert_eq!(frequency_label("1D").as_deref(), Some("Every day"));
+    assert_eq!(frequency_label("3d").as_deref(), Some("Every 3 days"));
+    assert_eq!(frequency_label("1W").as_deref(), Some("Every week"));
+    assert_eq!(
+        frequency_label("1W:1,3,5").as_deref(),
+        Some("Every Mon/Wed/Fri")
+    );
+    assert_eq!(
+        frequency_label("2W:2").as_deref(),
+        Some("Every 2 weeks on Tue")
+    );
+    assert_eq!(frequency_label("6M").as_deref(), Some("Every 6 months"));
+    assert_eq!(frequency_label("1Y").as_deref(), Some("Every year"));
+    assert_eq!(frequency_label("1Q"), None);
+}
+
+#[test]
+fn shifting_a_weekly_pattern_rotates_its_days() {
+    assert_eq!(shift_frequency_days("1W:1,3,5", 1).unwrap(), "1W:2,4,6");
+    // Saturday wraps round to Sunday, and the list stays sorted
+    assert_eq!(shift_frequency_days("2W:5,6", 1).unwrap(), "2W:0,6");
+    assert_eq!(shift_frequency_days("1W:0", -1).unwrap(), "1W:6");
+    assert_eq!(shift_frequency_days("1W:1,3", 14).unwrap(), "1W:1,3");
+    // Nothing to rotate without a weekday list
+    assert_eq!(shift_frequency_days("1D", 3).unwrap(), "1D");
+    assert!(shift_frequency_days("1X", 1).is_err());
+}
+
+#[test]
+fn occurrences_keep_the_source_time_and_skip_what_exists() {
+    let daily = next_task_occurrences("1D", SOURCE, SOURCE, SOURCE + 3 * DAY_MS).unwrap();
+    assert_eq!(
+        daily,
+        vec![SOURCE + DAY_MS, SOURCE + 2 * DAY_MS, SOURCE + 3 * DAY_MS]
+    );
+
+    // Resuming after the last generated copy only adds the ones after it
+    let resumed =
+        next_task_occurrences("1D", SOURCE, SOURCE + 2 * DAY_MS, SOURCE + 3 * DAY_MS).unwrap();
+    assert_eq!(resumed, vec![SOURCE + 3 * DAY_MS]);
+}
+
+#[test]
+fn occurrences_stop_at_the_task_end() {
+    let weekly = next_task_occurrences("1W", SOURCE, SOURCE, SOURCE + 15 * DAY_MS).unwrap();
+    assert_eq!(weekly, vec![SOURCE + 7 * DAY_MS, SOURCE + 14 * DAY_MS]);
+
+    assert!(next_task_occurrences("1D", SOURCE, SOURCE + DAY_MS, SOURCE)
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::json;
+
+use backend::tools::event::{
+    apply_schedule_pattern_handler, create_task_with_events_handler, task_date_range_violation,
+    CreateTaskWithEventsRequest, SchedulePatternRequest,
+};
+use common::unreachable_graph;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+fn request(goal_type: &str, event_offsets: &[i64]) -> CreateTaskWithEventsRequest {
+    serde_json::from_value(json!({
+        "task": {
+            "name": "Launch",
+            "goal_type": goal_type,
+            "start_timestamp": 10 * DAY_MS,
+            "end_timestamp": 20 * DAY_MS,
+        },
+        "events": event_offsets
+            .iter()
+            .map(|d| json!({ "scheduled_timestamp": d * DAY_MS, "duration": 60 }))
+            .collect::<Vec<_>>(),
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_task_date_range_violation() {
+    assert!(task_date_range_violation(Some(10), Some(20), 15).is_none());
+    assert!(task_date_range_violation(None, None, 5).is_none());
+
+    let before = task_date_range_violation(Some(10), Some(20), 5).unwrap();
+    assert_eq!(before.violation_type, "before_start");
+    assert_eq!(before.suggested_task_start, Some(5));
+    assert_eq!(before.suggested_task_end, Some(20));
+
+    let after = task_date_range_violation(Some(10), Some(20), 25).unwrap();
+    assert_eq!(after.violation_type, "after_end");
+    assert_eq!(after.suggested_task_end, Some(25));
+}
+
+#[tokio::test]
+async fn test_rejects_non_task_goal() {
+    let err =
+        create_task_with_events_handler(unreachable_graph().await, 1, request("project", &[12]))
+            .await
+            .unwrap_err();
+    assert_eq!(err.0, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_rejects_event_outside_task_range_before_writing() {
+    let err =
+        create_task_with_events_handler(unreachable_graph().await, 1, request("task", &[12, 25]))
+            .await
+            .unwrap_err();
+    assert_eq!(err.0, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(err.1.contains("task_date_range_violation"));
+}
+
+fn pattern(weekdays: Vec<u32>, start: i64, end: i64) -> SchedulePatternRequest {
+    SchedulePatternRequest {
+        weekdays,
+        time_of_day: 14 * 60 * 60 * 1000,
+        duration: 90,
+        start,
+        end,
+    }
+}
+
+#[tokio::test]
+async fn test_schedule_pattern_rejects_invalid_weekdays() {
+    for weekdays in [vec![], vec![2, 7]] {
+        let (status, _) = apply_schedule_pattern_handler(
+            unreachable_graph().await,
+            1,
+            1,
+            pattern(weekdays, 0, DAY_MS * 70),
+        )
+        .await
+        .expect_err("weekdays must be 0-6 and non-empty");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[tokio::test]
+async fn test_schedule_pattern_rejects_range_without_matching_days() {
+    // 1970-01-01 was a Thursday; a Thursday-only range asking for Tuesdays matches nothing
+    let (status, message) = apply_schedule_pattern_handler(
+        unreachable_graph().await,
+        1,
+        1,
+        pattern(vec![2], 0, DAY_MS - 1),
+    )
+    .await
+    .expect_err("no Tuesday in range");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(message.contains("match"));
+}
@@ -0,0 +1,66 @@
+use chrono::{TimeZone, Utc};
+use chrono_tz::Tz;
+
+use backend::tools::day::{group_incomplete_by_day, IncompleteEvent};
+
+fn utc_ms(y: i32, m: u32, d: u32, h: u32) -> i64 {
+    Utc.with_ymd_and_hms(y, m, d, h, 0, 0)
+        .unwrap()
+        .timestamp_millis()
+}
+
+fn event(id: i64, scheduled_timestamp: i64) -> IncompleteEvent {
+    IncompleteEvent {
+        id,
+        name: format!("Event {}", id),
+        scheduled_timestamp,
+        duration: Some(30),
+        priority: "medium".to_string(),
+        resolution_status: "pending".to_string(),
+        parent_id: 100,
+        parent_name: "Parent".to_string(),
+        parent_type: "task".to_string(),
+    }
+}
+
+#[test]
+fn test_groups_by_local_date_and_skips_empty_days() {
+    let events = vec![
+        event(1, utc_ms(2024, 3, 4, 9)),
+        event(2, utc_ms(2024, 3, 4, 18)),
+        event(3, utc_ms(2024, 3, 6, 8)),
+    ];
+
+    let days = group_incomplete_by_day(events, Tz::UTC);
+    let summary: Vec<(&str, Vec<i64>)> = days
+        .iter()
+        .map(|d| (d.date.as_str(), d.events.iter().map(|e| e.id).collect()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![("2024-03-04", vec![1, 2]), ("2024-03-06", vec![3])]
+    );
+}
+
+#[test]
+fn test_day_boundaries_follow_the_timezone() {
+    let tz: Tz = "America/New_York".parse().unwrap();
+    // 03:00 UTC on the 5th is still the evening of the 4th in New York
+    let events = vec![
+        event(1, utc_ms(2024, 3, 4, 20)),
+        event(2, utc_ms(2024, 3, 5, 3)),
+        event(3, utc_ms(2024, 3, 5, 6)),
+    ];
+
+    let days = group_incomplete_by_day(events, tz);
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0].date, "2024-03-04");
+    assert_eq!(days[0].events.len(), 2);
+    assert_eq!(days[1].date, "2024-03-05");
+    assert_eq!(days[1].events[0].id, 3);
+}
+
+#[test]
+fn test_empty_window_has_no_days() {
+    assert!(group_incomplete_by_day(Vec::new(), Tz::UTC).is_empty());
+}
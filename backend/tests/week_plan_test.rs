@@ -0,0 +1,48 @@
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+
+use backend::tools::day::{align_week_start, bucket_week_events};
+
+fn utc_ms(y: i32, m: u32, d: u32, h: u32) -> i64 {
+    Utc.with_ymd_and_hms(y, m, d, h, 0, 0)
+        .unwrap()
+        .timestamp_millis()
+}
+
+#[test]
+fn test_align_week_start_respects_preference() {
+    // Wednesday 2024-01-10
+    let wednesday = utc_ms(2024, 1, 10, 15);
+    assert_eq!(align_week_start(wednesday, false), utc_ms(2024, 1, 7, 0));
+    assert_eq!(align_week_start(wednesday, true), utc_ms(2024, 1, 8, 0));
+
+    // A Sunday is the first day of a Sunday week but the last day of a Monday week
+    let sunday = utc_ms(2024, 1, 14, 9);
+    assert_eq!(align_week_start(sunday, false), utc_ms(2024, 1, 14, 0));
+    assert_eq!(align_week_start(sunday, true), utc_ms(2024, 1, 8, 0));
+}
+
+#[test]
+fn test_bucket_week_events_totals() {
+    let week_start = utc_ms(2024, 1, 7, 0);
+    let events = vec![
+        json!({"id": 1, "scheduled_timestamp": utc_ms(2024, 1, 7, 9), "duration": 30, "resolution_status": "completed"}),
+        json!({"id": 2, "scheduled_timestamp": utc_ms(2024, 1, 7, 13), "duration": 60, "resolution_status": "pending"}),
+        json!({"id": 3, "scheduled_timestamp": utc_ms(2024, 1, 13, 23), "duration": null, "resolution_status": "completed"}),
+        // Next week; dropped
+        json!({"id": 4, "scheduled_timestamp": utc_ms(2024, 1, 14, 1), "duration": 45}),
+    ];
+
+    let days = bucket_week_events(week_start, events);
+
+    assert_eq!(days.len(), 7);
+    assert_eq!(days[0].date, "2024-01-07");
+    assert_eq!(days[0].total_events, 2);
+    assert_eq!(days[0].completed_events, 1);
+    assert_eq!(days[0].scheduled_minutes, 90);
+    assert_eq!(days[6].date, "2024-01-13");
+    assert_eq!(days[6].total_events, 1);
+    assert_eq!(days[6].completed_events, 1);
+    assert_eq!(days[6].scheduled_minutes, 0);
+    assert!(days[1..6].iter().all(|d| d.total_events == 0));
+}
@@ -0,0 +1,37 @@
+use backend::tools::stats::{completion_streaks, DailyStats};
+
+fn days(completed: &[i32]) -> Vec<DailyStats> {
+    completed
+        .iter()
+        .enumerate()
+        .map(|(i, &completed_events)| DailyStats {
+            date: format!("2026-03-{:02}", i + 1),
+            score: 0.0,
+            total_events: completed_events.max(1),
+            completed_events,
+            weighted_total: 0.0,
+            weighted_completed: 0.0,
+        })
+        .collect()
+}
+
+#[test]
+fn streaks_count_consecutive_days_with_a_completion() {
+    assert_eq!(
+        completion_streaks(&days(&[1, 2, 0, 1, 1, 1, 0]), false),
+        (0, 3)
+    );
+    assert_eq!(completion_streaks(&days(&[1, 0, 1, 1]), false), (2, 2));
+    assert_eq!(completion_streaks(&days(&[0, 0, 0]), false), (0, 0));
+    assert_eq!(completion_streaks(&[], false), (0, 0));
+}
+
+#[test]
+fn an_unfinished_today_does_not_break_the_current_streak() {
+    let week = days(&[0, 1, 1, 0]);
+    assert_eq!(completion_streaks(&week, true), (2, 2));
+    assert_eq!(completion_streaks(&week, false), (0, 2));
+
+    // Completions today still count toward it
+    assert_eq!(completion_streaks(&days(&[1, 1, 1]), true), (3, 3));
+}
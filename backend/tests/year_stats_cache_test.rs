@@ -0,0 +1,21 @@
+use backend::tools::stats::years_touched;
+use chrono::{TimeZone, Utc};
+
+fn ms(y: i32, m: u32, d: u32, h: u32) -> i64 {
+    Utc.with_ymd_and_hms(y, m, d, h, 0, 0)
+        .unwrap()
+        .timestamp_millis()
+}
+
+#[test]
+fn mid_year_event_touches_one_year() {
+    assert_eq!(years_touched(ms(2025, 6, 15, 12)), vec![2025]);
+}
+
+#[test]
+fn events_near_new_year_touch_both_years() {
+    // Still Dec 31 west of UTC
+    assert_eq!(years_touched(ms(2025, 1, 1, 3)), vec![2024, 2025]);
+    // Already Jan 1 east of UTC
+    assert_eq!(years_touched(ms(2024, 12, 31, 20)), vec![2024, 2025]);
+}